@@ -28,7 +28,10 @@ Phaeton acts as the **"Gatekeeper"** of your data pipeline. Phaeton employs a
 :license: MIT, see LICENSE for more details.
 """
 
-from .engine import Engine, EngineResult
+import logging as _logging
+import builtins as _builtins
+from typing import Callable, List, Optional, Union
+from .engine import Engine, EngineResult, Job
 from .pipeline import Pipeline
 from .exceptions import (
     Error, 
@@ -40,8 +43,11 @@ from .exceptions import (
 )
 
 __all__ = [
-    "Engine", "EngineResult", "Pipeline",
-    "Error", "ValueError", "SchemaError", "ConfigurationError", "StateError", "EngineError"
+    "Engine", "EngineResult", "Job", "Pipeline", "CancelToken", "PipelineResult", "PreparedPipeline", "StepResult", "NativePipeline",
+    "Dataset", "DatasetResult",
+    "Error", "ValueError", "SchemaError", "ConfigurationError", "StateError", "EngineError",
+    "CoreError", "IoError", "FileNotFoundError", "EncodingError", "ParseError", "InvalidStepError",
+    "ColumnNotFoundError", "CastError", "RowFailedError", "SpecError", "SerializationError"
 ]
 
 _HARDCODED_VERSION = "0.3.0"
@@ -51,6 +57,34 @@ try:
 except ImportError:
     _rust_version = _HARDCODED_VERSION
 
+try:
+    from ._phaeton import CancelToken, PipelineResult, PreparedPipeline, StepResult, Dataset, DatasetResult
+    from ._phaeton import Pipeline as NativePipeline
+except ImportError:
+    CancelToken = PipelineResult = PreparedPipeline = StepResult = NativePipeline = Dataset = DatasetResult = None
+
+# Errors raised by the Rust core, one class per failure kind, all under CoreError (a RuntimeError)
+try:
+    from ._phaeton import (
+        CoreError,
+        IoError,
+        FileNotFoundError,
+        EncodingError,
+        ParseError,
+        InvalidStepError,
+        ColumnNotFoundError,
+        CastError,
+        RowFailedError,
+        SpecError,
+        SerializationError
+    )
+except ImportError:
+    CoreError = IoError = FileNotFoundError = EncodingError = ParseError = InvalidStepError = None
+    ColumnNotFoundError = CastError = RowFailedError = SpecError = SerializationError = None
+
+# Core log records go to 'phaeton.*'; stay silent unless the application configures logging
+_logging.getLogger("phaeton").addHandler(_logging.NullHandler())
+
 
 
 def version() -> str:
@@ -62,7 +96,13 @@ def version() -> str:
     """
     return f"Phaeton v{_HARDCODED_VERSION} (Phaeton Rust Core: v{_rust_version})"
 
-def probe(source: str) -> dict:
+def probe(
+    source: str, 
+    sample_rows: int = 100, 
+    sample_bytes: int = 8192, 
+    full_scan: bool = False, 
+    count_rows: bool = False
+) -> dict:
     """
     Analyzes the first few bytes of a file to automatically detect metadata.
     
@@ -71,21 +111,596 @@ def probe(source: str) -> dict:
 
     Args:
         source (str): Path to the input file.
+        sample_rows (int): Number of data rows sampled for type inference. Defaults to 100.
+        sample_bytes (int): Number of bytes read from the start of the file. Defaults to 8192.
+            Increase it when quirks only appear deeper in the file.
+        full_scan (bool): Read the entire file and sample every row for authoritative 
+            results. Ignores `sample_rows`/`sample_bytes`; best for smaller files.
+        count_rows (bool): Stream the whole file once to report an exact row count 
+            in 'exact_rows'. Defaults to False (estimate only).
 
     Returns:
         dict: A dictionary containing:
             - 'encoding' (str): Detected encoding (e.g., 'windows-1252', 'utf-8').
-            - 'delimiter' (str): Detected CSV delimiter (e.g., ',', ';', '\t').
-            - 'headers' (List[str]): Inferred column headers.
+            - 'delimiter' (str): Detected CSV delimiter (e.g., ',', ';', '\t'), chosen by how 
+              consistently it splits the sampled lines into the same number of fields.
+            - 'delimiter_confidence' (float): Share of sampled lines agreeing on that field count.
+            - 'line_terminator' (str): Dominant line ending, one of '\n', '\r\n' or '\r'.
+            - 'unterminated_last_line' (bool): True if the file does not end with a line break.
+            - 'quotechar' (str): Detected quoting character (`"` or `'`).
+            - 'escape' (str): Escape style for embedded quotes, 'double' (`""`) or 'backslash' (`\"`).
+            - 'file_size' (int): File size in bytes.
+            - 'estimated_rows' (int): Data row estimate (file size / average sampled row 
+              length), useful for progress bars and choosing a batch size.
+            - 'exact_rows' (Optional[int]): Exact data row count when `count_rows=True`.
+            - 'has_headers' (bool): Whether the first row looks like a header rather than 
+              data, judged by comparing its values to the type profile of the rows below.
+            - 'headers' (List[str]): Column headers, parsed with the detected dialect so 
+              quoted names containing the delimiter stay intact.
             - 'confidence' (float): Confidence score of the detection.
+            - 'encodings' (List[dict]): Ranked `{'encoding', 'confidence'}` candidates among 
+              UTF-8, windows-1252, ISO-8859-1, UTF-16LE/BE, Shift_JIS and GBK.
+            - 'header_issues' (List[dict]): Problematic headers as `{'index', 'name', 'issue', 
+              'suggested'}`, where issue is 'duplicate', 'empty' or 'whitespace'. These break 
+              lookup by column name.
+            - 'suggested_headers' (List[str]): Trimmed, unique header names (e.g. `id`, `id_2`), 
+              usable with `.rename()`.
+            - 'column_types' (List[dict]): Per-column `{'name', 'type', 'confidence'}`, where 
+              type is one of 'int', 'float', 'bool', 'date', 'datetime', 'string' and 
+              confidence is the fraction of sampled non-empty values conforming to it.
+            - 'ragged_rows' (dict): `{'checked', 'longer', 'shorter', 'examples'}` counting 
+              sampled rows with more or fewer fields than the header; examples lists up to 
+              10 `{'line', 'fields'}` entries (1-based file line numbers).
+            - 'date_formats' (List[dict]): For date/datetime columns, `{'name', 'formats', 
+              'ambiguous', 'warning'}` where formats ranks matching strptime patterns 
+              (`{'format', 'share'}`), ready to pass to `.cast(format=...)`. `ambiguous` is 
+              True when patterns tie, e.g. '%d/%m/%Y' vs '%m/%d/%Y' with no day above 12.
+            - 'sample' (List[dict]): The first few parsed rows (header -> value), to verify 
+              the detected delimiter and encoding produce sensible fields.
 
     Example:
         >>> info = phaeton.probe("raw_data.csv")
         >>> print(info['encoding'])
         'windows-1252'
     """
+    if sample_bytes <= 0:
+        raise ValueError("Parameter 'sample_bytes' must be a positive integer.")
+    try:
+        from . import _phaeton
+        return _phaeton.probe_file_header(source, sample_rows, sample_bytes, full_scan, count_rows)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot probe file.")
+
+def profile(source: str, top_k: int = 10, delimiter: Optional[str] = None) -> dict:
+    """
+    Streams the entire file and computes per-column statistics.
+
+    This is the "discovery" step before writing a pipeline: it tells you which 
+    columns are sparse, numeric, categorical, or unique keys.
+
+    Args:
+        source (str): Path to the input file.
+        top_k (int): Number of most frequent values to report per column. Defaults to 10.
+        delimiter (Optional[str]): CSV delimiter. If None, it is detected automatically.
+
+    Returns:
+        dict: A dictionary containing:
+            - 'rows' (int): Total number of data rows, malformed records included.
+            - 'malformed_rows' (int): Records the CSV parser could not read; column 
+              statistics cover the other rows.
+            - 'columns' (List[dict]): Per-column statistics with keys 'name', 'count', 
+              'nulls', 'null_ratio', 'distinct_estimate' (HyperLogLog), 'min', 'max', 
+              'numeric' (count/min/max/mean/stddev, or None for non-numeric columns) 
+              and 'top_values' (list of [value, count]).
+
+    Example:
+        >>> stats = phaeton.profile("raw_data.csv")
+        >>> stats['columns'][0]['null_ratio']
+        0.02
+    """
+    if delimiter is not None and len(delimiter) != 1:
+        raise ValueError("Parameter 'delimiter' must be a single character.")
+    try:
+        from . import _phaeton
+        return _phaeton.profile_file(source, top_k, delimiter)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot profile file.")
+
+def report(source: str, output: str, format: Optional[str] = None, top_k: int = 10) -> dict:
+    """
+    Profiles a file and writes the result as a self-contained report document.
+
+    Handy as data-quality evidence attached to a delivery: the report carries the 
+    source path, generation time, row count and every per-column statistic from 
+    `phaeton.profile()`.
+
+    Args:
+        source (str): Path to the input file.
+        output (str): Destination path of the report.
+        format (Optional[str]): 'json' or 'html'. If None, inferred from the extension 
+            of `output` (defaults to 'html').
+        top_k (int): Number of most frequent values listed per column. Defaults to 10.
+
+    Returns:
+        dict: The profile that was written (same shape as `phaeton.profile()`).
+
+    Example:
+        >>> phaeton.report("delivery.csv", "delivery_profile.html")
+    """
+    if format is None:
+        format = "json" if output.lower().endswith(".json") else "html"
+    if format not in ("json", "html"):
+        raise ValueError(f"Invalid report format '{format}'. Expected 'json' or 'html'.")
+    try:
+        from . import _phaeton
+        return _phaeton.write_profile_report(source, output, format, top_k)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot write report.")
+
+def suggest_pipeline(source: Union[str, dict], top_k: int = 10) -> dict:
+    """
+    Recommends a starter list of cleaning steps from a column profile.
+
+    The heuristics trim string columns, cast numeric-looking columns, and align 
+    low-cardinality categoricals against their dominant values. The result is a 
+    starting point to edit, not a finished pipeline.
+
+    Args:
+        source (Union[str, dict]): Path to the input file (profiled on the fly) or 
+            a result previously returned by `phaeton.profile()`.
+        top_k (int): Top values collected per column when profiling a path. Defaults to 10.
+
+    Returns:
+        dict: A dictionary containing:
+            - 'steps' (List[dict]): Step definitions, applicable with `Pipeline.extend()`. 
+              Each carries only the keys it needs; defaults apply to the rest.
+            - 'notes' (List[str]): Advice without a matching step (e.g. mostly-empty columns).
+
+    Example:
+        >>> plan = phaeton.suggest_pipeline("raw_data.csv")
+        >>> pipe = eng.ingest("raw_data.csv").extend(plan['steps'])
+    """
+    stats = profile(source, top_k=top_k) if isinstance(source, str) else source
+    try:
+        from . import _phaeton
+        return _phaeton.suggest_pipeline(stats)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot suggest pipeline.")
+
+def join_files(
+    left: str,
+    right: str,
+    on: Union[str, List[str]],
+    how: str = "inner",
+    output: str = "joined.csv",
+    right_on: Union[str, List[str], None] = None,
+    strategy: str = "auto",
+    delimiter: Optional[str] = None,
+    right_delimiter: Optional[str] = None,
+    suffix: str = "_right",
+    memory_limit: int = 512 * 1024 * 1024
+) -> dict:
+    """
+    Joins two CSV files on key columns and writes the result as CSV, streaming 
+    instead of loading both sides into pandas.
+
+    The output holds every left column, then every right column except the keys. 
+    Right columns whose name the left file already uses get `suffix`. Key values are 
+    compared with surrounding whitespace trimmed; rows with an empty key never match.
+
+    Two strategies:
+
+    - 'hash': loads the right file into a hash table and streams the left file past 
+      it. Rows keep the left file's order; unmatched right rows (right/outer joins) 
+      come last.
+    - 'sort_merge': sorts both files by key in runs that fit `memory_limit`, kept in 
+      a scratch directory next to the output, then merges them. Rows come out in 
+      key order. Use it when the right file does not fit in memory.
+
+    'auto' picks hash join when the right file fits in `memory_limit`.
+
+    Args:
+        left (str): Path of the left file.
+        right (str): Path of the right file, e.g. the lookup table.
+        on (Union[str, List[str]]): Key column(s) of the left file.
+        how (str): 'inner', 'left', 'right' or 'outer'. Defaults to 'inner'.
+        output (str): Path of the joined CSV file.
+        right_on (Union[str, List[str], None]): Key column(s) of the right file when 
+            named differently, in the order of `on`.
+        strategy (str): 'auto', 'hash' or 'sort_merge'. Defaults to 'auto'.
+        delimiter (Optional[str]): Delimiter of the left file, also used for the 
+            output. If None, it is detected automatically.
+        right_delimiter (Optional[str]): Delimiter of the right file. If None, it is 
+            detected automatically.
+        suffix (str): Suffix for right columns whose name the left file uses.
+        memory_limit (int): Memory budget in bytes. Defaults to 512 MiB.
+
+    Returns:
+        dict: 'left', 'right', 'output', 'strategy' ('hash' or 'sort_merge'), 
+            'left_rows', 'right_rows', 'output_rows', 'matched_rows', 
+            'unmatched_left', 'unmatched_right' and 'duration_ms'.
+
+    Example:
+        >>> phaeton.join_files("orders.csv", "customers.csv", on="customer_id", 
+        ...                    how="left", output="orders_enriched.csv")
+    """
+    for name, value in (("delimiter", delimiter), ("right_delimiter", right_delimiter)):
+        if value is not None and len(value) != 1:
+            raise ValueError(f"Parameter '{name}' must be a single character.")
+    keys = [on] if isinstance(on, str) else list(on)
+    right_keys = [right_on] if isinstance(right_on, str) else right_on
+    try:
+        from . import _phaeton
+        return _phaeton.join_files(
+            left, right, keys, output, how, right_keys, strategy,
+            delimiter, right_delimiter, suffix, memory_limit
+        )
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot join files.")
+
+def concat_files(
+    sources: List[str],
+    output: str = "combined.csv",
+    missing: str = "fill",
+    columns: Optional[List[str]] = None,
+    source_column: Optional[str] = None,
+    delimiter: Optional[str] = None,
+    output_delimiter: Optional[str] = None
+) -> dict:
+    """
+    Appends several CSV files into one, reconciling their schemas: columns are 
+    matched by name (surrounding whitespace trimmed), whatever their order in 
+    each file.
+
+    The output holds every column of every file, in order of first appearance, 
+    unless `columns` names them. Rows are streamed file by file, in the order of 
+    `sources`. Every header is read before the output is created, so a file 
+    that fails the `missing` policy leaves no partial output behind.
+
+    Args:
+        sources (List[str]): Paths of the files, in output order.
+        output (str): Path of the combined CSV file.
+        missing (str): What to do with a file that lacks output columns: 'fill' 
+            writes blanks, 'fail' raises. Defaults to 'fill'.
+        columns (Optional[List[str]]): Output columns, in this order. Columns of 
+            the files not listed are dropped.
+        source_column (Optional[str]): Name of an extra column holding the path 
+            each row came from.
+        delimiter (Optional[str]): Delimiter of the sources. If None, it is 
+            detected per file.
+        output_delimiter (Optional[str]): Delimiter of the output. Defaults to 
+            the first file's.
+
+    Returns:
+        dict: 'output', 'columns', 'rows', 'duration_ms' and 'files', one entry 
+            per source with 'path', 'rows', 'columns' (output columns it has), 
+            'missing' (written blank) and 'dropped' (not in the output).
+
+    Example:
+        >>> report = phaeton.concat_files(["jan.csv", "feb.csv"], output="q1.csv")
+        >>> [(f['path'], f['missing']) for f in report['files']]
+        [('jan.csv', []), ('feb.csv', ['discount'])]
+    """
+    if isinstance(sources, str):
+        sources = [sources]
+    for name, value in (("delimiter", delimiter), ("output_delimiter", output_delimiter)):
+        if value is not None and len(value) != 1:
+            raise ValueError(f"Parameter '{name}' must be a single character.")
+    try:
+        from . import _phaeton
+        return _phaeton.concat_files(
+            list(sources), output, missing, columns, source_column, delimiter, output_delimiter
+        )
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot concatenate files.")
+
+def diff_files(
+    old: str,
+    new: str,
+    key_cols: Union[str, List[str]],
+    added: Optional[str] = None,
+    removed: Optional[str] = None,
+    changed: Optional[str] = None,
+    columns: Optional[List[str]] = None,
+    delimiter: Optional[str] = None,
+    new_delimiter: Optional[str] = None
+) -> dict:
+    """
+    Compares two versions of a CSV file row by row, matching rows on key columns, 
+    e.g. to check that a pipeline change only touched the rows it should.
+
+    Values are compared exactly as written; key values with surrounding whitespace 
+    trimmed. Keys must be unique in each file. The old file is held in memory and 
+    the new one is streamed.
+
+    Args:
+        old (str): Path of the old version, e.g. the output before the change.
+        new (str): Path of the new version.
+        key_cols (Union[str, List[str]]): Column(s) identifying a row in both files.
+        added (Optional[str]): Writes the rows only in the new file here.
+        removed (Optional[str]): Writes the rows only in the old file here.
+        changed (Optional[str]): Writes the changed rows here, as they are in the new 
+            file, with a `_phaeton_changes` column such as `amount: 10 -> 12; status: open -> paid`.
+        columns (Optional[List[str]]): Columns compared. Defaults to every non-key 
+            column both files have.
+        delimiter (Optional[str]): Delimiter of the old file, and of the new one unless 
+            `new_delimiter` is set. If None, it is detected automatically.
+        new_delimiter (Optional[str]): Delimiter of the new file.
+
+    Returns:
+        dict: 'old', 'new', 'old_rows', 'new_rows', 'added', 'removed', 'changed', 
+            'unchanged', 'changed_columns' (changed rows per column), 'columns_added', 
+            'columns_removed' and 'duration_ms'.
+
+    Example:
+        >>> report = phaeton.diff_files("before.csv", "after.csv", "order_id", 
+        ...                             changed="changed.csv")
+        >>> report['changed_columns']
+        {'amount': 12}
+    """
+    for name, value in (("delimiter", delimiter), ("new_delimiter", new_delimiter)):
+        if value is not None and len(value) != 1:
+            raise ValueError(f"Parameter '{name}' must be a single character.")
+    keys = [key_cols] if isinstance(key_cols, str) else list(key_cols)
+    try:
+        from . import _phaeton
+        return _phaeton.diff_files(
+            old, new, keys, added, removed, changed, columns, delimiter, new_delimiter
+        )
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot compare files.")
+
+def drift(
+    source: str,
+    baseline: str,
+    null_factor: float = 3.0,
+    mean_tolerance: float = 1.0,
+    update: bool = False,
+    delimiter: Optional[str] = None
+) -> dict:
+    """
+    Compares the column statistics of a file with a stored baseline.
+
+    Without a baseline file, the file's statistics are saved as the baseline and 
+    no shifts are reported. See `Pipeline.drift()` for the kinds of shift.
+
+    Args:
+        source (str): Path of the file to check.
+        baseline (str): Path of the baseline file (JSON).
+        null_factor (float): Growth of the null rate reported as drift. Defaults to 3.0.
+        mean_tolerance (float): Mean shift reported as drift, in baseline standard 
+            deviations. Defaults to 1.0.
+        update (bool): Replace the baseline with the file's statistics after comparing.
+        delimiter (Optional[str]): CSV delimiter. If None, it is detected automatically.
+
+    Returns:
+        dict: A dictionary containing:
+            - 'baseline' (str): The baseline path.
+            - 'created' (bool): True if the baseline was created by this call.
+            - 'updated' (bool): True if the baseline was replaced.
+            - 'baseline_rows' / 'rows' (int): Row counts of the baseline and the file.
+            - 'drifted' (bool): True if any shift was found.
+            - 'shifts' (List[dict]): One entry per shift with 'column', 'kind', 
+              'baseline', 'current', 'values' and 'message'.
+
+    Example:
+        >>> report = phaeton.drift("incoming/orders.csv", "baselines/orders.json")
+        >>> report['drifted']
+        False
+    """
+    if delimiter is not None and len(delimiter) != 1:
+        raise ValueError("Parameter 'delimiter' must be a single character.")
+    try:
+        from . import _phaeton
+        return _phaeton.drift_check(source, baseline, null_factor, mean_tolerance, update, delimiter)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot check drift.")
+
+def detokenize(
+    vault: str,
+    tokens: List[str],
+    key: Optional[str] = None,
+    key_env: str = "PHAETON_VAULT_KEY"
+) -> List[Optional[str]]:
+    """
+    Looks up the original values of tokens made by a `tokenize` step.
+
+    Args:
+        vault (str): Path of the vault file the tokens were made with.
+        tokens (List[str]): Tokens to resolve.
+        key (Optional[str]): Vault passphrase. If None, it is read from `key_env`.
+        key_env (str): Environment variable holding the passphrase when `key` is None.
+
+    Returns:
+        List[Optional[str]]: The original value of each token, in order; None for 
+        values that are not tokens of this vault.
+
+    Example:
+        >>> phaeton.detokenize("pii.vault", ["tok_3f2a9c..."])
+        ['alice@example.com']
+    """
+    try:
+        from . import _phaeton
+        return _phaeton.detokenize_values(vault, tokens, key, key_env)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot read the vault.")
+
+def describe_steps() -> List[dict]:
+    """
+    Describes every step action the engine executes, generated from the Rust step 
+    definitions so it always matches what `validate_pipeline()` and `run()` accept.
+
+    Returns:
+        List[dict]: One entry per action with 'action' and 'params'. Each param has 
+            'name', 'type' (e.g. "column", "string | list[string]", "number"), 
+            'required', 'default' (None when there is none) and 'choices' 
+            (allowed values, or None when any value of the type is accepted).
+
+    Example:
+        >>> cast = next(a for a in phaeton.describe_steps() if a['action'] == 'cast')
+        >>> [p['name'] for p in cast['params'] if p['required']]
+        ['col']
+    """
+    try:
+        from . import _phaeton
+        return _phaeton.describe_steps()
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot describe steps.")
+
+def validate_pipeline(source: str, steps: List[dict], header_match: Optional[str] = None,
+                      strict_steps: bool = True, rename_duplicates: bool = False) -> List[dict]:
+    """
+    Checks a list of steps against a source file without processing any data.
+
+    Only the header row is read. Every step is resolved and compiled (columns, 
+    regexes, reference files, parameters), and all problems are collected instead 
+    of stopping at the first one.
+
+    Args:
+        source (str): Path to the input file.
+        steps (List[dict]): Step definitions, e.g. `pipe._steps` or the output of 
+            `phaeton.suggest_pipeline()`.
+        header_match (str, optional): 'exact' (default), 'trim' or 'ignore_case', as in 
+            `Engine.ingest()`.
+        strict_steps (bool): Report unknown parameters as errors, as `Engine(strict_steps=True)` 
+            would fail on them. With False they are warnings. Defaults to True.
+        rename_duplicates (bool): Make repeated header names unique first, as in 
+            `Engine.ingest()`. Defaults to False.
+
+    Returns:
+        List[dict]: Findings as `{'step', 'action', 'level', 'message'}`, where level 
+            is 'error' (the run would fail) or 'warning' (e.g. unknown parameters that 
+            are ignored). An empty list means the pipeline is valid.
+
+    Example:
+        >>> issues = phaeton.validate_pipeline("raw_data.csv", plan['steps'])
+        >>> [i['message'] for i in issues if i['level'] == 'error']
+        ["Column not found: emial (did you mean 'email'?)"]
+    """
+    try:
+        from . import _phaeton
+        return _phaeton.validate_pipeline(source, steps, header_match, strict_steps, rename_duplicates)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot validate pipeline.")
+
+def execute_from_spec(
+    path: str,
+    progress: Optional[Callable[[dict], None]] = None,
+    cancel: Optional["CancelToken"] = None
+) -> List["PipelineResult"]:
+    """
+    Runs the pipelines defined in a YAML or JSON spec file, so pipelines can live in 
+    version control as configuration instead of Python code.
+
+    `${VAR}` and `${VAR:-default}` in any string are replaced from the environment 
+    (`$$` is a literal `$`). The file is checked before anything runs: unknown keys, 
+    invalid strategies, steps with errors and pipelines without an output are all 
+    reported together.
+
+    Args:
+        path (str): Spec file. `.json` is parsed as JSON, anything else as YAML.
+        progress (Callable[[dict], None], optional): Per-batch callback, as in `Engine.exec()`.
+        cancel (phaeton.CancelToken, optional): Token to stop every pipeline early.
+
+    Returns:
+        List[PipelineResult]: One result per pipeline, in spec order. Failed pipelines 
+            have `ok=False` and the message in `error`.
+
+    Raises:
+        ValueError: If the spec cannot be read, parsed, interpolated or validated.
+
+    Example:
+        ```yaml
+        version: 1
+        config: {workers: 4, on_error: quarantine}
+        pipelines:
+          - source: ${DATA_DIR}/orders.csv
+            delimiter: ";"
+            steps:
+              - {action: prune, col: order_id}
+              - {action: cast, col: amount, type: float, clean: true}
+            output: {path: clean/orders.csv, delimiter: ","}
+            quarantine: clean/orders_rejected.csv
+        ```
+    """
+    try:
+        from . import _phaeton
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+    try:
+        return _phaeton.execute_from_spec(path, progress, cancel)
+    except (_builtins.ValueError, _phaeton.CoreError) as e:
+        raise ValueError(str(e)) from None
+
+def set_log_filter(directives: str) -> None:
+    """
+    Sets which Rust core log records are forwarded to Python's `logging` module.
+
+    Records are emitted under the `phaeton.*` logger hierarchy (`phaeton.engine`, 
+    `phaeton.streaming`, `phaeton.processors.probe`, ...), so handlers, formatting and 
+    final levels are configured with the standard `logging` API. This filter decides 
+    what the core produces in the first place; the initial value comes from the 
+    `PHAETON_LOG` environment variable and defaults to 'info'.
+
+    Args:
+        directives (str): A level ('error', 'warn', 'info', 'debug', 'trace') or 
+            comma-separated per-module directives, e.g. 'warn,phaeton::streaming=debug'.
+
+    Example:
+        >>> logging.basicConfig(level=logging.DEBUG)
+        >>> phaeton.set_log_filter("debug")
+    """
+    try:
+        from . import _phaeton
+        _phaeton.set_log_filter(directives)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot configure logging.")
+
+def metrics(format: str = "dict", reset: bool = False) -> Union[dict, str]:
+    """
+    Returns process-wide counters covering every pipeline run in this interpreter.
+
+    Counters are updated after each batch, so a snapshot taken from another thread 
+    (e.g. a `/metrics` handler of a daemonized job) reflects runs still in progress.
+
+    Args:
+        format (str): 'dict' for a Python dict or 'prometheus' for the Prometheus 
+            text exposition format. Defaults to 'dict'.
+        reset (bool): Zero all counters after taking the snapshot. Defaults to False.
+
+    Returns:
+        Union[dict, str]: With 'dict', the keys 'runs_started', 'runs_completed', 
+            'runs_failed', 'runs_cancelled', 'rows_processed', 'rows_saved', 
+            'rows_quarantined', 'rows_skipped', 'bytes_read', 'quarantine_rate', 
+            'batch_latency_buckets' (cumulative `[upper_bound_seconds, count]` pairs), 
+            'batch_latency_count', 'batch_latency_sum' and 'step_timings' (per action 
+            `{'action', 'rows', 'seconds'}`, filled only while step timings are enabled).
+
+    Example:
+        >>> phaeton.metrics()['quarantine_rate']
+        0.013
+    """
+    if format not in ("dict", "prometheus"):
+        raise ValueError(f"Invalid metrics format '{format}'. Expected 'dict' or 'prometheus'.")
+    try:
+        from . import _phaeton
+        return _phaeton.metrics_snapshot(format, reset)
+    except ImportError:
+        raise EngineError("Phaeton Rust Core not found. Cannot read metrics.")
+
+def enable_step_timings(enabled: bool = True) -> None:
+    """
+    Turns per-step timing on or off for subsequent runs.
+
+    When enabled, `phaeton.metrics()` reports the time spent in each step action. 
+    This adds a clock read per step and row, so it is off by default.
+
+    Args:
+        enabled (bool): Whether to time steps. Defaults to True.
+    """
     try:
         from . import _phaeton
-        return _phaeton.probe_file_header(source)
+        _phaeton.set_step_timings(enabled)
     except ImportError:
-        raise EngineError("Phaeton Rust Core not found. Cannot probe file.")
\ No newline at end of file
+        raise EngineError("Phaeton Rust Core not found. Cannot configure metrics.")
@@ -1,5 +1,5 @@
 import threading
-from typing import List, Union, Dict
+from typing import List, Union, Dict, Callable, Optional, Any, Literal, get_args
 from ._internal import ACCESS_TOKEN
 from .exceptions import (
     ConfigurationError,
@@ -12,49 +12,227 @@ from .pipeline import (
     ScrubMode, 
     MatchMode, 
     FillMethod, 
-    CastType
+    CastType,
+    ReadMode,
+    RaggedPolicy,
+    HeaderMatch
 )
 
+def _check_batch_size(batch_size) -> None:
+    if batch_size == "auto":
+        return
+    if isinstance(batch_size, str) or batch_size <= 0:
+        raise ConfigurationError(f"Batch size must be greater than 0 or 'auto', got {batch_size!r}.")
+
+
 class EngineResult:
     """
     Encapsulates the statistical results of a pipeline execution.
     This class is immutable (Read-Only).
 
     Attributes:
-        processed (int): Total number of rows read from the source.
+        source (str): Path of the input file.
+        output (str): Path the cleaned rows were written to.
+        quarantine (Optional[str]): Path of the quarantine file, if one was set.
+        processed (int): Total number of rows read from the source, malformed records included.
         saved (int): Total number of rows successfully cleaned and saved.
         quarantined (int): Total number of rows rejected and sent to quarantine.
-        duration (int): Execution time in milliseconds.
+        skipped (int): Rows dropped by the `on_error('skip')` strategy.
+        malformed (int): Records the CSV parser could not read. They are also counted 
+            in `quarantined` or `skipped`, depending on the `on_error()` strategy.
+        duration (int): Execution time in milliseconds, measured inside the Rust stream loop.
+        bytes_read (int): Number of source bytes consumed.
+        rows_per_sec (float): Processing throughput in rows per second.
+        bytes_per_sec (float): Processing throughput in bytes per second.
+        cancelled (bool): True if the run was stopped early (CancelToken or Ctrl+C). 
+            The counts then cover the batches completed before stopping.
+        steps (List[StepResult]): Rows rejected per row-level step, as `.step` (position 
+            in the step list), `.action` and `.rejected`.
+        warnings (List[str]): Non-fatal problems, e.g. ignored parameters or rejected 
+            rows dropped for lack of a quarantine path.
+        lineage (Optional[str]): Path of the lineage sidecar written by `Pipeline.lineage()`.
+        manifest (Optional[str]): Path of the checksum manifest written by `Pipeline.manifest()`.
+        increment (Optional[str]): For `Pipeline.incremental()` runs, how the source 
+            compared to the state file: 'new', 'appended', 'unchanged' (the run was 
+            skipped) or 'changed' (read again in full).
+        routes (List[RouteResult]): Rows taken by each route of `Pipeline.route()`, 
+            as `.name`, `.output` and `.rows`. Routed rows are not counted in `saved`.
+        drift (List[DriftShift]): Shifts of the output statistics against the baseline 
+            of `Pipeline.drift()`, with `.column`, `.kind`, `.baseline`, `.current`, 
+            `.values` and `.message`.
+        expectations (List[ExpectationResult]): Outcome of every `expect_*` step, with 
+            `.rule`, `.column`, `.checked`, `.matched`, `.observed`, `.expected`, 
+            `.passed` and `.severity`.
+        error (Optional[str]): Why the run failed, or None. Batch execution reports 
+            failures here instead of raising, so the other pipelines keep their results. 
+            An expectation of severity 'error' that did not hold also fails the run.
+        ok (bool): True unless the run failed.
+        result (PipelineResult): The underlying typed result from the Rust core.
     """
 
-    __slots__ = ('_processed', '_saved', '_quarantined', '_duration')
+    __slots__ = ('_result',)
+
+    def __init__(self, result: Any):
+        self._result = result
+
+    @property
+    def source(self) -> str:
+        return self._result.source
+
+    @property
+    def output(self) -> str:
+        return self._result.output
 
-    def __init__(self, stats: Dict[str, int]):
-        self._processed = stats.get("processed_rows", 0)
-        self._saved = stats.get("saved_rows", 0)
-        self._quarantined = stats.get("quarantined_rows", 0)
-        self._duration = stats.get("duration_ms", 0)
+    @property
+    def quarantine(self) -> Optional[str]:
+        return self._result.quarantine
 
     @property
     def processed(self) -> int:
-        return self._processed
+        return self._result.processed
 
     @property
     def saved(self) -> int:
-        return self._saved
+        return self._result.saved
 
     @property
     def quarantined(self) -> int:
-        return self._quarantined
+        return self._result.quarantined
+
+    @property
+    def skipped(self) -> int:
+        return self._result.skipped
+
+    @property
+    def malformed(self) -> int:
+        return self._result.malformed
 
     @property
     def duration(self) -> int:
-        return self._duration
+        return round(self._result.duration * 1000)
+
+    @property
+    def bytes_read(self) -> int:
+        return self._result.bytes_read
+
+    @property
+    def rows_per_sec(self) -> float:
+        return self._result.rows_per_sec
+
+    @property
+    def bytes_per_sec(self) -> float:
+        return self._result.bytes_per_sec
+
+    @property
+    def cancelled(self) -> bool:
+        return self._result.cancelled
+
+    @property
+    def steps(self) -> list:
+        return self._result.steps
+
+    @property
+    def warnings(self) -> List[str]:
+        return self._result.warnings
+
+    @property
+    def lineage(self) -> Optional[str]:
+        return self._result.lineage
+
+    @property
+    def manifest(self) -> Optional[str]:
+        return self._result.manifest
+
+    @property
+    def increment(self) -> Optional[str]:
+        return self._result.increment
+
+    @property
+    def routes(self) -> list:
+        return self._result.routes
+
+    @property
+    def drift(self) -> list:
+        return self._result.drift
+
+    @property
+    def expectations(self) -> list:
+        return self._result.expectations
+
+    @property
+    def error(self) -> Optional[str]:
+        return self._result.error
+
+    @property
+    def ok(self) -> bool:
+        return self._result.error is None
+
+    @property
+    def result(self) -> Any:
+        return self._result
     
     def __repr__(self):
+        if self.error is not None:
+            return f"<EngineResult | FAILED: {self.error}>"
+        status = ", CANCELLED" if self.cancelled else ""
         return (f"<EngineResult | Processed: {self.processed}, "
                 f"Saved: {self.saved}, Quarantined: {self.quarantined} "
-                f"({self.duration}ms)>")
+                f"({self.duration}ms, {self.rows_per_sec:.0f} rows/s{status})>")
+
+class Job:
+    """
+    Handle of pipelines running in the background, returned by `Engine.start()`.
+
+    Attributes:
+        status (str): 'running', 'completed' or 'cancelled'. Failed pipelines still 
+            count as completed; check `ok`/`error` on their results.
+        done (bool): True once every pipeline has finished.
+    """
+
+    __slots__ = ('_job',)
+
+    def __init__(self, job: Any):
+        self._job = job
+
+    @property
+    def status(self) -> str:
+        return self._job.status
+
+    @property
+    def done(self) -> bool:
+        return self._job.done
+
+    def progress(self) -> Dict[str, dict]:
+        """
+        Returns the latest progress of each pipeline, keyed by source path, with the 
+        same keys as the `progress` callback of `exec()`. Pipelines that have not 
+        finished a batch yet are missing.
+        """
+        return self._job.progress()
+
+    def poll(self) -> Optional[List[EngineResult]]:
+        """Returns the results if the job is done, else None. Never blocks."""
+        results = self._job.poll()
+        return None if results is None else [EngineResult(r) for r in results]
+
+    def join(self, timeout: Optional[float] = None) -> List[EngineResult]:
+        """
+        Waits for the job and returns one result per pipeline, in order.
+
+        Args:
+            timeout (float, optional): Maximum seconds to wait. Defaults to no limit.
+
+        Raises:
+            TimeoutError: If the job is still running after `timeout` seconds.
+        """
+        return [EngineResult(r) for r in self._job.join(timeout)]
+
+    def cancel(self) -> None:
+        """Stops every pipeline after the batch in flight; the results are then partial."""
+        self._job.cancel()
+
+    def __repr__(self):
+        return f"<Job | {self.status}>"
 
 class Engine:
     """
@@ -76,20 +254,47 @@ class Engine:
                     cls._instance = super(Engine, cls).__new__(cls)
         return cls._instance
     
-    def __init__(self, workers: int = 0, batch_size: int = 10000, strict: bool = False):
+    def __init__(
+        self, 
+        workers: int = 0, 
+        batch_size: Union[int, Literal["auto"]] = 10000, 
+        strict: bool = False, 
+        preserve_order: bool = True,
+        read_mode: ReadMode = "auto",
+        batch_memory: int = 16 * 1024 * 1024,
+        strict_steps: bool = True
+    ):
         """
         Initialize the Engine configuration:
 
         Args:
             workers (int, optional): Number of CPU threads to use. 
                 Set to 0 to automatically use all available cores. Defaults to 0.
-            batch_size (int, optional): Number of rows to process in each batch. 
-                Defaults to 10000.
+            batch_size (Union[int, 'auto'], optional): Number of rows to process in each batch, 
+                or 'auto' to size batches from the measured row width so that each batch 
+                stays within `batch_memory`. Wide files then get fewer rows per batch and 
+                narrow files more. Defaults to 10000.
             strict (bool): If True, performs schema validation (checks column existence) 
                             before execution. Defaults to False for maximum performance.
+            preserve_order (bool): If True, output and quarantine rows appear in the same 
+                relative order as in the source file. This holds for every worker count 
+                and batch size. If False, rows are written as soon as a worker finishes 
+                them, which overlaps writing with processing for more throughput. Defaults to True.
+            read_mode (ReadMode): How sources are read. 'mmap' memory-maps the file, 
+                avoiding a read syscall per buffer on very large inputs; 'buffered' uses 
+                plain reads. 'auto' maps regular files and falls back to buffered reads 
+                for pipes, FIFOs and anything that cannot be mapped. Defaults to 'auto'.
+            batch_memory (int): Memory budget per batch in bytes for `batch_size='auto'`. 
+                It is lowered when batches in flight would use over a quarter of the 
+                available memory. Defaults to 16 MiB.
+            strict_steps (bool): If True, a step with an unknown action or an unexpected 
+                parameter (e.g. a misspelled 'colum') fails the pipeline before any row is 
+                read, naming the step index. If False, unknown actions are skipped and 
+                unknown parameters ignored with a warning in the result. Defaults to True.
 
         Raises:
-            ConfigurationError: If workers is negative or batch_size is <= 0.
+            ConfigurationError: If workers is negative, batch_size is neither 'auto' nor > 0, 
+                batch_memory is <= 0 or read_mode is unknown.
         """
 
         if self._initialized:
@@ -99,25 +304,106 @@ class Engine:
             if not self._initialized:
                 if workers < 0:
                     raise ConfigurationError("Number of workers cannot be negative.")
-                if batch_size <= 0:
-                    raise ConfigurationError("Batch size must be greater than 0.")
-                self._config = {"workers": workers, "batch_size": batch_size, "strict": strict}
+                _check_batch_size(batch_size)
+                if batch_memory <= 0:
+                    raise ConfigurationError("Batch memory must be greater than 0.")
+                if read_mode not in get_args(ReadMode):
+                    raise ConfigurationError(f"Unknown read_mode {read_mode!r}. Expected one of {get_args(ReadMode)}.")
+                self._config = {
+                    "workers": workers, 
+                    "batch_size": batch_size, 
+                    "strict": strict, 
+                    "preserve_order": preserve_order,
+                    "read_mode": read_mode,
+                    "batch_memory": batch_memory,
+                    "strict_steps": strict_steps
+                }
                 self._strict = strict
                 self._initialized = True
 
-    def ingest(self, source: str) -> Pipeline:
+    def ingest(
+        self, 
+        source: Union[str, Any], 
+        delimiter: Optional[str] = None, 
+        batch_size: Union[int, Literal["auto"], None] = None,
+        read_mode: Optional[ReadMode] = None,
+        ragged: Optional[RaggedPolicy] = None,
+        header_match: Optional[HeaderMatch] = None,
+        rename_duplicates: bool = False
+    ) -> Pipeline:
         """
-        Creates a new data processing pipeline for a specific source file.
+        Creates a new data processing pipeline for a specific source file, or for an 
+        in-memory frame.
 
         Args:
-            source (str): Path to the input file (CSV, parquet, etc.).
+            source (Union[str, Any]): Path to the input file (CSV, parquet, etc.), or a 
+                frame exporting the Arrow C stream interface (a polars DataFrame, a pyarrow 
+                Table) or a polars LazyFrame, which is collected when the pipeline runs. 
+                Frame columns are read without a copy, as their text form (nulls are 
+                empty cells); such pipelines run with `run()`, `to_arrow()`, 
+                `to_pandas()` or `to_polars()`.
+            delimiter (str, optional): Single-character field delimiter of the source, 
+                or 'auto' to sniff it. Defaults to ','.
+            batch_size (Union[int, 'auto'], optional): Rows per batch for this pipeline only, 
+                or 'auto' for memory-based sizing. Defaults to the engine's batch_size.
+            read_mode (ReadMode, optional): 'auto', 'mmap' or 'buffered' for this source only, 
+                e.g. 'buffered' for a named pipe. Defaults to the engine's read_mode.
+            ragged (RaggedPolicy, optional): What to do with rows whose field count differs 
+                from the header:
+                - 'pad': Pad short rows with empty fields; long rows are quarantined.
+                - 'truncate': Pad short rows and drop the extra fields of long rows.
+                - 'quarantine': Reject the row with a `Ragged:` reason, handled like a 
+                    malformed record by `on_error()` (default).
+                - 'error': Abort the run.
+            header_match (HeaderMatch, optional): How step column names find source headers:
+                - 'exact': Byte-exact names (default).
+                - 'trim': Ignore surrounding whitespace, so 'Email' finds ' Email '.
+                - 'ignore_case': Ignore surrounding whitespace and case, so 'email' finds ' Email '.
+                An exact match always wins; a name matching several headers is an error.
+            rename_duplicates (bool): If True, repeated header names are made unique before 
+                any step runs ('amount', 'amount' -> 'amount', 'amount_2'), in the output 
+                too. Otherwise target them as 'amount[0]', 'amount[1]' (0-based); a bare 
+                'amount' is an error while it is ambiguous. Defaults to False.
 
         Returns:
             Pipeline: A new pipeline builder instance.
         """
+        data = None
+        if not isinstance(source, str):
+            if not (hasattr(source, "__arrow_c_stream__") or hasattr(source, "collect") or hasattr(source, "to_arrow")):
+                raise ConfigurationError(
+                    f"Source must be a file path or an Arrow-compatible frame, got {type(source).__name__}."
+                )
+            if delimiter is not None or read_mode is not None:
+                raise ConfigurationError("delimiter and read_mode only apply to file sources.")
+            data, source = source, f"<{type(source).__name__}>"
+
+        options = {}
+        if delimiter is not None:
+            if not isinstance(delimiter, str) or (len(delimiter) != 1 and delimiter != "auto"):
+                raise ConfigurationError(f"Delimiter must be a single character or 'auto', got {delimiter!r}.")
+            options["delimiter"] = delimiter
+        if batch_size is not None:
+            _check_batch_size(batch_size)
+            options["batch_size"] = batch_size
+        if read_mode is not None:
+            if read_mode not in get_args(ReadMode):
+                raise ConfigurationError(f"Unknown read_mode {read_mode!r}. Expected one of {get_args(ReadMode)}.")
+            options["read_mode"] = read_mode
+        if ragged is not None:
+            if ragged not in get_args(RaggedPolicy):
+                raise ConfigurationError(f"Unknown ragged policy {ragged!r}. Expected one of {get_args(RaggedPolicy)}.")
+            options["ragged"] = ragged
+        if header_match is not None:
+            if header_match not in get_args(HeaderMatch):
+                raise ConfigurationError(f"Unknown header_match {header_match!r}. Expected one of {get_args(HeaderMatch)}.")
+            options["header_match"] = header_match
+        if rename_duplicates:
+            options["rename_duplicates"] = True
+
         self._ingest_counter += 1
         base_alias = f"PIPE-{self._ingest_counter}"
-        return Pipeline(source, self._config, alias=base_alias, token=ACCESS_TOKEN)
+        return Pipeline(source, self._config, alias=base_alias, token=ACCESS_TOKEN, options=options, data=data)
 
     def validate(self, pipelines: Union[Pipeline, List[Pipeline]]) -> bool:
         """
@@ -152,18 +438,35 @@ class Engine:
         
         return all_passed
 
-    def exec(self, pipelines: Union[Pipeline, List[Pipeline]]) -> Union[EngineResult, List[EngineResult]]:
+    def exec(
+        self, 
+        pipelines: Union[Pipeline, List[Pipeline]], 
+        progress: Optional[Callable[[dict], None]] = None,
+        cancel: Optional[Any] = None
+    ) -> Union[EngineResult, List[EngineResult]]:
         """
         Executes multiple pipelines in parallel using the Rust backend.
 
         Args:
             pipelines (List[Pipeline]): A list of configured Pipeline objects.
+            progress (Callable[[dict], None], optional): Called after every batch of every 
+                pipeline with a dict of 'source', 'processed_rows', 'saved_rows', 
+                'quarantined_rows', 'bytes_read', 'total_bytes', 'elapsed_ms' and 
+                'eta_ms' (None until an estimate is available). Use 'source' to tell 
+                concurrent pipelines apart. Exceptions raised inside it are printed and ignored.
+            cancel (phaeton.CancelToken, optional): Token whose `.cancel()` (e.g. from 
+                another thread) stops every pipeline after the batch in flight. Ctrl+C 
+                has the same effect. Outputs are flushed and the results are partial 
+                with `cancelled=True`.
 
         Returns:
-            Union[EngineResult, List[EngineResult]]: Result object(s) containing statistics.
+            Union[EngineResult, List[EngineResult]]: Result object(s) containing statistics, 
+                in the order of `pipelines`. With a list, a pipeline that fails (missing file, 
+                `on_error('fail')`, ...) does not abort the others: its result has `ok=False` 
+                and the message in `error`.
 
         Raises:
-            EngineError: If the Rust backend fails or is missing.
+            EngineError: If the Rust backend is missing, or if a single pipeline (not a list) fails.
             ConfigurationError: If output targets are missing.
             SchemaError: (If strict=True) If column validation fails.
         """
@@ -171,7 +474,132 @@ class Engine:
         if isinstance(pipelines, Pipeline):
             pipelines = [pipelines]
             single_pipe = True
-        
+
+        payloads = self._payloads(pipelines)
+        if not payloads:
+            return None if single_pipe else []
+
+        try:
+            from . import _phaeton
+            
+            raw_results = _phaeton.execute_batch(payloads, self._config, progress, cancel, self._quarantine_dispatch(pipelines))
+            return self._results(pipelines, raw_results, single_pipe)
+
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+    async def exec_async(
+        self, 
+        pipelines: Union[Pipeline, List[Pipeline]], 
+        progress: Optional[Callable[[dict], None]] = None,
+        cancel: Optional[Any] = None
+    ) -> Union[EngineResult, List[EngineResult]]:
+        """
+        Awaitable `exec()` for asyncio code such as FastAPI handlers. The pipelines run 
+        on a background thread, so the event loop keeps serving requests meanwhile.
+
+        Cancelling the awaiting task (or a timeout around it) stops every pipeline after 
+        the batch in flight. `progress` is called from the worker threads, not the 
+        event loop; use `loop.call_soon_threadsafe` to touch loop-bound objects from it.
+
+        Args:
+            pipelines: Single Pipeline or List of Pipelines.
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `exec()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run from elsewhere.
+
+        Returns:
+            Union[EngineResult, List[EngineResult]]: Same as `exec()`.
+
+        Example:
+            >>> @app.post("/clean")
+            ... async def clean():
+            ...     result = await eng.exec_async(eng.ingest("raw.csv").prune().dump("clean.csv"))
+            ...     return result.to_dict()
+        """
+        single_pipe = False
+        if isinstance(pipelines, Pipeline):
+            pipelines = [pipelines]
+            single_pipe = True
+
+        payloads = self._payloads(pipelines)
+        if not payloads:
+            return None if single_pipe else []
+
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        raw_results = await _phaeton.execute_batch_future(payloads, self._config, progress, cancel, self._quarantine_dispatch(pipelines))
+        return self._results(pipelines, raw_results, single_pipe)
+
+    def _results(self, pipelines: List[Pipeline], raw_results: List[Any], single_pipe: bool) -> Union[EngineResult, List[EngineResult]]:
+        results = [EngineResult(r) for r in raw_results]
+
+        if single_pipe:
+            if results and not results[0].ok:
+                raise EngineError(f"[{pipelines[0]._alias}] {results[0].error}")
+            return results[0] if results else None
+
+        return results
+
+    def start(self, pipelines: Union[Pipeline, List[Pipeline]], cancel: Optional[Any] = None) -> "Job":
+        """
+        Starts pipelines in the background and returns immediately.
+
+        Same execution as `exec()`, but the caller's thread is free while it runs: 
+        poll the returned `Job` from a web handler or scheduler instead of blocking.
+
+        Args:
+            pipelines: Single Pipeline or List of Pipelines.
+            cancel (phaeton.CancelToken, optional): Token to stop every pipeline after 
+                the batch in flight; `Job.cancel()` does the same.
+
+        Returns:
+            Job: Handle exposing `status`, `progress()`, `poll()`, `join()` and `cancel()`.
+                Keep a reference to it: dropping the handle cancels the job.
+
+        Raises:
+            EngineError: If the Rust backend is missing.
+            ConfigurationError: If output targets are missing.
+            SchemaError: (If strict=True) If column validation fails.
+        """
+        if isinstance(pipelines, Pipeline):
+            pipelines = [pipelines]
+
+        payloads = self._payloads(pipelines)
+
+        try:
+            from . import _phaeton
+            return Job(_phaeton.execute_batch_async(payloads, self._config, cancel, self._quarantine_dispatch(pipelines)))
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+    def _quarantine_dispatch(self, pipelines: List[Pipeline]) -> Optional[Callable[[List[dict]], None]]:
+        """Routes quarantined rows to the `on_quarantine()` callback of their pipeline, by source."""
+        callbacks: Dict[str, Callable] = {}
+        for p in pipelines:
+            if p._on_quarantine is None:
+                continue
+            if callbacks.get(p._source, p._on_quarantine) is not p._on_quarantine:
+                raise ConfigurationError(
+                    f"Pipelines reading '{p._source}' register different on_quarantine() callbacks; "
+                    "rows cannot be told apart. Run them separately or share one callback."
+                )
+            callbacks[p._source] = p._on_quarantine
+
+        if not callbacks:
+            return None
+
+        def dispatch(rows: List[dict]) -> None:
+            callback = callbacks.get(rows[0]["source"])
+            if callback is not None:
+                callback(rows)
+
+        return dispatch
+
+    def _payloads(self, pipelines: List[Pipeline]) -> List[Dict]:
+        """Validates pipelines for execution and builds the payloads sent to the Rust core."""
         if self._strict:
             for p in pipelines:
                 p._validate()
@@ -179,6 +607,10 @@ class Engine:
         payloads = []
         
         for p in pipelines:
+            if p._data is not None:
+                raise ConfigurationError(
+                    f"Pipeline {p._alias} reads an in-memory frame; run it with .run() or .to_polars() instead."
+                )
             if not p._output_target and not p._quarantine_path and not p._has_peeked:
                 raise ConfigurationError(
                     f"Pipeline for '{p._source}' (Alias: {p._alias}) has no output target. "
@@ -194,22 +626,9 @@ class Engine:
                 "quarantine": p._quarantine_path,
                 "output": p._output_target,
                 "format": p._output_format,
-                "config": self._config
+                "on_error": p._on_error,
+                "config": self._config,
+                **p._options
             })
-            
-        if not payloads:
-            return None if single_pipe else []
 
-        try:
-            from . import _phaeton
-            
-            raw_results = _phaeton.execute_batch(payloads, self._config)
-            results = [EngineResult(r) for r in raw_results]
-            
-            if single_pipe:
-                return results[0] if results else None
-            
-            return results
-
-        except ImportError:
-            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
\ No newline at end of file
+        return payloads
\ No newline at end of file
@@ -1,4 +1,4 @@
-from typing import List, Dict, Optional, Literal, Union, Any, get_args
+from typing import List, Dict, Optional, Literal, Union, Any, Callable, get_args
 from phaeton.exceptions import ValueError as PhaetonValueError, EngineError, ConfigurationError, SchemaError
 from tabulate import tabulate
 from ._internal import ACCESS_TOKEN
@@ -6,13 +6,24 @@ import copy
 
 # --- Type Definitions ---
 HeaderCase = Literal["snake", "camel", "pascal", "kebab", "constant"]
-ScrubMode = Literal["email", "html", "trim", "lower", "upper", "currency", "numeric_only"]
-MatchMode = Literal["exact", "contains", "startswith", "endswith", "regex"]
+ScrubMode = Literal["email", "html", "trim", "lower", "upper", "currency", "numeric_only", "clean_invisible", "ascii_only", "plain_punctuation", "percent", "ascii_digits", "transliterate"]
+MatchMode = Literal["exact", "contains", "startswith", "endswith", "regex", "in", "fuzzy"]
 FillMethod = Literal["fixed", "ffill"]
 DateFmt = Literal["iso", "us", "eu", "auto"]
-CastType = Literal["int", "float", "str", "bool"]
+CastType = Literal["int", "float", "str", "bool", "date", "datetime", "decimal"]
 ExportFormat = Literal["csv", "parquet", "arrow"]
 OnError = Literal["quarantine", "null", "ignore"]
+ErrorStrategy = Literal["quarantine", "skip", "fail"]
+ReadMode = Literal["auto", "mmap", "buffered"]
+RaggedPolicy = Literal["pad", "truncate", "quarantine", "error"]
+HeaderMatch = Literal["exact", "trim", "ignore_case"]
+# A header name, or a 0-based column position
+Column = Union[str, int]
+ColumnKind = Literal["constant", "filename", "row_number", "timestamp"]
+CountryFormat = Literal["alpha2", "alpha3"]
+PhoneticAlgorithm = Literal["soundex", "metaphone"]
+SimilarityMetric = Literal["jaro_winkler", "jaro", "levenshtein", "token_sort", "ngram"]
+ExpectSeverity = Literal["warn", "error"]
 
 class Pipeline:
     """
@@ -21,11 +32,15 @@ class Pipeline:
     This class employs a Lazy Builder pattern: methods record instructions ("steps") 
     but do not execute them immediately. Execution is triggered only via `.run()`, 
     `Engine.exec()`, or `.peek()`.
+
+    Wherever a step takes a column, a 0-based position (e.g. `2`) can be given instead 
+    of the header name. Positions are checked against the header width before any row is read.
     
     Attributes:
         alias (str): A unique identifier for tracking this pipeline in logs/previews.
     """
-    def __init__(self, source: str, config: dict, alias: str, token: Any = None):
+    def __init__(self, source: str, config: dict, alias: str, token: Any = None, options: Optional[Dict] = None,
+                 data: Any = None):
 
         if token is not ACCESS_TOKEN:
             raise PermissionError(
@@ -34,11 +49,17 @@ class Pipeline:
             )
         
         self._source = source
+        # In-memory frame (Arrow C stream or polars LazyFrame) read instead of `source`
+        self._data = data
         self._config = config
         self._steps: List[Dict] = []
         self._quarantine_path: Optional[str] = None
         self._output_target: Optional[str] = None
         self._output_format: str = "csv"
+        self._on_error: ErrorStrategy = "quarantine"
+        self._on_quarantine: Optional[Callable[[List[dict]], None]] = None
+        # Per-pipeline overrides of the engine config (batch_size, delimiter, output_delimiter)
+        self._options: Dict[str, Any] = dict(options or {})
 
         self._strict = config.get("strict", False)
         self._alias = alias 
@@ -59,8 +80,8 @@ class Pipeline:
 
         VALIDATION_RULES = {
             "scrub":   {"mode": get_args(ScrubMode)},
-            "keep":    {"mode": get_args(MatchMode)},
-            "discard": {"mode": get_args(MatchMode)},
+            "keep":    {"mode": get_args(MatchMode), "metric": get_args(SimilarityMetric)},
+            "discard": {"mode": get_args(MatchMode), "metric": get_args(SimilarityMetric)},
             "fill":    {"method": get_args(FillMethod)},
             
             "cast":    {
@@ -69,10 +90,21 @@ class Pipeline:
             },
             
             "headers": {"style": get_args(HeaderCase)},
+
+            "add_column": {"kind": get_args(ColumnKind)},
+
+            "align": {"metric": get_args(SimilarityMetric), "phonetic": get_args(PhoneticAlgorithm)},
+
+            "country": {"format": get_args(CountryFormat)},
             
             "dump": {"format": get_args(ExportFormat)}
         }
 
+        self._check_params(VALIDATION_RULES)
+        if self._data is not None:
+            # Frame columns are only known once the frame is read
+            return
+
         try:
             meta = probe(self._source)
         except Exception as e:
@@ -81,17 +113,29 @@ class Pipeline:
         if not meta or "headers" not in meta:
             raise SchemaError(f"[{self._alias}] Invalid Schema: No headers found in '{self._source}'.")
 
-        raw_headers = meta["headers"]
-        if isinstance(raw_headers, str):
-            actual_headers = [h.strip() for h in raw_headers.split(",")]
-        else:
-            actual_headers = raw_headers
-
+        # Columns are resolved by the engine itself, so header_match, `name[k]` and
+        # rename_duplicates are honoured exactly as they will be at run time
+        from . import _phaeton
+        diagnostics = _phaeton.validate_pipeline(
+            self._source, self._steps,
+            header_match=self._options.get("header_match"),
+            strict_steps=self._config.get("strict_steps", True),
+            rename_duplicates=self._options.get("rename_duplicates", False),
+            delimiter=self._options.get("delimiter"),
+        )
+        for d in diagnostics:
+            if d["level"] == "error":
+                raise SchemaError(
+                    f"[{self._alias}] Schema Mismatch! Step {d['step']} ({d['action']}): {d['message']}. "
+                    f"Available: {list(meta['headers'])}"
+                )
+
+    def _check_params(self, rules: Dict[str, Dict[str, tuple]]) -> None:
         for step in self._steps:
             action = step.get("action")
-            
-            if action in VALIDATION_RULES:
-                for param_name, allowed_values in VALIDATION_RULES[action].items():
+
+            if action in rules:
+                for param_name, allowed_values in rules[action].items():
                     user_val = step.get(param_name)
                     
                     if user_val is None:
@@ -103,29 +147,32 @@ class Pipeline:
                             f"Allowed: {allowed_values}"
                         )
 
-            col_target = step.get("col")
-            
-            if not col_target or col_target == "*":
-                continue
-            
-            targets = col_target if isinstance(col_target, list) else [col_target]
-            
-            for col in targets:
-                if action in {"combine", "split"}: 
-                    continue
-                
-                if col not in actual_headers:
-                    raise SchemaError(
-                        f"[{self._alias}] Schema Mismatch! Column '{col}' not found in source. "
-                        f"Available: {actual_headers}"
-                    )
-                
-    def _prepare_match_pattern(self, match: Union[str, int, float, List, tuple], mode: str) -> tuple:
-        if mode == 'regex' and isinstance(match, (list, tuple)):
-            raise PhaetonValueError("Regex mode does not support list input directly.")
+    def _require_file(self, method: str) -> None:
+        """Rejects methods that read the source file directly when the pipeline reads a frame."""
+        if self._data is not None:
+            raise ConfigurationError(
+                f"[{self._alias}] {method}() needs a source file; this pipeline reads an in-memory frame. "
+                "Use run(), to_arrow(), to_pandas() or to_polars()."
+            )
+
+    def _frame(self) -> Any:
+        """The frame handed to the Rust core: lazy polars frames are collected at run time."""
+        data = self._data
+        if data is None:
+            return None
+        if not hasattr(data, "__arrow_c_stream__") and hasattr(data, "collect"):
+            data = data.collect()
+        if not hasattr(data, "__arrow_c_stream__") and hasattr(data, "to_arrow"):
+            # Frames predating the Arrow PyCapsule interface export through pyarrow
+            data = data.to_arrow()
+        return data
 
-        if isinstance(match, (list, tuple)):
+    def _prepare_match_pattern(self, match: Union[str, int, float, List, tuple], mode: str) -> tuple:
+        if isinstance(match, (list, tuple, set, frozenset)):
             return [str(m) for m in match], mode
+
+        if mode == 'in':
+            return [str(match)], mode
             
         return str(match), mode
     
@@ -133,53 +180,129 @@ class Pipeline:
     # 1. UTILITY & PREVIEW
     # ==========================================
 
+    def preview(self, n: int = 5, col: Union[str, List[str], None] = None) -> Dict:
+        """
+        Runs the pipeline over the first `n` source rows without writing anything, 
+        and reports what would happen to each row.
+
+        Args:
+            n (int): Number of source rows to run through the steps. Defaults to 5.
+            col (Union[str, List[str], None]): Column(s) whose values are returned. 
+                If None, returns all columns.
+
+        Returns:
+            Dict: 'headers' (the returned columns, as named in the output) and 'rows', 
+                one entry per source row with 'row' (1-based), 'status' ('kept', 
+                'quarantined', 'skipped' or 'failed', following `on_error()`), 'step' 
+                (index of the rejecting step, or None), 'reason', 'values' (after the 
+                steps that ran) and 'notes' (guesses the steps made, such as reading 
+                '1,234' as one thousand two hundred thirty-four). A 'failed' row would 
+                abort the run, so it ends the preview.
+        """
+        self._require_file("preview")
+        if self._strict:
+            self._validate()
+
+        if not isinstance(n, int):
+            raise PhaetonValueError("Parameter 'n' must be an integer.")
+        n = 5 if n <= 0 else n
+        target_cols = [col] if isinstance(col, str) else col
+
+        try:
+            from . import _phaeton
+        except ImportError as e:
+            raise EngineError("Phaeton Rust Core Missing!") from e
+
+        return _phaeton.preview_pipeline(
+            self._source, self._steps, n, target_cols, self._options.get("delimiter"),
+            self._options.get("ragged"), self._options.get("header_match"),
+            self._options.get("rename_duplicates", False), self._on_error
+        )
+
     def peek(self, n: int = 5, col: Union[str, List[str], None] = None) -> "Pipeline":
         """
         Executes a DRY RUN to preview the cleaning results.
         
-        This runs the actual Rust pipeline on a stream of data until `n` valid rows 
-        are collected, then prints them in a formatted table. 
+        Runs the actual Rust pipeline over the first `n` source rows and prints them in 
+        a formatted table with each row's status (kept, quarantined, skipped or failed) 
+        and, for rejected rows, the reason. Use `preview()` to get the same data back.
         
         **Note:** This does not write to disk. It is safe to chain `.peek()` before `.dump()`.
 
         Args:
-            n (int): Number of kept/valid rows to display. Defaults to 5.
+            n (int): Number of source rows to preview. Defaults to 5.
             col (Union[str, List[str], None]): Specific column(s) to inspect. 
                 If None, displays all columns.
         """
-        if self._strict:
-            self._validate()
+        result = self.preview(n, col)
+        rows = result["rows"]
 
-        try:
-            from . import _phaeton
+        if not rows:
+            print(f"WARN: Pipeline Result is empty for {self._source}")
+        else:
+            display_cols = col if col else "All Columns"
+            kept = sum(1 for r in rows if r["status"] == "kept")
+            print(f"\nPREVIEW: {self._source} [{self._alias}]") 
+            print(f"(First {len(rows)} rows, {kept} kept | Cols: {display_cols})")
+            table = [[r["row"], r["status"], *r["values"], r["reason"] or ""] for r in rows]
+            headers = ["row", "status", *result["headers"], "reason"]
+            print(tabulate(table, headers=headers, tablefmt="rounded_outline", disable_numparse=True))
+            for r in rows:
+                for note in r.get("notes", []):
+                    print(f"NOTE row {r['row']}: {note}")
 
-            if not isinstance(n, int):
-                raise PhaetonValueError("Parameter 'n' must be an integer.")
+        self._has_peeked = True 
+        return self
 
-            n = 5 if n <= 0 else n
-            target_cols = [col] if isinstance(col, str) else col
-            headers, rows = _phaeton.preview_pipeline(self._source, self._steps, n, target_cols)
-            
-            if not rows:
-                print(f"WARN: Pipeline Result is empty for {self._source}")
-            else:
-                display_cols = col if col else "All Columns"
-                print(f"\nPREVIEW: {self._source} [{self._alias}]") 
-                print(f"(Top {n} rows | Cols: {display_cols})")
-                print(tabulate(rows, headers=headers, tablefmt="rounded_outline", disable_numparse=True))
+    def explain(self, show: bool = True) -> List[Dict]:
+        """
+        Returns the compiled step plan without reading any data rows.
+
+        Each step is resolved against the source headers, so the plan shows which 
+        column positions are touched, which patterns were compiled, and whether the 
+        step filters rows, rewrites values, or appends columns.
 
+        Args:
+            show (bool): Print the plan as a table. Defaults to True.
+
+        Returns:
+            List[Dict]: One entry per step with 'step', 'action', 'effect' ('filter', 
+                'transform', 'append', 'header', 'output' or 'ignored'), 'columns' (`{'index', 'name'}`), 
+                'adds_columns', 'quarantines' (whether rows can be sent to quarantine) 
+                and 'detail'.
+        """
+        self._require_file("explain")
+        try:
+            from . import _phaeton
         except ImportError as e:
             raise EngineError("Phaeton Rust Core Missing!") from e
 
-        self._has_peeked = True 
-        return self
+        plan = _phaeton.explain_pipeline(self._source, self._steps, self._options.get("delimiter"),
+                                         self._options.get("header_match"),
+                                         self._options.get("rename_duplicates", False))
+
+        if show:
+            rows = [
+                [
+                    entry["step"], entry["action"], entry["effect"],
+                    ", ".join(f"{c['name']}[{c['index']}]" for c in entry["columns"]),
+                    "yes" if entry["quarantines"] else "",
+                    entry["detail"],
+                ]
+                for entry in plan
+            ]
+            print(f"\nPLAN: {self._source} [{self._alias}]")
+            print(tabulate(rows, headers=["#", "Action", "Effect", "Columns", "Quarantine", "Detail"],
+                           tablefmt="rounded_outline", disable_numparse=True))
+        return plan
 
     # ==========================================
     # 2. ELIMINATION (Row Filtering)
     # ==========================================
 
-    def keep(self, col: str,
-            match: Union[str, int, float, List, tuple], mode: MatchMode = "exact") -> "Pipeline":
+    def keep(self, col: Column,
+            match: Union[str, int, float, List, tuple], mode: MatchMode = "exact",
+            threshold: float = 0.85, metric: SimilarityMetric = "jaro_winkler") -> "Pipeline":
         """
         Retains only the rows where the specified column matches the pattern.
         
@@ -193,15 +316,27 @@ class Pipeline:
                 - 'exact': Strict equality.
                 - 'contains': Substring check.
                 - 'startswith' / 'endswith': Prefix/Suffix check.
-                - 'regex': Rust-flavored Regex pattern.
+                A list of patterns in these modes matches if any pattern does; the list is
+                compiled into one Aho-Corasick automaton, so long lists cost a single scan.
+                - 'regex': Rust-flavored Regex pattern. A list of patterns is compiled into
+                    one RegexSet and matches if any pattern does (single pass per cell).
+                - 'in': Set membership; `match` is a list of allowed values 
+                    (compiled into a hash set, fast for large lists).
+                - 'fuzzy': Matches when similarity to any pattern reaches `threshold`.
+            threshold (float): Similarity threshold for 'fuzzy' mode (0.0 to 1.0). Defaults to 0.85.
+            metric (SimilarityMetric): Similarity function for 'fuzzy' mode. Defaults to 'jaro_winkler'.
         """
         match, mode = self._prepare_match_pattern(match, mode)
 
-        self._steps.append({"action": "keep", "col": col, "match": match, "mode": mode})
+        step = {"action": "keep", "col": col, "match": match, "mode": mode}
+        if mode == "fuzzy":
+            step.update({"threshold": threshold, "metric": metric})
+        self._steps.append(step)
         return self
 
-    def discard(self, col: str,
-                match: Union[str, int, float, List, tuple], mode: MatchMode = "exact") -> "Pipeline":
+    def discard(self, col: Column,
+                match: Union[str, int, float, List, tuple], mode: MatchMode = "exact",
+                threshold: float = 0.85, metric: SimilarityMetric = "jaro_winkler") -> "Pipeline":
         """
         Discards rows where the specified column matches the pattern. 
         The inverse operation of `keep`.
@@ -211,15 +346,25 @@ class Pipeline:
 
         Args:
             col (str): The column to check.
-            match (str): The value or pattern to discard.
-            mode (MatchMode, optional): Matching strategy. Defaults to "exact".
+            match (str): The value or pattern to discard. For mode 'in', a list of forbidden values.
+                Lists also work with 'exact', 'contains', 'startswith' and 'endswith' (one
+                Aho-Corasick scan for all patterns); the quarantine reason names the matched one.
+                With 'regex', a list is compiled into one RegexSet and the reason carries the
+                index of the matching regex.
+            mode (MatchMode, optional): Matching strategy. Defaults to "exact". 
+                Use 'fuzzy' to discard near-duplicates of known templates (e.g., spam text).
+            threshold (float): Similarity threshold for 'fuzzy' mode (0.0 to 1.0). Defaults to 0.85.
+            metric (SimilarityMetric): Similarity function for 'fuzzy' mode. Defaults to 'jaro_winkler'.
         """
         match, mode = self._prepare_match_pattern(match, mode)
 
-        self._steps.append({"action": "discard", "col": col, "match": match, "mode": mode})
+        step = {"action": "discard", "col": col, "match": match, "mode": mode}
+        if mode == "fuzzy":
+            step.update({"threshold": threshold, "metric": metric})
+        self._steps.append(step)
         return self
 
-    def prune(self, col: Union[str, List[str], None] = None) -> "Pipeline":
+    def prune(self, col: Union[Column, List[Column], None] = None) -> "Pipeline":
         """
         Drops rows containing empty or NULL strings.
         
@@ -240,27 +385,76 @@ class Pipeline:
     # 3. TRANSFORMATION (Content Cleaning)
     # ==========================================
 
-    def scrub(self, col: str, mode: ScrubMode) -> "Pipeline":
+    def scrub(self, col: Column, mode: ScrubMode, transliterate: bool = False, collapse_whitespace: bool = False,
+              decimal: Optional[Literal[".", ","]] = None, chars: Optional[str] = None) -> "Pipeline":
         """
         Applies advanced string sanitization to a column.
 
         Modes:
             - 'currency': Uses **Skeleton Extraction** to handle global formats 
-                (US, EU, ID), converts string to a float-ready format.
+                (US, EU, ID), converts string to a float-ready format. Scientific 
+                notation is written out ('1.2E+05' -> '120000'). Negatives keep their 
+                sign, including '(1,234.50)' and '1234.50-' (-> '-1234.50').
             - 'numeric_only': Aggressively strips non-digits (e.g., for Phone/NIK).
-            - 'html': Efficiently strips HTML tags.
+            - 'html': Strips HTML tags, then decodes entities (`&amp;` -> '&', 
+                `&nbsp;`, `&#39;`, `&#x27;`, ...). Unknown entities are kept.
             - 'email': Masks email username for privacy (e.g., 'j***e@gmail.com').
-            - 'trim': Removes leading/trailing whitespace.
+            - 'trim': Removes leading/trailing whitespace, and the characters in 
+                `chars` if given.
             - 'lower' / 'upper': Case conversion.
+            - 'clean_invisible': Removes emoji, zero-width characters, BOM remnants 
+                and control characters (line breaks become spaces).
+            - 'ascii_only': Keeps only printable ASCII characters.
+            - 'plain_punctuation': Turns curly quotes into ' and ", en/em dashes 
+                into '-', '…' into '...' and no-break spaces into plain spaces, 
+                so Word-pasted text matches exact filters.
+            - 'percent': Turns percentages into fractions ('12.5%' -> '0.125'); 
+                values without '%' are left alone.
+            - 'ascii_digits': Turns digits of other scripts into ASCII ('１２３' and 
+                '١٢٣' -> '123'), with full-width signs and separators and the Arabic 
+                decimal separator, so the values pass numeric casts.
+            - 'transliterate': Best-effort ASCII spelling through a transliteration 
+                table: 'Москва' -> 'Moskva', 'Αθήνα' -> 'Athena', '北京' -> 'Bei Jing'. 
+                Characters without a spelling are removed.
 
         Args:
             col (str): The target column.
             mode (ScrubMode): The cleaning mode to apply.
+            transliterate (bool): For 'ascii_only', spells non-ASCII text in ASCII 
+                like the 'transliterate' mode (e.g., 'José' -> 'Jose', 'Москва' -> 'Moskva') 
+                instead of dropping it.
+            collapse_whitespace (bool): For 'html', turns runs of whitespace left 
+                by tags and `&nbsp;` into single spaces and trims the ends.
+            decimal (str, optional): For 'currency' and 'percent', the decimal 
+                separator ('.' or ','); the other one is dropped as a thousands 
+                separator. If None, it is guessed per value, and a single separator 
+                followed by three digits ('1,234') is read as thousands. `preview()` 
+                notes the values where that guess was made.
+            chars (str, optional): For 'trim', extra characters stripped from both 
+                ends along with whitespace, in any mix (e.g., `chars="'\"*;"` turns 
+                `'"ACME Corp"'` into `ACME Corp`).
         """
-        self._steps.append({"action": "scrub", "col": col, "mode": mode})
+        if chars is not None:
+            if mode != "trim":
+                raise PhaetonValueError("Parameter 'chars' is only valid for the 'trim' mode.")
+            if not isinstance(chars, str) or not chars:
+                raise PhaetonValueError("Parameter 'chars' must be a non-empty string.")
+        if decimal is not None:
+            if mode not in ("currency", "percent"):
+                raise PhaetonValueError("Parameter 'decimal' is only valid for the 'currency' and 'percent' modes.")
+            if decimal not in (".", ","):
+                raise PhaetonValueError(f"Invalid decimal separator '{decimal}'. Expected '.' or ','.")
+        step = {"action": "scrub", "col": col, "mode": mode, "transliterate": transliterate}
+        if collapse_whitespace:
+            step["collapse_whitespace"] = True
+        if decimal is not None:
+            step["decimal"] = decimal
+        if chars is not None:
+            step["chars"] = chars
+        self._steps.append(step)
         return self
 
-    def fill(self, col: str, value: Union[str, int, float, None] = None, method: FillMethod = 'fixed') -> "Pipeline":
+    def fill(self, col: Column, value: Union[str, int, float, None] = None, method: FillMethod = 'fixed') -> "Pipeline":
         """
         Imputes missing (empty/null/whitespace) values.
         
@@ -290,18 +484,76 @@ class Pipeline:
         self._steps.append({"action": "reformat", "col": col, "to": to_fmt, "from": from_fmt})
         return self
 
-    def fuzzyalign(self, col: str, ref: List[str], threshold: float = 0.85) -> "Pipeline":
+    def fuzzyalign(self, col: Column, ref: Union[List[str], str], threshold: float = 0.85,
+                   metric: SimilarityMetric = "jaro_winkler", audit: bool = False,
+                   ref_col: Optional[str] = None,
+                   phonetic: Optional[PhoneticAlgorithm] = None) -> "Pipeline":
         """
-        Corrects typos by aligning values to a reference list using Jaro-Winkler distance.
+        Corrects typos by aligning values to a reference list using string similarity.
         
         Useful for fixing categorical inconsistencies (e.g. "Jkrta" -> "Jakarta").
 
         Args:
             col (str): The column to align.
-            ref (List[str]): A list of valid canonical strings.
+            ref (Union[List[str], str]): A list of valid canonical strings, or a path to a 
+                reference file loaded by the engine (one value per line, or a CSV with `ref_col`).
             threshold (float): Similarity threshold (0.0 to 1.0). Defaults to 0.85.
+            metric (SimilarityMetric): Similarity function:
+                - 'jaro_winkler' (default) / 'jaro': Good for short typos.
+                - 'levenshtein': Normalized edit distance.
+                - 'token_sort': Ignores word order ("Smith, John" == "John Smith").
+                - 'ngram': Character bigram overlap (Sørensen-Dice).
+            audit (bool): If True, appends `<col>_original` (value before alignment) and 
+                `<col>_score` (best similarity score found) columns for review.
+            ref_col (Optional[str]): Column to read when `ref` points to a CSV file.
+            phonetic (Optional[PhoneticAlgorithm]): Pre-buckets references by sound 
+                ('soundex' or 'metaphone'). A value sharing a phonetic code with a reference 
+                entry aligns to the closest such entry if it still meets `threshold` 
+                (e.g., "Steven" -> "Stephen"); otherwise the normal similarity search applies.
+
+        Raises:
+            PhaetonValueError: If `threshold` is outside [0.0, 1.0] or `ref` is empty. A 
+                reference file with no values fails when the pipeline is prepared, and a 
+                very large reference list adds a warning to the result.
+        """
+        if ref_col is not None and not isinstance(ref, str):
+            raise PhaetonValueError("Parameter 'ref_col' requires 'ref' to be a file path.")
+        if isinstance(ref, str):
+            if not ref.strip():
+                raise PhaetonValueError("Parameter 'ref' must be a non-empty list or a file path.")
+        elif not ref or not all(isinstance(r, str) for r in ref):
+            raise PhaetonValueError("Parameter 'ref' must be a non-empty list of strings or a file path.")
+        if isinstance(threshold, bool) or not isinstance(threshold, (int, float)) or not 0.0 <= threshold <= 1.0:
+            raise PhaetonValueError(f"Parameter 'threshold' must be between 0.0 and 1.0, got {threshold!r}.")
+
+        self._steps.append({
+            "action": "align",
+            "col": col,
+            "ref": ref,
+            "threshold": threshold,
+            "metric": metric,
+            "audit": audit,
+            "ref_col": ref_col,
+            "phonetic": phonetic
+        })
+        return self
+
+    def country(self, col: Column, format: CountryFormat = "alpha2", threshold: float = 0.9) -> "Pipeline":
+        """
+        Standardizes free-form country names to ISO 3166-1 codes.
+
+        Resolves official names, codes, and common aliases (e.g., "U.S.A.", 
+        "Amerika Serikat", "United States" -> "US"), falling back to fuzzy 
+        matching for typos. Unrecognized values are quarantined.
+
+        Args:
+            col (str): The column containing country names.
+            format (CountryFormat): Output code style, 'alpha2' ("US") or 'alpha3' ("USA").
+            threshold (float): Minimum similarity for the fuzzy fallback (0.0 to 1.0). Defaults to 0.9.
         """
-        self._steps.append({"action": "align", "col": col, "ref": ref, "threshold": threshold})
+        if not 0.0 <= threshold <= 1.0:
+            raise PhaetonValueError("Parameter 'threshold' must be between 0.0 and 1.0.")
+        self._steps.append({"action": "country", "col": col, "format": format, "threshold": threshold})
         return self
 
     def decode(self, encoding: str = "utf-8-sig") -> "Pipeline":
@@ -342,27 +594,82 @@ class Pipeline:
         self._steps.append({"action": "headers", "style": style})
         return self
 
-    def cast(self, col: str, dtype: CastType, clean: bool = False, on_error: OnError = "quarantine") -> "Pipeline":
+    def cast(self, col: Column, dtype: CastType, clean: bool = False, on_error: OnError = "quarantine",
+             format: Union[str, List[str], None] = None,
+             precision: Optional[int] = None, scale: Optional[int] = None,
+             validate_only: bool = False,
+             true_values: Optional[List[str]] = None, false_values: Optional[List[str]] = None,
+             percent: Optional[Literal["fraction", "number"]] = None) -> "Pipeline":
         """
-        Enforces data type on a column (String, Integer, Float, Boolean).
+        Enforces data type on a column (String, Integer, Float, Boolean, Date, Datetime, Decimal).
 
         If `clean` is True, Phaeton attempts to strip non-numeric characters before casting 
         (e.g., "$ 5,000" -> 5000.0).
 
+        Valid values are written back in their normalized form (e.g., "$1,234.50" -> "1234.50", 
+        "Yes" -> "true", "31/01/2024" -> "2024-01-31") unless `validate_only` is True.
+        Numeric casts accept scientific notation and write it out in full 
+        (e.g., "1.2E+05" -> "120000").
+
         Args:
             col (str): The target column.
             type (CastType): The target data type.
             clean (bool): If True, applies intelligent scrubbing before casting 
                 (e.g., stripping currency symbols from "$ 5,000" before float cast).
             on_error (OnError, optional): Action if cast fails ('quarantine', 'null', 'ignore'). Defaults to "quarantine".
+            format (Union[str, List[str], None]): strftime pattern(s) for 'date'/'datetime' 
+                (e.g., "%d/%m/%Y"). The first matching pattern wins. If None, ISO 8601 is expected.
+            precision (Optional[int]): Total significant digits for 'decimal'. Defaults to 18.
+            scale (Optional[int]): Fractional digits for 'decimal'. Defaults to 2. 
+                Values with more fractional digits are rejected, never rounded.
+            validate_only (bool): If True, only checks the type and leaves the cell untouched.
+            true_values (Optional[List[str]]): Words read as true by 'bool' casts, compared 
+                trimmed and case-insensitively (e.g., ["ya", "aktif"]). Defaults to 
+                "true", "1", "yes", "y" and "t".
+            false_values (Optional[List[str]]): Words read as false by 'bool' casts 
+                (e.g., ["tidak", "nonaktif"]). Defaults to "false", "0", "no", "n" and "f". 
+                Each list replaces the defaults of its own side only.
+            percent (Optional[str]): How 'int', 'float' and 'decimal' casts read a 
+                trailing '%': 'fraction' ("12.5%" -> 0.125) or 'number' ("12.5%" -> 12.5). 
+                If None, percentages fail the cast.
         """
-        self._steps.append({
+        if format is not None and dtype not in ("date", "datetime"):
+            raise PhaetonValueError("Parameter 'format' is only valid for 'date' and 'datetime' casts.")
+        if (precision is not None or scale is not None) and dtype != "decimal":
+            raise PhaetonValueError("Parameters 'precision' and 'scale' are only valid for 'decimal' casts.")
+        if (true_values is not None or false_values is not None) and dtype != "bool":
+            raise PhaetonValueError("Parameters 'true_values' and 'false_values' are only valid for 'bool' casts.")
+        for name, words in (("true_values", true_values), ("false_values", false_values)):
+            if words is not None and (not isinstance(words, (list, tuple)) or not words
+                                      or not all(isinstance(w, str) for w in words)):
+                raise PhaetonValueError(f"Parameter '{name}' must be a non-empty list of strings.")
+        if percent is not None:
+            if dtype not in ("int", "float", "decimal"):
+                raise PhaetonValueError("Parameter 'percent' is only valid for 'int', 'float' and 'decimal' casts.")
+            if percent not in ("fraction", "number"):
+                raise PhaetonValueError(f"Invalid percent handling '{percent}'. Expected: fraction, number.")
+
+        step = {
             "action": "cast", 
             "col": col, 
             "type": dtype, 
             "clean": clean,
-            "on_error": on_error
-        })
+            "on_error": on_error,
+            "validate_only": validate_only
+        }
+        if format is not None:
+            step["format"] = format
+        if precision is not None:
+            step["precision"] = precision
+        if scale is not None:
+            step["scale"] = scale
+        if true_values is not None:
+            step["true_values"] = list(true_values)
+        if false_values is not None:
+            step["false_values"] = list(false_values)
+        if percent is not None:
+            step["percent"] = percent
+        self._steps.append(step)
         return self
 
     def split(self, col: str, delimiter: str, into: List[str]) -> "Pipeline":
@@ -391,11 +698,105 @@ class Pipeline:
         self._steps.append({"action": "combine", "cols": cols, "glue": glue, "into": into})
         return self
 
+    def add_column(self, name: str, value: Union[str, int, float, None] = None, kind: ColumnKind = "constant") -> "Pipeline":
+        """
+        Appends a new column to every row, typically for lineage/audit fields.
+
+        The new column can be referenced by name in any step added after it.
+
+        Args:
+            name (str): Name of the new column.
+            value (Any): The constant value to write (required if kind='constant').
+            kind (ColumnKind):
+                - 'constant': Writes the provided `value`.
+                - 'filename': Writes the source file name.
+                - 'row_number': Writes the 1-based row number from the source.
+                - 'timestamp': Writes the processing start time (RFC 3339).
+        """
+        if kind == "constant" and value is None:
+            raise PhaetonValueError("Must provide a 'value' when kind='constant'.")
+        self._steps.append({"action": "add_column", "name": name, "value": value, "kind": kind})
+        return self
+
+    def convert_currency(self, col: Column, currency_col: Column, to: str,
+                         rates: Union[Dict[str, float], str], decimals: int = 2, clean: bool = False) -> "Pipeline":
+        """
+        Converts monetary amounts into a single target currency.
+
+        Each row's source currency is read from `currency_col`, which is rewritten 
+        to `to` after conversion. Rows with an unknown currency or a non-numeric 
+        amount are quarantined.
+
+        Args:
+            col (str): The column holding the amount.
+            currency_col (str): The column holding the source currency code (e.g., 'EUR').
+            to (str): The target currency code.
+            rates (Union[Dict[str, float], str]): Rate table against a common base currency 
+                (e.g., {'USD': 1.0, 'EUR': 1.08}), or a path to a CSV file with `currency,rate` columns.
+                Every rate must be a positive number.
+            decimals (int): Number of decimal places in the output. Defaults to 2.
+            clean (bool): If True, applies currency scrubbing before parsing 
+                (e.g., "$ 5,000" -> 5000).
+        """
+        if decimals < 0:
+            raise PhaetonValueError("Parameter 'decimals' cannot be negative.")
+        self._steps.append({
+            "action": "convert_currency",
+            "col": col,
+            "currency_col": currency_col,
+            "to": to,
+            "rates": rates,
+            "decimals": decimals,
+            "clean": clean
+        })
+        return self
+
+    def json_extract(self, col: Column, path: str, into: str) -> "Pipeline":
+        """
+        Extracts a value from a column containing JSON into a new column.
+
+        Rows whose cell is not valid JSON are quarantined. Missing paths produce 
+        an empty value; nested objects/arrays are written as compact JSON.
+
+        Args:
+            col (str): The column holding the JSON document.
+            path (str): JSONPath-style location (e.g., '$.payload.user.id', '$.items[0].sku').
+            into (str): Name of the new column.
+        """
+        self._steps.append({"action": "json_extract", "col": col, "path": path, "into": into})
+        return self
+
+    def format(self, template: str, col: Optional[Column] = None, into: Optional[str] = None) -> "Pipeline":
+        """
+        Builds values from a template that references other columns, e.g. composite keys.
+
+        Fields are written `{column}` or `{column:spec}`, where spec is a subset of
+        Python's format mini-language: `[[fill]align][0][width][.precision][s|d|f]`
+        (e.g., '{order_id}-{line_no:03}'). Use `{{` and `}}` for literal braces.
+        Rows whose value does not fit a `d` or `f` field are quarantined; empty
+        cells render as empty text.
+
+        Args:
+            template (str): The template to fill for each row.
+            col (str, optional): Existing column to overwrite with the result.
+            into (str, optional): Name of a new column for the result.
+                Give exactly one of `col` and `into`.
+        """
+        if (col is None) == (into is None):
+            raise PhaetonValueError("format() needs exactly one of 'col' and 'into'")
+        step = {"action": "format", "template": template}
+        if col is not None:
+            step["col"] = col
+        else:
+            step["into"] = into
+        self._steps.append(step)
+        return self
+
     # ==========================================
     # 5. MAPPING & SECURITY
     # ==========================================
 
-    def map(self, col: str, mapping: Dict[str, str], default: Optional[str] = None) -> "Pipeline":
+    def map(self, col: Column, mapping: Dict[str, str], default: Optional[str] = None) -> "Pipeline":
         """
         Maps values in a column using a dictionary lookup (VLOOKUP style).
 
@@ -408,7 +809,7 @@ class Pipeline:
         self._steps.append({"action": "map", "col": col, "mapping": mapping, "default": default})
         return self
 
-    def hash(self, col: Union[str, List[str]], salt: Optional[str] = None) -> "Pipeline":
+    def hash(self, col: Union[Column, List[Column]], salt: Optional[str] = None) -> "Pipeline":
         """
         Anonymizes data in the specified column(s) using SHA-256 hashing.
         
@@ -427,49 +828,589 @@ class Pipeline:
         self._steps.append({"action": "hash", "col": col, "salt": salt})
         return self
 
-    def dedupe(self, col: Union[str, List[str], None] = None) -> "Pipeline":
+    def mask(
+        self,
+        col: Union[Column, List[Column]],
+        salt: Optional[str] = None,
+        keep_first: int = 0,
+        keep_last: int = 0,
+        luhn: bool = False
+    ) -> "Pipeline":
+        """
+        Masks values while keeping their format, for IDs and account numbers.
+
+        Digits are replaced by digits and letters by letters of the same case; 
+        separators such as dashes, spaces and dots stay in place, so the length and 
+        shape of each value are unchanged and format checks downstream still pass. 
+        The same value always masks the same way for a given salt. Empty values are 
+        left empty.
+
+        Args:
+            col (Union[str, List[str]]): The column(s) to mask.
+            salt (Optional[str]): Secret that keys the masking. If None, uses the 
+                default system salt.
+            keep_first (int): Leading letters/digits kept as they are (e.g. a bank code).
+            keep_last (int): Trailing letters/digits kept as they are (e.g. the last 4 
+                digits of a card number).
+            luhn (bool): Adjust the last masked digit so the digits pass a Luhn check, 
+                like card numbers and many account numbers.
+
+        Example:
+            >>> pipe.mask("card_number", keep_last=4, luhn=True)
+        """
+        if col is None:
+            raise PhaetonValueError("Mask step requires a target column (str or list).")
+        if keep_first < 0 or keep_last < 0:
+            raise PhaetonValueError("Mask 'keep_first' and 'keep_last' cannot be negative.")
+
+        self._steps.append({
+            "action": "mask", "col": col, "salt": salt,
+            "keep_first": keep_first, "keep_last": keep_last, "luhn": luhn
+        })
+        return self
+
+    def tokenize(self, col: Union[Column, List[Column]], vault: str, key_env: str = "PHAETON_VAULT_KEY") -> "Pipeline":
+        """
+        Replaces values with tokens (`tok_` + 32 hex chars) kept in an encrypted vault file.
+
+        Unlike `hash`, tokenization is reversible for whoever holds the vault key: 
+        `detokenize` (or `phaeton.detokenize`) restores the original values. The same 
+        value always gets the same token within a vault, across runs, so tokenized 
+        columns still join. Empty values are left empty.
+
+        Args:
+            col (Union[str, List[str]]): The column(s) to tokenize.
+            vault (str): Path of the vault file; created on first use.
+            key_env (str): Environment variable holding the vault passphrase. The key 
+                itself is never part of the pipeline, so it stays out of `dump()` and 
+                lineage sidecars.
+        """
+        if col is None:
+            raise PhaetonValueError("Tokenize step requires a target column (str or list).")
+
+        self._steps.append({"action": "tokenize", "col": col, "vault": vault, "key_env": key_env})
+        return self
+
+    def detokenize(self, col: Union[Column, List[Column]], vault: str, key_env: str = "PHAETON_VAULT_KEY") -> "Pipeline":
+        """
+        Restores the original values of tokens made by `tokenize` with the same vault.
+
+        Values that are not tokens of the vault are left as they are.
+
+        Args:
+            col (Union[str, List[str]]): The column(s) holding tokens.
+            vault (str): Path of the vault file.
+            key_env (str): Environment variable holding the vault passphrase.
+        """
+        if col is None:
+            raise PhaetonValueError("Detokenize step requires a target column (str or list).")
+
+        self._steps.append({"action": "detokenize", "col": col, "vault": vault, "key_env": key_env})
+        return self
+
+    def fingerprint(self, col: Union[Column, List[Column], None] = None, into: str = "_fingerprint") -> "Pipeline":
+        """
+        Appends a stable SHA-256 checksum of the selected columns as a new column.
+
+        The checksum is deterministic across runs and machines, so it can be compared 
+        against previous exports for change-data-capture diffs.
+
+        Args:
+            col (Union[str, List[str], None]): 
+                - None: Fingerprints ALL columns present at this point of the pipeline.
+                - str / List[str]: Fingerprints only the given column(s), in the given order.
+            into (str): Name of the new column. Defaults to "_fingerprint".
+        """
+        target = col if col is not None else "*"
+        self._steps.append({"action": "fingerprint", "col": target, "into": into})
+        return self
+
+    def dedupe(self, col: Union[Column, List[Column], None] = None, store: Optional[str] = None) -> "Pipeline":
         """
         Removes duplicate rows based on unique content.
         
         Uses an efficient in-memory sharded HashSet to track seen rows.
 
+        With a `store`, the keys (64-bit hashes, 8 bytes each) are loaded from 
+        that file before the run and saved back after it, so a record delivered 
+        again in tomorrow's file is still caught. Runs in parallel (e.g. 
+        `Engine.exec()`) each start from the store as it was, so they do not see 
+        each other's rows; all their keys are kept.
+
         Args:
             col (Union[str, List[str], None]): 
                 - None: Dedupe based on ALL columns (entire row must be identical).
                 - str: Dedupe based on a SINGLE column (e.g., 'id').
                 - List[str]: Dedupe based on a COMBINATION of columns (Composite Key).
+            store (str, optional): Key store file, created by the first run. 
+                Delete it to start over.
         """
 
-        self._steps.append({"action": "dedupe", "col": col})
+        step = {"action": "dedupe", "col": col}
+        if store is not None:
+            step["store"] = store
+        self._steps.append(step)
         return self
 
     # ==========================================
-    # 6. OUTPUT CONFIGURATION
+    # 6. DATA QUALITY (Expectations)
+    # ==========================================
+    # Expectations never change or reject rows. They count the rows that reach them 
+    # (place them after the cleaning steps to check the output) and are reported in 
+    # `result.expectations` once the run is over. A failed expectation of severity 
+    # 'warn' adds a warning; one of severity 'error' marks the result as failed 
+    # (`ok=False`, `error` set, a raised EngineError for a single `Engine.exec()`).
+
+    def _expect(self, rule: str, col: Optional[Column], mostly: Optional[float], severity: ExpectSeverity, **params) -> "Pipeline":
+        if severity not in get_args(ExpectSeverity):
+            raise PhaetonValueError(f"Invalid severity '{severity}'. Expected one of {get_args(ExpectSeverity)}.")
+        if mostly is not None and (isinstance(mostly, bool) or not isinstance(mostly, (int, float)) or not 0.0 <= mostly <= 1.0):
+            raise PhaetonValueError(f"mostly must be a number between 0.0 and 1.0, got {mostly!r}.")
+
+        step = {"action": "expect", "rule": rule, "severity": severity}
+        if col is not None:
+            step["col"] = col
+        if mostly is not None:
+            step["mostly"] = mostly
+        step.update({k: v for k, v in params.items() if v is not None})
+        self._steps.append(step)
+        return self
+
+    def expect_not_null(self, col: Column, mostly: float = 1.0, severity: ExpectSeverity = "warn") -> "Pipeline":
+        """
+        Expects the column to be filled: at least `mostly` of the rows reaching 
+        this step must have a non-blank value.
+
+        Args:
+            col (str): Column to check.
+            mostly (float): Required share of non-null values, 0.0 to 1.0. Defaults to 1.0.
+            severity (ExpectSeverity): 'warn' (default) only reports a failure; 
+                'error' fails the run.
+        """
+        return self._expect("not_null", col, mostly, severity)
+
+    def expect_unique(self, col: Column, mostly: float = 1.0, severity: ExpectSeverity = "warn") -> "Pipeline":
+        """
+        Expects the column's values to be distinct: the number of distinct values 
+        divided by the number of non-blank values must be at least `mostly`. Blank 
+        values are left to `expect_not_null()`.
+
+        Args:
+            col (str): Column to check.
+            mostly (float): Required share of distinct values. Defaults to 1.0.
+            severity (ExpectSeverity): 'warn' (default) or 'error'.
+        """
+        return self._expect("unique", col, mostly, severity)
+
+    def expect_in_set(self, col: Column, values: List[Union[str, int, float]], mostly: float = 1.0, 
+                      severity: ExpectSeverity = "warn") -> "Pipeline":
+        """
+        Expects the column's non-blank values (trimmed) to come from `values`.
+
+        Args:
+            col (str): Column to check.
+            values (List[str]): Accepted values.
+            mostly (float): Required share of values in the set. Defaults to 1.0.
+            severity (ExpectSeverity): 'warn' (default) or 'error'.
+
+        Example:
+            >>> pipe.expect_in_set("status", ["ACTIVE", "INACTIVE"], mostly=0.99)
+        """
+        if not isinstance(values, (list, tuple, set)) or not values:
+            raise PhaetonValueError("expect_in_set requires a non-empty list of values.")
+        return self._expect("in_set", col, mostly, severity, values=[v if isinstance(v, str) else str(v) for v in values])
+
+    def expect_match(self, col: Column, pattern: str, mostly: float = 1.0, severity: ExpectSeverity = "warn") -> "Pipeline":
+        """
+        Expects the column's non-blank values to match the regular expression `pattern` 
+        (anywhere in the value; anchor it with `^...$` for a full match).
+
+        Args:
+            col (str): Column to check.
+            pattern (str): Regular expression.
+            mostly (float): Required share of matching values. Defaults to 1.0.
+            severity (ExpectSeverity): 'warn' (default) or 'error'.
+        """
+        return self._expect("match", col, mostly, severity, pattern=pattern)
+
+    def expect_row_count(self, min: Optional[int] = None, max: Optional[int] = None, 
+                         severity: ExpectSeverity = "warn") -> "Pipeline":
+        """
+        Expects the number of rows reaching this step to lie within `min` and `max` 
+        (inclusive). Placed last, it checks the number of rows written.
+
+        Args:
+            min (int, optional): Lowest accepted row count.
+            max (int, optional): Highest accepted row count.
+            severity (ExpectSeverity): 'warn' (default) or 'error'.
+        """
+        if min is None and max is None:
+            raise PhaetonValueError("expect_row_count requires min, max or both.")
+        for name, bound in (("min", min), ("max", max)):
+            if bound is not None and (isinstance(bound, bool) or not isinstance(bound, int) or bound < 0):
+                raise PhaetonValueError(f"{name} must be a non-negative integer, got {bound!r}.")
+        if min is not None and max is not None and min > max:
+            raise PhaetonValueError(f"min ({min}) is greater than max ({max}).")
+        return self._expect("row_count", None, None, severity, min=min, max=max)
+
+    # ==========================================
+    # 7. OUTPUT CONFIGURATION
     # ==========================================
 
-    def quarantine(self, path: str) -> "Pipeline":
+    def quarantine(self, path: str, raw: bool = False) -> "Pipeline":
         """
         Defines the output path for 'Quarantine' (Rejected) rows.
         
         Rows rejected by strict filters or type errors will be saved here 
-        with an extra `_phaeton_reason` column.
+        with an extra `_phaeton_reason` column. Records the CSV parser cannot 
+        read are quarantined with empty fields and a `Parse:` reason that gives 
+        the line number and the error.
+
+        Args:
+            path (str): Quarantine file path.
+            raw (bool): Also add a `_phaeton_raw` column holding the source text 
+                of each malformed record (empty for other rows). Defaults to False.
         """
         self._quarantine_path = path
+        if raw:
+            self._options["quarantine_raw"] = True
+        else:
+            self._options.pop("quarantine_raw", None)
+        return self
+
+    def on_error(self, strategy: ErrorStrategy) -> "Pipeline":
+        """
+        Sets how rows that fail to parse, or that a step cannot process 
+        (e.g. a failed cast or invalid JSON), are handled during the run.
+
+        Filter rejections (`keep`, `discard`, `prune`, `dedupe`) are unaffected and 
+        always go to quarantine.
+
+        Args:
+            strategy (ErrorStrategy): 
+                - 'quarantine': Route the row to quarantine with the reason (default).
+                - 'skip': Drop the row silently; it is counted in 'skipped_rows'.
+                - 'fail': Abort the run on the first failure.
+        """
+        if strategy not in get_args(ErrorStrategy):
+            raise PhaetonValueError(
+                f"Invalid on_error strategy '{strategy}'. Expected one of {get_args(ErrorStrategy)}."
+            )
+        self._on_error = strategy
+        return self
+
+    def on_quarantine(self, callback: Optional[Callable[[List[dict]], None]], batch_size: int = 1000) -> "Pipeline":
+        """
+        Registers a callback receiving the quarantined rows while the run goes on, 
+        e.g. to push rejections into a review queue or an alerting system.
+
+        Rows are handed over in lists of up to `batch_size` (at the latest when a 
+        batch of the run completes), so the GIL is taken once per list, not per row. 
+        Each row is a dict with 'source', 'row' (1-based data row), 'step' (index of 
+        the rejecting step), 'reason' and 'values' (output column -> value). Records 
+        the parser rejects have no 'row' or 'step'; their reason gives the line. Rows dropped by `on_error('skip')` are 
+        not reported. The callback runs on a worker thread; exceptions raised inside 
+        it are printed and ignored. It works with or without a `quarantine()` file.
+
+        Args:
+            callback (Callable[[List[dict]], None]): Receiver of the rejected rows; 
+                None removes a registered one.
+            batch_size (int): Maximum rows per call. Defaults to 1000.
+
+        Example:
+            >>> pipe.on_quarantine(lambda rows: queue.put_many(rows), batch_size=500)
+        """
+        if callback is not None and not callable(callback):
+            raise PhaetonValueError(f"on_quarantine expects a callable, got {type(callback).__name__}.")
+        if isinstance(batch_size, bool) or not isinstance(batch_size, int) or batch_size < 1:
+            raise PhaetonValueError(f"batch_size must be a positive integer, got {batch_size!r}.")
+        self._on_quarantine = callback
+        if callback is None:
+            self._options.pop("quarantine_batch", None)
+        else:
+            self._options["quarantine_batch"] = batch_size
+        return self
+
+    def lineage(self, path: Optional[str] = None, enabled: bool = True) -> "Pipeline":
+        """
+        Writes an audit sidecar (JSON) once the run has written its output.
+
+        The sidecar records the source path, size and SHA-256, the SHA-256 of 
+        the output and quarantine files, the steps with a SHA-256 of their 
+        canonical JSON (equal pipelines hash alike), the phaeton version, start 
+        and finish timestamps, the row counts and the warnings of the run. Its 
+        path is returned in `PipelineResult.lineage`. In-memory runs 
+        (`to_arrow()`, `to_pandas()`, ...) write no sidecar.
+
+        Args:
+            path (str, optional): Sidecar path. Defaults to `<output>.lineage.json`.
+            enabled (bool): False removes a configured sidecar. Defaults to True.
+
+        Example:
+            >>> pipe.dump("clean/orders.csv").lineage()  # clean/orders.csv.lineage.json
+        """
+        if not enabled:
+            self._options.pop("lineage", None)
+        else:
+            self._options["lineage"] = path if path is not None else True
+        return self
+
+    def manifest(self, path: Optional[str] = None, verify: bool = True, enabled: bool = True) -> "Pipeline":
+        """
+        Writes a checksum manifest (JSON) of every file the run produced, for 
+        dataset handoffs that require one.
+
+        The manifest lists the output, quarantine and route files, and the lineage 
+        sidecar if there is one, each with its path, role, size and SHA-256. With 
+        `verify`, the CSV files are read back first: their row counts must match 
+        the rows the run wrote, or the run fails and no manifest is written. Runs 
+        of `incremental()` that append to their files are not counted. Its path is 
+        returned in `PipelineResult.manifest`. In-memory runs write no manifest.
+
+        Args:
+            path (str, optional): Manifest path. Defaults to `<output>.manifest.json`.
+            verify (bool): Re-read the files and check their row counts. Defaults to True.
+            enabled (bool): False removes a configured manifest. Defaults to True.
+
+        Example:
+            >>> pipe.dump("delivery/orders.csv").manifest()  # delivery/orders.csv.manifest.json
+        """
+        if not enabled:
+            self._options.pop("manifest", None)
+        else:
+            self._options["manifest"] = {"path": path, "verify": verify}
+        return self
+
+    def drift(
+        self,
+        baseline: Optional[str],
+        null_factor: float = 3.0,
+        mean_tolerance: float = 1.0,
+        update: bool = False
+    ) -> "Pipeline":
+        """
+        Compares the column statistics of the output with a stored baseline once 
+        the run has written it, to catch silent upstream changes.
+
+        The first run profiles its output and saves the statistics as the baseline 
+        (a JSON file). Later runs report these shifts against it:
+
+        - 'missing_column' / 'new_column': the columns changed.
+        - 'type_changed': a numeric column now holds text, or the other way round.
+        - 'null_rate': the share of empty values grew by `null_factor` or more 
+          (and by at least one percentage point).
+        - 'new_values': a categorical column (20 distinct values or fewer in the 
+          baseline) has values the baseline never saw.
+        - 'mean_shift': the mean of a numeric column moved by more than 
+          `mean_tolerance` baseline standard deviations.
+
+        Shifts are listed in `PipelineResult.drift` and added to its warnings; they 
+        do not fail the run. In-memory runs (`to_arrow()`, `to_pandas()`, ...) are 
+        not checked.
+
+        Args:
+            baseline (str): Path of the baseline file. None turns the check off.
+            null_factor (float): Growth of the null rate reported as drift. Defaults to 3.0.
+            mean_tolerance (float): Mean shift reported as drift, in baseline standard 
+                deviations. Defaults to 1.0.
+            update (bool): Replace the baseline with this run's statistics after 
+                comparing, so it follows slow, expected change. Defaults to False.
+
+        Example:
+            >>> result = pipe.dump("clean/orders.csv").drift("baselines/orders.json").run()
+            >>> [s.message for s in result.drift]
+            ["Null rate of 'email' rose from 2.0% to 9.5% (4.8x)"]
+        """
+        if baseline is None:
+            self._options.pop("drift", None)
+            return self
+        if null_factor <= 1.0:
+            raise PhaetonValueError("Drift 'null_factor' must be greater than 1.")
+        if mean_tolerance <= 0:
+            raise PhaetonValueError("Drift 'mean_tolerance' must be positive.")
+        self._options["drift"] = {
+            "baseline": baseline, "null_factor": null_factor,
+            "mean_tolerance": mean_tolerance, "update": update
+        }
+        return self
+
+    def route(
+        self,
+        name: str,
+        when: Dict[str, Any],
+        output: Optional[str] = None,
+        delimiter: Optional[str] = None,
+        columns: Optional[List[str]] = None,
+        header: bool = True
+    ) -> "Pipeline":
+        """
+        Sends the kept rows matching a condition to their own output instead of 
+        the one given to `dump()`, splitting one input into several files in a 
+        single pass.
+
+        Routes are tried in the order they were added and the first match takes 
+        the row; rows no route matches go to the main output. A route without 
+        `output` drops its rows (e.g. test accounts). Conditions see the row after 
+        every step and compare trimmed values. A condition is a test, or a tree 
+        of them:
+
+        - `{"col": "email", "op": "empty"}`, with op one of 'empty', 'not_empty', 
+          'eq', 'ne', 'contains', 'starts_with', 'ends_with', 'regex', 'in' and 
+          'not_in' (with a list), or 'gt', 'ge', 'lt' and 'le' (numeric; values 
+          that are not numbers never match). All but the first two need `"value"`.
+        - `{"all": [...]}`, `{"any": [...]}` and `{"not": {...}}`.
+
+        Routed rows are not counted in `PipelineResult.saved`; `PipelineResult.routes` 
+        has the rows each route took. In-memory runs route the same way.
+
+        Args:
+            name (str): Unique name of the route, used in the result.
+            when (dict): Condition a row must meet.
+            output (str, optional): CSV file of the route. None drops the rows.
+            delimiter (str, optional): Single-character delimiter of the route's file. 
+                Defaults to the output delimiter.
+            columns (List[str], optional): Columns written, in this order. Defaults to all.
+            header (bool): Write a header row. Defaults to True.
+
+        Example:
+            >>> (pipe.route("test", {"col": "email", "op": "ends_with", "value": "@example.com"})
+            ...      .route("followup", {"col": "email", "op": "empty"}, output="followup.csv")
+            ...      .dump("clean.csv"))
+        """
+        if not isinstance(name, str) or not name:
+            raise PhaetonValueError("Route name must be a non-empty string.")
+        if not isinstance(when, dict):
+            raise PhaetonValueError(f"Condition of route '{name}' must be a dictionary.")
+        if delimiter is not None and (not isinstance(delimiter, str) or len(delimiter) != 1):
+            raise PhaetonValueError(f"Route delimiter must be a single character, got {delimiter!r}.")
+        routes = self._options.setdefault("routes", [])
+        if any(r["name"] == name for r in routes):
+            raise PhaetonValueError(f"Route '{name}' is defined twice.")
+        route: Dict[str, Any] = {"name": name, "when": copy.deepcopy(when), "output": output, "header": header}
+        if delimiter is not None:
+            route["delimiter"] = delimiter
+        if columns is not None:
+            route["columns"] = list(columns)
+        routes.append(route)
+        return self
+
+    def notify(
+        self,
+        target: str,
+        on: str = "always",
+        format: str = "json",
+        headers: Optional[Dict[str, str]] = None,
+        timeout: float = 10.0
+    ) -> "Pipeline":
+        """
+        Tells a webhook or a command when the run finishes, so orchestrators and 
+        chat alerts need no wrapper script around it.
+
+        An http(s) `target` receives a POST of the run's stats as JSON (the 
+        `PipelineResult.to_dict()` fields plus `event`: "completed", "failed" or 
+        "cancelled"); failed runs send `source`, `output` and `error` instead. Any 
+        other target is run as a shell command with that JSON on stdin and 
+        `PHAETON_EVENT` / `PHAETON_SOURCE` in its environment. A notification that 
+        cannot be delivered is added to the run's warnings; it never fails the run. 
+        Call it again to notify several targets.
+
+        Args:
+            target (str): Webhook URL or shell command.
+            on (str): 'always', 'success' or 'failure'. Defaults to 'always'.
+            format (str): 'json', or 'slack' to post `{"text": "<summary>"}` to an 
+                incoming webhook. Defaults to 'json'.
+            headers (Dict[str, str], optional): Extra HTTP headers, e.g. Authorization.
+            timeout (float): Seconds to wait for the webhook. Defaults to 10.
+
+        Example:
+            >>> pipe.notify("https://hooks.slack.com/services/T000/B000/XXX", on="failure", format="slack")
+            >>> pipe.notify("jq . >> runs.log")
+        """
+        if not isinstance(target, str) or not target.strip():
+            raise PhaetonValueError("Notification target must be a non-empty string.")
+        if on not in ("always", "success", "failure"):
+            raise PhaetonValueError(f"Invalid 'on' value '{on}'. Expected: always, success, failure.")
+        if format not in ("json", "slack"):
+            raise PhaetonValueError(f"Invalid notification format '{format}'. Expected: json, slack.")
+        kind = "url" if target.startswith(("http://", "https://")) else "command"
+        notifier: Dict[str, Any] = {kind: target, "on": on, "format": format, "timeout": timeout}
+        if headers:
+            notifier["headers"] = dict(headers)
+        # A new list, so forks add targets of their own
+        self._options["notify"] = self._options.get("notify", []) + [notifier]
         return self
 
-    def dump(self, path: str, format: ExportFormat = "csv") -> "Pipeline":
+    def incremental(self, state: Optional[str]) -> "Pipeline":
+        """
+        Remembers in a JSON state file how far each source was read, so nightly 
+        runs over growing files only handle new data.
+
+        Every source is recorded by path with the byte offset, line and row count 
+        reached, and a SHA-256 of the bytes read. On the next run with the same 
+        state file:
+
+        - An unchanged source is skipped; its outputs are left as they are.
+        - A source that grew is read from where the last run stopped, and the 
+          new rows are appended to the output and quarantine files (the header 
+          row is only written to new files). Row numbers continue from the last run.
+        - A source whose earlier bytes changed is read again in full and its 
+          outputs are replaced.
+
+        Only complete lines are read: a last line without its line break (a writer 
+        caught mid-append) is left for the next run.
+
+        `PipelineResult.increment` tells which case applied. A cancelled run records 
+        how far it got; a failed run leaves the state as it was (discard what it 
+        appended before running again). Runs over a frame or in memory ignore the state.
+
+        Args:
+            state (str): Path of the state file, created on the first run. One file 
+                can serve many pipelines. None turns incremental runs off.
+
+        Example:
+            >>> pipe.dump("clean/events.csv").incremental("state/events.json")
+        """
+        if state is None:
+            self._options.pop("state", None)
+        else:
+            self._options["state"] = state
+        return self
+
+    def dump(self, path: str, format: ExportFormat = "csv", delimiter: Optional[str] = None) -> "Pipeline":
         """
         Defines the final output destination for Cleaned Data.
 
         Args:
             path (str): Target file path.
             format (ExportFormat): File format ('parquet', 'csv', 'arrow'). Defaults to "csv".
+            delimiter (str, optional): Single-character delimiter of the output and 
+                quarantine files. Defaults to the input delimiter.
         """
+        if delimiter is not None:
+            if not isinstance(delimiter, str) or len(delimiter) != 1:
+                raise PhaetonValueError(f"Output delimiter must be a single character, got {delimiter!r}.")
+            self._options["output_delimiter"] = delimiter
         self._output_target = path
         self._output_format = format
         self._steps.append({"action": "dump", "path": path, "format": format})
         return self
 
+    def extend(self, steps: List[Any]) -> "Pipeline":
+        """
+        Appends step definitions, e.g. the output of `phaeton.suggest_pipeline()` or 
+        steps built by the typed constructors in `phaeton.steps` (`Scrub(...)`, `Cast(...)`).
+
+        Args:
+            steps (List[Any]): Step dictionaries, each with an 'action' key, or `Step` objects.
+        """
+        for step in steps:
+            if hasattr(step, "to_dict"):
+                step = step.to_dict()
+            if not isinstance(step, dict) or "action" not in step:
+                raise PhaetonValueError("Each step must be a dictionary with an 'action' key.")
+            self._steps.append(copy.deepcopy(step))
+        return self
+
     def fork(self, tag: Optional[str] = None) -> "Pipeline":
         """
         Creates a deep copy of the current pipeline state. 
@@ -492,6 +1433,13 @@ class Pipeline:
 
         new_obj = copy.copy(self)
         new_obj._steps = copy.deepcopy(self._steps)
+        # Options naming files of the parent's output do not carry over
+        new_obj._options = {
+            k: v for k, v in self._options.items()
+            if k not in ("output_delimiter", "drift", "routes")
+            and not (k == "lineage" and isinstance(v, str))
+            and not (k == "manifest" and isinstance(v, dict) and v.get("path") is not None)
+        }
         
         new_obj._output_target = None 
         new_obj._quarantine_path = None 
@@ -502,34 +1450,248 @@ class Pipeline:
         
         return new_obj
     
-    def run(self):
+    def run(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
         """
         Triggers execution for this single pipeline.
         For parallel execution of multiple pipelines, use `engine.exec([p1, p2])`.
+
+        Args:
+            progress (Callable[[dict], None], optional): Called after every batch with a 
+                dict of 'source', 'processed_rows', 'saved_rows', 'quarantined_rows', 
+                'bytes_read', 'total_bytes', 'elapsed_ms' and 'eta_ms'. Handy for driving 
+                tqdm or dashboards.
+            cancel (phaeton.CancelToken, optional): Token whose `.cancel()` stops the run 
+                after the batch in flight. Ctrl+C has the same effect; either way the 
+                outputs are flushed and partial stats are returned with 'cancelled' set.
+
+        Returns:
+            PipelineResult: Typed run result with `source`, `output`, `quarantine`, 
+                `processed`, `saved`, `quarantined`, `skipped`, `bytes_read`, `duration` 
+                (float seconds), `rows_per_sec`, `bytes_per_sec`, `cancelled`, `steps` 
+                (rows rejected per step) and `warnings`. `to_dict()` returns the flat 
+                stats dict with the previous keys ('processed_rows', 'duration_ms', ...).
+        """
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        return _phaeton.execute_pipeline(self._run_payload(), progress, cancel, self._frame(), self._on_quarantine)
+
+    async def run_async(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
         """
+        Awaitable `run()` for asyncio code such as FastAPI handlers. The engine runs on 
+        a background thread, so the event loop is never blocked.
+
+        Cancelling the awaiting task (e.g. `asyncio.wait_for` timing out) stops the run 
+        after the batch in flight. `progress` is called from the worker thread.
+
+        Args:
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `run()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run from elsewhere.
+
+        Returns:
+            PipelineResult: Same as `run()`.
+
+        Example:
+            >>> result = await eng.ingest("raw.csv").prune().dump("clean.csv").run_async()
+        """
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        return await _phaeton.execute_pipeline_future(self._run_payload(), progress, cancel, self._frame(), self._on_quarantine)
+
+    def stream(self, cancel: Optional[Any] = None):
+        """
+        Starts this pipeline in the background and returns an iterator over its 
+        per-batch stats, for orchestration code that watches a long run.
+
+        Each item is the same dict `run(progress=...)` receives. Calling `.cancel()` on 
+        the iterator (or on `cancel`) stops the run after the batch in flight; iteration 
+        then ends normally. Once exhausted, the final `PipelineResult` is available as 
+        `.result`. Dropping the iterator early also cancels the run.
+
+        Args:
+            cancel (phaeton.CancelToken, optional): Token that can stop the run from elsewhere.
+
+        Returns:
+            RunStream: Iterator of progress dicts with `.cancel()`, `.done` and `.result`.
+
+        Example:
+            >>> run = pipe.stream()
+            >>> for stats in run:
+            ...     if stats['quarantined_rows'] > 0.2 * stats['processed_rows']:
+            ...         run.cancel()
+            >>> run.result.cancelled
+        """
+        self._require_file("stream")
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        return _phaeton.stream_pipeline(self._run_payload(), cancel)
+
+    def to_arrow(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
+        """
+        Runs this pipeline and returns the cleaned rows as a `pyarrow.Table`, without 
+        writing an output file. Meant for notebook-scale data that fits in memory.
+
+        Every column is a string column (cast it in pandas/pyarrow as needed) and 
+        empty cells are nulls. Rejected rows still go to the `quarantine()` path if one 
+        is set; `dump()` is not needed and is ignored here.
+
+        Args:
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `run()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run early; the 
+                table then holds the rows of the batches that completed.
+
+        Returns:
+            pyarrow.Table: The kept rows, with the output column names.
+
+        Raises:
+            ConfigurationError: If `pyarrow` is not installed.
+        """
+        try:
+            import pyarrow.ipc
+        except ImportError:
+            raise ConfigurationError("to_arrow() requires pyarrow. Install it with 'pip install pyarrow'.")
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        _, ipc = _phaeton.execute_to_dataframe(self._run_payload(require_output=False), progress, cancel, self._frame(), self._on_quarantine)
+        return pyarrow.ipc.open_stream(ipc).read_all()
+
+    def to_pandas(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
+        """
+        Runs this pipeline and returns the cleaned rows as a pandas DataFrame, 
+        converted from `to_arrow()` without an intermediate CSV file.
+
+        Args:
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `run()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run early.
+
+        Returns:
+            pandas.DataFrame: The kept rows; empty cells are missing values.
+
+        Example:
+            >>> df = eng.ingest("sales.csv").scrub("name", "trim").keep("status", "ACTIVE").to_pandas()
+        """
+        return self.to_arrow(progress, cancel).to_pandas()
+
+    def to_polars(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
+        """
+        Runs this pipeline and returns the cleaned rows as a polars DataFrame. The 
+        batches are handed over through the Arrow C stream interface, so nothing is 
+        serialized or copied on the way. Columns are strings, as in `to_arrow()`.
+
+        Together with `eng.ingest(df)` this slots phaeton steps into a polars workflow.
+
+        Args:
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `run()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run early.
+
+        Returns:
+            polars.DataFrame: The kept rows, with the output column names.
+
+        Raises:
+            ConfigurationError: If `polars` is not installed.
+
+        Example:
+            >>> clean = eng.ingest(df).scrub("email", "email").dedupe("email").to_polars()
+        """
+        try:
+            import polars
+        except ImportError:
+            raise ConfigurationError("to_polars() requires polars. Install it with 'pip install polars'.")
+
+        return polars.DataFrame(self.to_arrow_stream(progress, cancel))
+
+    def to_arrow_stream(self, progress: Optional[Callable[[dict], None]] = None, cancel: Optional[Any] = None):
+        """
+        Runs this pipeline and returns the cleaned rows as an object exporting the 
+        Arrow C stream interface (`__arrow_c_stream__`), accepted without a copy by 
+        polars, pyarrow (`pyarrow.table(...)`) and other Arrow-aware libraries. 
+        Needs neither pyarrow nor polars.
+
+        Args:
+            progress (Callable[[dict], None], optional): Per-batch callback, as in `run()`.
+            cancel (phaeton.CancelToken, optional): Token to stop the run early.
+
+        Returns:
+            ArrowStream: The kept rows, with `num_rows` and `column_names`.
+        """
+        try:
+            from . import _phaeton
+        except ImportError:
+            raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
+
+        _, stream = _phaeton.execute_to_arrow_stream(self._run_payload(require_output=False), progress, cancel, self._frame(), self._on_quarantine)
+        return stream
+
+    def prepare(self):
+        """
+        Compiles this pipeline's steps into a reusable object for running the same 
+        cleaning logic over many files, e.g. thousands of daily exports.
+
+        Steps are converted once, and compiled (regexes, reference lists, phonetic 
+        indexes) once per distinct header layout. Columns are bound by name, so files 
+        only need the referenced columns, not the same column order. Reference files 
+        are read on the first run of each layout. Stateful steps (`dedupe`, forward 
+        `fill`) still start fresh on every file.
+
+        The ingest options, `on_error()` strategy and output delimiter of this pipeline 
+        become defaults that each run can override. The `.dump()` path is not used: 
+        every run names its own output.
+
+        Returns:
+            PreparedPipeline: Object with `run(source, output, quarantine=None, 
+                progress=None, cancel=None)` returning a `PipelineResult`, and 
+                `run_batch(runs, progress=None, cancel=None)` running a list of dicts 
+                ({'source', 'output', 'quarantine', ...}) in parallel with per-run errors.
+
+        Example:
+            >>> prepared = eng.ingest("template.csv").prune().cast("price", "float").prepare()
+            >>> for day in days:
+            ...     prepared.run(f"raw/{day}.csv", f"clean/{day}.csv")
+        """
+        self._require_file("prepare")
         if self._strict:
             self._validate()
 
-        if not self._output_target and not self._quarantine_path and not self._has_peeked:
-            raise ConfigurationError(
-                f"Pipeline {self._alias} (source: {self._source}) has no output defined. "
-                "Call .dump() or .quarantine() before .run()."
-            )
-
         try:
             from . import _phaeton
         except ImportError:
             raise EngineError("Phaeton Rust Core not found. Cannot execute pipeline.")
 
-        payload = {
+        config = {**self._config, "on_error": self._on_error, **self._options}
+        return _phaeton.PreparedPipeline(self._steps, config)
+
+    def _run_payload(self, require_output: bool = True) -> Dict:
+        """Validates the pipeline for execution and builds the payload sent to the Rust core."""
+        if self._strict:
+            self._validate()
+
+        if require_output and not self._output_target and not self._quarantine_path and not self._has_peeked:
+            raise ConfigurationError(
+                f"Pipeline {self._alias} (source: {self._source}) has no output defined. "
+                "Call .dump() or .quarantine() before .run()."
+            )
+
+        return {
             "source": self._source,
             "steps": self._steps,
             "quarantine": self._quarantine_path,
             "output": self._output_target,
             "format": self._output_format,
-            "config": self._config
+            "on_error": self._on_error,
+            "config": self._config,
+            **self._options
         }
-        return _phaeton.execute_pipeline(payload)
     
     def __repr__(self):
         return f"<Phaeton Pipeline | Source: {self._source} | Steps: {len(self._steps)}>"
\ No newline at end of file
@@ -0,0 +1,49 @@
+"""
+Typed step constructors backed by the Rust core.
+
+Each constructor checks its parameters when it is called and returns a `Step`, 
+which `Pipeline.extend()` and `NativePipeline.add()` accept like a step dict:
+
+    from phaeton.steps import Scrub, Cast, Align
+
+    pipe.extend([
+        Scrub("name", "trim"),
+        Cast("price", "float", clean=True),
+        Align("city", ["Jakarta", "Bandung"], threshold=0.9),
+    ])
+
+A misspelled mode, an unknown cast type or an out-of-range threshold raises 
+`ValueError` at the constructor instead of being found when the pipeline runs.
+"""
+
+from ._phaeton import (
+    Step,
+    Keep,
+    Discard,
+    Prune,
+    Scrub,
+    Cast,
+    Fill,
+    Dedupe,
+    Align,
+    Map,
+    Hash,
+    Mask,
+    Tokenize,
+    Detokenize,
+    AddColumn,
+    ConvertCurrency,
+    JsonExtract,
+    Format,
+    Country,
+    Fingerprint,
+    Expect,
+    Rename,
+    Headers,
+)
+
+__all__ = [
+    "Step", "Keep", "Discard", "Prune", "Scrub", "Cast", "Fill", "Dedupe", "Align", "Map", "Hash",
+    "Mask", "Tokenize", "Detokenize",
+    "AddColumn", "ConvertCurrency", "JsonExtract", "Format", "Country", "Fingerprint", "Expect", "Rename", "Headers",
+]
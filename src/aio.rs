@@ -0,0 +1,96 @@
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use pythonize::depythonize;
+use serde_json::Value;
+
+use crate::engine::Engine;
+use crate::interop;
+use crate::streaming::CancelFlag;
+use crate::{batch_engine, batch_results, progress_callback, quarantine_callback, CancelToken, PipelineResult};
+
+/// Trips the run's cancel flag when the awaiting task goes away first
+/// (`asyncio.CancelledError`, a timeout), so the engine stops at the next batch
+/// boundary instead of running to completion unobserved
+struct CancelOnDrop(Option<CancelFlag>);
+
+impl CancelOnDrop {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(cancel) = &self.0 {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Run `job` on tokio's blocking pool and wait for it without holding the event loop
+async fn run_blocking<T: Send + 'static>(cancel: CancelFlag, job: impl FnOnce() -> T + Send + 'static) -> PyResult<T> {
+    let guard = CancelOnDrop(Some(cancel));
+    let result = tokio::task::spawn_blocking(job).await
+        .map_err(|e| PyRuntimeError::new_err(format!("Pipeline worker failed: {}", e)));
+    guard.disarm();
+    result
+}
+
+/// Awaitable variant of `execute_pipeline`: the engine runs on a background
+/// thread and the returned asyncio future resolves to the `PipelineResult`.
+/// Cancelling the awaiting task cancels the run.
+#[pyfunction]
+#[pyo3(signature = (payload_py, progress=None, cancel=None, data=None, on_quarantine=None))]
+pub fn execute_pipeline_future<'py>(
+    py: Python<'py>,
+    payload_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    data: Option<&PyAny>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<&'py PyAny> {
+    let payload: HashMap<String, Value> = depythonize(payload_py.as_ref(py))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid payload format: {}", e)))?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = Engine::new(0, 10000)
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine))
+        .with_arrow_input(data.map(interop::arrow_reader).transpose()?);
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let report = run_blocking(cancel, move || engine.execute_single(payload)).await??;
+        Ok(PipelineResult::from(report))
+    })
+}
+
+/// Awaitable variant of `execute_batch`, resolving to one `PipelineResult` per payload
+#[pyfunction]
+#[pyo3(signature = (payloads_py, config_py, progress=None, cancel=None, on_quarantine=None))]
+pub fn execute_batch_future<'py>(
+    py: Python<'py>,
+    payloads_py: PyObject,
+    config_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<&'py PyAny> {
+    let (payloads, engine) = batch_engine(py, payloads_py, config_py)?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = engine
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine));
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let reports = run_blocking(cancel, move || {
+            let reports = engine.execute_parallel(&payloads);
+            batch_results(reports, &payloads)
+        }).await?;
+        Ok(reports)
+    })
+}
@@ -0,0 +1,317 @@
+//! `phaeton` command line: the cleaning engine without a Python interpreter,
+//! for cron jobs and shell-based schedulers. Every command prints JSON to stdout;
+//! logs go to stderr (filter with `PHAETON_LOG`, default `warn`).
+//!
+//! Exit codes: 0 on success, 1 if the command or any pipeline failed (an `expect`
+//! step of severity `error` that did not hold counts as a failure, as does drift
+//! found by `phaeton drift` and a difference found by `phaeton diff`), 2 on usage errors.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+use tracing_subscriber::EnvFilter;
+
+use _phaeton::engine::{file_payloads, parse_delimiter, results_json, PreparedSteps};
+use _phaeton::error::{PhaetonError, Result};
+use _phaeton::processors::concat::{self, ConcatOptions, MissingColumns};
+use _phaeton::processors::diff::{self, DiffOptions};
+use _phaeton::processors::drift::{self, DriftOptions};
+use _phaeton::processors::join::{self, JoinKind, JoinOptions, JoinStrategy};
+use _phaeton::processors::probe::{detect_file_metadata, ProbeOptions};
+use _phaeton::spec::{self, Spec};
+use _phaeton::streaming::{ErrorStrategy, HeaderMatch, RaggedPolicy, StreamProcessor};
+
+#[cfg(feature = "server")]
+mod server;
+
+#[derive(Parser)]
+#[command(name = "phaeton", version, about = "Streaming CSV cleaning engine")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Detect encoding, delimiter, headers and column types of a file
+    Probe {
+        path: String,
+        /// Rows sampled for type inference
+        #[arg(long, default_value_t = 100)]
+        sample_rows: usize,
+        /// Read the whole file instead of a sample
+        #[arg(long)]
+        full_scan: bool,
+        /// Count the data rows exactly
+        #[arg(long)]
+        count_rows: bool,
+    },
+    /// Compare the column statistics of a file with a baseline, creating it when missing
+    Drift {
+        path: String,
+        /// Baseline file (JSON)
+        #[arg(long)]
+        baseline: String,
+        /// Growth of the null rate reported as drift
+        #[arg(long, default_value_t = 3.0)]
+        null_factor: f64,
+        /// Mean shift reported as drift, in baseline standard deviations
+        #[arg(long, default_value_t = 1.0)]
+        mean_tolerance: f64,
+        /// Replace the baseline with the file's statistics after comparing
+        #[arg(long)]
+        update: bool,
+    },
+    /// Join two files on key columns
+    Join {
+        left: String,
+        right: String,
+        /// Key column(s) of the left file; repeat for composite keys
+        #[arg(long, required = true)]
+        on: Vec<String>,
+        /// Key column(s) of the right file when named differently
+        #[arg(long)]
+        right_on: Vec<String>,
+        /// inner, left, right or outer
+        #[arg(long, default_value = "inner")]
+        how: String,
+        #[arg(long)]
+        output: String,
+        /// auto, hash or sort_merge
+        #[arg(long, default_value = "auto")]
+        strategy: String,
+        /// Memory budget in bytes; larger right files are joined by sort-merge
+        #[arg(long, default_value_t = join::DEFAULT_MEMORY_LIMIT)]
+        memory_limit: u64,
+    },
+    /// Compare two versions of a file on key columns
+    Diff {
+        old: String,
+        new: String,
+        /// Key column(s); repeat for composite keys
+        #[arg(long, required = true)]
+        key: Vec<String>,
+        /// Column(s) compared; defaults to every non-key column both files have
+        #[arg(long)]
+        columns: Vec<String>,
+        /// Write the rows only in the new file here
+        #[arg(long)]
+        added: Option<String>,
+        /// Write the rows only in the old file here
+        #[arg(long)]
+        removed: Option<String>,
+        /// Write the changed rows, with what changed, here
+        #[arg(long)]
+        changed: Option<String>,
+    },
+    /// Append files into one, matching their columns by name
+    Concat {
+        #[arg(required = true)]
+        sources: Vec<String>,
+        #[arg(long)]
+        output: String,
+        /// fill (blanks for columns a file lacks) or fail
+        #[arg(long, default_value = "fill")]
+        missing: String,
+        /// Output column(s), in order; other columns are dropped. Defaults to all
+        #[arg(long)]
+        columns: Vec<String>,
+        /// Add a column holding the path each row came from
+        #[arg(long)]
+        source_column: Option<String>,
+    },
+    /// Run the first rows of a source through a spec pipeline without writing anything
+    Preview {
+        /// Spec file (YAML or JSON) holding the steps
+        #[arg(long)]
+        spec: Option<String>,
+        /// Pipeline of the spec, by name or 0-based position
+        #[arg(long)]
+        pipeline: Option<String>,
+        /// Source to preview instead of the pipeline's own
+        source: Option<String>,
+        /// Number of source rows
+        #[arg(short, default_value_t = 5)]
+        n: usize,
+    },
+    /// Run every pipeline of a spec file in parallel
+    Run {
+        #[arg(long)]
+        spec: String,
+    },
+    /// Run submitted specs as jobs behind an HTTP API
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+        /// Bearer token every request but /health must carry; defaults to $PHAETON_SERVER_TOKEN (required)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Apply one spec pipeline to many files, writing each under --output-dir
+    Batch {
+        #[arg(long)]
+        spec: String,
+        /// Pipeline of the spec, by name or 0-based position
+        #[arg(long)]
+        pipeline: Option<String>,
+        #[arg(long)]
+        output_dir: PathBuf,
+        /// Where `<name>_quarantine.csv` files go; rejected rows are not kept without it
+        #[arg(long)]
+        quarantine_dir: Option<PathBuf>,
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let filter = EnvFilter::try_from_env("PHAETON_LOG").unwrap_or_else(|_| EnvFilter::new("warn"));
+    tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr).init();
+
+    match execute(cli.command) {
+        Ok((output, ok)) => {
+            // A closed pipe (`phaeton probe x.csv | head`) is not an error of the run
+            let _ = writeln!(std::io::stdout().lock(), "{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+            if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+        },
+        Err(e) => {
+            eprintln!("phaeton: {}", e);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+/// Run a command, returning its JSON output and whether everything succeeded
+fn execute(command: Command) -> Result<(Value, bool)> {
+    match command {
+        Command::Probe { path, sample_rows, full_scan, count_rows } => {
+            let options = ProbeOptions { sample_rows, full_scan, count_rows, ..ProbeOptions::default() };
+            Ok((serde_json::to_value(detect_file_metadata(&path, &options)?)?, true))
+        },
+        Command::Drift { path, baseline, null_factor, mean_tolerance, update } => {
+            let options = DriftOptions { null_factor, mean_tolerance, update };
+            let report = drift::check(&path, &baseline, None, &options)?;
+            let ok = !report.drifted;
+            Ok((serde_json::to_value(report)?, ok))
+        },
+        Command::Join { left, right, on, right_on, how, output, strategy, memory_limit } => {
+            let options = JoinOptions {
+                how: JoinKind::parse(&how)?,
+                on,
+                right_on: (!right_on.is_empty()).then_some(right_on),
+                strategy: JoinStrategy::parse(&strategy)?,
+                memory_limit,
+                ..JoinOptions::default()
+            };
+            Ok((serde_json::to_value(join::join_files(&left, &right, &output, &options)?)?, true))
+        },
+        Command::Diff { old, new, key, columns, added, removed, changed } => {
+            let options = DiffOptions {
+                key,
+                columns: (!columns.is_empty()).then_some(columns),
+                added,
+                removed,
+                changed,
+                ..DiffOptions::default()
+            };
+            let stats = diff::diff_files(&old, &new, &options)?;
+            let ok = stats.identical();
+            Ok((serde_json::to_value(stats)?, ok))
+        },
+        Command::Concat { sources, output, missing, columns, source_column } => {
+            let options = ConcatOptions {
+                missing: MissingColumns::parse(&missing)?,
+                columns: (!columns.is_empty()).then_some(columns),
+                source_column,
+                ..ConcatOptions::default()
+            };
+            Ok((serde_json::to_value(concat::concat_files(&sources, &output, &options)?)?, true))
+        },
+        Command::Preview { spec, pipeline, source, n } => {
+            let (mut payload, config) = match spec {
+                Some(path) => {
+                    let spec = spec::load(&path)?;
+                    (select(&spec, pipeline.as_deref())?, spec.config())
+                },
+                None => (HashMap::new(), HashMap::new()),
+            };
+            if let Some(source) = source {
+                payload.insert("source".to_string(), Value::from(source));
+            }
+            Ok((serde_json::to_value(preview(&payload, &config, n)?)?, true))
+        },
+        #[cfg(feature = "server")]
+        Command::Serve { addr, token } => {
+            let token = token.or_else(|| std::env::var("PHAETON_SERVER_TOKEN").ok()).filter(|t| !t.is_empty())
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "serve needs a bearer token: pass --token or set PHAETON_SERVER_TOKEN"))?;
+            server::serve(&addr, token)?;
+            Ok((Value::Null, true))
+        },
+        Command::Run { spec } => {
+            let spec = spec::load(&spec)?;
+            let payloads = spec.payloads();
+            Ok(results_json(spec.engine().execute_parallel(&payloads), &payloads))
+        },
+        Command::Batch { spec, pipeline, output_dir, quarantine_dir, files } => {
+            let spec = spec::load(&spec)?;
+            let template = select(&spec, pipeline.as_deref())?;
+            std::fs::create_dir_all(&output_dir)?;
+            if let Some(dir) = &quarantine_dir {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            let payloads = file_payloads(&template, &files, &output_dir, quarantine_dir.as_deref());
+
+            let steps = template.get("steps").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default();
+            let engine = spec.engine().with_prepared(Some(Arc::new(PreparedSteps::new(steps))));
+            Ok(results_json(engine.execute_parallel(&payloads), &payloads))
+        },
+    }
+}
+
+/// The payload of the pipeline named `selector` (or at that 0-based position);
+/// a spec with a single pipeline needs no selector
+fn select(spec: &Spec, selector: Option<&str>) -> Result<HashMap<String, Value>> {
+    let mut payloads = spec.payloads();
+    let index = match selector {
+        Some(selector) => spec.pipelines.iter().position(|p| p.name.as_deref() == Some(selector))
+            .or_else(|| selector.parse::<usize>().ok().filter(|&i| i < payloads.len()))
+            .ok_or_else(|| PhaetonError::SpecError(format!("No pipeline named '{}' in the spec", selector)))?,
+        None if payloads.len() == 1 => 0,
+        None => return Err(PhaetonError::SpecError(format!(
+            "The spec has {} pipelines; choose one with --pipeline", payloads.len()
+        ))),
+    };
+    Ok(payloads.swap_remove(index))
+}
+
+fn preview(payload: &HashMap<String, Value>, config: &HashMap<String, Value>, n: usize) -> Result<_phaeton::streaming::Preview> {
+    let setting = |key: &str| payload.get(key).or_else(|| config.get(key)).and_then(|v| v.as_str());
+    let source = setting("source")
+        .ok_or_else(|| PhaetonError::InvalidStep("Give a source, or a --spec whose pipeline has one".into()))?
+        .to_string();
+    let steps = payload.get("steps").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default();
+
+    let delimiter = setting("delimiter").map_or(Ok(b','), |d| parse_delimiter(&source, d))?;
+    let ragged = setting("ragged").map(RaggedPolicy::parse).transpose()?.unwrap_or_default();
+    let on_error = setting("on_error").map(ErrorStrategy::parse).transpose()?.unwrap_or_default();
+    let header_match = setting("header_match").map(HeaderMatch::parse).transpose()?.unwrap_or_default();
+    let rename_duplicates = payload.get("rename_duplicates").and_then(Value::as_bool).unwrap_or(false);
+
+    StreamProcessor::new(source, steps, 1000)
+        .with_delimiter(delimiter)
+        .with_ragged(ragged)
+        .with_on_error(on_error)
+        .with_header_match(header_match)
+        .with_rename_duplicates(rename_duplicates)
+        .peek(n, None)
+}
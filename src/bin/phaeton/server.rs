@@ -0,0 +1,314 @@
+//! `phaeton serve`: spec runs submitted over HTTP and tracked as jobs.
+//!
+//! - `GET /health`
+//! - `POST /jobs` with a spec (YAML, or JSON with `Content-Type: application/json`):
+//!   queues it and answers `202` with the job
+//! - `GET /jobs`, `GET /jobs/{id}`: status (queued, running, completed, failed,
+//!   cancelled) and progress
+//! - `GET /jobs/{id}/stats`: per-pipeline results once the job has finished
+//! - `DELETE /jobs/{id}`: cancels a queued or running job
+//!
+//! Pipelines read and write files on the server's host, so it listens on
+//! localhost unless told otherwise, and every request but `/health` needs
+//! `Authorization: Bearer <token>`. Specs whose `notify` runs a command are
+//! refused: only webhooks can be notified from a submitted spec. Submitted specs
+//! are not interpolated, so `${VAR}` stays literal text.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Local};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use _phaeton::engine::results_json;
+use _phaeton::error::{PhaetonError, Result};
+use _phaeton::spec;
+use _phaeton::streaming::{CancelFlag, Progress, ProgressCallback};
+
+/// Finished jobs kept for polling; the oldest go first
+const KEEP_FINISHED: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Queued => "queued",
+            Status::Running => "running",
+            Status::Completed => "completed",
+            Status::Failed => "failed",
+            Status::Cancelled => "cancelled",
+        }
+    }
+
+    fn finished(self) -> bool {
+        matches!(self, Status::Completed | Status::Failed | Status::Cancelled)
+    }
+}
+
+/// Latest progress report of one source
+#[derive(Clone, Copy, Default)]
+struct SourceProgress {
+    processed: u64,
+    saved: u64,
+    quarantined: u64,
+    bytes_read: u64,
+    total_bytes: u64,
+}
+
+struct Job {
+    status: Status,
+    pipelines: usize,
+    submitted_at: DateTime<Local>,
+    started_at: Option<DateTime<Local>>,
+    finished_at: Option<DateTime<Local>>,
+    cancel: CancelFlag,
+    progress: Arc<Mutex<HashMap<String, SourceProgress>>>,
+    /// Per-pipeline results, once finished
+    results: Option<Value>,
+}
+
+impl Job {
+    fn summary(&self, id: u64) -> Value {
+        let progress = self.progress.lock().unwrap_or_else(|p| p.into_inner());
+        let total = progress.values().fold(SourceProgress::default(), |mut sum, p| {
+            sum.processed += p.processed;
+            sum.saved += p.saved;
+            sum.quarantined += p.quarantined;
+            sum.bytes_read += p.bytes_read;
+            sum.total_bytes += p.total_bytes;
+            sum
+        });
+        let fraction = match self.status {
+            Status::Completed => Some(1.0),
+            _ if total.total_bytes > 0 => Some((total.bytes_read as f64 / total.total_bytes as f64).min(1.0)),
+            _ => None,
+        };
+        json!({
+            "id": id,
+            "status": self.status.as_str(),
+            "pipelines": self.pipelines,
+            "submitted_at": self.submitted_at.to_rfc3339(),
+            "started_at": self.started_at.map(|t| t.to_rfc3339()),
+            "finished_at": self.finished_at.map(|t| t.to_rfc3339()),
+            "progress": {
+                "processed_rows": total.processed,
+                "saved_rows": total.saved,
+                "quarantined_rows": total.quarantined,
+                "bytes_read": total.bytes_read,
+                "total_bytes": total.total_bytes,
+                "fraction": fraction,
+            },
+        })
+    }
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: AtomicU64,
+    jobs: Mutex<BTreeMap<u64, Job>>,
+}
+
+impl Jobs {
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, Job>> {
+        self.jobs.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Forget the oldest finished jobs beyond `KEEP_FINISHED`
+    fn prune(jobs: &mut BTreeMap<u64, Job>) {
+        let finished: Vec<u64> = jobs.iter().filter(|(_, j)| j.status.finished()).map(|(&id, _)| id).collect();
+        for id in finished.iter().take(finished.len().saturating_sub(KEEP_FINISHED)) {
+            jobs.remove(id);
+        }
+    }
+}
+
+struct AppState {
+    jobs: Jobs,
+    token: String,
+}
+
+type Shared = Arc<AppState>;
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+fn not_found(id: u64) -> Response {
+    error(StatusCode::NOT_FOUND, format!("No job {}", id))
+}
+
+async fn authorize(State(state): State<Shared>, request: Request, next: Next) -> Response {
+    let given = request.headers().get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if !given.is_some_and(|given| same_token(given.as_bytes(), state.token.as_bytes())) {
+        return error(StatusCode::UNAUTHORIZED, "Missing or wrong bearer token");
+    }
+    next.run(request).await
+}
+
+/// Looks at every byte whatever the first mismatch, so the response time does not
+/// tell a client how much of the token it got right
+fn same_token(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+async fn submit(State(state): State<Shared>, headers: HeaderMap, body: String) -> Response {
+    let is_json = headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+    // Submitted specs are taken literally: `${VAR}` would copy the server's environment into them
+    let spec = match spec::parse_verbatim(&body, is_json, "request body") {
+        Ok(spec) => spec,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    // A submitted spec must not run programs on the host
+    let commands = spec.notify_commands();
+    if !commands.is_empty() {
+        return error(StatusCode::FORBIDDEN, format!(
+            "notify commands are not accepted over HTTP ({}); notify a webhook URL instead", commands.join(", ")
+        ));
+    }
+    let payloads = spec.payloads();
+
+    let id = state.jobs.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let cancel: CancelFlag = Arc::new(AtomicBool::new(false));
+    let progress: Arc<Mutex<HashMap<String, SourceProgress>>> = Arc::default();
+    let job = Job {
+        status: Status::Queued,
+        pipelines: payloads.len(),
+        submitted_at: Local::now(),
+        started_at: None,
+        finished_at: None,
+        cancel: cancel.clone(),
+        progress: progress.clone(),
+        results: None,
+    };
+    let summary = job.summary(id);
+    {
+        let mut jobs = state.jobs.lock();
+        jobs.insert(id, job);
+        Jobs::prune(&mut jobs);
+    }
+    info!(job = id, pipelines = payloads.len(), "Job queued");
+
+    let worker = state.clone();
+    tokio::task::spawn_blocking(move || {
+        {
+            let mut jobs = worker.jobs.lock();
+            let Some(job) = jobs.get_mut(&id) else { return };
+            if job.status == Status::Cancelled {
+                return;
+            }
+            job.status = Status::Running;
+            job.started_at = Some(Local::now());
+        }
+        let on_progress: ProgressCallback = Arc::new(move |p: &Progress| {
+            progress.lock().unwrap_or_else(|p| p.into_inner()).insert(p.source.to_string(), SourceProgress {
+                processed: p.processed,
+                saved: p.saved,
+                quarantined: p.quarantined,
+                bytes_read: p.bytes_read,
+                total_bytes: p.total_bytes,
+            });
+        });
+        let engine = spec.engine().with_progress(Some(on_progress)).with_cancel(Some(cancel.clone()));
+        let (results, ok) = results_json(engine.execute_parallel(&payloads), &payloads);
+
+        let status = if cancel.load(Ordering::Relaxed) {
+            Status::Cancelled
+        } else if ok {
+            Status::Completed
+        } else {
+            Status::Failed
+        };
+        info!(job = id, status = status.as_str(), "Job finished");
+        if let Some(job) = worker.jobs.lock().get_mut(&id) {
+            job.status = status;
+            job.finished_at = Some(Local::now());
+            job.results = Some(results);
+        }
+    });
+
+    (StatusCode::ACCEPTED, Json(summary)).into_response()
+}
+
+async fn list(State(state): State<Shared>) -> Json<Value> {
+    let jobs = state.jobs.lock();
+    Json(Value::Array(jobs.iter().map(|(&id, job)| job.summary(id)).collect()))
+}
+
+async fn status(State(state): State<Shared>, Path(id): Path<u64>) -> Response {
+    match state.jobs.lock().get(&id) {
+        Some(job) => Json(job.summary(id)).into_response(),
+        None => not_found(id),
+    }
+}
+
+async fn stats(State(state): State<Shared>, Path(id): Path<u64>) -> Response {
+    let jobs = state.jobs.lock();
+    let Some(job) = jobs.get(&id) else { return not_found(id) };
+    match &job.results {
+        Some(results) => Json(json!({ "id": id, "status": job.status.as_str(), "results": results })).into_response(),
+        None => error(StatusCode::CONFLICT, format!("Job {} is {}; its stats are ready once it finishes", id, job.status.as_str())),
+    }
+}
+
+async fn cancel(State(state): State<Shared>, Path(id): Path<u64>) -> Response {
+    let mut jobs = state.jobs.lock();
+    let Some(job) = jobs.get_mut(&id) else { return not_found(id) };
+    match job.status {
+        Status::Queued => {
+            job.status = Status::Cancelled;
+            job.finished_at = Some(Local::now());
+            job.results = Some(Value::Array(Vec::new()));
+        },
+        Status::Running => job.cancel.store(true, Ordering::Relaxed),
+        _ => return error(StatusCode::CONFLICT, format!("Job {} has already finished", id)),
+    }
+    info!(job = id, "Job cancelled");
+    (StatusCode::ACCEPTED, Json(job.summary(id))).into_response()
+}
+
+/// Serve the job API on `addr` until the process is stopped
+pub fn serve(addr: &str, token: String) -> Result<()> {
+    let state: Shared = Arc::new(AppState { jobs: Jobs::default(), token });
+    let app = Router::new()
+        .route("/jobs", get(list).post(submit))
+        .route("/jobs/:id", get(status).delete(cancel))
+        .route("/jobs/:id/stats", get(stats))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .route("/health", get(health))
+        .with_state(state);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_io().build()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        info!(addr = %listener.local_addr()?, "Serving");
+        if !addr.starts_with("127.") && !addr.starts_with("localhost") && !addr.starts_with("[::1]") {
+            warn!(addr, "Listening beyond localhost; jobs can read and write any file the server can");
+        }
+        axum::serve(listener, app).await.map_err(PhaetonError::from)
+    })
+}
@@ -0,0 +1,386 @@
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use pythonize::{depythonize, pythonize};
+use serde_json::Value;
+
+use crate::engine::{parse_delimiter, Engine};
+use crate::notify::Notifier;
+use crate::processors::drift::DriftOptions;
+use crate::steps::{self, value_error, Step};
+use crate::streaming::{ErrorStrategy, HeaderMatch, RaggedPolicy, Route};
+use crate::{progress_callback, quarantine_callback, run_interruptible, CancelToken, PipelineResult};
+
+/// Fluent pipeline builder implemented in the extension. Every method builds its
+/// step through the typed constructors in `steps`, so a typo fails at the line
+/// that made it instead of when the pipeline runs.
+#[pyclass(name = "Pipeline")]
+pub struct PipelineBuilder {
+    source: String,
+    steps: Vec<HashMap<String, Value>>,
+    settings: HashMap<String, Value>,
+    quarantine: Option<String>,
+}
+
+/// Check the source options shared by the builder and `Dataset`, as payload settings
+pub fn source_settings(
+    source: &str,
+    delimiter: Option<String>,
+    header_match: Option<String>,
+    rename_duplicates: bool,
+    ragged: Option<String>
+) -> PyResult<HashMap<String, Value>> {
+    let mut settings = HashMap::new();
+    if let Some(delimiter) = delimiter {
+        if delimiter != "auto" {
+            parse_delimiter(source, &delimiter).map_err(|e| value_error(e.to_string()))?;
+        }
+        settings.insert("delimiter".to_string(), Value::from(delimiter));
+    }
+    if let Some(header_match) = header_match {
+        HeaderMatch::parse(&header_match).map_err(|e| value_error(e.to_string()))?;
+        settings.insert("header_match".to_string(), Value::from(header_match));
+    }
+    if let Some(ragged) = ragged {
+        RaggedPolicy::parse(&ragged).map_err(|e| value_error(e.to_string()))?;
+        settings.insert("ragged".to_string(), Value::from(ragged));
+    }
+    settings.insert("rename_duplicates".to_string(), Value::from(rename_duplicates));
+    Ok(settings)
+}
+
+impl PipelineBuilder {
+    /// Append a step built (and checked) by one of the constructors in `steps`
+    fn append(mut slf: PyRefMut<'_, Self>, step: PyResult<Step>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.steps.push(step?.step);
+        Ok(slf)
+    }
+
+    fn payload(&self, output: String, quarantine: Option<String>) -> HashMap<String, Value> {
+        let mut payload = self.settings.clone();
+        payload.insert("source".to_string(), Value::from(self.source.clone()));
+        payload.insert("output".to_string(), Value::from(output));
+        payload.insert("quarantine".to_string(), quarantine.or_else(|| self.quarantine.clone()).map_or(Value::Null, Value::from));
+        payload.insert("steps".to_string(), Value::from(self.steps.iter().cloned().map(|s| Value::Object(s.into_iter().collect())).collect::<Vec<_>>()));
+        payload
+    }
+}
+
+#[pymethods]
+impl PipelineBuilder {
+    #[new]
+    #[pyo3(signature = (source, delimiter=None, header_match=None, rename_duplicates=false, ragged=None))]
+    fn new(
+        source: String,
+        delimiter: Option<String>,
+        header_match: Option<String>,
+        rename_duplicates: bool,
+        ragged: Option<String>
+    ) -> PyResult<Self> {
+        let settings = source_settings(&source, delimiter, header_match, rename_duplicates, ragged)?;
+        Ok(Self { source, steps: Vec::new(), settings, quarantine: None })
+    }
+
+    /// Keep rows whose column matches the pattern
+    #[pyo3(signature = (col, r#match, mode="exact", threshold=None, metric=None))]
+    fn keep<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, r#match: &PyAny, mode: &str, threshold: Option<f64>, metric: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::keep_step(col, r#match, mode, threshold, metric))
+    }
+
+    /// Drop rows whose column matches the pattern
+    #[pyo3(signature = (col, r#match, mode="exact", threshold=None, metric=None))]
+    fn discard<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, r#match: &PyAny, mode: &str, threshold: Option<f64>, metric: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::discard_step(col, r#match, mode, threshold, metric))
+    }
+
+    /// Drop rows with empty values in the column(s), or in any column
+    #[pyo3(signature = (col=None))]
+    fn prune<'py>(slf: PyRefMut<'py, Self>, col: Option<&PyAny>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::prune_step(col))
+    }
+
+    #[pyo3(signature = (col, mode, transliterate=false, collapse_whitespace=false, decimal=None, chars=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn scrub<'py>(
+        slf: PyRefMut<'py, Self>,
+        col: &PyAny,
+        mode: &str,
+        transliterate: bool,
+        collapse_whitespace: bool,
+        decimal: Option<&str>,
+        chars: Option<&str>
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::scrub_step(col, mode, transliterate, collapse_whitespace, decimal, chars))
+    }
+
+    #[pyo3(signature = (col, dtype, clean=false, on_error="quarantine", format=None, precision=None, scale=None, validate_only=false, true_values=None, false_values=None, percent=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn cast<'py>(
+        slf: PyRefMut<'py, Self>,
+        col: &PyAny,
+        dtype: &str,
+        clean: bool,
+        on_error: &str,
+        format: Option<&PyAny>,
+        precision: Option<u32>,
+        scale: Option<u32>,
+        validate_only: bool,
+        true_values: Option<Vec<String>>,
+        false_values: Option<Vec<String>>,
+        percent: Option<&str>
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::cast_step(col, dtype, clean, on_error, format, precision, scale, validate_only, true_values, false_values, percent))
+    }
+
+    #[pyo3(signature = (col, value=None, method="fixed"))]
+    fn fill<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, value: Option<&PyAny>, method: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::fill_step(col, value, method))
+    }
+
+    #[pyo3(signature = (col=None, store=None))]
+    fn dedupe<'py>(slf: PyRefMut<'py, Self>, col: Option<&PyAny>, store: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::dedupe_step(col, store))
+    }
+
+    /// Align values to a reference list (or file) by string similarity
+    #[pyo3(signature = (col, r#ref, threshold=0.85, metric="jaro_winkler", audit=false, ref_col=None, phonetic=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn align<'py>(
+        slf: PyRefMut<'py, Self>,
+        col: &PyAny,
+        r#ref: &PyAny,
+        threshold: f64,
+        metric: &str,
+        audit: bool,
+        ref_col: Option<String>,
+        phonetic: Option<String>
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::align_step(col, r#ref, threshold, metric, audit, ref_col, phonetic))
+    }
+
+    #[pyo3(signature = (col, mapping, default=None))]
+    fn map<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, mapping: HashMap<String, String>, default: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::map_step(col, mapping, default))
+    }
+
+    #[pyo3(signature = (col, salt=None))]
+    fn hash<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, salt: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::hash_step(col, salt))
+    }
+
+    #[pyo3(signature = (col, salt=None, keep_first=0, keep_last=0, luhn=false))]
+    fn mask<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, salt: Option<String>, keep_first: usize, keep_last: usize, luhn: bool) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::mask_step(col, salt, keep_first, keep_last, luhn))
+    }
+
+    #[pyo3(signature = (col, vault, key_env="PHAETON_VAULT_KEY"))]
+    fn tokenize<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, vault: String, key_env: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::tokenize_step(col, vault, key_env))
+    }
+
+    #[pyo3(signature = (col, vault, key_env="PHAETON_VAULT_KEY"))]
+    fn detokenize<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, vault: String, key_env: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::detokenize_step(col, vault, key_env))
+    }
+
+    #[pyo3(signature = (name, value=None, kind="constant"))]
+    fn add_column<'py>(slf: PyRefMut<'py, Self>, name: String, value: Option<&PyAny>, kind: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::add_column_step(name, value, kind))
+    }
+
+    #[pyo3(signature = (col, currency_col, to, rates, decimals=2, clean=false))]
+    fn convert_currency<'py>(
+        slf: PyRefMut<'py, Self>,
+        col: &PyAny,
+        currency_col: &PyAny,
+        to: String,
+        rates: &PyAny,
+        decimals: u32,
+        clean: bool
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::convert_currency_step(col, currency_col, to, rates, decimals, clean))
+    }
+
+    fn json_extract<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, path: String, into: String) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::json_extract_step(col, path, into))
+    }
+
+    #[pyo3(signature = (template, col=None, into=None))]
+    fn format<'py>(slf: PyRefMut<'py, Self>, template: String, col: Option<&PyAny>, into: Option<String>) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::format_step(template, col, into))
+    }
+
+    #[pyo3(signature = (col, format="alpha2", threshold=0.9))]
+    fn country<'py>(slf: PyRefMut<'py, Self>, col: &PyAny, format: &str, threshold: f64) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::country_step(col, format, threshold))
+    }
+
+    #[pyo3(signature = (col=None, into="_fingerprint"))]
+    fn fingerprint<'py>(slf: PyRefMut<'py, Self>, col: Option<&PyAny>, into: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::fingerprint_step(col, into))
+    }
+
+    #[pyo3(signature = (rule, col=None, mostly=1.0, values=None, pattern=None, min=None, max=None, severity="warn"))]
+    #[allow(clippy::too_many_arguments)]
+    fn expect<'py>(
+        slf: PyRefMut<'py, Self>,
+        rule: &str,
+        col: Option<&PyAny>,
+        mostly: f64,
+        values: Option<&PyAny>,
+        pattern: Option<String>,
+        min: Option<u64>,
+        max: Option<u64>,
+        severity: &str
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::expect_step(rule, col, mostly, values, pattern, min, max, severity))
+    }
+
+    fn rename(slf: PyRefMut<'_, Self>, mapping: HashMap<String, String>) -> PyResult<PyRefMut<'_, Self>> {
+        Self::append(slf, steps::rename_step(mapping))
+    }
+
+    #[pyo3(signature = (style="snake"))]
+    fn headers<'py>(slf: PyRefMut<'py, Self>, style: &str) -> PyResult<PyRefMut<'py, Self>> {
+        Self::append(slf, steps::headers_step(style))
+    }
+
+    /// Append steps built by the typed constructors (`Scrub(...)`, `Cast(...)`, ...)
+    #[pyo3(signature = (*steps))]
+    fn add(mut slf: PyRefMut<'_, Self>, steps: Vec<Step>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.steps.extend(steps.into_iter().map(|s| s.step));
+        Ok(slf)
+    }
+
+    /// How rows that fail a step or cannot be parsed are handled: quarantine, skip or fail
+    fn on_error<'py>(mut slf: PyRefMut<'py, Self>, strategy: &str) -> PyResult<PyRefMut<'py, Self>> {
+        ErrorStrategy::parse(strategy).map_err(|e| value_error(e.to_string()))?;
+        slf.settings.insert("on_error".to_string(), Value::from(strategy));
+        Ok(slf)
+    }
+
+    /// Where rejected rows go; `raw` adds the source text of malformed records
+    #[pyo3(signature = (path, raw=false))]
+    fn quarantine<'py>(mut slf: PyRefMut<'py, Self>, path: String, raw: bool) -> PyResult<PyRefMut<'py, Self>> {
+        slf.quarantine = Some(path);
+        slf.settings.insert("quarantine_raw".to_string(), Value::from(raw));
+        Ok(slf)
+    }
+
+    /// Write a lineage sidecar (source and pipeline hashes, steps, timestamps, row
+    /// counts) to `path`, or to `<output>.lineage.json` without one
+    #[pyo3(signature = (path=None))]
+    fn lineage(mut slf: PyRefMut<'_, Self>, path: Option<String>) -> PyRefMut<'_, Self> {
+        slf.settings.insert("lineage".to_string(), path.map_or(Value::Bool(true), Value::from));
+        slf
+    }
+
+    /// After the run, write a manifest with the size and SHA-256 of every file it
+    /// produced to `path`, or to `<output>.manifest.json` without one. With `verify`,
+    /// the files are read back first and the run fails if their row counts differ.
+    #[pyo3(signature = (path=None, verify=true))]
+    fn manifest(mut slf: PyRefMut<'_, Self>, path: Option<String>, verify: bool) -> PyRefMut<'_, Self> {
+        slf.settings.insert("manifest".to_string(), serde_json::json!({ "path": path, "verify": verify }));
+        slf
+    }
+
+    /// Remember in the JSON file `state` how far the source was read, so the next
+    /// run skips it when unchanged and only reads the rows added since
+    fn incremental(mut slf: PyRefMut<'_, Self>, state: String) -> PyRefMut<'_, Self> {
+        slf.settings.insert("state".to_string(), Value::from(state));
+        slf
+    }
+
+    /// Compare the statistics of the output with the baseline at `baseline` after the
+    /// run, reporting shifts as warnings; the first run creates the baseline
+    #[pyo3(signature = (baseline, null_factor=3.0, mean_tolerance=1.0, update=false))]
+    fn drift(mut slf: PyRefMut<'_, Self>, baseline: String, null_factor: f64, mean_tolerance: f64, update: bool) -> PyResult<PyRefMut<'_, Self>> {
+        DriftOptions { null_factor, mean_tolerance, update }.check()?;
+        slf.settings.insert("drift".to_string(), serde_json::json!({
+            "baseline": baseline, "null_factor": null_factor, "mean_tolerance": mean_tolerance, "update": update,
+        }));
+        Ok(slf)
+    }
+
+    /// Send the kept rows matching `when` to `output` instead of the pipeline's output,
+    /// or drop them when `output` is None. Routes are tried in the order they were added.
+    #[pyo3(signature = (name, when, output=None, delimiter=None, columns=None, header=true))]
+    fn route<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: String,
+        when: &PyAny,
+        output: Option<String>,
+        delimiter: Option<String>,
+        columns: Option<Vec<String>>,
+        header: bool
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let when = depythonize(when).map_err(|e| value_error(format!("Invalid condition of route '{}': {}", name, e)))?;
+        let route = Route { name, when, output, delimiter, columns, header };
+        let route = serde_json::to_value(route).map_err(|e| value_error(e.to_string()))?;
+        match slf.settings.entry("routes".to_string()).or_insert_with(|| Value::Array(Vec::new())) {
+            Value::Array(routes) => routes.push(route),
+            other => *other = Value::Array(vec![route]),
+        }
+        Ok(slf)
+    }
+
+    /// When the run finishes, POST its stats as JSON to `target` when it is an http(s)
+    /// URL, or run it as a shell command with the stats on stdin. `on` is always,
+    /// success or failure; `format="slack"` posts a one-line `text` message instead.
+    /// A notification that cannot be delivered becomes a warning of the run.
+    #[pyo3(signature = (target, on="always", format="json", headers=None, timeout=10.0))]
+    fn notify<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        target: String,
+        on: &str,
+        format: &str,
+        headers: Option<HashMap<String, String>>,
+        timeout: f64
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let kind = if target.starts_with("http://") || target.starts_with("https://") { "url" } else { "command" };
+        let notifier = serde_json::json!({ kind: target, "on": on, "format": format, "headers": headers, "timeout": timeout });
+        Notifier::from_setting(&notifier)?;
+        match slf.settings.entry("notify".to_string()).or_insert_with(|| Value::Array(Vec::new())) {
+            Value::Array(notifiers) => notifiers.push(notifier),
+            other => *other = Value::Array(vec![notifier]),
+        }
+        Ok(slf)
+    }
+
+    /// The recorded steps, as the dicts `execute_pipeline` accepts
+    #[getter]
+    fn steps(&self, py: Python) -> PyResult<PyObject> {
+        pythonize(py, &self.steps).map_err(|e| value_error(e.to_string()))
+    }
+
+    /// Run the pipeline, writing kept rows to `output`. `on_quarantine` receives the
+    /// rejected rows as lists of dicts while the run goes on.
+    #[pyo3(signature = (output, quarantine=None, progress=None, cancel=None, on_quarantine=None))]
+    fn run(
+        &self,
+        py: Python,
+        output: String,
+        quarantine: Option<String>,
+        progress: Option<PyObject>,
+        cancel: Option<CancelToken>,
+        on_quarantine: Option<PyObject>
+    ) -> PyResult<PipelineResult> {
+        let payload = self.payload(output, quarantine);
+
+        let cancel = cancel.unwrap_or_default().flag;
+        let engine = Engine::new(0, 10000)
+            .with_progress(progress_callback(progress))
+            .with_cancel(Some(cancel.clone()))
+            .with_on_quarantine(quarantine_callback(on_quarantine));
+        let report = run_interruptible(py, &cancel, || engine.execute_single(payload))?;
+
+        Ok(report.into())
+    }
+
+    fn __len__(&self) -> usize {
+        self.steps.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Pipeline source='{}' steps={}>", self.source, self.steps.len())
+    }
+}
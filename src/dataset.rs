@@ -0,0 +1,303 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use pythonize::{depythonize, pythonize};
+use serde_json::Value;
+
+use crate::builder::source_settings;
+use crate::engine::{file_payloads, Engine, PreparedSteps};
+use crate::processors::probe::{detect_file_metadata, ProbeOptions};
+use crate::processors::validate::{self, Level};
+use crate::steps::{value_error, Step};
+use crate::streaming::{ErrorStrategy, HeaderMatch};
+use crate::{batch_results, progress_callback, quarantine_callback, run_interruptible, CancelToken, PipelineResult};
+
+/// A collection of files sharing one schema (e.g. a day of store exports),
+/// probed and cleaned as a unit. Files are resolved once, when the dataset is made.
+#[pyclass]
+pub struct Dataset {
+    files: Vec<String>,
+    settings: HashMap<String, Value>,
+    workers: usize,
+    batch_size: usize,
+}
+
+/// Steps from a list of dicts / `Step`s, a native `Pipeline` builder (`steps`)
+/// or a Python `Pipeline` (`_steps`)
+fn steps_from(steps: &PyAny) -> PyResult<Vec<HashMap<String, Value>>> {
+    let steps = if steps.is_instance_of::<PyList>() || steps.is_instance_of::<PyTuple>() {
+        steps
+    } else if steps.hasattr("_steps")? {
+        steps.getattr("_steps")?
+    } else {
+        steps.getattr("steps")?
+    };
+    steps.iter()?.map(|item| {
+        let item = item?;
+        match item.extract::<Step>() {
+            Ok(step) => Ok(step.step),
+            Err(_) => depythonize(item).map_err(|e| value_error(format!("Invalid step: {}", e))),
+        }
+    }).collect()
+}
+
+/// Glob pattern (sorted matches) or explicit list of paths
+fn resolve_files(source: &PyAny) -> PyResult<Vec<String>> {
+    if let Ok(pattern) = source.extract::<String>() {
+        let paths = glob::glob(&pattern).map_err(|e| value_error(format!("Invalid pattern '{}': {}", pattern, e)))?;
+        let mut files: Vec<String> = paths.filter_map(|p| p.ok())
+            .filter(|p| p.is_file())
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        if files.is_empty() {
+            return Err(value_error(format!("No files match '{}'", pattern)));
+        }
+        return Ok(files);
+    }
+    let paths: Vec<PathBuf> = source.extract()?;
+    if paths.is_empty() {
+        return Err(value_error("Dataset needs at least one file"));
+    }
+    Ok(paths.into_iter().map(|p| p.to_string_lossy().into_owned()).collect())
+}
+
+#[pymethods]
+impl Dataset {
+    #[new]
+    #[pyo3(signature = (source, delimiter=None, header_match=None, rename_duplicates=false, ragged=None, workers=0, batch_size=10000))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        source: &PyAny,
+        delimiter: Option<String>,
+        header_match: Option<String>,
+        rename_duplicates: bool,
+        ragged: Option<String>,
+        workers: usize,
+        batch_size: usize
+    ) -> PyResult<Self> {
+        let files = resolve_files(source)?;
+        let settings = source_settings(&files[0], delimiter, header_match, rename_duplicates, ragged)?;
+        Ok(Self { files, settings, workers, batch_size })
+    }
+
+    #[getter]
+    fn files(&self) -> Vec<String> {
+        self.files.clone()
+    }
+
+    /// Probe every file in parallel and compare its headers to the first readable
+    /// one. Returns 'headers' (the reference schema), 'consistent' and 'files', one
+    /// entry per file with 'path', 'ok', 'encoding', 'delimiter', 'headers',
+    /// 'estimated_rows', 'missing' and 'extra' columns, and 'error'.
+    #[pyo3(signature = (sample_rows=100))]
+    fn probe(&self, py: Python, sample_rows: usize) -> PyResult<PyObject> {
+        let options = ProbeOptions { sample_rows, ..ProbeOptions::default() };
+        let probes: Vec<Result<Value, String>> = py.allow_threads(|| {
+            self.files.par_iter()
+                .map(|path| detect_file_metadata(path, &options)
+                    .and_then(|meta| Ok(serde_json::to_value(meta)?))
+                    .map_err(|e| e.to_string()))
+                .collect()
+        });
+
+        let header_match = self.settings.get("header_match").and_then(Value::as_str)
+            .map(HeaderMatch::parse).transpose().map_err(|e| value_error(e.to_string()))?
+            .unwrap_or_default();
+        let key = |name: &str| match header_match {
+            HeaderMatch::Exact => name.to_string(),
+            HeaderMatch::Trim => name.trim().to_string(),
+            HeaderMatch::IgnoreCase => name.trim().to_lowercase(),
+        };
+        let headers_of = |meta: &Value| -> Vec<String> {
+            meta.get("headers").and_then(|h| serde_json::from_value(h.clone()).ok()).unwrap_or_default()
+        };
+        let reference: Vec<String> = probes.iter().find_map(|p| p.as_ref().ok()).map(headers_of).unwrap_or_default();
+        let reference_keys: Vec<String> = reference.iter().map(|h| key(h)).collect();
+
+        let mut consistent = true;
+        let files: Vec<Value> = self.files.iter().zip(&probes).map(|(path, probe)| match probe {
+            Ok(meta) => {
+                let headers = headers_of(meta);
+                let keys: Vec<String> = headers.iter().map(|h| key(h)).collect();
+                let missing: Vec<&String> = reference.iter().zip(&reference_keys).filter(|(_, k)| !keys.contains(k)).map(|(h, _)| h).collect();
+                let extra: Vec<&String> = headers.iter().zip(&keys).filter(|(_, k)| !reference_keys.contains(k)).map(|(h, _)| h).collect();
+                consistent &= missing.is_empty() && extra.is_empty();
+                serde_json::json!({
+                    "path": path,
+                    "ok": true,
+                    "encoding": meta.get("encoding"),
+                    "delimiter": meta.get("delimiter"),
+                    "headers": headers,
+                    "estimated_rows": meta.get("estimated_rows"),
+                    "missing": missing,
+                    "extra": extra,
+                    "error": Value::Null,
+                })
+            },
+            Err(error) => {
+                consistent = false;
+                serde_json::json!({ "path": path, "ok": false, "error": error })
+            },
+        }).collect();
+
+        let report = serde_json::json!({ "headers": reference, "consistent": consistent, "files": files });
+        pythonize(py, &report).map_err(|e| value_error(e.to_string()))
+    }
+
+    /// Run the steps over every file in parallel. Outputs keep their file names under
+    /// `output_dir`; rejected rows go to `<name>_quarantine.csv` under `quarantine_dir`.
+    /// A failing file does not stop the others. `lineage` writes `<file>.lineage.json`
+    /// next to each output. With a `state` file, files seen in full by an earlier run
+    /// are skipped and grown ones only have their new rows appended.
+    #[pyo3(signature = (steps, output_dir, quarantine_dir=None, on_error=None, progress=None, cancel=None, on_quarantine=None, lineage=false, state=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &self,
+        py: Python,
+        steps: &PyAny,
+        output_dir: PathBuf,
+        quarantine_dir: Option<PathBuf>,
+        on_error: Option<String>,
+        progress: Option<PyObject>,
+        cancel: Option<CancelToken>,
+        on_quarantine: Option<PyObject>,
+        lineage: bool,
+        state: Option<String>
+    ) -> PyResult<DatasetResult> {
+        let steps = steps_from(steps)?;
+        if let Some(d) = steps.iter().enumerate()
+            .flat_map(|(i, step)| validate::check_step(i, step, true))
+            .find(|d| d.level == Level::Error)
+        {
+            return Err(value_error(format!("Invalid step {} ({}): {}", d.step.unwrap_or(0), d.action, d.message)));
+        }
+
+        let mut settings = self.settings.clone();
+        if let Some(on_error) = on_error {
+            ErrorStrategy::parse(&on_error).map_err(|e| value_error(e.to_string()))?;
+            settings.insert("on_error".to_string(), Value::from(on_error));
+        }
+        if lineage {
+            settings.insert("lineage".to_string(), Value::Bool(true));
+        }
+        if let Some(state) = state {
+            settings.insert("state".to_string(), Value::from(state));
+        }
+        std::fs::create_dir_all(&output_dir)?;
+        if let Some(dir) = &quarantine_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+        let payloads = file_payloads(&settings, &self.files, &output_dir, quarantine_dir.as_deref());
+
+        let cancel = cancel.unwrap_or_default().flag;
+        let engine = Engine::new(self.workers, self.batch_size)
+            .with_prepared(Some(Arc::new(PreparedSteps::new(steps))))
+            .with_progress(progress_callback(progress))
+            .with_cancel(Some(cancel.clone()))
+            .with_on_quarantine(quarantine_callback(on_quarantine));
+        let start = Instant::now();
+        let reports = run_interruptible(py, &cancel, || engine.execute_parallel(&payloads));
+
+        DatasetResult::new(py, batch_results(reports, &payloads), start.elapsed().as_secs_f64())
+    }
+
+    fn __len__(&self) -> usize {
+        self.files.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Dataset files={}>", self.files.len())
+    }
+}
+
+/// Per-file results of a dataset run plus their totals
+#[pyclass(get_all)]
+pub struct DatasetResult {
+    files: Vec<Py<PipelineResult>>,
+    processed: u64,
+    saved: u64,
+    quarantined: u64,
+    skipped: u64,
+    bytes_read: u64,
+    /// Wall time of the whole run in seconds
+    duration: f64,
+    /// Files whose run failed
+    failed: usize,
+    cancelled: bool,
+}
+
+impl DatasetResult {
+    fn new(py: Python, results: Vec<PipelineResult>, duration: f64) -> PyResult<Self> {
+        let mut totals = Self {
+            files: Vec::with_capacity(results.len()),
+            processed: 0,
+            saved: 0,
+            quarantined: 0,
+            skipped: 0,
+            bytes_read: 0,
+            duration,
+            failed: 0,
+            cancelled: false,
+        };
+        for result in results {
+            totals.processed += result.processed;
+            totals.saved += result.saved;
+            totals.quarantined += result.quarantined;
+            totals.skipped += result.skipped;
+            totals.bytes_read += result.bytes_read;
+            totals.failed += usize::from(result.error.is_some());
+            totals.cancelled |= result.cancelled;
+            totals.files.push(Py::new(py, result)?);
+        }
+        Ok(totals)
+    }
+}
+
+#[pymethods]
+impl DatasetResult {
+    /// True when every file ran
+    #[getter]
+    fn ok(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Results of the files that failed, with their `error`
+    #[getter]
+    fn failures(&self, py: Python) -> Vec<Py<PipelineResult>> {
+        self.files.iter().filter(|r| r.borrow(py).error.is_some()).map(|r| r.clone_ref(py)).collect()
+    }
+
+    /// Totals plus the per-file `to_dict()` entries under 'files'
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("processed_rows", self.processed)?;
+        dict.set_item("saved_rows", self.saved)?;
+        dict.set_item("quarantined_rows", self.quarantined)?;
+        dict.set_item("skipped_rows", self.skipped)?;
+        dict.set_item("bytes_read", self.bytes_read)?;
+        dict.set_item("duration_ms", (self.duration * 1000.0).round() as u64)?;
+        dict.set_item("failed", self.failed)?;
+        dict.set_item("cancelled", self.cancelled)?;
+        let files = self.files.iter()
+            .map(|r| r.call_method0(py, "to_dict"))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item("files", files)?;
+        Ok(dict.into())
+    }
+
+    fn __len__(&self) -> usize {
+        self.files.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<DatasetResult files={} failed={} | processed={} saved={} quarantined={} ({:.3}s)>",
+            self.files.len(), self.failed, self.processed, self.saved, self.quarantined, self.duration
+        )
+    }
+}
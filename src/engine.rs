@@ -1,12 +1,105 @@
 use rayon::prelude::*;
 use std::collections::HashMap;
-use crate::streaming::StreamProcessor;
-use crate::error::Result;
-use std::time::Instant;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use arrow::ffi_stream::ArrowArrayStreamReader;
+use crate::streaming::{ArrowTable, StreamProcessor, ProgressCallback, QuarantineCallback, QuarantineHook, CancelFlag, ErrorStrategy, HeaderMatch, RaggedPolicy, ReadMode, DEFAULT_BATCH_MEMORY, ExecutionStats, Route, SourcePosition, StepCache};
+use crate::processors::drift::{self, DriftOptions, DriftReport};
+use crate::processors::expect::Severity;
+use crate::processors::validate::{self, Level};
+use crate::error::{PhaetonError, Result};
+use serde_json::Value;
+use crate::metrics::{self, RunOutcome};
+use crate::lineage;
+use crate::manifest;
+use crate::notify::{self, Notifier};
+use crate::state::{self, Increment};
+use chrono::Local;
+use tracing::{info, warn};
+
+/// Outcome of one pipeline: where it read and wrote, its stats, and non-fatal warnings
+pub struct PipelineReport {
+    pub source: String,
+    pub output: String,
+    pub quarantine: Option<String>,
+    pub stats: ExecutionStats,
+    pub warnings: Vec<String>,
+    /// Sidecar written by the `lineage` setting
+    pub lineage: Option<String>,
+    /// How the source compared to the `state` file: new, appended, unchanged or changed
+    pub increment: Option<&'static str>,
+    /// Output statistics compared to the baseline of the `drift` setting
+    pub drift: Option<DriftReport>,
+    /// Checksum manifest written by the `manifest` setting
+    pub manifest: Option<String>,
+}
+
+impl PipelineReport {
+    /// Flat stats using the same keys as `PipelineResult.to_dict()`
+    pub fn to_json(&self) -> Value {
+        let stats = &self.stats;
+        serde_json::json!({
+            "source": self.source,
+            "output": self.output,
+            "quarantine": self.quarantine,
+            "processed_rows": stats.processed,
+            "saved_rows": stats.saved,
+            "quarantined_rows": stats.quarantined,
+            "skipped_rows": stats.skipped,
+            "malformed_rows": stats.malformed,
+            "bytes_read": stats.bytes_read,
+            "duration_ms": (stats.duration.as_secs_f64() * 1000.0).round() as u64,
+            "rows_per_sec": stats.rows_per_sec,
+            "bytes_per_sec": stats.bytes_per_sec,
+            "cancelled": stats.cancelled,
+            "steps": stats.steps.iter()
+                .map(|s| serde_json::json!({ "step": s.step, "action": s.action, "rejected": s.rejected }))
+                .collect::<Vec<_>>(),
+            "warnings": self.warnings,
+            "lineage": self.lineage,
+            "increment": self.increment,
+            "drift": self.drift,
+            "manifest": self.manifest,
+            "routes": stats.routes,
+            "expectations": stats.expectations,
+            "error": self.expectation_error(),
+        })
+    }
+
+    /// Why the run counts as failed when an `expect` step of severity 'error' did not
+    /// hold; the outputs are written all the same
+    pub fn expectation_error(&self) -> Option<String> {
+        let failed: Vec<String> = self.stats.expectations.iter()
+            .filter(|e| !e.passed && e.severity == Severity::Error)
+            .map(|e| e.summary())
+            .collect();
+        (!failed.is_empty()).then(|| format!("Expectations failed: {}", failed.join("; ")))
+    }
+}
+
+/// Steps parsed once and shared by every run of a prepared pipeline
+pub struct PreparedSteps {
+    pub steps: Vec<HashMap<String, Value>>,
+    pub cache: Arc<StepCache>,
+}
+
+impl PreparedSteps {
+    pub fn new(steps: Vec<HashMap<String, Value>>) -> Self {
+        Self { steps, cache: Arc::new(StepCache::default()) }
+    }
+}
 
 pub struct Engine {
-    workers: usize,
     batch_size: usize,
+    progress: Option<ProgressCallback>,
+    cancel: Option<CancelFlag>,
+    /// Engine-wide settings used when a payload does not carry its own
+    defaults: HashMap<String, Value>,
+    /// Replaces the `steps` of every payload
+    prepared: Option<Arc<PreparedSteps>>,
+    /// Rows of the next run, read from an Arrow stream instead of the payload's source
+    arrow_input: Mutex<Option<ArrowArrayStreamReader>>,
+    on_quarantine: Option<QuarantineCallback>,
 }
 
 impl Engine {
@@ -27,50 +120,412 @@ impl Engine {
             .ok(); // Ignore if already built
         
         Self { 
-            workers: actual_workers,
-            batch_size: if batch_size == 0 { 10_000 } else { batch_size } 
+            batch_size: if batch_size == 0 { 10_000 } else { batch_size },
+            progress: None,
+            cancel: None,
+            defaults: HashMap::new(),
+            prepared: None,
+            arrow_input: Mutex::new(None),
+            on_quarantine: None,
         }
     }
 
+    /// Report per-batch progress of every pipeline run by this engine
+    pub fn with_progress(mut self, progress: Option<ProgressCallback>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Share one cancellation flag across every pipeline run by this engine
+    pub fn with_cancel(mut self, cancel: Option<CancelFlag>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Fallback settings (batch_size, batch_memory, on_error, delimiter, output_delimiter, preserve_order, read_mode, ragged, header_match, rename_duplicates, strict_steps, lineage, state, drift, routes, manifest, notify) for payloads
+    pub fn with_defaults(mut self, defaults: HashMap<String, Value>) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Run these steps, compiled once per header layout, instead of each payload's own
+    pub fn with_prepared(mut self, prepared: Option<Arc<PreparedSteps>>) -> Self {
+        self.prepared = prepared;
+        self
+    }
+
+    /// Hand the quarantined rows of every run to `callback`, `quarantine_batch`
+    /// rows at a time (a payload setting, default 1000)
+    pub fn with_on_quarantine(mut self, callback: Option<QuarantineCallback>) -> Self {
+        self.on_quarantine = callback;
+        self
+    }
+
+    /// Feed the next run from an Arrow stream (a polars or pyarrow frame); the
+    /// payload's `source` then only names the run in reports and logs
+    pub fn with_arrow_input(mut self, input: Option<ArrowArrayStreamReader>) -> Self {
+        self.arrow_input = Mutex::new(input);
+        self
+    }
+
+    /// Resolve a setting: the payload itself, then its embedded `config`, then the engine defaults
+    fn setting<'a>(&'a self, payload: &'a HashMap<String, Value>, key: &str) -> Option<&'a Value> {
+        payload.get(key)
+            .or_else(|| payload.get("config").and_then(|c| c.get(key)))
+            .or_else(|| self.defaults.get(key))
+            .filter(|v| !v.is_null())
+    }
+
+    fn delimiter(&self, payload: &HashMap<String, Value>, key: &str, source: &str) -> Result<Option<u8>> {
+        self.setting(payload, key)
+            .and_then(|v| v.as_str())
+            .map(|d| parse_delimiter(source, d))
+            .transpose()
+    }
+
     /// Execute single pipeline (non-parallel)
-    pub fn execute_single(&self, payload: HashMap<String, serde_json::Value>) -> Result<HashMap<String, u64>> {
-        let start = Instant::now();
-        
+    pub fn execute_single(&self, payload: HashMap<String, Value>) -> Result<PipelineReport> {
+        let notifiers = self.setting(&payload, "notify").map(Notifier::from_setting).transpose()?.unwrap_or_default();
+        if notifiers.is_empty() {
+            return self.execute_one(payload);
+        }
+        let source = payload.get("source").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let output = payload.get("output").and_then(|v| v.as_str()).unwrap_or("output.csv").to_string();
+
+        // The run's outcome is handed on whatever it is; a notification that
+        // cannot be delivered is a warning, never a failed run
+        let mut result = self.execute_one(payload);
+        let event = notify::event(&result, &source, &output);
+        for failure in notify::deliver(&notifiers, &event) {
+            warn!(source = %source, error = %failure, "Notification not delivered");
+            if let Ok(report) = &mut result {
+                report.warnings.push(failure);
+            }
+        }
+        result
+    }
+
+    fn execute_one(&self, payload: HashMap<String, Value>) -> Result<PipelineReport> {
+        let output = payload.get("output")
+            .and_then(|v| v.as_str())
+            .unwrap_or("output.csv")
+            .to_string();
+
+        // `true` puts the sidecar next to the output, a string names it
+        let lineage = match self.setting(&payload, "lineage") {
+            Some(Value::Bool(true)) => Some(lineage::default_path(&output)),
+            Some(Value::String(path)) => Some(path.clone()),
+            _ => None,
+        };
+        let steps = match (&lineage, &self.prepared) {
+            (None, _) => Value::Null,
+            (Some(_), Some(prepared)) => serde_json::to_value(&prepared.steps)?,
+            (Some(_), None) => payload.get("steps").cloned().unwrap_or_else(|| Value::Array(Vec::new())),
+        };
+        let drift = self.setting(&payload, "drift").map(DriftOptions::from_setting).transpose()?;
+        // The output is profiled with the delimiter it was written with
+        let drift_source = payload.get("source").and_then(|v| v.as_str()).unwrap_or_default();
+        let drift_delimiter = match drift {
+            Some(_) => match self.delimiter(&payload, "output_delimiter", drift_source)? {
+                Some(d) => Some(d),
+                None => self.delimiter(&payload, "delimiter", drift_source)?,
+            },
+            None => None,
+        };
+        let manifest = self.setting(&payload, "manifest").map(|m| manifest::from_setting(m, &output)).transpose()?.flatten();
+        let started_at = Local::now();
+
+        // Incremental runs skip the sources the state file has seen in full and
+        // append what was added to the others
+        let state_path = self.setting(&payload, "state").and_then(|v| v.as_str()).map(str::to_string)
+            .filter(|_| self.arrow_input.lock().is_ok_and(|input| input.is_none()));
+        let source = payload.get("source").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let increment = state_path.as_deref().map(|path| state::check(path, &source)).transpose()?;
+        if increment == Some(Increment::Unchanged) {
+            info!(source = %source, "Source unchanged since the last run, skipped");
+            let quarantine = payload.get("quarantine").and_then(|v| v.as_str()).map(str::to_string);
+            return Ok(PipelineReport {
+                source,
+                output,
+                quarantine,
+                stats: ExecutionStats::default(),
+                warnings: Vec::new(),
+                lineage: None,
+                increment: Some(Increment::Unchanged.as_str()),
+                drift: None,
+                manifest: None,
+            });
+        }
+        let resume = match increment {
+            Some(Increment::Appended(position)) => Some(position),
+            _ => None,
+        };
+
+        let (mut report, ()) = self.run(payload, output.clone(), resume, |processor, quarantine| {
+            processor.execute(&output, quarantine).map(|stats| (stats, ()))
+        })?;
+        report.increment = increment.map(Increment::as_str);
+
+        if let Some(path) = state_path {
+            if let Err(e) = state::record(&path, &report.source, report.stats.end) {
+                warn!(source = %report.source, error = %e, "Run state not saved");
+                report.warnings.push(format!("State file {} was not updated: {}", path, e));
+            }
+        }
+
+        // Statistics of the output against the baseline; shifts are warnings, not failures
+        if let Some((baseline, options)) = drift {
+            match drift::check(&report.output, &baseline, drift_delimiter, &options) {
+                Ok(result) => {
+                    for shift in &result.shifts {
+                        warn!(source = %report.source, column = %shift.column, kind = shift.kind, "Drift against the baseline");
+                        report.warnings.push(format!("Drift: {}", shift.message));
+                    }
+                    report.drift = Some(result);
+                },
+                Err(e) => {
+                    warn!(source = %report.source, error = %e, "Drift check failed");
+                    report.warnings.push(format!("Drift baseline {} was not checked: {}", baseline, e));
+                },
+            }
+        }
+
+        if let Some(path) = lineage {
+            let document = lineage::document(&report, &steps, started_at, Local::now());
+            match lineage::write(&path, &document) {
+                Ok(()) => report.lineage = Some(path),
+                Err(e) => {
+                    warn!(source = %report.source, error = %e, "Lineage sidecar not written");
+                    report.warnings.push(format!("Lineage sidecar {} was not written: {}", path, e));
+                },
+            }
+        }
+
+        // Files whose row counts do not match the run fail it; no manifest vouches for them
+        if let Some((path, verify)) = manifest {
+            let entries = manifest::build(&report, verify, resume.is_some())
+                .inspect_err(|e| warn!(source = %report.source, error = %e, "Output verification failed"))?;
+            let document = manifest::document(&report, &entries, verify && resume.is_none());
+            match lineage::write(&path, &document) {
+                Ok(()) => report.manifest = Some(path),
+                Err(e) => {
+                    warn!(source = %report.source, error = %e, "Manifest not written");
+                    report.warnings.push(format!("Manifest {} was not written: {}", path, e));
+                },
+            }
+        }
+        Ok(report)
+    }
+
+    /// Execute a single pipeline into an in-memory Arrow table instead of an
+    /// output file. The report's `output` is empty.
+    pub fn execute_to_arrow(&self, payload: HashMap<String, Value>) -> Result<(PipelineReport, ArrowTable)> {
+        self.run(payload, String::new(), None, |processor, quarantine| processor.execute_to_arrow(quarantine))
+    }
+
+    /// Resolve the settings of one payload, build its processor and hand it to
+    /// `execute`, with the logging, metrics and warnings every run shares. A
+    /// `resume` position continues the source there and appends to the outputs.
+    fn run<T>(
+        &self,
+        payload: HashMap<String, Value>,
+        output: String,
+        resume: Option<SourcePosition>,
+        execute: impl FnOnce(&StreamProcessor, Option<&str>) -> Result<(ExecutionStats, T)>
+    ) -> Result<(PipelineReport, T)> {
         let source = payload.get("source")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| crate::error::PhaetonError::InvalidStep("Missing 'source'".into()))?
+            .ok_or_else(|| PhaetonError::InvalidStep("Missing 'source'".into()))?
             .to_string();
         
-        let steps: Vec<HashMap<String, serde_json::Value>> = payload.get("steps")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-        
-        let output = payload.get("output")
-            .and_then(|v| v.as_str())
-            .unwrap_or("output.csv");
+        let steps: Vec<HashMap<String, Value>> = match &self.prepared {
+            Some(prepared) => prepared.steps.clone(),
+            None => payload.get("steps")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+        };
         
         let quarantine = payload.get("quarantine")
-            .and_then(|v| v.as_str());
-        
-        let processor = StreamProcessor::new(source, steps, 0, self.batch_size);
-        let stats = processor.execute(output, quarantine)?;
-        
-        let mut result = HashMap::new();
-        result.insert("processed_rows".to_string(), stats.processed);
-        result.insert("saved_rows".to_string(), stats.saved);
-        result.insert("quarantined_rows".to_string(), stats.quarantined);
-        result.insert("duration_ms".to_string(), start.elapsed().as_millis() as u64);
+            .and_then(|v| v.as_str())
+            .map(|q| q.to_string());
+
+        let on_error = match self.setting(&payload, "on_error").and_then(|v| v.as_str()) {
+            Some(strategy) => ErrorStrategy::parse(strategy)?,
+            None => ErrorStrategy::default(),
+        };
+
+        // A row count, or "auto" to size batches from the row width and `batch_memory`
+        let (batch_size, batch_memory) = match self.setting(&payload, "batch_size") {
+            Some(Value::String(s)) if s == "auto" => {
+                let budget = self.setting(&payload, "batch_memory")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&n| n > 0)
+                    .map_or(DEFAULT_BATCH_MEMORY, |n| n as usize);
+                (self.batch_size, Some(budget))
+            },
+            Some(Value::String(s)) => {
+                return Err(PhaetonError::InvalidStep(format!("batch_size must be a positive integer or 'auto', got '{}'", s)));
+            },
+            other => (other.and_then(|v| v.as_u64()).filter(|&n| n > 0).map_or(self.batch_size, |n| n as usize), None),
+        };
+
+        let preserve_order = self.setting(&payload, "preserve_order")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let read_mode = match self.setting(&payload, "read_mode").and_then(|v| v.as_str()) {
+            Some(mode) => ReadMode::parse(mode)?,
+            None => ReadMode::default(),
+        };
+
+        let ragged = match self.setting(&payload, "ragged").and_then(|v| v.as_str()) {
+            Some(policy) => RaggedPolicy::parse(policy)?,
+            None => RaggedPolicy::default(),
+        };
+
+        let header_match = match self.setting(&payload, "header_match").and_then(|v| v.as_str()) {
+            Some(mode) => HeaderMatch::parse(mode)?,
+            None => HeaderMatch::default(),
+        };
+
+        let delimiter = self.delimiter(&payload, "delimiter", &source)?.unwrap_or(b',');
+        let output_delimiter = self.delimiter(&payload, "output_delimiter", &source)?;
         
-        Ok(result)
+        let strict = self.setting(&payload, "strict_steps").and_then(|v| v.as_bool()).unwrap_or(true);
+
+        let routes: Vec<Route> = match self.setting(&payload, "routes") {
+            Some(routes) => serde_json::from_value(routes.clone())
+                .map_err(|e| PhaetonError::InvalidStep(format!("Invalid routes: {}", e)))?,
+            None => Vec::new(),
+        };
+        if let Some(route) = routes.iter().find(|r| r.output.as_deref().is_some_and(|p| p == output || Some(p) == quarantine.as_deref())) {
+            return Err(PhaetonError::InvalidStep(format!(
+                "Route '{}' writes to '{}', which is already the pipeline's output or quarantine", route.name, route.output.as_deref().unwrap_or_default()
+            )));
+        }
+
+        // Problems that do not stop the run, such as ignored parameters
+        let mut warnings: Vec<String> = steps.iter().enumerate()
+            .flat_map(|(i, step)| validate::check_step(i, step, strict))
+            .filter(|d| d.level == Level::Warning)
+            .map(|d| format!("Step {} ({}): {}", d.step.unwrap_or(0), d.action, d.message))
+            .collect();
+
+        let processor = StreamProcessor::new(source.clone(), steps, batch_size)
+            .with_progress(self.progress.clone())
+            .with_cancel(self.cancel.clone())
+            .with_on_error(on_error)
+            .with_delimiter(delimiter)
+            .with_output_delimiter(output_delimiter)
+            .with_preserve_order(preserve_order)
+            .with_read_mode(read_mode)
+            .with_batch_memory(batch_memory)
+            .with_ragged(ragged)
+            .with_header_match(header_match)
+            .with_strict(strict)
+            .with_rename_duplicates(self.setting(&payload, "rename_duplicates").and_then(|v| v.as_bool()).unwrap_or(false))
+            .with_quarantine_raw(self.setting(&payload, "quarantine_raw").and_then(|v| v.as_bool()).unwrap_or(false))
+            .with_step_cache(self.prepared.as_ref().map(|p| p.cache.clone()))
+            .with_on_quarantine(self.on_quarantine.clone().map(|callback| QuarantineHook {
+                callback,
+                batch: self.setting(&payload, "quarantine_batch").and_then(|v| v.as_u64()).filter(|&n| n > 0).unwrap_or(1000) as usize,
+            }))
+            .with_resume(resume)
+            .with_whole_lines(self.setting(&payload, "state").is_some())
+            .with_append(resume.is_some())
+            .with_routes(routes)
+            .with_arrow_input(self.arrow_input.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take());
+        info!(source = %source, output = %output, "Pipeline started");
+        metrics::run_started();
+        let (stats, out) = execute(&processor, quarantine.as_deref())
+            .inspect_err(|e| {
+                metrics::run_finished(RunOutcome::Failed);
+                warn!(source = %source, error = %e, "Pipeline failed");
+            })?;
+        metrics::run_finished(if stats.cancelled { RunOutcome::Cancelled } else { RunOutcome::Completed });
+        info!(
+            source = %source,
+            processed = stats.processed,
+            saved = stats.saved,
+            quarantined = stats.quarantined,
+            skipped = stats.skipped,
+            malformed = stats.malformed,
+            duration_ms = stats.duration.as_millis() as u64,
+            cancelled = stats.cancelled,
+            "Pipeline finished"
+        );
+
+        warnings.extend(stats.warnings.iter().cloned());
+        warnings.extend(stats.expectations.iter()
+            .filter(|e| !e.passed && e.severity == Severity::Warn)
+            .map(|e| format!("Expectation failed: {}", e.summary())));
+        if quarantine.is_none() && stats.quarantined > 0 {
+            warnings.push(format!("{} rejected rows were dropped because no quarantine path is set", stats.quarantined));
+        }
+        for warning in &warnings {
+            warn!(source = %source, "{}", warning);
+        }
+
+        Ok((PipelineReport { source, output, quarantine, stats, warnings, lineage: None, increment: None, drift: None, manifest: None }, out))
     }
     
-    /// Execute BATCH pipelines in PARALLEL 
-    pub fn execute_parallel(&self, payloads: Vec<HashMap<String, serde_json::Value>>) -> Result<Vec<HashMap<String, u64>>> {
-        let results: Result<Vec<_>> = payloads
+    /// Execute BATCH pipelines in PARALLEL. Each payload gets its own result, in
+    /// input order, so one failing file does not discard the others.
+    pub fn execute_parallel(&self, payloads: &[HashMap<String, Value>]) -> Vec<Result<PipelineReport>> {
+        payloads
             .par_iter() // RAYON PARALLEL ITERATOR
             .map(|payload| self.execute_single(payload.clone()))
-            .collect();
-        
-        results
+            .collect()
+    }
+}
+
+/// One JSON entry per pipeline, in payload order; a failed pipeline carries its
+/// `error`. Also tells whether every pipeline succeeded.
+pub fn results_json(reports: Vec<Result<PipelineReport>>, payloads: &[HashMap<String, Value>]) -> (Value, bool) {
+    let mut ok = true;
+    let entries = reports.into_iter().zip(payloads).map(|(report, payload)| match report {
+        Ok(report) => {
+            ok &= !report.stats.cancelled && report.expectation_error().is_none();
+            report.to_json()
+        },
+        Err(e) => {
+            ok = false;
+            serde_json::json!({
+                "source": payload.get("source"),
+                "output": payload.get("output"),
+                "error": e.to_string(),
+            })
+        },
+    }).collect();
+    (Value::Array(entries), ok)
+}
+
+/// One payload per file from a template: each output keeps the file name under
+/// `output_dir`, rejected rows go to `<stem>_quarantine.csv` under `quarantine_dir`
+pub fn file_payloads(
+    template: &HashMap<String, Value>,
+    files: &[String],
+    output_dir: &Path,
+    quarantine_dir: Option<&Path>
+) -> Vec<HashMap<String, Value>> {
+    files.iter().map(|file| {
+        let name = Path::new(file).file_name().map_or_else(|| file.clone(), |n| n.to_string_lossy().into_owned());
+        let stem = Path::new(&name).file_stem().map_or_else(|| name.clone(), |s| s.to_string_lossy().into_owned());
+        let mut payload = template.clone();
+        payload.insert("source".to_string(), Value::from(file.as_str()));
+        payload.insert("output".to_string(), Value::from(output_dir.join(&name).to_string_lossy().into_owned()));
+        payload.insert("quarantine".to_string(), quarantine_dir.map_or(Value::Null, |dir| {
+            Value::from(dir.join(format!("{}_quarantine.csv", stem)).to_string_lossy().into_owned())
+        }));
+        payload
+    }).collect()
+}
+
+/// Parse a delimiter setting: a single ASCII character, or "auto" to sniff it from the file
+pub fn parse_delimiter(source: &str, delimiter: &str) -> Result<u8> {
+    match delimiter {
+        "auto" => crate::processors::probe::sniff_delimiter(source),
+        d if d.len() == 1 => Ok(d.as_bytes()[0]),
+        d => Err(PhaetonError::InvalidStep(format!("Invalid delimiter '{}': expected a single ASCII character or 'auto'", d))),
     }
-}
\ No newline at end of file
+}
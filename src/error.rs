@@ -1,3 +1,6 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,7 +8,7 @@ pub enum PhaetonError {
     #[error("File not found: {0}")]
     FileNotFound(String),
     
-    #[error("Encoding detection failed: {0}")]
+    #[error("Encoding error: {0}")]
     EncodingError(String),
     
     #[error("CSV parsing error: {0}")]
@@ -17,14 +20,109 @@ pub enum PhaetonError {
     #[error("Invalid pipeline step: {0}")]
     InvalidStep(String),
     
-    #[error("Type cast failed for column '{col}': {reason}")]
-    CastError { col: String, reason: String },
+    #[error("Type cast failed for column '{col}'{}: {reason}", location(.row, .line))]
+    CastError { col: String, row: Option<u64>, line: Option<u64>, reason: String },
+    
+    #[error("Row {row} failed: {reason}")]
+    RowFailed { row: u64, reason: String },
+    
+    #[error("Invalid spec: {0}")]
+    SpecError(String),
     
     #[error("Column not found: {0}")]
     ColumnNotFound(String),
     
     #[error("Serialization error: {0}")]
     SerdeError(#[from] serde_json::Error),
+
+    #[error("Arrow error: {0}")]
+    ArrowError(#[from] arrow::error::ArrowError),
+}
+
+impl PhaetonError {
+    /// Attach the source position of the failing value to a cast error
+    pub fn at_row(self, row_num: u64, line_num: Option<u64>) -> Self {
+        match self {
+            PhaetonError::CastError { col, reason, .. } => PhaetonError::CastError { col, row: Some(row_num), line: line_num, reason },
+            other => other,
+        }
+    }
+}
+
+/// " (row 3, line 4)" for errors that know where they happened
+fn location(row: &Option<u64>, line: &Option<u64>) -> String {
+    match (row, line) {
+        (Some(r), Some(l)) => format!(" (row {}, line {})", r, l),
+        (Some(r), None) => format!(" (row {})", r),
+        _ => String::new(),
+    }
+}
+
+pub type Result<T> = std::result::Result<T, PhaetonError>;
+
+create_exception!(_phaeton, CoreError, PyRuntimeError, "Base class of the errors raised by the Rust core.");
+create_exception!(_phaeton, IoError, CoreError, "Reading or writing a file failed.");
+create_exception!(_phaeton, FileNotFoundError, IoError, "The source or a referenced file does not exist.");
+create_exception!(_phaeton, EncodingError, CoreError, "The source encoding could not be detected or decoded.");
+create_exception!(_phaeton, ParseError, CoreError, "The CSV parser could not read the source.");
+create_exception!(_phaeton, InvalidStepError, CoreError, "A step definition is invalid.");
+create_exception!(_phaeton, ColumnNotFoundError, InvalidStepError, "A step refers to a column the source does not have.");
+create_exception!(_phaeton, CastError, CoreError, "A value could not be cast; carries `column`, `row`, `line` and `reason`.");
+create_exception!(_phaeton, RowFailedError, CoreError, "A row failed under on_error='fail'; carries `row` and `reason`.");
+create_exception!(_phaeton, SpecError, CoreError, "A pipeline spec file is invalid.");
+create_exception!(_phaeton, SerializationError, CoreError, "A value could not be serialized or deserialized.");
+
+/// Each variant becomes its own Python exception class, so callers can catch
+/// specific failures instead of matching on message text
+impl From<PhaetonError> for PyErr {
+    fn from(err: PhaetonError) -> PyErr {
+        let message = err.to_string();
+        match err {
+            PhaetonError::FileNotFound(_) => FileNotFoundError::new_err(message),
+            PhaetonError::EncodingError(_) => EncodingError::new_err(message),
+            // The csv reader reports undecodable headers and fields as its own Utf8 error
+            PhaetonError::CsvError(ref e) if matches!(e.kind(), csv::ErrorKind::Utf8 { .. }) => EncodingError::new_err(message),
+            PhaetonError::CsvError(_) => ParseError::new_err(message),
+            PhaetonError::IoError(_) => IoError::new_err(message),
+            PhaetonError::InvalidStep(_) => InvalidStepError::new_err(message),
+            PhaetonError::ColumnNotFound(_) => ColumnNotFoundError::new_err(message),
+            PhaetonError::SpecError(_) => SpecError::new_err(message),
+            PhaetonError::SerdeError(_) | PhaetonError::ArrowError(_) => SerializationError::new_err(message),
+            PhaetonError::CastError { col, row, line, reason } => {
+                let py_err = CastError::new_err(message);
+                Python::with_gil(|py| {
+                    let value = py_err.value(py);
+                    let _ = value.setattr("column", col);
+                    let _ = value.setattr("row", row);
+                    let _ = value.setattr("line", line);
+                    let _ = value.setattr("reason", reason);
+                });
+                py_err
+            },
+            PhaetonError::RowFailed { row, reason } => {
+                let py_err = RowFailedError::new_err(message);
+                Python::with_gil(|py| {
+                    let value = py_err.value(py);
+                    let _ = value.setattr("row", row);
+                    let _ = value.setattr("reason", reason);
+                });
+                py_err
+            },
+        }
+    }
 }
 
-pub type Result<T> = std::result::Result<T, PhaetonError>;
\ No newline at end of file
+pub fn register(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("CoreError", py.get_type::<CoreError>())?;
+    m.add("IoError", py.get_type::<IoError>())?;
+    m.add("FileNotFoundError", py.get_type::<FileNotFoundError>())?;
+    m.add("EncodingError", py.get_type::<EncodingError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("InvalidStepError", py.get_type::<InvalidStepError>())?;
+    m.add("ColumnNotFoundError", py.get_type::<ColumnNotFoundError>())?;
+    m.add("CastError", py.get_type::<CastError>())?;
+    m.add("RowFailedError", py.get_type::<RowFailedError>())?;
+    m.add("SpecError", py.get_type::<SpecError>())?;
+    m.add("SerializationError", py.get_type::<SerializationError>())?;
+    Ok(())
+}
\ No newline at end of file
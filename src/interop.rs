@@ -0,0 +1,78 @@
+use std::ffi::CString;
+
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow::record_batch::RecordBatchIterator;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+
+use crate::error::PhaetonError;
+use crate::streaming::ArrowTable;
+
+/// Capsule name required by the Arrow PyCapsule interface for C streams
+const STREAM_CAPSULE: &str = "arrow_array_stream";
+
+/// Take the rows of a frame exporting the Arrow C stream interface (a polars
+/// DataFrame, a pyarrow Table or RecordBatchReader) without copying them
+pub fn arrow_reader(data: &PyAny) -> PyResult<ArrowArrayStreamReader> {
+    if !data.hasattr("__arrow_c_stream__")? {
+        return Err(PyTypeError::new_err(format!(
+            "Expected a frame exporting the Arrow C stream interface (__arrow_c_stream__), got {}",
+            data.get_type().name()?
+        )));
+    }
+    let capsule: &PyCapsule = data.call_method0("__arrow_c_stream__")?.downcast()?;
+    if capsule.name()?.map(|n| n.to_bytes()) != Some(STREAM_CAPSULE.as_bytes()) {
+        return Err(PyTypeError::new_err("__arrow_c_stream__ did not return an 'arrow_array_stream' capsule"));
+    }
+    // SAFETY: the capsule name guarantees it holds an FFI_ArrowArrayStream; from_raw
+    // moves the stream out and leaves a released one behind for the capsule destructor
+    let reader = unsafe { ArrowArrayStreamReader::from_raw(capsule.pointer() as *mut FFI_ArrowArrayStream) };
+    Ok(reader.map_err(PhaetonError::from)?)
+}
+
+/// Kept rows of an in-memory run. Exports the Arrow C stream interface, so
+/// `polars.DataFrame(stream)` or `pyarrow.table(stream)` take the batches
+/// without a copy.
+#[pyclass]
+pub struct ArrowStream {
+    table: ArrowTable,
+}
+
+impl From<ArrowTable> for ArrowStream {
+    fn from(table: ArrowTable) -> Self {
+        Self { table }
+    }
+}
+
+#[pymethods]
+impl ArrowStream {
+    /// Export the table as an `arrow_array_stream` capsule. The batches are shared,
+    /// so the stream can be consumed more than once.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__(&self, py: Python, requested_schema: Option<PyObject>) -> PyResult<PyObject> {
+        let _ = requested_schema;
+        let batches = RecordBatchIterator::new(self.table.batches.clone().into_iter().map(Ok), self.table.schema.clone());
+        let stream = FFI_ArrowArrayStream::new(Box::new(batches));
+        let name = CString::new(STREAM_CAPSULE).expect("capsule name has no NUL");
+        Ok(PyCapsule::new(py, stream, Some(name))?.into())
+    }
+
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.table.num_rows()
+    }
+
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        self.table.schema.fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.table.num_rows()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<ArrowStream rows={} columns={}>", self.table.num_rows(), self.table.schema.fields().len())
+    }
+}
@@ -1,73 +1,966 @@
+// pyo3 0.20's #[pymethods] expansion trips this lint on newer toolchains
+#![allow(non_local_definitions)]
+
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
 use std::collections::HashMap;
-use pythonize::depythonize;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use pythonize::{depythonize, pythonize};
 use serde_json::Value;
 
-mod engine;
-mod processors;
-mod streaming;
-mod error;
+mod aio;
+mod builder;
+mod dataset;
+pub mod engine;
+mod interop;
+mod lineage;
+mod manifest;
+mod notify;
+pub mod processors;
+pub mod streaming;
+pub mod error;
+mod logging;
+mod metrics;
+pub mod spec;
+mod state;
+mod steps;
 
-use engine::Engine;
-use streaming::StreamProcessor;
+use engine::{Engine, PipelineReport, PreparedSteps};
+use processors::{concat, diff, drift, expect, join};
+use streaming::{StreamProcessor, Progress, ProgressCallback, QuarantineBatch, QuarantineCallback, CancelFlag, ErrorStrategy, HeaderMatch, RaggedPolicy};
 
-/// Probe file to detect metadata: encoding, delimiter, headers, etc.
+/// Probe file to detect metadata: encoding, delimiter, headers, column types, etc.
 #[pyfunction]
-fn probe_file_header(path: String) -> PyResult<HashMap<String, String>> {
-    let result = processors::probe::detect_file_metadata(&path)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+#[pyo3(signature = (path, sample_rows=100, sample_bytes=8192, full_scan=false, count_rows=false))]
+fn probe_file_header(py: Python, path: String, sample_rows: usize, sample_bytes: usize, full_scan: bool, count_rows: bool) -> PyResult<PyObject> {
+    let options = processors::probe::ProbeOptions { sample_rows, sample_bytes, full_scan, count_rows };
+    let result = processors::probe::detect_file_metadata(&path, &options)?;
     
-    Ok(result)
+    pythonize(py, &result)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Profile the whole file: nulls, distinct estimates, min/max, numeric stats, top values
+#[pyfunction]
+#[pyo3(signature = (path, top_k=10, delimiter=None))]
+fn profile_file(py: Python, path: String, top_k: usize, delimiter: Option<char>) -> PyResult<PyObject> {
+    let result = py.allow_threads(|| processors::profile::profile_file(&path, delimiter.map(|d| d as u8), top_k))?;
+
+    pythonize(py, &result)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Profile the file and write the result as a standalone JSON or HTML report
+#[pyfunction]
+#[pyo3(signature = (path, output, format="html", top_k=10, delimiter=None))]
+fn write_profile_report(py: Python, path: String, output: String, format: &str, top_k: usize, delimiter: Option<char>) -> PyResult<PyObject> {
+    let profile = py.allow_threads(|| -> error::Result<_> {
+        let profile = processors::profile::profile_file(&path, delimiter.map(|d| d as u8), top_k)?;
+        processors::report::write_report(&path, &profile, &output, format)?;
+        Ok(profile)
+    })?;
+
+    pythonize(py, &profile)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Profile `source` and compare it with the drift baseline at `baseline`, which
+/// is created from this file when it does not exist
+#[pyfunction]
+#[pyo3(signature = (source, baseline, null_factor=3.0, mean_tolerance=1.0, update=false, delimiter=None))]
+fn drift_check(py: Python, source: String, baseline: String, null_factor: f64, mean_tolerance: f64, update: bool, delimiter: Option<char>) -> PyResult<PyObject> {
+    let options = drift::DriftOptions { null_factor, mean_tolerance, update };
+    let report = py.allow_threads(|| drift::check(&source, &baseline, delimiter.map(|d| d as u8), &options))?;
+
+    pythonize(py, &report)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Join two files on key columns into `output`, returning the join stats
+#[pyfunction]
+#[pyo3(signature = (left, right, on, output, how="inner", right_on=None, strategy="auto", delimiter=None, right_delimiter=None, suffix="_right".to_string(), memory_limit=join::DEFAULT_MEMORY_LIMIT))]
+#[allow(clippy::too_many_arguments)]
+fn join_files(
+    py: Python,
+    left: String,
+    right: String,
+    on: Vec<String>,
+    output: String,
+    how: &str,
+    right_on: Option<Vec<String>>,
+    strategy: &str,
+    delimiter: Option<char>,
+    right_delimiter: Option<char>,
+    suffix: String,
+    memory_limit: u64,
+) -> PyResult<PyObject> {
+    let options = join::JoinOptions {
+        how: join::JoinKind::parse(how)?,
+        on,
+        right_on,
+        strategy: join::JoinStrategy::parse(strategy)?,
+        delimiter: delimiter.map(|d| d as u8),
+        right_delimiter: right_delimiter.map(|d| d as u8),
+        suffix,
+        memory_limit,
+    };
+    let stats = py.allow_threads(|| join::join_files(&left, &right, &output, &options))?;
+
+    pythonize(py, &stats)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Append `sources` into `output`, matching their columns by name, returning which
+/// file contributed which columns
+#[pyfunction]
+#[pyo3(signature = (sources, output, missing="fill", columns=None, source_column=None, delimiter=None, output_delimiter=None))]
+#[allow(clippy::too_many_arguments)]
+fn concat_files(
+    py: Python,
+    sources: Vec<String>,
+    output: String,
+    missing: &str,
+    columns: Option<Vec<String>>,
+    source_column: Option<String>,
+    delimiter: Option<char>,
+    output_delimiter: Option<char>,
+) -> PyResult<PyObject> {
+    let options = concat::ConcatOptions {
+        missing: concat::MissingColumns::parse(missing)?,
+        columns,
+        source_column,
+        delimiter: delimiter.map(|d| d as u8),
+        output_delimiter: output_delimiter.map(|d| d as u8),
+    };
+    let stats = py.allow_threads(|| concat::concat_files(&sources, &output, &options))?;
+
+    pythonize(py, &stats)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Compare `new` with `old` on key columns, writing the added, removed and changed
+/// rows where asked, returning the counts
+#[pyfunction]
+#[pyo3(signature = (old, new, key, added=None, removed=None, changed=None, columns=None, delimiter=None, new_delimiter=None))]
+#[allow(clippy::too_many_arguments)]
+fn diff_files(
+    py: Python,
+    old: String,
+    new: String,
+    key: Vec<String>,
+    added: Option<String>,
+    removed: Option<String>,
+    changed: Option<String>,
+    columns: Option<Vec<String>>,
+    delimiter: Option<char>,
+    new_delimiter: Option<char>,
+) -> PyResult<PyObject> {
+    let options = diff::DiffOptions {
+        key,
+        columns,
+        delimiter: delimiter.map(|d| d as u8),
+        new_delimiter: new_delimiter.map(|d| d as u8),
+        added,
+        removed,
+        changed,
+    };
+    let stats = py.allow_threads(|| diff::diff_files(&old, &new, &options))?;
+
+    pythonize(py, &stats)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
 }
 
-/// Preview n rows of the pipeline
+/// Original values of vault tokens, None for values that are not tokens of the vault
 #[pyfunction]
+#[pyo3(signature = (vault, tokens, key=None, key_env=processors::vault::DEFAULT_KEY_ENV))]
+fn detokenize_values(vault: String, tokens: Vec<String>, key: Option<String>, key_env: &str) -> PyResult<Vec<Option<String>>> {
+    let key = match key {
+        Some(key) => key,
+        None => processors::vault::passphrase(key_env)?,
+    };
+    let entries = processors::vault::open_entries(&vault, &key)?;
+    Ok(tokens.iter().map(|token| entries.get(token.trim()).cloned()).collect())
+}
+
+/// Recommend a starter step list from a `profile_file` result
+#[pyfunction]
+fn suggest_pipeline(py: Python, profile_py: &PyAny) -> PyResult<PyObject> {
+    let profile: HashMap<String, Value> = depythonize(profile_py)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+    pythonize(py, &processors::profile::suggest_pipeline(&profile))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Actions, parameters, types, defaults and allowed values of every engine step
+#[pyfunction]
+fn describe_steps(py: Python) -> PyResult<PyObject> {
+    pythonize(py, &processors::validate::describe_steps())
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Resolve an optional source delimiter ("auto" sniffs it), defaulting to ','
+fn source_delimiter(source: &str, delimiter: Option<String>) -> PyResult<u8> {
+    delimiter.map_or(Ok(b','), |d| engine::parse_delimiter(source, &d))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Resolve an optional header matching mode, defaulting to exact names
+fn parse_header_match(header_match: Option<String>) -> PyResult<HeaderMatch> {
+    header_match.as_deref().map(HeaderMatch::parse).transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Preview the first n rows of the source through the pipeline, with what happens to each
+#[pyfunction]
+#[pyo3(signature = (source, steps_py, n, columns, delimiter=None, ragged=None, header_match=None, rename_duplicates=false, on_error=None))]
+#[allow(clippy::too_many_arguments)]
 fn preview_pipeline(
-    _py: Python, 
+    py: Python, 
     source: String, 
     steps_py: PyObject, 
     n: usize, 
-    columns: Option<Vec<String>>
-) -> PyResult<(Vec<String>, Vec<Vec<String>>)> {
+    columns: Option<Vec<String>>,
+    delimiter: Option<String>,
+    ragged: Option<String>,
+    header_match: Option<String>,
+    rename_duplicates: bool,
+    on_error: Option<String>
+) -> PyResult<PyObject> {
 
-    let steps: Vec<HashMap<String, Value>> = depythonize(steps_py.as_ref(_py))
+    let steps: Vec<HashMap<String, Value>> = depythonize(steps_py.as_ref(py))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid steps format: {}", e)))?;
 
-    let processor = StreamProcessor::new(source, steps, 0, 1000); 
+    let delimiter = source_delimiter(&source, delimiter)?;
+    let ragged = ragged.as_deref().map(RaggedPolicy::parse).transpose()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+        .unwrap_or_default();
+    let on_error = on_error.as_deref().map(ErrorStrategy::parse).transpose()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?
+        .unwrap_or_default();
+    let header_match = parse_header_match(header_match)?;
+    let processor = StreamProcessor::new(source, steps, 1000)
+        .with_delimiter(delimiter)
+        .with_ragged(ragged)
+        .with_on_error(on_error)
+        .with_header_match(header_match)
+        .with_rename_duplicates(rename_duplicates);
     
-    let preview = processor.peek(n, columns)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    let preview = processor.peek(n, columns)?;
     
-    Ok(preview)
+    pythonize(py, &preview)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Check a pipeline against the source headers without processing any rows
+#[pyfunction]
+#[pyo3(signature = (source, steps_py, header_match=None, strict_steps=true, rename_duplicates=false, delimiter=None))]
+fn validate_pipeline(
+    py: Python,
+    source: String,
+    steps_py: PyObject,
+    header_match: Option<String>,
+    strict_steps: bool,
+    rename_duplicates: bool,
+    delimiter: Option<String>
+) -> PyResult<PyObject> {
+    let steps: Vec<HashMap<String, Value>> = depythonize(steps_py.as_ref(py))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid steps format: {}", e)))?;
+
+    let header_match = parse_header_match(header_match)?;
+    let delimiter = source_delimiter(&source, delimiter)?;
+    let diagnostics = StreamProcessor::new(source, steps, 0)
+        .with_delimiter(delimiter)
+        .with_header_match(header_match)
+        .with_strict(strict_steps)
+        .with_rename_duplicates(rename_duplicates)
+        .validate()?;
+
+    pythonize(py, &diagnostics)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Describe the compiled step plan without processing any rows
+#[pyfunction]
+#[pyo3(signature = (source, steps_py, delimiter=None, header_match=None, rename_duplicates=false))]
+fn explain_pipeline(
+    py: Python,
+    source: String,
+    steps_py: PyObject,
+    delimiter: Option<String>,
+    header_match: Option<String>,
+    rename_duplicates: bool
+) -> PyResult<PyObject> {
+    let steps: Vec<HashMap<String, Value>> = depythonize(steps_py.as_ref(py))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid steps format: {}", e)))?;
+
+    let delimiter = source_delimiter(&source, delimiter)?;
+    let header_match = parse_header_match(header_match)?;
+    let plan = StreamProcessor::new(source, steps, 0)
+        .with_delimiter(delimiter)
+        .with_header_match(header_match)
+        .with_rename_duplicates(rename_duplicates)
+        .explain()?;
+
+    pythonize(py, &plan)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
+
+/// Owned copy of a `Progress` snapshot, so it can outlive the batch it describes
+struct ProgressEvent {
+    source: String,
+    processed: u64,
+    saved: u64,
+    quarantined: u64,
+    bytes_read: u64,
+    total_bytes: u64,
+    elapsed_ms: u64,
+    eta_ms: Option<u64>,
 }
 
-/// Execute single pipeline (.run())
+impl From<&Progress<'_>> for ProgressEvent {
+    fn from(p: &Progress) -> Self {
+        Self {
+            source: p.source.to_string(),
+            processed: p.processed,
+            saved: p.saved,
+            quarantined: p.quarantined,
+            bytes_read: p.bytes_read,
+            total_bytes: p.total_bytes,
+            elapsed_ms: p.elapsed_ms,
+            eta_ms: p.eta_ms,
+        }
+    }
+}
+
+impl ProgressEvent {
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let event = PyDict::new(py);
+        event.set_item("source", &self.source)?;
+        event.set_item("processed_rows", self.processed)?;
+        event.set_item("saved_rows", self.saved)?;
+        event.set_item("quarantined_rows", self.quarantined)?;
+        event.set_item("bytes_read", self.bytes_read)?;
+        event.set_item("total_bytes", self.total_bytes)?;
+        event.set_item("elapsed_ms", self.elapsed_ms)?;
+        event.set_item("eta_ms", self.eta_ms)?;
+        Ok(event)
+    }
+}
+
+/// Process-wide counters of every run so far, as a dict or Prometheus text
+#[pyfunction]
+#[pyo3(signature = (format="dict", reset=false))]
+fn metrics_snapshot(py: Python, format: &str, reset: bool) -> PyResult<PyObject> {
+    let snapshot = metrics::snapshot();
+    if reset { metrics::reset(); }
+
+    match format {
+        "dict" => pythonize(py, &snapshot)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())),
+        "prometheus" => Ok(metrics::to_prometheus(&snapshot).into_py(py)),
+        other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown metrics format '{}'. Expected 'dict' or 'prometheus'", other))),
+    }
+}
+
+/// Toggle per-step timing, which costs a clock read per step and row
 #[pyfunction]
-fn execute_pipeline(_py: Python, payload_py: PyObject) -> PyResult<HashMap<String, u64>> {
-    let payload: HashMap<String, Value> = depythonize(payload_py.as_ref(_py))
+fn set_step_timings(enabled: bool) {
+    metrics::set_step_timings(enabled);
+}
+
+/// Wrap a Python callable as a progress callback. Each event is a dict;
+/// exceptions raised by the callable are printed and do not stop the run.
+fn progress_callback(callback: Option<PyObject>) -> Option<ProgressCallback> {
+    callback.map(|cb| -> ProgressCallback {
+        Arc::new(move |p: &Progress| {
+            Python::with_gil(|py| {
+                let result = ProgressEvent::from(p).to_dict(py)
+                    .and_then(|event| cb.call1(py, (event,)));
+                if let Err(e) = result { e.print(py); }
+            })
+        })
+    })
+}
+
+/// Wrap a Python callable receiving lists of quarantined rows, each a dict with
+/// 'source', 'row', 'step', 'reason' and 'values' (output column -> value)
+fn quarantine_callback(callback: Option<PyObject>) -> Option<QuarantineCallback> {
+    callback.map(|cb| -> QuarantineCallback {
+        Arc::new(move |batch: QuarantineBatch| {
+            Python::with_gil(|py| {
+                let result = batch.rows.iter().map(|r| {
+                    let row = PyDict::new(py);
+                    row.set_item("source", batch.source)?;
+                    row.set_item("row", r.row)?;
+                    row.set_item("step", r.step)?;
+                    row.set_item("reason", &r.reason)?;
+                    let values = PyDict::new(py);
+                    for (header, value) in batch.headers.iter().zip(&r.values) {
+                        values.set_item(header, value)?;
+                    }
+                    row.set_item("values", values)?;
+                    Ok(row)
+                }).collect::<PyResult<Vec<_>>>()
+                    .and_then(|rows| cb.call1(py, (rows,)));
+                // A failing consumer must not take the pipeline down with it
+                if let Err(e) = result { e.print(py); }
+            })
+        })
+    })
+}
+
+/// Handle for stopping a running pipeline from another thread
+#[pyclass]
+#[derive(Clone, Default)]
+struct CancelToken {
+    flag: CancelFlag,
+}
+
+#[pymethods]
+impl CancelToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the run to stop after the batch in flight
+    fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<CancelToken cancelled={}>", self.cancelled())
+    }
+}
+
+/// Rows rejected by one step of a pipeline run
+#[pyclass(get_all)]
+#[derive(Clone)]
+struct StepResult {
+    step: usize,
+    action: String,
+    rejected: u64,
+}
+
+#[pymethods]
+impl StepResult {
+    fn __repr__(&self) -> String {
+        format!("<StepResult #{} {} rejected={}>", self.step, self.action, self.rejected)
+    }
+}
+
+/// Rows one route of a pipeline run took
+#[pyclass(get_all)]
+#[derive(Clone)]
+struct RouteResult {
+    name: String,
+    /// None for a route that drops its rows
+    output: Option<String>,
+    rows: u64,
+}
+
+#[pymethods]
+impl RouteResult {
+    fn __repr__(&self) -> String {
+        format!("<RouteResult {} rows={}>", self.name, self.rows)
+    }
+}
+
+/// Outcome of one `expect` step over a run
+#[pyclass(get_all)]
+#[derive(Clone)]
+struct ExpectationResult {
+    step: usize,
+    rule: String,
+    column: Option<String>,
+    /// Values evaluated: every row for not_null and row_count, non-empty values otherwise
+    checked: u64,
+    /// Values satisfying the rule
+    matched: u64,
+    /// Share of checked values that matched, or the row count for row_count
+    observed: f64,
+    expected: String,
+    passed: bool,
+    severity: String,
+}
+
+impl From<expect::Outcome> for ExpectationResult {
+    fn from(outcome: expect::Outcome) -> Self {
+        Self {
+            step: outcome.step,
+            rule: outcome.rule.to_string(),
+            column: outcome.column,
+            checked: outcome.checked,
+            matched: outcome.matched,
+            observed: outcome.observed,
+            expected: outcome.expected,
+            passed: outcome.passed,
+            severity: outcome.severity.as_str().to_string(),
+        }
+    }
+}
+
+#[pymethods]
+impl ExpectationResult {
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("step", self.step)?;
+        dict.set_item("rule", &self.rule)?;
+        dict.set_item("column", &self.column)?;
+        dict.set_item("checked", self.checked)?;
+        dict.set_item("matched", self.matched)?;
+        dict.set_item("observed", self.observed)?;
+        dict.set_item("expected", &self.expected)?;
+        dict.set_item("passed", self.passed)?;
+        dict.set_item("severity", &self.severity)?;
+        Ok(dict.into())
+    }
+
+    fn __repr__(&self) -> String {
+        let subject = self.column.as_ref().map_or_else(|| self.rule.clone(), |col| format!("{} '{}'", self.rule, col));
+        format!("<ExpectationResult #{} {} {} ({})>", self.step, subject, if self.passed { "passed" } else { "failed" }, self.expected)
+    }
+}
+
+/// A significant change of a column against a drift baseline
+#[pyclass(get_all)]
+#[derive(Clone)]
+struct DriftShift {
+    column: String,
+    /// missing_column, new_column, type_changed, null_rate, new_values or mean_shift
+    kind: String,
+    /// Null rate or mean of the baseline, for the kinds that compare numbers
+    baseline: Option<f64>,
+    current: Option<f64>,
+    /// Categorical values the baseline never saw
+    values: Vec<String>,
+    message: String,
+}
+
+impl From<drift::Shift> for DriftShift {
+    fn from(shift: drift::Shift) -> Self {
+        Self {
+            column: shift.column,
+            kind: shift.kind.to_string(),
+            baseline: shift.baseline,
+            current: shift.current,
+            values: shift.values,
+            message: shift.message,
+        }
+    }
+}
+
+#[pymethods]
+impl DriftShift {
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("column", &self.column)?;
+        dict.set_item("kind", &self.kind)?;
+        dict.set_item("baseline", self.baseline)?;
+        dict.set_item("current", self.current)?;
+        dict.set_item("values", &self.values)?;
+        dict.set_item("message", &self.message)?;
+        Ok(dict.into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<DriftShift {} '{}'>", self.kind, self.column)
+    }
+}
+
+/// Outcome of one pipeline run
+#[pyclass(get_all)]
+struct PipelineResult {
+    source: String,
+    output: String,
+    quarantine: Option<String>,
+    processed: u64,
+    saved: u64,
+    quarantined: u64,
+    skipped: u64,
+    /// Records the CSV parser rejected (included in quarantined or skipped)
+    malformed: u64,
+    bytes_read: u64,
+    /// Wall time in seconds
+    duration: f64,
+    rows_per_sec: f64,
+    bytes_per_sec: f64,
+    cancelled: bool,
+    steps: Vec<StepResult>,
+    /// Rows taken by each route, in route order; they are not counted in `saved`
+    routes: Vec<RouteResult>,
+    warnings: Vec<String>,
+    /// Path of the lineage sidecar, when one was written
+    lineage: Option<String>,
+    /// Path of the checksum manifest, when one was written
+    manifest: Option<String>,
+    /// How the source compared to the state file of an incremental run
+    increment: Option<String>,
+    /// Shifts of the output statistics against the drift baseline
+    drift: Vec<DriftShift>,
+    /// Outcome of every `expect` step, in step order
+    expectations: Vec<ExpectationResult>,
+    /// Why the run failed: set by batch execution, where failures do not raise, and
+    /// when an expectation of severity 'error' did not hold
+    error: Option<String>,
+}
+
+impl PipelineResult {
+    /// Result of a batch payload that failed: identifies the payload and carries the error
+    fn failed(payload: &HashMap<String, Value>, error: String) -> Self {
+        let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        Self {
+            source: text("source").unwrap_or_default(),
+            output: text("output").unwrap_or_default(),
+            quarantine: text("quarantine"),
+            processed: 0,
+            saved: 0,
+            quarantined: 0,
+            skipped: 0,
+            malformed: 0,
+            bytes_read: 0,
+            duration: 0.0,
+            rows_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            cancelled: false,
+            steps: Vec::new(),
+            routes: Vec::new(),
+            warnings: Vec::new(),
+            lineage: None,
+            manifest: None,
+            increment: None,
+            drift: Vec::new(),
+            expectations: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+impl From<PipelineReport> for PipelineResult {
+    fn from(report: PipelineReport) -> Self {
+        let error = report.expectation_error();
+        let stats = report.stats;
+        Self {
+            source: report.source,
+            output: report.output,
+            quarantine: report.quarantine,
+            processed: stats.processed,
+            saved: stats.saved,
+            quarantined: stats.quarantined,
+            skipped: stats.skipped,
+            malformed: stats.malformed,
+            bytes_read: stats.bytes_read,
+            duration: stats.duration.as_secs_f64(),
+            rows_per_sec: stats.rows_per_sec,
+            bytes_per_sec: stats.bytes_per_sec,
+            cancelled: stats.cancelled,
+            steps: stats.steps.into_iter()
+                .map(|s| StepResult { step: s.step, action: s.action, rejected: s.rejected })
+                .collect(),
+            routes: stats.routes.into_iter()
+                .map(|r| RouteResult { name: r.name, output: r.output, rows: r.rows })
+                .collect(),
+            warnings: report.warnings,
+            lineage: report.lineage,
+            manifest: report.manifest,
+            increment: report.increment.map(str::to_string),
+            drift: report.drift.map(|d| d.shifts.into_iter().map(DriftShift::from).collect()).unwrap_or_default(),
+            expectations: stats.expectations.into_iter().map(ExpectationResult::from).collect(),
+            error,
+        }
+    }
+}
+
+#[pymethods]
+impl PipelineResult {
+    /// Flat dict using the historical stat keys (`processed_rows`, `duration_ms`, ...)
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        dict.set_item("source", &self.source)?;
+        dict.set_item("output", &self.output)?;
+        dict.set_item("quarantine", &self.quarantine)?;
+        dict.set_item("processed_rows", self.processed)?;
+        dict.set_item("saved_rows", self.saved)?;
+        dict.set_item("quarantined_rows", self.quarantined)?;
+        dict.set_item("skipped_rows", self.skipped)?;
+        dict.set_item("malformed_rows", self.malformed)?;
+        dict.set_item("bytes_read", self.bytes_read)?;
+        dict.set_item("duration_ms", (self.duration * 1000.0).round() as u64)?;
+        dict.set_item("rows_per_sec", self.rows_per_sec)?;
+        dict.set_item("bytes_per_sec", self.bytes_per_sec)?;
+        dict.set_item("cancelled", self.cancelled)?;
+        let steps: Vec<PyObject> = self.steps.iter().map(|s| {
+            let step = PyDict::new(py);
+            step.set_item("step", s.step)?;
+            step.set_item("action", &s.action)?;
+            step.set_item("rejected", s.rejected)?;
+            Ok(step.into())
+        }).collect::<PyResult<_>>()?;
+        dict.set_item("steps", steps)?;
+        let routes: Vec<PyObject> = self.routes.iter().map(|r| {
+            let route = PyDict::new(py);
+            route.set_item("name", &r.name)?;
+            route.set_item("output", &r.output)?;
+            route.set_item("rows", r.rows)?;
+            Ok(route.into())
+        }).collect::<PyResult<_>>()?;
+        dict.set_item("routes", routes)?;
+        dict.set_item("warnings", &self.warnings)?;
+        dict.set_item("lineage", &self.lineage)?;
+        dict.set_item("manifest", &self.manifest)?;
+        dict.set_item("increment", &self.increment)?;
+        let drift = self.drift.iter()
+            .map(|s| s.to_dict(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item("drift", drift)?;
+        let expectations = self.expectations.iter()
+            .map(|e| e.to_dict(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item("expectations", expectations)?;
+        dict.set_item("error", &self.error)?;
+        Ok(dict.into())
+    }
+
+    /// True unless the run failed
+    #[getter]
+    fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// True when every `expect` step held, whatever its severity
+    #[getter]
+    fn expectations_passed(&self) -> bool {
+        self.expectations.iter().all(|e| e.passed)
+    }
+
+    fn __repr__(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("<PipelineResult {} | failed: {}>", self.source, error);
+        }
+        let status = if self.cancelled { ", cancelled" } else { "" };
+        format!(
+            "<PipelineResult {} | processed={} saved={} quarantined={} skipped={} ({:.3}s{})>",
+            self.source, self.processed, self.saved, self.quarantined, self.skipped, self.duration, status
+        )
+    }
+}
+
+/// Run `job` on a worker thread with the GIL released, polling for Ctrl+C
+/// meanwhile. An interrupt trips `cancel` so the run stops cleanly at the next
+/// batch boundary and still returns its partial stats.
+fn run_interruptible<T: Send>(py: Python, cancel: &CancelFlag, job: impl FnOnce() -> T + Send) -> T {
+    py.allow_threads(|| {
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(job);
+            while !handle.is_finished() {
+                std::thread::sleep(Duration::from_millis(50));
+                if Python::with_gil(|py| py.check_signals()).is_err() {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            }
+            handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    })
+}
+
+/// Execute single pipeline (.run()). With `data` (a frame exporting the Arrow
+/// C stream interface) the rows come from the frame instead of the source file.
+#[pyfunction]
+#[pyo3(signature = (payload_py, progress=None, cancel=None, data=None, on_quarantine=None))]
+fn execute_pipeline(
+    py: Python,
+    payload_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    data: Option<&PyAny>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<PipelineResult> {
+    let payload: HashMap<String, Value> = depythonize(payload_py.as_ref(py))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid payload format: {}", e)))?;
 
-    let engine = Engine::new(0, 10000); 
-    let stats = engine.execute_single(payload)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = Engine::new(0, 10000)
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine))
+        .with_arrow_input(data.map(interop::arrow_reader).transpose()?);
+    // Release the GIL so other Python threads keep running during the stream
+    let report = run_interruptible(py, &cancel, || engine.execute_single(payload))?;
     
-    Ok(stats)
+    Ok(report.into())
 }
 
-/// Execute BATCH pipelines (Parallel)
+/// Execute a single pipeline and return the kept rows as an Arrow IPC stream
+/// (bytes, for `pyarrow.ipc.open_stream`) instead of writing the output file
 #[pyfunction]
-fn execute_batch(
-    _py: Python,
-    payloads_py: PyObject, 
-    config_py: PyObject
-) -> PyResult<Vec<HashMap<String, u64>>> {
-    
+#[pyo3(signature = (payload_py, progress=None, cancel=None, data=None, on_quarantine=None))]
+fn execute_to_dataframe(
+    py: Python,
+    payload_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    data: Option<&PyAny>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<(PipelineResult, PyObject)> {
+    let (report, table) = execute_in_memory(py, payload_py, progress, cancel, data, on_quarantine)?;
+    Ok((report, PyBytes::new(py, &table.to_ipc()?).into()))
+}
+
+/// Execute a single pipeline and hand the kept rows over through the Arrow C
+/// stream interface (for `polars.DataFrame(...)`), without serializing them
+#[pyfunction]
+#[pyo3(signature = (payload_py, progress=None, cancel=None, data=None, on_quarantine=None))]
+fn execute_to_arrow_stream(
+    py: Python,
+    payload_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    data: Option<&PyAny>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<(PipelineResult, interop::ArrowStream)> {
+    let (report, table) = execute_in_memory(py, payload_py, progress, cancel, data, on_quarantine)?;
+    Ok((report, table.into()))
+}
+
+fn execute_in_memory(
+    py: Python,
+    payload_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    data: Option<&PyAny>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<(PipelineResult, streaming::ArrowTable)> {
+    let payload: HashMap<String, Value> = depythonize(payload_py.as_ref(py))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid payload format: {}", e)))?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = Engine::new(0, 10000)
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine))
+        .with_arrow_input(data.map(interop::arrow_reader).transpose()?);
+    let (report, table) = run_interruptible(py, &cancel, || engine.execute_to_arrow(payload))?;
+
+    Ok((report.into(), table))
+}
+
+/// Iterator over the per-batch progress of a pipeline running on a background thread.
+/// Dropping or cancelling it stops the run at the next batch boundary.
+#[pyclass]
+struct RunStream {
+    events: Option<Receiver<ProgressEvent>>,
+    cancel: CancelFlag,
+    handle: Option<JoinHandle<error::Result<PipelineReport>>>,
+    #[pyo3(get)]
+    result: Option<Py<PipelineResult>>,
+}
+
+#[pymethods]
+impl RunStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        let Some(mut events) = self.events.take() else { return Ok(None) };
+
+        loop {
+            // The receiver moves into the GIL-free section and back, since it is not Sync
+            let received;
+            (events, received) = py.allow_threads(move || {
+                let received = events.recv_timeout(Duration::from_millis(50));
+                (events, received)
+            });
+
+            match received {
+                Ok(event) => {
+                    self.events = Some(events);
+                    return Ok(Some(event.to_dict(py)?.into()));
+                },
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(interrupt) = py.check_signals() {
+                        self.cancel.store(true, Ordering::Relaxed);
+                        self.events = Some(events);
+                        return Err(interrupt);
+                    }
+                },
+                // The worker dropped its sender: the run is over
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let handle = self.handle.take().expect("run finished once");
+        let report = py.allow_threads(|| handle.join())
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        self.result = Some(Py::new(py, PipelineResult::from(report))?);
+        Ok(None)
+    }
+
+    /// Ask the run to stop after the batch in flight; iteration then ends normally
+    fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    #[getter]
+    fn done(&self) -> bool {
+        self.handle.is_none()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<RunStream done={}>", self.done())
+    }
+}
+
+impl Drop for RunStream {
+    fn drop(&mut self) {
+        // An abandoned stream should not keep writing files in the background
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start a pipeline in the background and iterate over its per-batch stats
+#[pyfunction]
+#[pyo3(signature = (payload_py, cancel=None))]
+fn stream_pipeline(py: Python, payload_py: PyObject, cancel: Option<CancelToken>) -> PyResult<RunStream> {
+    let payload: HashMap<String, Value> = depythonize(payload_py.as_ref(py))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid payload format: {}", e)))?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let (sender, events) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let progress: ProgressCallback = Arc::new(move |p: &Progress| {
+        // The receiver is gone once the stream is dropped; the run is being cancelled then
+        let _ = sender.lock().map(|s| s.send(ProgressEvent::from(p)));
+    });
+
+    let engine = Engine::new(0, 10000)
+        .with_progress(Some(progress))
+        .with_cancel(Some(cancel.clone()));
+    let handle = std::thread::spawn(move || engine.execute_single(payload));
+
+    Ok(RunStream { events: Some(events), cancel, handle: Some(handle), result: None })
+}
+
+/// Parse batch payloads and config into an engine; payloads may override batch_size,
+/// on_error and delimiters, with the batch config as the fallback
+fn batch_engine(py: Python, payloads_py: PyObject, config_py: PyObject) -> PyResult<(Vec<HashMap<String, Value>>, Engine)> {
     // Translating Payloads (List of Dicts)
-    let payloads: Vec<HashMap<String, Value>> = depythonize(payloads_py.as_ref(_py))
+    let payloads: Vec<HashMap<String, Value>> = depythonize(payloads_py.as_ref(py))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid payloads: {}", e)))?;
     
     // Translating Config (Dict)
-    let config: HashMap<String, Value> = depythonize(config_py.as_ref(_py))
+    let config: HashMap<String, Value> = depythonize(config_py.as_ref(py))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid config: {}", e)))?;
 
     // Get number of workers
@@ -79,20 +972,321 @@ fn execute_batch(
     let batch_size = config.get("batch_size")
         .and_then(|v| v.as_u64())
         .unwrap_or(10_000) as usize;
-    
-    let engine = Engine::new(workers, batch_size);
-    let results = engine.execute_parallel(payloads)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
-    
-    Ok(results)
+
+    Ok((payloads, Engine::new(workers, batch_size).with_defaults(config)))
+}
+
+/// One result per payload, in order; failed payloads carry their error instead of raising
+fn batch_results(reports: Vec<error::Result<PipelineReport>>, payloads: &[HashMap<String, Value>]) -> Vec<PipelineResult> {
+    reports.into_iter().zip(payloads)
+        .map(|(report, payload)| match report {
+            Ok(report) => report.into(),
+            Err(e) => PipelineResult::failed(payload, e.to_string()),
+        })
+        .collect()
+}
+
+/// Execute BATCH pipelines (Parallel)
+#[pyfunction]
+#[pyo3(signature = (payloads_py, config_py, progress=None, cancel=None, on_quarantine=None))]
+fn execute_batch(
+    py: Python,
+    payloads_py: PyObject, 
+    config_py: PyObject,
+    progress: Option<PyObject>,
+    cancel: Option<CancelToken>,
+    on_quarantine: Option<PyObject>
+) -> PyResult<Vec<PipelineResult>> {
+    let (payloads, engine) = batch_engine(py, payloads_py, config_py)?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = engine
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine));
+    let reports = run_interruptible(py, &cancel, || engine.execute_parallel(&payloads));
+
+    Ok(batch_results(reports, &payloads))
+}
+
+/// Handle of a batch running on a background thread. Status, progress and results
+/// are polled without blocking; `join()` waits with the GIL released.
+/// Dropping the handle cancels the job at the next batch boundary.
+#[pyclass]
+struct BatchJob {
+    cancel: CancelFlag,
+    /// Latest progress of each source
+    progress: Arc<Mutex<HashMap<String, ProgressEvent>>>,
+    payloads: Vec<HashMap<String, Value>>,
+    handle: Option<JoinHandle<Vec<error::Result<PipelineReport>>>>,
+    results: Option<Vec<Py<PipelineResult>>>,
+}
+
+impl BatchJob {
+    /// Collect the results once the worker thread is done
+    fn collect(&mut self, py: Python) -> PyResult<()> {
+        if !self.handle.as_ref().is_some_and(|h| h.is_finished()) {
+            return Ok(());
+        }
+        let handle = self.handle.take().expect("checked above");
+        let reports = handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        let payloads = std::mem::take(&mut self.payloads);
+        self.results = Some(batch_results(reports, &payloads).into_iter()
+            .map(|result| Py::new(py, result))
+            .collect::<PyResult<_>>()?);
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl BatchJob {
+    /// 'running', 'completed' or 'cancelled'. Failures of single payloads show up in their results.
+    #[getter]
+    fn status(&mut self, py: Python) -> PyResult<&'static str> {
+        self.collect(py)?;
+        Ok(match &self.results {
+            None => "running",
+            Some(results) if results.iter().any(|r| r.borrow(py).cancelled) => "cancelled",
+            Some(_) => "completed",
+        })
+    }
+
+    #[getter]
+    fn done(&mut self, py: Python) -> PyResult<bool> {
+        self.collect(py)?;
+        Ok(self.results.is_some())
+    }
+
+    /// Latest per-batch progress of each source, keyed by source path
+    fn progress(&self, py: Python) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        let progress = self.progress.lock()
+            .map_err(|_| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Progress state is poisoned"))?;
+        for (source, event) in progress.iter() {
+            dict.set_item(source, event.to_dict(py)?)?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Results in payload order if the job is done, else None. Never blocks.
+    fn poll(&mut self, py: Python) -> PyResult<Option<Vec<Py<PipelineResult>>>> {
+        self.collect(py)?;
+        Ok(self.results.as_ref().map(|r| r.iter().map(|x| x.clone_ref(py)).collect()))
+    }
+
+    /// Wait for the results, at most `timeout` seconds. Ctrl+C cancels the job.
+    #[pyo3(signature = (timeout=None))]
+    fn join(&mut self, py: Python, timeout: Option<f64>) -> PyResult<Vec<Py<PipelineResult>>> {
+        let started = std::time::Instant::now();
+        loop {
+            if let Some(results) = self.poll(py)? {
+                return Ok(results);
+            }
+            if timeout.is_some_and(|t| started.elapsed().as_secs_f64() >= t) {
+                return Err(PyErr::new::<pyo3::exceptions::PyTimeoutError, _>("Batch job is still running"));
+            }
+            py.allow_threads(|| std::thread::sleep(Duration::from_millis(50)));
+            if let Err(interrupt) = py.check_signals() {
+                self.cancel.store(true, Ordering::Relaxed);
+                return Err(interrupt);
+            }
+        }
+    }
+
+    /// Ask every pipeline to stop after the batch in flight; the results are then partial
+    fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    fn __repr__(&mut self, py: Python) -> PyResult<String> {
+        Ok(format!("<BatchJob status={}>", self.status(py)?))
+    }
+}
+
+impl Drop for BatchJob {
+    fn drop(&mut self) {
+        // Nobody can collect the results any more; stop writing files
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start BATCH pipelines on a background thread and return a handle to poll
+#[pyfunction]
+#[pyo3(signature = (payloads_py, config_py, cancel=None, on_quarantine=None))]
+fn execute_batch_async(py: Python, payloads_py: PyObject, config_py: PyObject, cancel: Option<CancelToken>, on_quarantine: Option<PyObject>) -> PyResult<BatchJob> {
+    let (payloads, engine) = batch_engine(py, payloads_py, config_py)?;
+
+    let cancel = cancel.unwrap_or_default().flag;
+    let progress = Arc::new(Mutex::new(HashMap::new()));
+    let latest = progress.clone();
+    let callback: ProgressCallback = Arc::new(move |p: &Progress| {
+        if let Ok(mut latest) = latest.lock() {
+            latest.insert(p.source.to_string(), ProgressEvent::from(p));
+        }
+    });
+
+    let engine = engine
+        .with_progress(Some(callback))
+        .with_cancel(Some(cancel.clone()))
+        .with_on_quarantine(quarantine_callback(on_quarantine));
+    let job_payloads = payloads.clone();
+    let handle = std::thread::spawn(move || engine.execute_parallel(&job_payloads));
+
+    Ok(BatchJob { cancel, progress, payloads, handle: Some(handle), results: None })
+}
+
+/// Run every pipeline of a YAML/JSON spec file in parallel
+#[pyfunction]
+#[pyo3(signature = (path, progress=None, cancel=None))]
+fn execute_from_spec(py: Python, path: String, progress: Option<PyObject>, cancel: Option<CancelToken>) -> PyResult<Vec<PipelineResult>> {
+    let spec = spec::load(&path)?;
+    let payloads = spec.payloads();
+    let config = spec.config();
+
+    let workers = spec.config.workers.unwrap_or(0) as usize;
+    let batch_size = spec.config.batch_size.as_ref().and_then(spec::BatchSize::rows).unwrap_or(10_000) as usize;
+    let cancel = cancel.unwrap_or_default().flag;
+    let engine = Engine::new(workers, batch_size)
+        .with_defaults(config)
+        .with_progress(progress_callback(progress))
+        .with_cancel(Some(cancel.clone()));
+    let reports = run_interruptible(py, &cancel, || engine.execute_parallel(&payloads));
+
+    Ok(batch_results(reports, &payloads))
+}
+
+/// Steps deserialized once and compiled once per header layout, to run against
+/// many files. Settings (on_error, delimiter, output_delimiter, batch_size, workers)
+/// given here are defaults that each run can override.
+#[pyclass]
+struct PreparedPipeline {
+    prepared: Arc<PreparedSteps>,
+    config: HashMap<String, Value>,
+}
+
+impl PreparedPipeline {
+    fn engine(&self) -> Engine {
+        let workers = self.config.get("workers").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let batch_size = self.config.get("batch_size").and_then(|v| v.as_u64()).unwrap_or(10_000) as usize;
+        Engine::new(workers, batch_size)
+            .with_defaults(self.config.clone())
+            .with_prepared(Some(self.prepared.clone()))
+    }
+}
+
+#[pymethods]
+impl PreparedPipeline {
+    #[new]
+    #[pyo3(signature = (steps_py, config_py=None))]
+    fn new(py: Python, steps_py: PyObject, config_py: Option<PyObject>) -> PyResult<Self> {
+        let steps: Vec<HashMap<String, Value>> = depythonize(steps_py.as_ref(py))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid steps format: {}", e)))?;
+        let config: HashMap<String, Value> = match config_py {
+            Some(config) => depythonize(config.as_ref(py))
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid config: {}", e)))?,
+            None => HashMap::new(),
+        };
+        Ok(Self { prepared: Arc::new(PreparedSteps::new(steps)), config })
+    }
+
+    /// Run against one file
+    #[pyo3(signature = (source, output, quarantine=None, progress=None, cancel=None))]
+    fn run(
+        &self,
+        py: Python,
+        source: String,
+        output: String,
+        quarantine: Option<String>,
+        progress: Option<PyObject>,
+        cancel: Option<CancelToken>
+    ) -> PyResult<PipelineResult> {
+        let mut payload = HashMap::new();
+        payload.insert("source".to_string(), Value::from(source));
+        payload.insert("output".to_string(), Value::from(output));
+        payload.insert("quarantine".to_string(), quarantine.map_or(Value::Null, Value::from));
+
+        let cancel = cancel.unwrap_or_default().flag;
+        let engine = self.engine()
+            .with_progress(progress_callback(progress))
+            .with_cancel(Some(cancel.clone()));
+        let report = run_interruptible(py, &cancel, || engine.execute_single(payload))?;
+
+        Ok(report.into())
+    }
+
+    /// Run against many files in parallel. Each run is a dict with 'source', 'output',
+    /// optional 'quarantine' and optional setting overrides; failures are reported per run.
+    #[pyo3(signature = (runs_py, progress=None, cancel=None))]
+    fn run_batch(
+        &self,
+        py: Python,
+        runs_py: PyObject,
+        progress: Option<PyObject>,
+        cancel: Option<CancelToken>
+    ) -> PyResult<Vec<PipelineResult>> {
+        let runs: Vec<HashMap<String, Value>> = depythonize(runs_py.as_ref(py))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid runs: {}", e)))?;
+
+        let cancel = cancel.unwrap_or_default().flag;
+        let engine = self.engine()
+            .with_progress(progress_callback(progress))
+            .with_cancel(Some(cancel.clone()));
+        let reports = run_interruptible(py, &cancel, || engine.execute_parallel(&runs));
+
+        Ok(batch_results(reports, &runs))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<PreparedPipeline steps={} compiled_layouts={}>",
+            self.prepared.steps.len(), self.prepared.cache.layouts()
+        )
+    }
 }
 
 #[pymodule]
-fn _phaeton(_py: Python, m: &PyModule) -> PyResult<()> {
+fn _phaeton(py: Python, m: &PyModule) -> PyResult<()> {
+    logging::init();
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(logging::set_log_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(set_step_timings, m)?)?;
     m.add_function(wrap_pyfunction!(probe_file_header, m)?)?;
+    m.add_function(wrap_pyfunction!(profile_file, m)?)?;
+    m.add_function(wrap_pyfunction!(write_profile_report, m)?)?;
+    m.add_function(wrap_pyfunction!(suggest_pipeline, m)?)?;
+    m.add_function(wrap_pyfunction!(detokenize_values, m)?)?;
+    m.add_function(wrap_pyfunction!(drift_check, m)?)?;
+    m.add_function(wrap_pyfunction!(join_files, m)?)?;
+    m.add_function(wrap_pyfunction!(concat_files, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_files, m)?)?;
+    m.add_function(wrap_pyfunction!(describe_steps, m)?)?;
     m.add_function(wrap_pyfunction!(preview_pipeline, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_pipeline, m)?)?;
+    m.add_function(wrap_pyfunction!(explain_pipeline, m)?)?;
     m.add_function(wrap_pyfunction!(execute_pipeline, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_to_dataframe, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_to_arrow_stream, m)?)?;
     m.add_function(wrap_pyfunction!(execute_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_batch_async, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::execute_pipeline_future, m)?)?;
+    m.add_function(wrap_pyfunction!(aio::execute_batch_future, m)?)?;
+    m.add_function(wrap_pyfunction!(execute_from_spec, m)?)?;
+    m.add_function(wrap_pyfunction!(stream_pipeline, m)?)?;
+    m.add_class::<CancelToken>()?;
+    m.add_class::<PipelineResult>()?;
+    m.add_class::<StepResult>()?;
+    m.add_class::<RouteResult>()?;
+    m.add_class::<ExpectationResult>()?;
+    m.add_class::<DriftShift>()?;
+    m.add_class::<RunStream>()?;
+    m.add_class::<BatchJob>()?;
+    m.add_class::<PreparedPipeline>()?;
+    m.add_class::<builder::PipelineBuilder>()?;
+    m.add_class::<interop::ArrowStream>()?;
+    m.add_class::<dataset::Dataset>()?;
+    m.add_class::<dataset::DatasetResult>()?;
+    steps::register(m)?;
+    error::register(py, m)?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+// LINEAGE SIDECARS
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::engine::PipelineReport;
+use crate::error::Result;
+
+/// Where the sidecar of `output` goes when the `lineage` setting is `true`
+pub fn default_path(output: &str) -> String {
+    format!("{}.lineage.json", output)
+}
+
+/// SHA-256 of a file's bytes, or None when it cannot be read (e.g. an Arrow
+/// input whose `source` only names the run)
+pub(crate) fn file_digest(path: &str) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).ok()?;
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Object keys sorted at every level, so equal step lists serialize (and hash) alike
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Object(keys.into_iter().map(|k| (k.clone(), canonical(&map[k]))).collect::<Map<_, _>>())
+        },
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+/// SHA-256 of the canonical JSON of the steps; the same pipeline hashes the same
+/// across runs and machines
+pub fn steps_digest(steps: &Value) -> String {
+    let text = serde_json::to_string(&canonical(steps)).unwrap_or_default();
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// Audit record of how one output was produced
+pub fn document(report: &PipelineReport, steps: &Value, started_at: DateTime<Local>, finished_at: DateTime<Local>) -> Value {
+    let stats = &report.stats;
+    let steps = canonical(steps);
+    json!({
+        "generator": format!("phaeton {}", env!("CARGO_PKG_VERSION")),
+        "source": {
+            "path": report.source,
+            "sha256": file_digest(&report.source),
+            "size": std::fs::metadata(&report.source).map(|m| m.len()).ok(),
+        },
+        "output": {
+            "path": report.output,
+            "sha256": file_digest(&report.output),
+        },
+        "quarantine": report.quarantine.as_ref().map(|path| json!({ "path": path, "sha256": file_digest(path) })),
+        "pipeline": {
+            "sha256": steps_digest(&steps),
+            "steps": steps,
+        },
+        "started_at": started_at.to_rfc3339(),
+        "finished_at": finished_at.to_rfc3339(),
+        "rows": {
+            "processed": stats.processed,
+            "saved": stats.saved,
+            "quarantined": stats.quarantined,
+            "skipped": stats.skipped,
+            "malformed": stats.malformed,
+        },
+        "cancelled": stats.cancelled,
+        "warnings": report.warnings,
+    })
+}
+
+/// Write the sidecar to `path`
+pub fn write(path: &str, document: &Value) -> Result<()> {
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, document)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
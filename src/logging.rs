@@ -0,0 +1,120 @@
+use pyo3::prelude::*;
+use std::fmt::Write;
+use std::sync::OnceLock;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+/// Environment variable holding the initial filter, e.g. `PHAETON_LOG=debug`
+/// or `PHAETON_LOG=info,phaeton::streaming=debug`.
+pub const FILTER_ENV: &str = "PHAETON_LOG";
+const DEFAULT_FILTER: &str = "info";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Forwards tracing events to Python's `logging` module under the `phaeton.*`
+/// logger hierarchy, so applications configure handlers and levels as usual.
+///
+/// Every event takes the GIL. Events must therefore only be emitted from
+/// threads that do not block a GIL holder: never from inside rayon closures
+/// driven by a call that keeps the GIL (probe, profile, peek).
+struct PyLoggingLayer;
+
+impl<S: Subscriber> Layer<S> for PyLoggingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let level = match *meta.level() {
+            Level::ERROR => 40,
+            Level::WARN => 30,
+            Level::INFO => 20,
+            Level::DEBUG => 10,
+            Level::TRACE => 5,
+        };
+        let name = logger_name(meta.target());
+
+        Python::with_gil(|py| {
+            let result = py.import("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (level, visitor.finish())));
+            // A broken handler must not take the pipeline down with it
+            if let Err(e) = result { e.print(py); }
+        });
+    }
+}
+
+/// `_phaeton::streaming` -> `phaeton.streaming`
+fn logger_name(target: &str) -> String {
+    target.trim_start_matches('_').replace("::", ".")
+}
+
+/// Renders the `message` field followed by the other fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() { self.message } else { format!("{}{}", self.message, self.fields) }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Targets are written as they appear to Python (`phaeton::streaming`), while the
+/// Rust module paths carry the extension name (`_phaeton::streaming`)
+fn normalize_directives(directives: &str) -> String {
+    directives.split(',')
+        .map(|d| if d.trim_start().starts_with("phaeton") { format!("_{}", d.trim_start()) } else { d.to_string() })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_filter(directives: &str) -> PyResult<EnvFilter> {
+    EnvFilter::try_new(normalize_directives(directives))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid log filter '{}': {}", directives, e)))
+}
+
+/// Install the Python-bridging subscriber. Called once at module import; a
+/// subscriber installed earlier by the host process is left alone.
+pub fn init() {
+    let directives = std::env::var(FILTER_ENV).unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    let filter = EnvFilter::try_new(normalize_directives(&directives)).unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    if tracing_subscriber::registry().with(filter).with(PyLoggingLayer).try_init().is_ok() {
+        let _ = FILTER_HANDLE.set(handle);
+    }
+}
+
+/// Replace the active filter, e.g. `"debug"` or `"warn,phaeton::engine=info"`
+#[pyfunction]
+pub fn set_log_filter(directives: &str) -> PyResult<()> {
+    let filter = parse_filter(directives)?;
+    let handle = FILTER_HANDLE.get()
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Phaeton logging is not active: another tracing subscriber is installed"))?;
+    handle.reload(filter)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+}
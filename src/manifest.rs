@@ -0,0 +1,113 @@
+// CHECKSUM MANIFESTS
+
+use std::fs::File;
+use std::io::{self, BufReader};
+
+use chrono::Local;
+use csv::{ByteRecord, ReaderBuilder};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::engine::PipelineReport;
+use crate::error::{PhaetonError, Result};
+use crate::lineage;
+
+/// Where the manifest of `output` goes unless the `manifest` setting names a path
+pub fn default_path(output: &str) -> String {
+    format!("{}.manifest.json", output)
+}
+
+/// Path and verification of a `manifest` setting: `true`, a path, or an object
+/// with `path` and `verify` (default true). None when it is off.
+pub fn from_setting(setting: &Value, output: &str) -> Result<Option<(String, bool)>> {
+    match setting {
+        Value::Bool(false) => Ok(None),
+        Value::Bool(true) => Ok(Some((default_path(output), true))),
+        Value::String(path) => Ok(Some((path.clone(), true))),
+        Value::Object(map) => {
+            let path = map.get("path").and_then(Value::as_str).map_or_else(|| default_path(output), str::to_string);
+            Ok(Some((path, map.get("verify").and_then(Value::as_bool).unwrap_or(true))))
+        },
+        other => Err(PhaetonError::InvalidStep(format!("The manifest setting must be true, a path or an object, got {}", other))),
+    }
+}
+
+/// One file produced by the run
+#[derive(Serialize, Debug)]
+pub struct Entry {
+    pub path: String,
+    /// output, quarantine, route or lineage
+    pub role: &'static str,
+    pub size: u64,
+    pub sha256: String,
+    /// Data rows found when the file was read back; None when not verified
+    pub rows: Option<u64>,
+}
+
+/// Data rows of a CSV file; record boundaries do not depend on the delimiter
+fn count_rows(path: &str, header: bool) -> Result<u64> {
+    let file = File::open(path)?;
+    let mut reader = ReaderBuilder::new().has_headers(header).flexible(true).from_reader(BufReader::new(file));
+    let mut record = ByteRecord::new();
+    let mut rows = 0;
+    while reader.read_byte_record(&mut record)? {
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+fn entry(path: &str, role: &'static str) -> Result<Entry> {
+    let size = std::fs::metadata(path)?.len();
+    let sha256 = lineage::file_digest(path)
+        .ok_or_else(|| PhaetonError::from(io::Error::new(io::ErrorKind::NotFound, format!("{} could not be read", path))))?;
+    Ok(Entry { path: path.to_string(), role, size, sha256, rows: None })
+}
+
+/// Checksums of every file the run wrote. With `verify`, the CSV files are read
+/// back and their row counts must match the run's; an appending run is not
+/// counted, as its files also hold earlier rows.
+pub fn build(report: &PipelineReport, verify: bool, appended: bool) -> Result<Vec<Entry>> {
+    let stats = &report.stats;
+    // (path, role, header row, rows the run wrote)
+    let mut files: Vec<(&str, &'static str, bool, u64)> = vec![(report.output.as_str(), "output", true, stats.saved)];
+    if let Some(path) = &report.quarantine {
+        files.push((path, "quarantine", true, stats.quarantined));
+    }
+    for route in stats.routes.iter() {
+        if let Some(path) = &route.output {
+            files.push((path, "route", route.header, route.rows));
+        }
+    }
+
+    let mut entries = Vec::with_capacity(files.len() + 1);
+    let mut mismatches = Vec::new();
+    for (path, role, header, expected) in files {
+        let mut entry = entry(path, role)?;
+        if verify && !appended {
+            let rows = count_rows(path, header)?;
+            if rows != expected {
+                mismatches.push(format!("{} has {} rows, the run wrote {}", path, rows, expected));
+            }
+            entry.rows = Some(rows);
+        }
+        entries.push(entry);
+    }
+    if !mismatches.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Output verification failed: {}", mismatches.join("; "))).into());
+    }
+    if let Some(path) = &report.lineage {
+        entries.push(entry(path, "lineage")?);
+    }
+    Ok(entries)
+}
+
+/// The manifest document handed over with the files
+pub fn document(report: &PipelineReport, entries: &[Entry], verified: bool) -> Value {
+    json!({
+        "generator": format!("phaeton {}", env!("CARGO_PKG_VERSION")),
+        "created_at": Local::now().to_rfc3339(),
+        "source": report.source,
+        "verified": verified,
+        "files": entries,
+    })
+}
@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bounds (seconds) of the batch latency histogram buckets
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Process-wide counters, shared by every run of this interpreter
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// Per-step timing costs two clock reads per step and row, so it is opt-in
+static STEP_TIMINGS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default)]
+struct Metrics {
+    runs_started: AtomicU64,
+    runs_completed: AtomicU64,
+    runs_failed: AtomicU64,
+    runs_cancelled: AtomicU64,
+    rows_processed: AtomicU64,
+    rows_saved: AtomicU64,
+    rows_quarantined: AtomicU64,
+    rows_skipped: AtomicU64,
+    bytes_read: AtomicU64,
+    batch_latency: Histogram,
+    /// action -> (rows, nanoseconds)
+    steps: Mutex<BTreeMap<String, (u64, u64)>>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    /// Non-cumulative counts; the last slot is the +Inf overflow bucket
+    counts: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let slot = LATENCY_BUCKETS.iter().position(|&bound| secs <= bound).unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[slot].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+pub enum RunOutcome {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+pub fn run_started() {
+    METRICS.runs_started.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn run_finished(outcome: RunOutcome) {
+    let counter = match outcome {
+        RunOutcome::Completed => &METRICS.runs_completed,
+        RunOutcome::Failed => &METRICS.runs_failed,
+        RunOutcome::Cancelled => &METRICS.runs_cancelled,
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Record one processed batch, so snapshots stay live during long runs
+pub fn batch_done(rows: u64, saved: u64, quarantined: u64, skipped: u64, bytes: u64, elapsed: Duration) {
+    METRICS.rows_processed.fetch_add(rows, Ordering::Relaxed);
+    METRICS.rows_saved.fetch_add(saved, Ordering::Relaxed);
+    METRICS.rows_quarantined.fetch_add(quarantined, Ordering::Relaxed);
+    METRICS.rows_skipped.fetch_add(skipped, Ordering::Relaxed);
+    METRICS.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+    METRICS.batch_latency.observe(elapsed);
+}
+
+pub fn step_timings_enabled() -> bool {
+    STEP_TIMINGS.load(Ordering::Relaxed)
+}
+
+pub fn set_step_timings(enabled: bool) {
+    STEP_TIMINGS.store(enabled, Ordering::Relaxed);
+}
+
+/// Fold the time spent in one step during a run into the totals
+pub fn add_step_time(action: &str, clock: &StepClock) {
+    if let Ok(mut steps) = METRICS.steps.lock() {
+        let entry = steps.entry(action.to_string()).or_default();
+        entry.0 += clock.rows.load(Ordering::Relaxed);
+        entry.1 += clock.nanos.load(Ordering::Relaxed);
+    }
+}
+
+/// Rows and time spent in one prepared step during a run
+#[derive(Default)]
+pub struct StepClock {
+    pub rows: AtomicU64,
+    pub nanos: AtomicU64,
+}
+
+/// Adds the time until drop to a step clock; covers every exit of a step
+pub struct StepTimer<'a> {
+    clock: &'a StepClock,
+    started: Instant,
+}
+
+impl<'a> StepTimer<'a> {
+    pub fn start(clock: &'a StepClock) -> Self {
+        Self { clock, started: Instant::now() }
+    }
+}
+
+impl Drop for StepTimer<'_> {
+    fn drop(&mut self) {
+        self.clock.rows.fetch_add(1, Ordering::Relaxed);
+        self.clock.nanos.fetch_add(self.started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+#[derive(Serialize)]
+pub struct StepTiming {
+    pub action: String,
+    pub rows: u64,
+    pub seconds: f64,
+}
+
+#[derive(Serialize)]
+pub struct Snapshot {
+    pub runs_started: u64,
+    pub runs_completed: u64,
+    pub runs_failed: u64,
+    pub runs_cancelled: u64,
+    pub rows_processed: u64,
+    pub rows_saved: u64,
+    pub rows_quarantined: u64,
+    pub rows_skipped: u64,
+    pub bytes_read: u64,
+    pub quarantine_rate: f64,
+    /// Cumulative `(upper bound seconds, count)` pairs, ending with +Inf
+    pub batch_latency_buckets: Vec<(f64, u64)>,
+    pub batch_latency_count: u64,
+    pub batch_latency_sum: f64,
+    pub step_timings: Vec<StepTiming>,
+}
+
+pub fn snapshot() -> Snapshot {
+    let load = |c: &AtomicU64| c.load(Ordering::Relaxed);
+    let m = &*METRICS;
+
+    let mut cumulative = 0;
+    let batch_latency_buckets: Vec<(f64, u64)> = LATENCY_BUCKETS.iter().copied().chain(std::iter::once(f64::INFINITY))
+        .zip(m.batch_latency.counts.iter())
+        .map(|(bound, count)| {
+            cumulative += load(count);
+            (bound, cumulative)
+        })
+        .collect();
+
+    let step_timings = m.steps.lock()
+        .map(|steps| steps.iter()
+            .map(|(action, &(rows, nanos))| StepTiming { action: action.clone(), rows, seconds: nanos as f64 / 1e9 })
+            .collect())
+        .unwrap_or_default();
+
+    let processed = load(&m.rows_processed);
+    Snapshot {
+        runs_started: load(&m.runs_started),
+        runs_completed: load(&m.runs_completed),
+        runs_failed: load(&m.runs_failed),
+        runs_cancelled: load(&m.runs_cancelled),
+        rows_processed: processed,
+        rows_saved: load(&m.rows_saved),
+        rows_quarantined: load(&m.rows_quarantined),
+        rows_skipped: load(&m.rows_skipped),
+        bytes_read: load(&m.bytes_read),
+        quarantine_rate: if processed > 0 { load(&m.rows_quarantined) as f64 / processed as f64 } else { 0.0 },
+        batch_latency_buckets,
+        batch_latency_count: cumulative,
+        batch_latency_sum: load(&m.batch_latency.sum_micros) as f64 / 1e6,
+        step_timings,
+    }
+}
+
+/// Zero every counter, e.g. between scrapes of a job that reports deltas
+pub fn reset() {
+    let m = &*METRICS;
+    for counter in [
+        &m.runs_started, &m.runs_completed, &m.runs_failed, &m.runs_cancelled,
+        &m.rows_processed, &m.rows_saved, &m.rows_quarantined, &m.rows_skipped, &m.bytes_read,
+        &m.batch_latency.sum_micros,
+    ] {
+        counter.store(0, Ordering::Relaxed);
+    }
+    for count in &m.batch_latency.counts {
+        count.store(0, Ordering::Relaxed);
+    }
+    if let Ok(mut steps) = m.steps.lock() { steps.clear(); }
+}
+
+/// Render a snapshot in the Prometheus text exposition format
+pub fn to_prometheus(s: &Snapshot) -> String {
+    let mut out = String::new();
+    let mut counter = |name: &str, help: &str, samples: &[(&str, u64)]| {
+        let _ = writeln!(out, "# HELP phaeton_{} {}", name, help);
+        let _ = writeln!(out, "# TYPE phaeton_{} counter", name);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "phaeton_{}{} {}", name, labels, value);
+        }
+    };
+
+    counter("runs_total", "Pipeline runs by outcome.", &[
+        ("{status=\"started\"}", s.runs_started),
+        ("{status=\"completed\"}", s.runs_completed),
+        ("{status=\"failed\"}", s.runs_failed),
+        ("{status=\"cancelled\"}", s.runs_cancelled),
+    ]);
+    counter("rows_total", "Rows by outcome.", &[
+        ("{status=\"processed\"}", s.rows_processed),
+        ("{status=\"saved\"}", s.rows_saved),
+        ("{status=\"quarantined\"}", s.rows_quarantined),
+        ("{status=\"skipped\"}", s.rows_skipped),
+    ]);
+    counter("bytes_read_total", "Source bytes consumed.", &[("", s.bytes_read)]);
+
+    let _ = writeln!(out, "# HELP phaeton_quarantine_ratio Quarantined rows over processed rows.");
+    let _ = writeln!(out, "# TYPE phaeton_quarantine_ratio gauge");
+    let _ = writeln!(out, "phaeton_quarantine_ratio {}", s.quarantine_rate);
+
+    let _ = writeln!(out, "# HELP phaeton_batch_duration_seconds Time to process one batch.");
+    let _ = writeln!(out, "# TYPE phaeton_batch_duration_seconds histogram");
+    for (bound, count) in &s.batch_latency_buckets {
+        let le = if bound.is_infinite() { "+Inf".to_string() } else { bound.to_string() };
+        let _ = writeln!(out, "phaeton_batch_duration_seconds_bucket{{le=\"{}\"}} {}", le, count);
+    }
+    let _ = writeln!(out, "phaeton_batch_duration_seconds_sum {}", s.batch_latency_sum);
+    let _ = writeln!(out, "phaeton_batch_duration_seconds_count {}", s.batch_latency_count);
+
+    if !s.step_timings.is_empty() {
+        let _ = writeln!(out, "# HELP phaeton_step_seconds_total Time spent in each step action.");
+        let _ = writeln!(out, "# TYPE phaeton_step_seconds_total counter");
+        for step in &s.step_timings {
+            let _ = writeln!(out, "phaeton_step_seconds_total{{action=\"{}\"}} {}", step.action, step.seconds);
+        }
+        let _ = writeln!(out, "# HELP phaeton_step_rows_total Rows that entered each step action.");
+        let _ = writeln!(out, "# TYPE phaeton_step_rows_total counter");
+        for step in &s.step_timings {
+            let _ = writeln!(out, "phaeton_step_rows_total{{action=\"{}\"}} {}", step.action, step.rows);
+        }
+    }
+    out
+}
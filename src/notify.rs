@@ -0,0 +1,202 @@
+// COMPLETION NOTIFICATIONS
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::engine::PipelineReport;
+use crate::error::{PhaetonError, Result};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a notification goes
+#[derive(Clone, Debug)]
+pub enum Target {
+    /// POSTed to this URL
+    Url(String),
+    /// Run through the shell with the event on stdin
+    Command(String),
+}
+
+/// Which outcomes are notified
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum When {
+    Always,
+    Success,
+    Failure,
+}
+
+/// Body of a webhook: the event JSON, or a Slack message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Slack,
+}
+
+#[derive(Clone, Debug)]
+pub struct Notifier {
+    pub target: Target,
+    pub when: When,
+    pub format: Format,
+    pub headers: Vec<(String, String)>,
+    pub timeout: Duration,
+}
+
+impl Notifier {
+    /// One `notify` entry: a URL or command, or an object with `url` or `command`
+    /// and any of `on`, `format`, `headers` and `timeout` (seconds)
+    fn parse(value: &Value) -> Result<Self> {
+        let invalid = |reason: String| PhaetonError::InvalidStep(format!("notify: {}", reason));
+        let target = |text: &str| if text.starts_with("http://") || text.starts_with("https://") {
+            Target::Url(text.to_string())
+        } else {
+            Target::Command(text.to_string())
+        };
+        let mut notifier = Self { target: Target::Command(String::new()), when: When::Always, format: Format::Json, headers: Vec::new(), timeout: DEFAULT_TIMEOUT };
+        match value {
+            Value::String(text) => notifier.target = target(text),
+            Value::Object(map) => {
+                notifier.target = match (map.get("url").and_then(Value::as_str), map.get("command").and_then(Value::as_str)) {
+                    (Some(url), None) => Target::Url(url.to_string()),
+                    (None, Some(command)) => Target::Command(command.to_string()),
+                    _ => return Err(invalid("give exactly one of 'url' and 'command'".to_string())),
+                };
+                notifier.when = match map.get("on").and_then(Value::as_str).unwrap_or("always") {
+                    "always" => When::Always,
+                    "success" => When::Success,
+                    "failure" => When::Failure,
+                    other => return Err(invalid(format!("unknown 'on' value '{}', expected always, success or failure", other))),
+                };
+                notifier.format = match map.get("format").and_then(Value::as_str).unwrap_or("json") {
+                    "json" => Format::Json,
+                    "slack" => Format::Slack,
+                    other => return Err(invalid(format!("unknown format '{}', expected json or slack", other))),
+                };
+                if let Some(headers) = map.get("headers").and_then(Value::as_object) {
+                    notifier.headers = headers.iter().map(|(k, v)| (k.clone(), v.as_str().map_or_else(|| v.to_string(), str::to_string))).collect();
+                }
+                if let Some(secs) = map.get("timeout").and_then(Value::as_f64).filter(|s| *s > 0.0) {
+                    notifier.timeout = Duration::from_secs_f64(secs);
+                }
+            },
+            other => return Err(invalid(format!("expected a URL, a command or an object, got {}", other))),
+        }
+        match &notifier.target {
+            Target::Url(url) if !url.starts_with("http://") && !url.starts_with("https://") => Err(invalid(format!("'{}' is not an http(s) URL", url))),
+            Target::Command(command) if command.trim().is_empty() => Err(invalid("the command is empty".to_string())),
+            _ => Ok(notifier),
+        }
+    }
+
+    /// The notifiers of a `notify` setting, which is one entry or a list of them
+    pub fn from_setting(setting: &Value) -> Result<Vec<Self>> {
+        match setting {
+            Value::Array(items) => items.iter().map(Self::parse).collect(),
+            single => Ok(vec![Self::parse(single)?]),
+        }
+    }
+
+    fn wants(&self, event: &str) -> bool {
+        match self.when {
+            When::Always => true,
+            When::Success => event == "completed",
+            When::Failure => event != "completed",
+        }
+    }
+
+    fn send(&self, event: &Value) -> std::result::Result<(), String> {
+        match &self.target {
+            Target::Url(url) => {
+                let body = match self.format {
+                    Format::Json => event.to_string(),
+                    Format::Slack => json!({ "text": summary(event) }).to_string(),
+                };
+                let mut request = ureq::post(url).timeout(self.timeout).set("Content-Type", "application/json");
+                for (name, value) in &self.headers {
+                    request = request.set(name, value);
+                }
+                request.send_string(&body).map(|_| ()).map_err(|e| e.to_string())
+            },
+            Target::Command(command) => {
+                let mut child = shell(command)
+                    .env("PHAETON_EVENT", event["event"].as_str().unwrap_or_default())
+                    .env("PHAETON_SOURCE", event["source"].as_str().unwrap_or_default())
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .spawn()
+                    .map_err(|e| e.to_string())?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    // A command that does not read its input is fine
+                    let _ = stdin.write_all(event.to_string().as_bytes());
+                }
+                let status = child.wait().map_err(|e| e.to_string())?;
+                if status.success() { Ok(()) } else { Err(format!("command exited with {}", status)) }
+            },
+        }
+    }
+
+    fn describe(&self) -> &str {
+        match &self.target {
+            Target::Url(url) => url,
+            Target::Command(command) => command,
+        }
+    }
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+/// One line for chat messages
+fn summary(event: &Value) -> String {
+    let source = event["source"].as_str().unwrap_or_default();
+    match event["event"].as_str().unwrap_or_default() {
+        "completed" | "cancelled" => format!(
+            "phaeton: {} {}: {} rows processed, {} saved, {} quarantined in {} ms",
+            source, event["event"].as_str().unwrap_or_default(), event["processed_rows"], event["saved_rows"], event["quarantined_rows"], event["duration_ms"]
+        ),
+        _ => format!("phaeton: {} failed: {}", source, event["error"].as_str().unwrap_or("unknown error")),
+    }
+}
+
+/// The event of a finished run: its stats with `event` set to completed, cancelled
+/// or failed (an error, or an expectation of severity 'error' that did not hold)
+pub fn event(result: &Result<PipelineReport>, source: &str, output: &str) -> Value {
+    match result {
+        Ok(report) => {
+            let mut event = report.to_json();
+            let name = match report.expectation_error() {
+                Some(error) => {
+                    event["error"] = Value::from(error);
+                    "failed"
+                },
+                None if report.stats.cancelled => "cancelled",
+                None => "completed",
+            };
+            event["event"] = Value::from(name);
+            event
+        },
+        Err(e) => json!({ "event": "failed", "source": source, "output": output, "error": e.to_string() }),
+    }
+}
+
+/// Hand `event` to every notifier that wants it; returns a warning per failed delivery
+pub fn deliver(notifiers: &[Notifier], event: &Value) -> Vec<String> {
+    let name = event["event"].as_str().unwrap_or_default();
+    notifiers.iter()
+        .filter(|n| n.wants(name))
+        .filter_map(|n| n.send(event).err().map(|e| format!("Notification to {} failed: {}", n.describe(), e)))
+        .collect()
+}
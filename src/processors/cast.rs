@@ -1,45 +1,223 @@
 use crate::error::{PhaetonError, Result};
 use crate::processors::text;
 use std::borrow::Cow;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 
-pub fn to_float(input: &str, col_name: &str, clean_first: bool) -> Result<f64> {
-    let cleaned = if clean_first {
-        text::scrub_currency(input) // Returns Cow
+/// How numeric casts read a trailing `%`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Percent {
+    /// `12.5%` -> 0.125
+    Fraction,
+    /// `12.5%` -> 12.5
+    Number,
+}
+
+impl Percent {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "fraction" => Ok(Percent::Fraction),
+            "number" => Ok(Percent::Number),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown percent handling '{}': expected fraction or number", other))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Percent::Fraction => "fraction",
+            Percent::Number => "number",
+        }
+    }
+}
+
+/// The number in `input` ready to parse: a trailing `%` read per `percent`, symbols
+/// stripped by `scrub` with `clean_first`, and scientific notation written out
+fn numeric_text<'a>(input: &'a str, clean_first: bool, percent: Option<Percent>, scrub: fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    let trimmed = input.trim();
+    let (number, fraction) = match (percent, trimmed.strip_suffix('%')) {
+        (Some(p), Some(number)) => (number.trim_end(), p == Percent::Fraction),
+        _ => (trimmed, false),
+    };
+    let value = if text::is_scientific(number) {
+        text::expand_scientific(number).map_or(Cow::Borrowed(number), Cow::Owned)
+    } else if clean_first {
+        scrub(number)
     } else {
-        Cow::Borrowed(input)
+        Cow::Borrowed(number)
     };
-    
-    // Parse directly
-    cleaned.trim().parse::<f64>()
+    if fraction {
+        text::shift_decimal(value.trim(), -2).map_or(value, Cow::Owned)
+    } else {
+        value
+    }
+}
+
+/// Validate a float, keeping the cleaned textual form (e.g. "$1,234.50" -> "1234.50")
+/// so write-back doesn't lose trailing zeros to float formatting.
+pub fn to_float_text(input: &str, col_name: &str, clean_first: bool, percent: Option<Percent>) -> Result<String> {
+    let cleaned = numeric_text(input, clean_first, percent, text::scrub_currency);
+
+    let trimmed = cleaned.trim();
+    trimmed.parse::<f64>()
+        .map(|_| trimmed.to_string())
         .map_err(|_| PhaetonError::CastError {
             col: col_name.to_string(),
+            row: None,
+            line: None,
             reason: format!("Cannot convert '{}' to float", input)
         })
 }
 
-pub fn to_int(input: &str, col_name: &str, clean_first: bool) -> Result<i64> {
-    let cleaned = if clean_first {
-        text::scrub_numeric_only(input)
-    } else {
-        Cow::Borrowed(input)
-    };
+pub fn to_int(input: &str, col_name: &str, clean_first: bool, percent: Option<Percent>) -> Result<i64> {
+    let cleaned = numeric_text(input, clean_first, percent, text::scrub_numeric_only);
     
     // Parse directly
     cleaned.trim().parse::<i64>()
         .map_err(|_| PhaetonError::CastError {
             col: col_name.to_string(),
+            row: None,
+            line: None,
             reason: format!("Cannot convert '{}' to int", input)
         })
 }
 
-pub fn to_bool(input: &str, col_name: &str) -> Result<bool> {
+const DEFAULT_TRUE_VALUES: &[&str] = &["true", "1", "yes", "y", "t"];
+const DEFAULT_FALSE_VALUES: &[&str] = &["false", "0", "no", "n", "f"];
+
+/// Words `to_bool` accepts for each side, stored trimmed and lowercased
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoolValues {
+    truthy: Vec<String>,
+    falsy: Vec<String>,
+}
+
+impl Default for BoolValues {
+    fn default() -> Self {
+        Self {
+            truthy: DEFAULT_TRUE_VALUES.iter().map(|s| s.to_string()).collect(),
+            falsy: DEFAULT_FALSE_VALUES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl BoolValues {
+    /// A custom list replaces the defaults of its own side only
+    pub fn new(true_values: Option<Vec<String>>, false_values: Option<Vec<String>>) -> Result<Self> {
+        let normalize = |values: Vec<String>| values.iter().map(|v| v.trim().to_lowercase()).collect::<Vec<_>>();
+        let defaults = Self::default();
+        let truthy = true_values.map(normalize).unwrap_or(defaults.truthy);
+        let falsy = false_values.map(normalize).unwrap_or(defaults.falsy);
+
+        if truthy.is_empty() || falsy.is_empty() {
+            return Err(PhaetonError::InvalidStep("'true_values' and 'false_values' cannot be empty".to_string()));
+        }
+        if let Some(both) = truthy.iter().find(|v| falsy.contains(v)) {
+            return Err(PhaetonError::InvalidStep(format!("'{}' is in both true_values and false_values", both)));
+        }
+        Ok(Self { truthy, falsy })
+    }
+
+    pub fn truthy(&self) -> &[String] {
+        &self.truthy
+    }
+
+    pub fn falsy(&self) -> &[String] {
+        &self.falsy
+    }
+}
+
+pub fn to_bool(input: &str, col_name: &str, values: &BoolValues) -> Result<bool> {
     let normalized = input.trim().to_lowercase();
-    match normalized.as_str() {
-        "true" | "1" | "yes" | "y" | "t" => Ok(true),
-        "false" | "0" | "no" | "n" | "f" => Ok(false),
-        _ => Err(PhaetonError::CastError {
+    if values.truthy.contains(&normalized) {
+        Ok(true)
+    } else if values.falsy.contains(&normalized) {
+        Ok(false)
+    } else {
+        Err(PhaetonError::CastError {
             col: col_name.to_string(),
+            row: None,
+            line: None,
             reason: format!("Cannot convert '{}' to bool", input)
         })
     }
-}
\ No newline at end of file
+}
+
+const DEFAULT_DATE_FORMATS: &[&str] = &["%Y-%m-%d"];
+const DEFAULT_DATETIME_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"];
+
+pub fn to_date(input: &str, col_name: &str, formats: &[String]) -> Result<NaiveDate> {
+    let value = input.trim();
+    let parsed = if formats.is_empty() {
+        DEFAULT_DATE_FORMATS.iter().find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+    } else {
+        formats.iter().find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+    };
+
+    parsed.ok_or_else(|| PhaetonError::CastError {
+        col: col_name.to_string(),
+        row: None,
+        line: None,
+        reason: format!("Cannot convert '{}' to date", input)
+    })
+}
+
+pub fn to_datetime(input: &str, col_name: &str, formats: &[String]) -> Result<NaiveDateTime> {
+    let value = input.trim();
+    let parsed = if formats.is_empty() {
+        // RFC 3339 first so offsets are honoured, then naive ISO variants
+        DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.naive_utc())
+            .or_else(|| DEFAULT_DATETIME_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(value, f).ok()))
+    } else {
+        formats.iter().find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+    };
+
+    parsed.ok_or_else(|| PhaetonError::CastError {
+        col: col_name.to_string(),
+        row: None,
+        line: None,
+        reason: format!("Cannot convert '{}' to datetime", input)
+    })
+}
+
+/// Validate a fixed-point decimal without going through binary floats.
+/// Returns the canonical representation padded to `scale` fractional digits.
+pub fn to_decimal(input: &str, col_name: &str, clean_first: bool, percent: Option<Percent>, precision: u32, scale: u32) -> Result<String> {
+    let cleaned = numeric_text(input, clean_first, percent, text::scrub_currency);
+
+    let fail = |reason: String| PhaetonError::CastError { col: col_name.to_string(), row: None, line: None, reason };
+
+    let value = cleaned.trim();
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let (int_part, frac_part) = match unsigned.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (unsigned, ""),
+    };
+
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(fail(format!("Cannot convert '{}' to decimal", input)));
+    }
+
+    let int_digits = int_part.trim_start_matches('0');
+    if frac_part.len() > scale as usize {
+        return Err(fail(format!("'{}' exceeds scale {} of decimal({}, {})", input, scale, precision, scale)));
+    }
+    if int_digits.len() > precision.saturating_sub(scale) as usize {
+        return Err(fail(format!("'{}' exceeds precision of decimal({}, {})", input, precision, scale)));
+    }
+
+    let mut normalized = String::with_capacity(int_digits.len() + scale as usize + 2);
+    let is_zero = int_digits.is_empty() && frac_part.bytes().all(|b| b == b'0');
+    if negative && !is_zero { normalized.push('-'); }
+    normalized.push_str(if int_digits.is_empty() { "0" } else { int_digits });
+    if scale > 0 {
+        normalized.push('.');
+        normalized.push_str(frac_part);
+        for _ in frac_part.len()..scale as usize { normalized.push('0'); }
+    }
+    Ok(normalized)
+}
@@ -0,0 +1,185 @@
+// FILE CONCATENATION
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::Instant;
+
+use csv::{ByteRecord, Reader, ReaderBuilder, WriterBuilder};
+use serde::Serialize;
+use tracing::info;
+
+use crate::error::{PhaetonError, Result};
+use crate::processors::probe;
+
+/// What happens to a file that lacks some of the output columns
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum MissingColumns {
+    /// Write blanks in their place
+    #[default]
+    Fill,
+    /// Fail before anything is written
+    Fail,
+}
+
+impl MissingColumns {
+    pub fn parse(missing: &str) -> Result<Self> {
+        match missing {
+            "fill" => Ok(MissingColumns::Fill),
+            "fail" => Ok(MissingColumns::Fail),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown missing column policy '{}': expected fill or fail", other))),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ConcatOptions {
+    pub missing: MissingColumns,
+    /// Output columns, in this order; columns of the files not listed are dropped.
+    /// Defaults to every column of every file, in order of first appearance.
+    pub columns: Option<Vec<String>>,
+    /// Adds a column holding the path each row came from
+    pub source_column: Option<String>,
+    /// Delimiter of the inputs; sniffed per file when None
+    pub delimiter: Option<u8>,
+    /// Delimiter of the output; defaults to the first file's
+    pub output_delimiter: Option<u8>,
+}
+
+/// What one input contributed to the output
+#[derive(Serialize, Debug)]
+pub struct ConcatFile {
+    pub path: String,
+    pub rows: u64,
+    /// Output columns the file has
+    pub columns: Vec<String>,
+    /// Output columns the file lacks, written blank
+    pub missing: Vec<String>,
+    /// Columns of the file not in the output
+    pub dropped: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConcatStats {
+    pub output: String,
+    pub columns: Vec<String>,
+    pub rows: u64,
+    pub files: Vec<ConcatFile>,
+    pub duration_ms: u64,
+}
+
+/// Header and delimiter of one source; its rows are read once the output is open
+struct Input {
+    path: String,
+    delimiter: u8,
+    headers: Vec<String>,
+}
+
+fn reader(path: &str, delimiter: u8) -> Result<Reader<BufReader<File>>> {
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    Ok(ReaderBuilder::new().flexible(true).delimiter(delimiter).from_reader(BufReader::new(file)))
+}
+
+fn inspect(path: &str, delimiter: Option<u8>) -> Result<Input> {
+    if !Path::new(path).exists() {
+        return Err(PhaetonError::FileNotFound(path.to_string()));
+    }
+    let delimiter = delimiter.map_or_else(|| probe::sniff_delimiter(path), Ok)?;
+    let headers: Vec<String> = reader(path, delimiter)?.headers()?.iter().map(|h| h.trim().to_string()).collect();
+    for (i, name) in headers.iter().enumerate() {
+        if headers[..i].contains(name) {
+            return Err(PhaetonError::InvalidStep(format!("Column '{}' appears more than once in {}", name, path)));
+        }
+    }
+    Ok(Input { path: path.to_string(), delimiter, headers })
+}
+
+/// Append `sources` into the CSV file `output`, matching columns by name (trimmed)
+/// whatever their order in each file
+pub fn concat_files(sources: &[String], output: &str, options: &ConcatOptions) -> Result<ConcatStats> {
+    let started = Instant::now();
+    if sources.is_empty() {
+        return Err(PhaetonError::InvalidStep("Concatenation needs at least one source file".to_string()));
+    }
+
+    // Every header is read before the output is created, so a failing policy
+    // leaves nothing behind
+    let inputs = sources.iter().map(|path| inspect(path, options.delimiter)).collect::<Result<Vec<_>>>()?;
+
+    let columns: Vec<String> = match &options.columns {
+        Some(columns) => columns.iter().map(|c| c.trim().to_string()).collect(),
+        None => {
+            let mut union: Vec<String> = Vec::new();
+            for name in inputs.iter().flat_map(|input| &input.headers) {
+                if !union.contains(name) {
+                    union.push(name.clone());
+                }
+            }
+            union
+        },
+    };
+    if columns.is_empty() {
+        return Err(PhaetonError::InvalidStep("Concatenation has no output columns".to_string()));
+    }
+    if let Some(name) = options.source_column.as_ref().filter(|name| columns.contains(name)) {
+        return Err(PhaetonError::InvalidStep(format!("Source column '{}' is already a column of the output", name)));
+    }
+
+    let mut files: Vec<ConcatFile> = inputs.iter().map(|input| ConcatFile {
+        path: input.path.clone(),
+        rows: 0,
+        columns: columns.iter().filter(|c| input.headers.contains(c)).cloned().collect(),
+        missing: columns.iter().filter(|c| !input.headers.contains(c)).cloned().collect(),
+        dropped: input.headers.iter().filter(|h| !columns.contains(h)).cloned().collect(),
+    }).collect();
+
+    if options.missing == MissingColumns::Fail {
+        let lacking: Vec<String> = files.iter()
+            .filter(|f| !f.missing.is_empty())
+            .map(|f| format!("{} lacks {}", f.path, f.missing.join(", ")))
+            .collect();
+        if !lacking.is_empty() {
+            return Err(PhaetonError::InvalidStep(format!("Files do not share the output columns: {}", lacking.join("; "))));
+        }
+    }
+
+    if let Some(dir) = Path::new(output).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.output_delimiter.unwrap_or(inputs[0].delimiter))
+        .from_writer(BufWriter::new(File::create(output)?));
+    let mut headers = columns.clone();
+    headers.extend(options.source_column.iter().cloned());
+    writer.write_record(&headers)?;
+
+    let mut record = ByteRecord::new();
+    let mut row = ByteRecord::new();
+    let mut rows = 0;
+    for (input, file) in inputs.iter().zip(&mut files) {
+        let positions: HashMap<&str, usize> = input.headers.iter().enumerate().map(|(i, h)| (h.as_str(), i)).collect();
+        // Field of each output column in this file's rows, None for a blank
+        let layout: Vec<Option<usize>> = columns.iter()
+            .map(|c| positions.get(c.as_str()).copied())
+            .collect();
+        let mut reader = reader(&input.path, input.delimiter)?;
+        while reader.read_byte_record(&mut record)? {
+            row.clear();
+            for idx in &layout {
+                row.push_field(idx.and_then(|i| record.get(i)).unwrap_or_default());
+            }
+            if options.source_column.is_some() {
+                row.push_field(input.path.as_bytes());
+            }
+            writer.write_byte_record(&row)?;
+            file.rows += 1;
+        }
+        rows += file.rows;
+    }
+    writer.flush()?;
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    info!(output, files = files.len(), rows, "Concatenated files");
+    Ok(ConcatStats { output: output.to_string(), columns: headers, rows, files, duration_ms })
+}
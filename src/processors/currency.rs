@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use csv::ReaderBuilder;
+use crate::error::{PhaetonError, Result};
+use crate::processors::text;
+use tracing::debug;
+
+/// Load a rate table from a CSV file with `currency,rate` columns (header row required)
+pub fn load_rates(path: &str) -> Result<HashMap<String, f64>> {
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(BufReader::new(file));
+
+    let mut rates = HashMap::new();
+    for result in reader.records() {
+        let record = result?;
+        let code = record.get(0).unwrap_or("").trim().to_uppercase();
+        let raw_rate = record.get(1).unwrap_or("").trim();
+
+        if code.is_empty() {
+            continue;
+        }
+
+        let rate = raw_rate.parse::<f64>()
+            .map_err(|_| PhaetonError::InvalidStep(format!("Invalid rate '{}' for currency '{}' in {}", raw_rate, code, path)))?;
+        let rate = checked_rate(&code, rate).map_err(|e| PhaetonError::InvalidStep(format!("{} in {}", e, path)))?;
+        rates.insert(code, rate);
+    }
+    debug!(path, currencies = rates.len(), "Loaded currency rates");
+    Ok(rates)
+}
+
+/// A rate of zero or below (or NaN/infinite) would turn every amount into inf or NaN
+pub fn checked_rate(code: &str, rate: f64) -> std::result::Result<f64, String> {
+    if rate.is_finite() && rate > 0.0 {
+        Ok(rate)
+    } else {
+        Err(format!("Rate for '{}' must be a positive number, got {}", code, rate))
+    }
+}
+
+/// Convert an amount between currencies.
+/// Rates are expressed against a common base: 1 unit of currency = `rate` base units.
+pub fn convert(input: &str, from: &str, to: &str, rates: &HashMap<String, f64>, clean_first: bool) -> std::result::Result<f64, String> {
+    let from_rate = rates.get(from)
+        .ok_or_else(|| format!("Currency: Unknown source currency '{}'", from))?;
+    let to_rate = rates.get(to)
+        .ok_or_else(|| format!("Currency: Unknown target currency '{}'", to))?;
+
+    let amount = if clean_first {
+        text::scrub_currency(input).trim().parse::<f64>()
+    } else {
+        input.trim().parse::<f64>()
+    }.map_err(|_| format!("Currency: Cannot convert '{}' to number", input))?;
+
+    Ok(amount * from_rate / to_rate)
+}
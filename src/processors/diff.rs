@@ -0,0 +1,218 @@
+// KEYED FILE DIFF
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::time::Instant;
+
+use csv::{ByteRecord, Reader, ReaderBuilder, Writer, WriterBuilder};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::info;
+
+use crate::error::{PhaetonError, Result};
+use crate::processors::probe;
+
+#[derive(Default)]
+pub struct DiffOptions {
+    /// Columns identifying a row in both files
+    pub key: Vec<String>,
+    /// Columns compared; defaults to every non-key column both files have
+    pub columns: Option<Vec<String>>,
+    /// Delimiters of the files; sniffed when None
+    pub delimiter: Option<u8>,
+    pub new_delimiter: Option<u8>,
+    /// Rows only in the new file, written with its header
+    pub added: Option<String>,
+    /// Rows only in the old file, written with its header
+    pub removed: Option<String>,
+    /// Changed rows as they are in the new file, plus a `_phaeton_changes` column
+    pub changed: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DiffStats {
+    pub old: String,
+    pub new: String,
+    pub old_rows: u64,
+    pub new_rows: u64,
+    pub added: u64,
+    pub removed: u64,
+    pub changed: u64,
+    pub unchanged: u64,
+    /// Changed rows per compared column, in column order; unchanged columns are left out
+    pub changed_columns: Map<String, Value>,
+    /// Columns only the new file has, and only the old one
+    pub columns_added: Vec<String>,
+    pub columns_removed: Vec<String>,
+    pub duration_ms: u64,
+}
+
+impl DiffStats {
+    pub fn identical(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.changed == 0
+    }
+}
+
+fn reader(path: &str, delimiter: Option<u8>) -> Result<(Reader<BufReader<File>>, u8)> {
+    if !Path::new(path).exists() {
+        return Err(PhaetonError::FileNotFound(path.to_string()));
+    }
+    let delimiter = delimiter.map_or_else(|| probe::sniff_delimiter(path), Ok)?;
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    Ok((ReaderBuilder::new().flexible(true).delimiter(delimiter).from_reader(BufReader::new(file)), delimiter))
+}
+
+fn writer(path: &str, delimiter: u8, headers: &ByteRecord) -> Result<Writer<BufWriter<File>>> {
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = WriterBuilder::new().delimiter(delimiter).from_writer(BufWriter::new(File::create(path)?));
+    writer.write_byte_record(headers)?;
+    Ok(writer)
+}
+
+fn names(headers: &ByteRecord) -> Vec<String> {
+    headers.iter().map(|h| String::from_utf8_lossy(h).trim().to_string()).collect()
+}
+
+fn position(headers: &[String], name: &str, path: &str) -> Result<usize> {
+    headers.iter().position(|h| h == name.trim())
+        .ok_or_else(|| PhaetonError::ColumnNotFound(format!("{} (in {})", name, path)))
+}
+
+/// Key of a row: the trimmed key fields, length-prefixed so ("ab", "c") and ("a", "bc") differ
+fn key_of(record: &ByteRecord, idxs: &[usize]) -> Vec<u8> {
+    let mut key = Vec::new();
+    for &idx in idxs {
+        let field = record.get(idx).unwrap_or_default().trim_ascii();
+        key.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        key.extend_from_slice(field);
+    }
+    key
+}
+
+fn show_key(record: &ByteRecord, idxs: &[usize]) -> String {
+    idxs.iter().map(|&i| String::from_utf8_lossy(record.get(i).unwrap_or_default()).trim().to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Compare `new` against `old` row by row, matching rows on the key columns. The
+/// old file is held in memory; the new one is streamed.
+pub fn diff_files(old: &str, new: &str, options: &DiffOptions) -> Result<DiffStats> {
+    let started = Instant::now();
+    if options.key.is_empty() {
+        return Err(PhaetonError::InvalidStep("Diff needs at least one key column".to_string()));
+    }
+    let (mut old_reader, old_delimiter) = reader(old, options.delimiter)?;
+    let (mut new_reader, new_delimiter) = reader(new, options.new_delimiter.or(options.delimiter))?;
+    let old_raw = old_reader.byte_headers()?.clone();
+    let new_raw = new_reader.byte_headers()?.clone();
+    let (old_headers, new_headers) = (names(&old_raw), names(&new_raw));
+
+    let old_keys = options.key.iter().map(|k| position(&old_headers, k, old)).collect::<Result<Vec<_>>>()?;
+    let new_keys = options.key.iter().map(|k| position(&new_headers, k, new)).collect::<Result<Vec<_>>>()?;
+    let compared: Vec<String> = match &options.columns {
+        Some(columns) => columns.iter().map(|c| c.trim().to_string()).collect(),
+        None => old_headers.iter()
+            .filter(|h| new_headers.contains(h) && !options.key.iter().any(|k| k.trim() == h.as_str()))
+            .cloned()
+            .collect(),
+    };
+    // (old index, new index) of every compared column
+    let pairs = compared.iter()
+        .map(|c| Ok((position(&old_headers, c, old)?, position(&new_headers, c, new)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Old rows by key; taken out as the new file matches them, so what is left was removed
+    let mut old_rows: Vec<Option<ByteRecord>> = Vec::new();
+    let mut by_key: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut record = ByteRecord::new();
+    while old_reader.read_byte_record(&mut record)? {
+        if by_key.insert(key_of(&record, &old_keys), old_rows.len()).is_some() {
+            return Err(PhaetonError::InvalidStep(format!("Key ({}) appears more than once in {}", show_key(&record, &old_keys), old)));
+        }
+        old_rows.push(Some(record.clone()));
+    }
+
+    let mut added = options.added.as_deref().map(|p| writer(p, new_delimiter, &new_raw)).transpose()?;
+    let mut changed = match options.changed.as_deref() {
+        Some(path) => {
+            let mut headers = new_raw.clone();
+            headers.push_field(b"_phaeton_changes");
+            Some(writer(path, new_delimiter, &headers)?)
+        },
+        None => None,
+    };
+
+    let mut stats = DiffStats {
+        old: old.to_string(),
+        new: new.to_string(),
+        old_rows: old_rows.len() as u64,
+        new_rows: 0,
+        added: 0,
+        removed: 0,
+        changed: 0,
+        unchanged: 0,
+        changed_columns: Map::new(),
+        columns_added: new_headers.iter().filter(|h| !old_headers.contains(h)).cloned().collect(),
+        columns_removed: old_headers.iter().filter(|h| !new_headers.contains(h)).cloned().collect(),
+        duration_ms: 0,
+    };
+    let mut column_changes = vec![0u64; pairs.len()];
+    // Keys of added rows, to catch them twice
+    let mut seen: HashSet<Vec<u8>> = HashSet::new();
+    let mut changes = Vec::new();
+
+    while new_reader.read_byte_record(&mut record)? {
+        stats.new_rows += 1;
+        let key = key_of(&record, &new_keys);
+        let Some(&idx) = by_key.get(&key) else {
+            if !seen.insert(key) {
+                return Err(PhaetonError::InvalidStep(format!("Key ({}) appears more than once in {}", show_key(&record, &new_keys), new)));
+            }
+            stats.added += 1;
+            if let Some(w) = &mut added { w.write_byte_record(&record)?; }
+            continue;
+        };
+        let Some(before) = old_rows[idx].take() else {
+            return Err(PhaetonError::InvalidStep(format!("Key ({}) appears more than once in {}", show_key(&record, &new_keys), new)));
+        };
+
+        changes.clear();
+        for (n, &(o, c)) in pairs.iter().enumerate() {
+            let (was, now) = (before.get(o).unwrap_or_default(), record.get(c).unwrap_or_default());
+            if was != now {
+                column_changes[n] += 1;
+                changes.push(format!("{}: {} -> {}", compared[n], String::from_utf8_lossy(was), String::from_utf8_lossy(now)));
+            }
+        }
+        if changes.is_empty() {
+            stats.unchanged += 1;
+            continue;
+        }
+        stats.changed += 1;
+        if let Some(w) = &mut changed {
+            let mut row = record.clone();
+            row.push_field(changes.join("; ").as_bytes());
+            w.write_byte_record(&row)?;
+        }
+    }
+
+    let mut removed = options.removed.as_deref().map(|p| writer(p, old_delimiter, &old_raw)).transpose()?;
+    for row in old_rows.iter().flatten() {
+        stats.removed += 1;
+        if let Some(w) = &mut removed { w.write_byte_record(row)?; }
+    }
+    for w in [&mut added, &mut changed, &mut removed].into_iter().flatten() {
+        w.flush()?;
+    }
+
+    stats.changed_columns = compared.iter().zip(column_changes)
+        .filter(|&(_, rows)| rows > 0)
+        .map(|(column, rows)| (column.clone(), Value::from(rows)))
+        .collect();
+    stats.duration_ms = started.elapsed().as_millis() as u64;
+    info!(old, new, added = stats.added, removed = stats.removed, changed = stats.changed, "Compared files");
+    Ok(stats)
+}
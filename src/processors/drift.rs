@@ -0,0 +1,252 @@
+// DRIFT DETECTION
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use chrono::Local;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::{PhaetonError, Result};
+use crate::processors::profile;
+
+const BASELINE_VERSION: u64 = 1;
+
+/// Top values profiled per column; categorical columns need their full value list
+const DRIFT_TOP_K: usize = 50;
+
+/// String columns with at most this many distinct values count as categorical
+const CATEGORICAL_MAX_DISTINCT: u64 = 20;
+
+/// Null rates closer than this are never reported, so 0.1% -> 0.3% is not a shift
+const MIN_NULL_DELTA: f64 = 0.01;
+
+/// How far the statistics of a run may move before it counts as drift
+#[derive(Clone, Debug)]
+pub struct DriftOptions {
+    /// Reported when the null rate grows by at least this factor
+    pub null_factor: f64,
+    /// Reported when the mean moves by more than this many baseline standard deviations
+    pub mean_tolerance: f64,
+    /// Replace the baseline with this run's statistics after comparing
+    pub update: bool,
+}
+
+impl Default for DriftOptions {
+    fn default() -> Self {
+        Self { null_factor: 3.0, mean_tolerance: 1.0, update: false }
+    }
+}
+
+impl DriftOptions {
+    /// Baseline path and options of a `drift` setting: a path, or an object with
+    /// `baseline` and any of `null_factor`, `mean_tolerance` and `update`
+    pub fn from_setting(setting: &Value) -> Result<(String, Self)> {
+        let defaults = Self::default();
+        match setting {
+            Value::String(path) => Ok((path.clone(), defaults)),
+            Value::Object(map) => {
+                let baseline = map.get("baseline").and_then(Value::as_str)
+                    .ok_or_else(|| PhaetonError::InvalidStep("The drift setting needs a 'baseline' path".to_string()))?;
+                let number = |key: &str, default: f64| map.get(key).and_then(Value::as_f64).unwrap_or(default);
+                let options = Self {
+                    null_factor: number("null_factor", defaults.null_factor),
+                    mean_tolerance: number("mean_tolerance", defaults.mean_tolerance),
+                    update: map.get("update").and_then(Value::as_bool).unwrap_or(false),
+                };
+                options.check()?;
+                Ok((baseline.to_string(), options))
+            },
+            other => Err(PhaetonError::InvalidStep(format!("The drift setting must be a baseline path or an object, got {}", other))),
+        }
+    }
+
+    pub fn check(&self) -> Result<()> {
+        if self.null_factor.is_nan() || self.null_factor <= 1.0 {
+            return Err(PhaetonError::InvalidStep(format!("Drift 'null_factor' must be greater than 1, got {}", self.null_factor)));
+        }
+        if self.mean_tolerance.is_nan() || self.mean_tolerance <= 0.0 {
+            return Err(PhaetonError::InvalidStep(format!("Drift 'mean_tolerance' must be positive, got {}", self.mean_tolerance)));
+        }
+        Ok(())
+    }
+}
+
+/// One significant change of a column against the baseline
+#[derive(Serialize, Clone, Debug)]
+pub struct Shift {
+    pub column: String,
+    /// missing_column, new_column, type_changed, null_rate, new_values or mean_shift
+    pub kind: &'static str,
+    /// Null rate or mean of the baseline, for the kinds that compare numbers
+    pub baseline: Option<f64>,
+    pub current: Option<f64>,
+    /// Categorical values the baseline never saw
+    pub values: Vec<String>,
+    pub message: String,
+}
+
+/// Outcome of comparing a file to its baseline
+#[derive(Serialize, Clone, Debug)]
+pub struct DriftReport {
+    pub baseline: String,
+    /// No baseline existed: this run's statistics became the baseline
+    pub created: bool,
+    /// The baseline was replaced by this run's statistics
+    pub updated: bool,
+    pub baseline_rows: u64,
+    pub rows: u64,
+    pub drifted: bool,
+    pub shifts: Vec<Shift>,
+}
+
+fn invalid_baseline(path: &str, reason: &str) -> PhaetonError {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Drift baseline '{}' {}", path, reason)).into()
+}
+
+/// The baseline profile at `path`, or None when there is none yet
+fn load(path: &str) -> Result<Option<Value>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let baseline: Value = serde_json::from_str(&text).map_err(|_| invalid_baseline(path, "is not valid JSON"))?;
+    if baseline["version"].as_u64() != Some(BASELINE_VERSION) || !baseline["columns"].is_array() {
+        return Err(invalid_baseline(path, "is not a phaeton drift baseline"));
+    }
+    Ok(Some(baseline))
+}
+
+/// Replace the baseline in one step
+fn save(path: &str, source: &str, profile: &HashMap<String, Value>) -> Result<()> {
+    let document = json!({
+        "version": BASELINE_VERSION,
+        "source": source,
+        "created_at": Local::now().to_rfc3339(),
+        "rows": profile.get("rows"),
+        "columns": profile.get("columns"),
+    });
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = format!("{}.tmp", path);
+    let mut writer = BufWriter::new(File::create(&temp)?);
+    serde_json::to_writer_pretty(&mut writer, &document)?;
+    writer.write_all(b"\n")?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Short form of a statistic for messages
+fn rounded(x: f64) -> f64 {
+    (x * 10_000.0).round() / 10_000.0
+}
+
+fn shift(column: &str, kind: &'static str, baseline: Option<f64>, current: Option<f64>, message: String) -> Shift {
+    Shift { column: column.to_string(), kind, baseline, current, values: Vec::new(), message }
+}
+
+/// Compare one column's statistics to its baseline
+fn compare_column(name: &str, base: &Value, cur: &Value, options: &DriftOptions, shifts: &mut Vec<Shift>) {
+    let (base_numeric, cur_numeric) = (&base["numeric"], &cur["numeric"]);
+    if cur["count"].as_u64().unwrap_or(0) > 0 && base["count"].as_u64().unwrap_or(0) > 0
+        && base_numeric.is_object() != cur_numeric.is_object() {
+        let (was, now) = if base_numeric.is_object() { ("numeric", "text") } else { ("text", "numeric") };
+        shifts.push(shift(name, "type_changed", None, None, format!("Column '{}' was {}, now {}", name, was, now)));
+    }
+
+    let base_nulls = base["null_ratio"].as_f64().unwrap_or(0.0);
+    let cur_nulls = cur["null_ratio"].as_f64().unwrap_or(0.0);
+    if cur_nulls - base_nulls >= MIN_NULL_DELTA && cur_nulls >= base_nulls * options.null_factor {
+        let growth = if base_nulls > 0.0 { format!(" ({:.1}x)", cur_nulls / base_nulls) } else { String::new() };
+        shifts.push(shift(name, "null_rate", Some(base_nulls), Some(cur_nulls), format!(
+            "Null rate of '{}' rose from {:.1}% to {:.1}%{}", name, base_nulls * 100.0, cur_nulls * 100.0, growth
+        )));
+    }
+
+    // New values are only certain when the baseline listed every value it saw
+    let base_values = base["top_values"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let base_distinct = base["distinct_estimate"].as_u64().unwrap_or(u64::MAX);
+    if !base_numeric.is_object() && base_distinct <= CATEGORICAL_MAX_DISTINCT && base_values.len() as u64 >= base_distinct {
+        let known: HashSet<&str> = base_values.iter().filter_map(|pair| pair[0].as_str()).collect();
+        let new: Vec<String> = cur["top_values"].as_array().into_iter().flatten()
+            .filter_map(|pair| pair[0].as_str())
+            .filter(|v| !known.contains(v))
+            .map(str::to_string)
+            .collect();
+        if !new.is_empty() {
+            let message = format!("Column '{}' has {} new value(s): {}", name, new.len(), new.join(", "));
+            shifts.push(Shift { values: new, ..shift(name, "new_values", None, None, message) });
+        }
+    }
+
+    let (Some(base_mean), Some(cur_mean)) = (base_numeric["mean"].as_f64(), cur_numeric["mean"].as_f64()) else { return };
+    let stddev = base_numeric["stddev"].as_f64().unwrap_or(0.0);
+    // Constant columns have no spread; measure against the mean itself
+    let scale = if stddev > 0.0 { stddev } else if base_mean != 0.0 { base_mean.abs() } else { 1.0 };
+    let moved = (cur_mean - base_mean).abs() / scale;
+    if moved > options.mean_tolerance {
+        shifts.push(shift(name, "mean_shift", Some(base_mean), Some(cur_mean), format!(
+            "Mean of '{}' moved from {} to {} ({:.1} baseline standard deviations)", name, rounded(base_mean), rounded(cur_mean), moved
+        )));
+    }
+}
+
+/// Significant changes of `current` (a `profile_file` result) against `baseline`
+pub fn compare(baseline: &Value, current: &HashMap<String, Value>, options: &DriftOptions) -> Vec<Shift> {
+    let columns = |profile: &Value| -> Vec<(String, Value)> {
+        profile.as_array().into_iter().flatten()
+            .filter_map(|c| c["name"].as_str().map(|name| (name.to_string(), c.clone())))
+            .collect()
+    };
+    let base_columns = columns(&baseline["columns"]);
+    let cur_columns = columns(current.get("columns").unwrap_or(&Value::Null));
+    let cur_by_name: HashMap<&str, &Value> = cur_columns.iter().map(|(n, c)| (n.as_str(), c)).collect();
+    let base_names: HashSet<&str> = base_columns.iter().map(|(n, _)| n.as_str()).collect();
+
+    let mut shifts = Vec::new();
+    for (name, base) in &base_columns {
+        match cur_by_name.get(name.as_str()) {
+            Some(cur) => compare_column(name, base, cur, options, &mut shifts),
+            None => shifts.push(shift(name, "missing_column", None, None, format!("Column '{}' is missing", name))),
+        }
+    }
+    for (name, _) in cur_columns.iter().filter(|(n, _)| !base_names.contains(n.as_str())) {
+        shifts.push(shift(name, "new_column", None, None, format!("Column '{}' is new", name)));
+    }
+    shifts
+}
+
+/// Profile `source` and compare it to the baseline at `baseline_path`. Without a
+/// baseline, this run's statistics become it.
+pub fn check(source: &str, baseline_path: &str, delimiter: Option<u8>, options: &DriftOptions) -> Result<DriftReport> {
+    options.check()?;
+    let current = profile::profile_file(source, delimiter, DRIFT_TOP_K)?;
+    let rows = current.get("rows").and_then(Value::as_u64).unwrap_or(0);
+
+    let Some(baseline) = load(baseline_path)? else {
+        save(baseline_path, source, &current)?;
+        return Ok(DriftReport {
+            baseline: baseline_path.to_string(), created: true, updated: false,
+            baseline_rows: rows, rows, drifted: false, shifts: Vec::new(),
+        });
+    };
+
+    let shifts = compare(&baseline, &current, options);
+    if options.update {
+        save(baseline_path, source, &current)?;
+    }
+    Ok(DriftReport {
+        baseline: baseline_path.to_string(),
+        created: false,
+        updated: options.update,
+        baseline_rows: baseline["rows"].as_u64().unwrap_or(0),
+        rows,
+        drifted: !shifts.is_empty(),
+        shifts,
+    })
+}
@@ -0,0 +1,226 @@
+// DATA QUALITY EXPECTATIONS
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::{PhaetonError, Result};
+use super::filter;
+
+const UNIQUE_SHARDS: usize = 64;
+
+/// What an `expect` step asserts about the rows that reach it
+#[derive(Clone)]
+pub enum Rule {
+    /// Share of non-empty values
+    NotNull,
+    /// Share of distinct values among the non-empty ones
+    Unique,
+    /// Share of non-empty values (trimmed) found in the set
+    InSet(HashSet<String>),
+    /// Share of non-empty values matching the regex
+    Matches(Regex),
+    /// Number of rows reaching the step
+    RowCount { min: Option<u64>, max: Option<u64> },
+}
+
+impl Rule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::NotNull => "not_null",
+            Rule::Unique => "unique",
+            Rule::InSet(_) => "in_set",
+            Rule::Matches(_) => "match",
+            Rule::RowCount { .. } => "row_count",
+        }
+    }
+}
+
+/// Whether a failed expectation fails the run or is only reported
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Warn,
+    Error,
+}
+
+impl Severity {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "warn" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown severity '{}'. Expected 'warn' or 'error'", other))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Counters shared by the workers evaluating one expectation
+struct Tally {
+    checked: AtomicU64,
+    matched: AtomicU64,
+    /// Hashes of the values seen so far, for `Rule::Unique`
+    seen: Vec<Mutex<HashSet<u64>>>,
+}
+
+impl Tally {
+    fn new(unique: bool) -> Self {
+        let shards = if unique { UNIQUE_SHARDS } else { 0 };
+        Self {
+            checked: AtomicU64::new(0),
+            matched: AtomicU64::new(0),
+            seen: (0..shards).map(|_| Mutex::new(HashSet::new())).collect(),
+        }
+    }
+
+    /// True the first time `value` is seen in this run
+    fn first_sighting(&self, value: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        self.seen[(hash as usize) % UNIQUE_SHARDS].lock().unwrap().insert(hash)
+    }
+}
+
+/// A compiled `expect` step. Rows pass through unchanged; the counts are turned
+/// into an `Outcome` once the run is over.
+#[derive(Clone)]
+pub struct Expectation {
+    pub rule: Rule,
+    pub column: Option<String>,
+    /// Smallest share of checked values that must satisfy the rule
+    pub mostly: f64,
+    pub severity: Severity,
+    tally: Arc<Tally>,
+}
+
+/// Result of one expectation over a run
+#[derive(Serialize, Debug, Clone)]
+pub struct Outcome {
+    /// Position of the step in the submitted step list
+    pub step: usize,
+    pub rule: &'static str,
+    pub column: Option<String>,
+    /// Values evaluated: every row for not_null and row_count, non-empty values otherwise
+    pub checked: u64,
+    /// Values satisfying the rule
+    pub matched: u64,
+    /// Share of checked values that matched, or the row count for row_count
+    pub observed: f64,
+    pub expected: String,
+    pub passed: bool,
+    pub severity: Severity,
+}
+
+impl Expectation {
+    pub fn new(rule: Rule, column: Option<String>, mostly: f64, severity: Severity) -> Result<Self> {
+        if !(0.0..=1.0).contains(&mostly) {
+            return Err(PhaetonError::InvalidStep(format!("Expectation 'mostly' must be between 0.0 and 1.0, got {}", mostly)));
+        }
+        if let Rule::RowCount { min: Some(min), max: Some(max) } = rule {
+            if min > max {
+                return Err(PhaetonError::InvalidStep(format!("Row count bounds are reversed: min {} > max {}", min, max)));
+            }
+        }
+        let tally = Arc::new(Tally::new(matches!(rule, Rule::Unique)));
+        Ok(Self { rule, column, mostly, severity, tally })
+    }
+
+    /// Copy for a new run, with the counts starting at zero
+    pub fn fresh(&self) -> Self {
+        Self { tally: Arc::new(Tally::new(matches!(self.rule, Rule::Unique))), ..self.clone() }
+    }
+
+    /// Count one row; `value` is the checked column's text (None when the row lacks it
+    /// or the rule has no column)
+    pub fn observe(&self, value: Option<&str>) {
+        let tally = &self.tally;
+        let matched = match (&self.rule, value) {
+            (Rule::RowCount { .. }, _) => true,
+            (Rule::NotNull, value) => !value.is_none_or(filter::is_empty),
+            // The remaining rules leave empty values to not_null
+            (_, None) => return,
+            (_, Some(v)) if filter::is_empty(v) => return,
+            (Rule::Unique, Some(v)) => tally.first_sighting(v),
+            (Rule::InSet(values), Some(v)) => values.contains(v.trim()),
+            (Rule::Matches(re), Some(v)) => re.is_match(v),
+        };
+        tally.checked.fetch_add(1, Ordering::Relaxed);
+        if matched { tally.matched.fetch_add(1, Ordering::Relaxed); }
+    }
+
+    /// Human-readable statement of what is expected, e.g. `>= 95% of values in a set of 3`
+    pub fn describe(&self) -> String {
+        let share = format!(">= {}%", percent(self.mostly));
+        match &self.rule {
+            Rule::NotNull => format!("{} of values not null", share),
+            Rule::Unique => format!("{} of values distinct", share),
+            Rule::InSet(values) => format!("{} of values in a set of {}", share, values.len()),
+            Rule::Matches(re) => format!("{} of values matching /{}/", share, re.as_str()),
+            Rule::RowCount { min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("between {} and {} rows", min, max),
+                (Some(min), None) => format!(">= {} rows", min),
+                (None, Some(max)) => format!("<= {} rows", max),
+                (None, None) => "any number of rows".to_string(),
+            },
+        }
+    }
+
+    pub fn outcome(&self, step: usize) -> Outcome {
+        let checked = self.tally.checked.load(Ordering::Relaxed);
+        let matched = self.tally.matched.load(Ordering::Relaxed);
+        let (observed, passed) = match self.rule {
+            Rule::RowCount { min, max } => (
+                checked as f64,
+                min.is_none_or(|min| checked >= min) && max.is_none_or(|max| checked <= max),
+            ),
+            // Nothing to check passes, as an empty column has no bad values
+            _ if checked == 0 => (1.0, true),
+            _ => (matched as f64 / checked as f64, matched as f64 >= self.mostly * checked as f64),
+        };
+        Outcome {
+            step,
+            rule: self.rule.name(),
+            column: self.column.clone(),
+            checked,
+            matched,
+            observed,
+            expected: self.describe(),
+            passed,
+            severity: self.severity,
+        }
+    }
+}
+
+impl Outcome {
+    /// One-line account of a failed expectation for errors and logs
+    pub fn summary(&self) -> String {
+        let subject = match &self.column {
+            Some(col) => format!("{} on '{}'", self.rule, col),
+            None => self.rule.to_string(),
+        };
+        let observed = match self.rule {
+            "row_count" => format!("{} rows", self.checked),
+            _ => format!("{}% ({} of {})", percent(self.observed), self.matched, self.checked),
+        };
+        format!("Step {} ({}): expected {}, got {}", self.step, subject, self.expected, observed)
+    }
+}
+
+/// 0.9512 -> "95.12"
+fn percent(share: f64) -> String {
+    let text = format!("{:.2}", share * 100.0);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -1,5 +1,8 @@
 // FILTERING MODE (DISCARD/KEEP)
 
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, Anchored, Input, MatchKind, StartKind};
+use crate::error::{PhaetonError, Result};
+
 /// Check if string is empty or whitespace-only
 pub fn is_empty(input: &str) -> bool {
     input.trim().is_empty()
@@ -23,4 +26,48 @@ pub fn starts_with_pattern(input: &str, pattern: &str) -> bool {
 /// Ends with pattern
 pub fn ends_with_pattern(input: &str, pattern: &str) -> bool {
     input.ends_with(pattern)
-}
\ No newline at end of file
+}
+
+/// A pattern list for keep/discard, compiled once into an Aho-Corasick automaton
+/// so a cell is scanned a single time however many patterns there are
+#[derive(Clone)]
+pub struct PatternSet {
+    patterns: Vec<String>,
+    mode: String,
+    automaton: AhoCorasick,
+}
+
+impl PatternSet {
+    pub fn new(patterns: Vec<String>, mode: &str) -> Result<Self> {
+        if !matches!(mode, "exact" | "contains" | "startswith" | "endswith") {
+            return Err(PhaetonError::InvalidStep(format!("Unknown match mode '{}'", mode)));
+        }
+        let automaton = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::Standard)
+            .start_kind(StartKind::Both)
+            .build(&patterns)
+            .map_err(|e| PhaetonError::InvalidStep(format!("Cannot compile {} patterns: {}", patterns.len(), e)))?;
+        Ok(PatternSet { patterns, mode: mode.to_string(), automaton })
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    /// The first pattern that matches `input` under the set's mode
+    pub fn find(&self, input: &str) -> Option<&str> {
+        let len = input.len();
+        let found = match self.mode.as_str() {
+            "contains" => self.automaton.find(input),
+            "startswith" => self.automaton.find(Input::new(input).anchored(Anchored::Yes)),
+            // Overlapping search sees every pattern ending at each position, not just the leftmost
+            "endswith" => self.automaton.find_overlapping_iter(input).find(|m| m.end() == len),
+            _ => self.automaton.find_overlapping_iter(input).find(|m| m.start() == 0 && m.end() == len),
+        };
+        found.map(|m| self.patterns[m.pattern().as_usize()].as_str())
+    }
+}
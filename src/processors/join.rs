@@ -0,0 +1,483 @@
+// TWO-FILE JOIN
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use csv::{ByteRecord, Reader, ReaderBuilder, Writer, WriterBuilder};
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::error::{PhaetonError, Result};
+use crate::processors::probe;
+
+/// Memory a join may use unless told otherwise
+pub const DEFAULT_MEMORY_LIMIT: u64 = 512 * 1024 * 1024;
+
+/// Loaded records take about this many times their size on disk
+const MEMORY_OVERHEAD: u64 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Outer,
+}
+
+impl JoinKind {
+    pub fn parse(how: &str) -> Result<Self> {
+        match how {
+            "inner" => Ok(JoinKind::Inner),
+            "left" => Ok(JoinKind::Left),
+            "right" => Ok(JoinKind::Right),
+            "outer" | "full" => Ok(JoinKind::Outer),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown join '{}': expected inner, left, right or outer", other))),
+        }
+    }
+
+    fn keeps_left(self) -> bool {
+        matches!(self, JoinKind::Left | JoinKind::Outer)
+    }
+
+    fn keeps_right(self) -> bool {
+        matches!(self, JoinKind::Right | JoinKind::Outer)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum JoinStrategy {
+    /// Hash join when the right file fits in `memory_limit`, sort-merge otherwise
+    #[default]
+    Auto,
+    Hash,
+    SortMerge,
+}
+
+impl JoinStrategy {
+    pub fn parse(strategy: &str) -> Result<Self> {
+        match strategy {
+            "auto" => Ok(JoinStrategy::Auto),
+            "hash" => Ok(JoinStrategy::Hash),
+            "sort_merge" => Ok(JoinStrategy::SortMerge),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown join strategy '{}': expected auto, hash or sort_merge", other))),
+        }
+    }
+}
+
+pub struct JoinOptions {
+    pub how: JoinKind,
+    /// Key columns of the left file
+    pub on: Vec<String>,
+    /// Key columns of the right file, when named differently
+    pub right_on: Option<Vec<String>>,
+    pub strategy: JoinStrategy,
+    /// Delimiters of the files; sniffed when None. The output uses the left one.
+    pub delimiter: Option<u8>,
+    pub right_delimiter: Option<u8>,
+    /// Appended to right columns whose name the left file already uses
+    pub suffix: String,
+    pub memory_limit: u64,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self {
+            how: JoinKind::Inner,
+            on: Vec::new(),
+            right_on: None,
+            strategy: JoinStrategy::Auto,
+            delimiter: None,
+            right_delimiter: None,
+            suffix: "_right".to_string(),
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JoinStats {
+    pub left: String,
+    pub right: String,
+    pub output: String,
+    /// hash or sort_merge
+    pub strategy: &'static str,
+    pub left_rows: u64,
+    pub right_rows: u64,
+    pub output_rows: u64,
+    /// Output rows pairing a left row with a right row
+    pub matched_rows: u64,
+    /// Left rows without a partner, written by left and outer joins
+    pub unmatched_left: u64,
+    /// Right rows without a partner, written by right and outer joins
+    pub unmatched_right: u64,
+    pub duration_ms: u64,
+}
+
+/// Join key: the trimmed key fields, length-prefixed so ("ab", "c") and ("a", "bc")
+/// differ. None when a key field is empty; such rows never match.
+type Key = Option<Vec<u8>>;
+
+fn key_of(record: &ByteRecord, idxs: &[usize]) -> Key {
+    let mut key = Vec::new();
+    for &idx in idxs {
+        let field = record.get(idx).unwrap_or_default().trim_ascii();
+        if field.is_empty() {
+            return None;
+        }
+        key.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        key.extend_from_slice(field);
+    }
+    Some(key)
+}
+
+fn delimiter_of(path: &str, delimiter: Option<u8>) -> Result<u8> {
+    if !Path::new(path).exists() {
+        return Err(PhaetonError::FileNotFound(path.to_string()));
+    }
+    delimiter.map_or_else(|| probe::sniff_delimiter(path), Ok)
+}
+
+fn reader(path: &str, delimiter: u8) -> Result<Reader<BufReader<File>>> {
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    Ok(ReaderBuilder::new()
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(BufReader::new(file)))
+}
+
+/// Positions of `names` among `headers`
+fn key_idxs(headers: &ByteRecord, names: &[String], path: &str) -> Result<Vec<usize>> {
+    names.iter().map(|name| {
+        headers.iter().position(|h| String::from_utf8_lossy(h).trim() == name.trim())
+            .ok_or_else(|| PhaetonError::ColumnNotFound(format!("{} (in {})", name, path)))
+    }).collect()
+}
+
+/// Where the columns of both files go in the output
+struct Layout {
+    left_keys: Vec<usize>,
+    right_keys: Vec<usize>,
+    left_width: usize,
+    /// Right columns written after the left ones: every one but the keys
+    right_payload: Vec<usize>,
+}
+
+impl Layout {
+    fn new(left: &ByteRecord, right: &ByteRecord, options: &JoinOptions, paths: (&str, &str)) -> Result<(Self, ByteRecord)> {
+        if options.on.is_empty() {
+            return Err(PhaetonError::InvalidStep("Join needs at least one key column in 'on'".to_string()));
+        }
+        let right_on = options.right_on.as_ref().unwrap_or(&options.on);
+        if right_on.len() != options.on.len() {
+            return Err(PhaetonError::InvalidStep(format!(
+                "Join 'on' names {} column(s) but 'right_on' names {}", options.on.len(), right_on.len()
+            )));
+        }
+        let left_keys = key_idxs(left, &options.on, paths.0)?;
+        let right_keys = key_idxs(right, right_on, paths.1)?;
+        let right_payload: Vec<usize> = (0..right.len()).filter(|i| !right_keys.contains(i)).collect();
+
+        let taken: HashSet<&[u8]> = left.iter().collect();
+        let mut headers = left.clone();
+        for &idx in &right_payload {
+            let name = &right[idx];
+            if taken.contains(name) {
+                headers.push_field(&[name, options.suffix.as_bytes()].concat());
+            } else {
+                headers.push_field(name);
+            }
+        }
+        Ok((Self { left_keys, right_keys, left_width: left.len(), right_payload }, headers))
+    }
+}
+
+/// Output writer that counts what it wrote
+struct JoinWriter<'a> {
+    writer: Writer<BufWriter<File>>,
+    layout: &'a Layout,
+    row: ByteRecord,
+    stats: JoinStats,
+}
+
+impl JoinWriter<'_> {
+    fn push_left(&mut self, left: &ByteRecord) {
+        for idx in 0..self.layout.left_width {
+            self.row.push_field(left.get(idx).unwrap_or_default());
+        }
+    }
+
+    fn push_right(&mut self, right: Option<&ByteRecord>) {
+        for &idx in &self.layout.right_payload {
+            self.row.push_field(right.and_then(|r| r.get(idx)).unwrap_or_default());
+        }
+    }
+
+    fn write_row(&mut self) -> Result<()> {
+        self.writer.write_byte_record(&self.row)?;
+        self.row.clear();
+        self.stats.output_rows += 1;
+        Ok(())
+    }
+
+    fn matched(&mut self, left: &ByteRecord, right: &ByteRecord) -> Result<()> {
+        self.push_left(left);
+        self.push_right(Some(right));
+        self.stats.matched_rows += 1;
+        self.write_row()
+    }
+
+    fn left_only(&mut self, left: &ByteRecord) -> Result<()> {
+        self.push_left(left);
+        self.push_right(None);
+        self.stats.unmatched_left += 1;
+        self.write_row()
+    }
+
+    /// A right row without partner: its keys fill the left key columns
+    fn right_only(&mut self, right: &ByteRecord) -> Result<()> {
+        for idx in 0..self.layout.left_width {
+            let field = match self.layout.left_keys.iter().position(|&k| k == idx) {
+                Some(nth) => right.get(self.layout.right_keys[nth]).unwrap_or_default(),
+                None => b"",
+            };
+            self.row.push_field(field);
+        }
+        self.push_right(Some(right));
+        self.stats.unmatched_right += 1;
+        self.write_row()
+    }
+}
+
+/// Join `left` and `right` on key columns into the CSV file `output`
+pub fn join_files(left: &str, right: &str, output: &str, options: &JoinOptions) -> Result<JoinStats> {
+    let started = Instant::now();
+    let left_delimiter = delimiter_of(left, options.delimiter)?;
+    let mut left_reader = reader(left, left_delimiter)?;
+    let mut right_reader = reader(right, delimiter_of(right, options.right_delimiter)?)?;
+    let (layout, headers) = Layout::new(left_reader.byte_headers()?, right_reader.byte_headers()?, options, (left, right))?;
+
+    let right_size = std::fs::metadata(right)?.len();
+    let sort_merge = match options.strategy {
+        JoinStrategy::Hash => false,
+        JoinStrategy::SortMerge => true,
+        JoinStrategy::Auto => right_size.saturating_mul(MEMORY_OVERHEAD) > options.memory_limit,
+    };
+
+    if let Some(dir) = Path::new(output).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut writer = WriterBuilder::new()
+        .delimiter(left_delimiter)
+        .from_writer(BufWriter::new(File::create(output)?));
+    writer.write_byte_record(&headers)?;
+
+    let mut out = JoinWriter {
+        writer,
+        layout: &layout,
+        row: ByteRecord::new(),
+        stats: JoinStats {
+            left: left.to_string(),
+            right: right.to_string(),
+            output: output.to_string(),
+            strategy: if sort_merge { "sort_merge" } else { "hash" },
+            left_rows: 0, right_rows: 0, output_rows: 0, matched_rows: 0,
+            unmatched_left: 0, unmatched_right: 0, duration_ms: 0,
+        },
+    };
+
+    if sort_merge {
+        // Sorted runs go to a scratch directory next to the output
+        let scratch = PathBuf::from(format!("{}.join-{}", output, std::process::id()));
+        std::fs::create_dir_all(&scratch)?;
+        let result = sort_merge_join(&mut out, &mut left_reader, &mut right_reader, options, &scratch);
+        let _ = std::fs::remove_dir_all(&scratch);
+        result?;
+    } else {
+        hash_join(&mut out, &mut left_reader, &mut right_reader, options.how)?;
+    }
+
+    out.writer.flush()?;
+    let mut stats = out.stats;
+    stats.duration_ms = started.elapsed().as_millis() as u64;
+    info!(left, right, output, strategy = stats.strategy, rows = stats.output_rows, "Joined files");
+    Ok(stats)
+}
+
+/// Load the right file into a hash table and stream the left one past it;
+/// output follows the left file's order, unmatched right rows come last
+fn hash_join(out: &mut JoinWriter, left: &mut Reader<BufReader<File>>, right: &mut Reader<BufReader<File>>, how: JoinKind) -> Result<()> {
+    let mut rights: Vec<ByteRecord> = Vec::new();
+    let mut table: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+    let mut record = ByteRecord::new();
+    while right.read_byte_record(&mut record)? {
+        if let Some(key) = key_of(&record, &out.layout.right_keys) {
+            table.entry(key).or_default().push(rights.len());
+        }
+        rights.push(record.clone());
+    }
+    out.stats.right_rows = rights.len() as u64;
+    debug!(rows = rights.len(), keys = table.len(), "Built join hash table");
+
+    let mut matched = vec![false; rights.len()];
+    while left.read_byte_record(&mut record)? {
+        out.stats.left_rows += 1;
+        match key_of(&record, &out.layout.left_keys).and_then(|key| table.get(&key)) {
+            Some(partners) => {
+                for &i in partners {
+                    matched[i] = true;
+                    out.matched(&record, &rights[i])?;
+                }
+            },
+            None if how.keeps_left() => out.left_only(&record)?,
+            None => {},
+        }
+    }
+
+    if how.keeps_right() {
+        for (row, _) in rights.iter().zip(&matched).filter(|(_, &m)| !m) {
+            out.right_only(row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Split a file into runs of at most `budget` bytes, each sorted by key
+fn sort_runs(reader: &mut Reader<BufReader<File>>, key_idxs: &[usize], budget: u64, scratch: &Path, side: &str) -> Result<(Vec<PathBuf>, u64)> {
+    let mut runs = Vec::new();
+    let mut rows = 0;
+    let mut chunk: Vec<(Key, ByteRecord)> = Vec::new();
+    let mut chunk_bytes = 0u64;
+    let mut record = ByteRecord::new();
+
+    let flush = |chunk: &mut Vec<(Key, ByteRecord)>, runs: &mut Vec<PathBuf>| -> Result<()> {
+        // Stable, so rows with equal keys keep their file order
+        chunk.sort_by(|a, b| a.0.cmp(&b.0));
+        let path = scratch.join(format!("{}-{}.csv", side, runs.len()));
+        let mut writer = WriterBuilder::new().flexible(true).from_writer(BufWriter::new(File::create(&path)?));
+        for (_, row) in chunk.drain(..) {
+            writer.write_byte_record(&row)?;
+        }
+        writer.flush()?;
+        runs.push(path);
+        Ok(())
+    };
+
+    while reader.read_byte_record(&mut record)? {
+        rows += 1;
+        chunk_bytes += record.as_slice().len() as u64 * MEMORY_OVERHEAD;
+        chunk.push((key_of(&record, key_idxs), record.clone()));
+        if chunk_bytes >= budget {
+            flush(&mut chunk, &mut runs)?;
+            chunk_bytes = 0;
+        }
+    }
+    if !chunk.is_empty() || runs.is_empty() {
+        flush(&mut chunk, &mut runs)?;
+    }
+    debug!(side, rows, runs = runs.len(), "Sorted join runs");
+    Ok((runs, rows))
+}
+
+/// K-way merge of sorted runs, yielding records in key order
+struct MergedRuns {
+    readers: Vec<Reader<BufReader<File>>>,
+    heads: Vec<Option<ByteRecord>>,
+    /// (key, run) of every run's head; run order breaks ties so equal keys keep file order
+    heap: BinaryHeap<Reverse<(Key, usize)>>,
+    key_idxs: Vec<usize>,
+}
+
+impl MergedRuns {
+    fn open(runs: &[PathBuf], key_idxs: &[usize]) -> Result<Self> {
+        let mut merged = Self { readers: Vec::new(), heads: Vec::new(), heap: BinaryHeap::new(), key_idxs: key_idxs.to_vec() };
+        for (i, path) in runs.iter().enumerate() {
+            let file = File::open(path)?;
+            merged.readers.push(ReaderBuilder::new().has_headers(false).flexible(true).from_reader(BufReader::new(file)));
+            merged.heads.push(None);
+            merged.advance(i)?;
+        }
+        Ok(merged)
+    }
+
+    fn advance(&mut self, run: usize) -> Result<()> {
+        let mut record = ByteRecord::new();
+        if self.readers[run].read_byte_record(&mut record)? {
+            self.heap.push(Reverse((key_of(&record, &self.key_idxs), run)));
+            self.heads[run] = Some(record);
+        }
+        Ok(())
+    }
+
+    fn peek_key(&self) -> Option<&Key> {
+        self.heap.peek().map(|Reverse((key, _))| key)
+    }
+
+    fn next(&mut self) -> Result<Option<(Key, ByteRecord)>> {
+        let Some(Reverse((key, run))) = self.heap.pop() else { return Ok(None) };
+        let record = self.heads[run].take().expect("every run in the heap has a head");
+        self.advance(run)?;
+        Ok(Some((key, record)))
+    }
+}
+
+/// Sort both files by key in runs that fit the memory limit, then merge them;
+/// output follows key order
+fn sort_merge_join(
+    out: &mut JoinWriter,
+    left: &mut Reader<BufReader<File>>,
+    right: &mut Reader<BufReader<File>>,
+    options: &JoinOptions,
+    scratch: &Path,
+) -> Result<()> {
+    let budget = (options.memory_limit / 2).max(1);
+    let (left_runs, left_rows) = sort_runs(left, &out.layout.left_keys, budget, scratch, "left")?;
+    let (right_runs, right_rows) = sort_runs(right, &out.layout.right_keys, budget, scratch, "right")?;
+    out.stats.left_rows = left_rows;
+    out.stats.right_rows = right_rows;
+
+    let mut lefts = MergedRuns::open(&left_runs, &out.layout.left_keys)?;
+    let mut rights = MergedRuns::open(&right_runs, &out.layout.right_keys)?;
+    let how = options.how;
+
+    loop {
+        let order = match (lefts.peek_key(), rights.peek_key()) {
+            (None, None) => break,
+            (Some(_), None) | (Some(None), Some(_)) => Ordering::Less,
+            (None, Some(_)) | (Some(Some(_)), Some(None)) => Ordering::Greater,
+            (Some(l), Some(r)) => l.cmp(r),
+        };
+        match order {
+            Ordering::Less => {
+                let (_, row) = lefts.next()?.expect("peeked");
+                if how.keeps_left() {
+                    out.left_only(&row)?;
+                }
+            },
+            Ordering::Greater => {
+                let (_, row) = rights.next()?.expect("peeked");
+                if how.keeps_right() {
+                    out.right_only(&row)?;
+                }
+            },
+            Ordering::Equal => {
+                // One key's right rows are held in memory while its left rows pass
+                let mut group = Vec::new();
+                let key = rights.peek_key().cloned().flatten();
+                while rights.peek_key().is_some_and(|k| k.as_ref() == key.as_ref()) {
+                    group.push(rights.next()?.expect("peeked").1);
+                }
+                while lefts.peek_key().is_some_and(|k| k.as_ref() == key.as_ref()) {
+                    let (_, row) = lefts.next()?.expect("peeked");
+                    for partner in &group {
+                        out.matched(&row, partner)?;
+                    }
+                }
+            },
+        }
+    }
+    Ok(())
+}
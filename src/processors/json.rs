@@ -0,0 +1,84 @@
+use serde_json::Value;
+use crate::error::{PhaetonError, Result};
+
+#[derive(Clone)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a JSONPath-like expression (`$.payload.items[0].id`) into segments
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>> {
+    let invalid = || PhaetonError::InvalidStep(format!("Invalid JSON path: {}", path));
+
+    let body = path.trim();
+    let body = body.strip_prefix('$').unwrap_or(body);
+
+    let mut segments = Vec::new();
+    let mut chars = body.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => {
+                let mut key = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == '.' || c == '[' { break; }
+                    key.push(c);
+                    chars.next();
+                }
+                if key.is_empty() { return Err(invalid()); }
+                segments.push(PathSegment::Key(key));
+            },
+            '[' => {
+                let mut inner = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' { break; }
+                    inner.push(c);
+                }
+                let inner = inner.trim();
+                if let Some(quoted) = inner.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))) {
+                    segments.push(PathSegment::Key(quoted.to_string()));
+                } else {
+                    let idx = inner.parse::<usize>().map_err(|_| invalid())?;
+                    segments.push(PathSegment::Index(idx));
+                }
+            },
+            _ if segments.is_empty() => {
+                // Allow a bare leading key ("payload.user")
+                let mut key = ch.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c == '.' || c == '[' { break; }
+                    key.push(c);
+                    chars.next();
+                }
+                segments.push(PathSegment::Key(key));
+            },
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(segments)
+}
+
+/// Walk a parsed document along the path
+pub fn extract<'a>(doc: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+    let mut current = doc;
+    for segment in path {
+        current = match segment {
+            PathSegment::Key(k) => current.get(k.as_str())?,
+            PathSegment::Index(i) => current.get(*i)?,
+        };
+    }
+    Some(current)
+}
+
+/// Render an extracted value as a CSV cell (strings unquoted, containers as compact JSON)
+pub fn to_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
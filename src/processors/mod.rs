@@ -3,6 +3,21 @@ pub mod probe;
 pub mod text;
 pub mod cast;
 pub mod filter;
+pub mod currency;
+pub mod json;
+pub mod similarity;
+pub mod country;
+pub mod phonetic;
+pub mod profile;
+pub mod report;
+pub mod validate;
+pub mod expect;
+pub mod vault;
+pub mod drift;
+pub mod join;
+pub mod concat;
+pub mod diff;
+pub mod template;
 
 // Re-exports 
 // pub use probe::detect_file_metadata;
\ No newline at end of file
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use crate::error::{PhaetonError, Result};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Algorithm {
+    Soundex,
+    Metaphone,
+}
+
+impl Algorithm {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "soundex" => Ok(Algorithm::Soundex),
+            "metaphone" => Ok(Algorithm::Metaphone),
+            unknown => Err(PhaetonError::InvalidStep(format!("Unknown phonetic algorithm: {}", unknown))),
+        }
+    }
+
+    pub fn encode(&self, input: &str) -> String {
+        match self {
+            Algorithm::Soundex => soundex(input),
+            Algorithm::Metaphone => metaphone(input),
+        }
+    }
+}
+
+/// Reference entries bucketed by phonetic code
+#[derive(Clone)]
+pub struct PhoneticIndex {
+    pub algorithm: Algorithm,
+    buckets: HashMap<String, Vec<usize>>,
+}
+
+impl PhoneticIndex {
+    pub fn build(algorithm: Algorithm, ref_list: &[String]) -> Self {
+        let mut buckets: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, target) in ref_list.iter().enumerate() {
+            let code = algorithm.encode(target);
+            if !code.is_empty() {
+                buckets.entry(code).or_default().push(i);
+            }
+        }
+        Self { algorithm, buckets }
+    }
+
+    pub fn candidates(&self, input: &str) -> Option<&[usize]> {
+        self.buckets.get(&self.algorithm.encode(input)).map(|v| v.as_slice())
+    }
+}
+
+fn ascii_letters(input: &str) -> Vec<char> {
+    input.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// American Soundex: letter + 3 digits ("Robert" -> "R163")
+pub fn soundex(input: &str) -> String {
+    let letters = ascii_letters(input);
+    let Some(&first) = letters.first() else { return String::new() };
+
+    let digit = |c: char| match c {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        _ => None,
+    };
+
+    let mut code = String::with_capacity(4);
+    code.push(first);
+    let mut last = digit(first);
+
+    for &c in &letters[1..] {
+        let d = digit(c);
+        if let Some(d) = d {
+            if last != Some(d) {
+                code.push(d);
+                if code.len() == 4 { break; }
+            }
+        }
+        // H and W don't separate letters with the same code; vowels do
+        if c != 'H' && c != 'W' {
+            last = d;
+        }
+    }
+
+    while code.len() < 4 { code.push('0'); }
+    code
+}
+
+fn is_vowel(c: Option<&char>) -> bool {
+    matches!(c, Some('A' | 'E' | 'I' | 'O' | 'U'))
+}
+
+/// Original Metaphone (Philips, 1990): "Stephen" and "Steven" -> "STFN"
+pub fn metaphone(input: &str) -> String {
+    let mut w = ascii_letters(input);
+    // Collapse adjacent duplicates except C
+    w.dedup_by(|a, b| a == b && *a != 'C');
+    if w.is_empty() { return String::new(); }
+
+    // Initial exceptions
+    match (w.first(), w.get(1)) {
+        (Some('K' | 'G' | 'P'), Some('N')) | (Some('A'), Some('E')) | (Some('W'), Some('R')) => { w.remove(0); },
+        (Some('X'), _) => w[0] = 'S',
+        (Some('W'), Some('H')) => { w.remove(1); },
+        _ => {}
+    }
+
+    let at = |i: isize| -> Option<&char> { if i < 0 { None } else { w.get(i as usize) } };
+    let mut code = String::new();
+
+    for (i, &c) in w.iter().enumerate() {
+        let ii = i as isize;
+        let prev = at(ii - 1);
+        let next = at(ii + 1);
+        let next2 = at(ii + 2);
+
+        match c {
+            'A' | 'E' | 'I' | 'O' | 'U' => if i == 0 { code.push(c); },
+            'B' => if !(prev == Some(&'M') && next.is_none()) { code.push('B'); },
+            'C' => {
+                if next == Some(&'I') && next2 == Some(&'A') { code.push('X'); }
+                else if next == Some(&'H') { code.push(if prev == Some(&'S') { 'K' } else { 'X' }); }
+                else if matches!(next, Some('I' | 'E' | 'Y')) { if prev != Some(&'S') { code.push('S'); } }
+                else { code.push('K'); }
+            },
+            'D' => {
+                if next == Some(&'G') && matches!(next2, Some('E' | 'Y' | 'I')) { code.push('J'); }
+                else { code.push('T'); }
+            },
+            'G' => {
+                let silent_gh = next == Some(&'H') && !is_vowel(next2) && next2.is_some();
+                let silent_gn = next == Some(&'N') && (next2.is_none() || (next2 == Some(&'E') && at(ii + 3) == Some(&'D')));
+                if silent_gh || silent_gn || (prev == Some(&'D') && matches!(next, Some('E' | 'Y' | 'I'))) {
+                    // silent
+                } else if matches!(next, Some('I' | 'E' | 'Y')) && prev != Some(&'G') {
+                    code.push('J');
+                } else {
+                    code.push('K');
+                }
+            },
+            'H' => {
+                let after_modifier = matches!(prev, Some('C' | 'S' | 'P' | 'T' | 'G'));
+                if !after_modifier && (!is_vowel(prev) || is_vowel(next)) { code.push('H'); }
+            },
+            'K' => if prev != Some(&'C') { code.push('K'); },
+            'P' => code.push(if next == Some(&'H') { 'F' } else { 'P' }),
+            'Q' => code.push('K'),
+            'S' => {
+                if next == Some(&'H') || (next == Some(&'I') && matches!(next2, Some('O' | 'A'))) { code.push('X'); }
+                else { code.push('S'); }
+            },
+            'T' => {
+                if next == Some(&'I') && matches!(next2, Some('O' | 'A')) { code.push('X'); }
+                else if next == Some(&'H') { code.push('0'); }
+                else if !(next == Some(&'C') && next2 == Some(&'H')) { code.push('T'); }
+            },
+            'V' => code.push('F'),
+            'W' | 'Y' => if is_vowel(next) { code.push(c); },
+            'X' => code.push_str("KS"),
+            'Z' => code.push('S'),
+            other => code.push(other),
+        }
+    }
+    code
+}
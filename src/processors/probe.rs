@@ -1,105 +1,754 @@
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::collections::HashMap;
 use encoding_rs::Encoding;
+use csv::{ReaderBuilder, StringRecord};
+use serde::Serialize;
+use serde_json::{json, Value};
 use crate::error::Result;
+use tracing::debug;
+use crate::processors::cast;
 
-const PROBE_SIZE: usize = 8192; // Read first 8KB
+const PROBE_SIZE: usize = 8192; // Read first 8KB by default
 
-pub fn detect_file_metadata(path: &str) -> Result<HashMap<String, String>> {
+// Example line numbers kept in the ragged-row report
+const RAGGED_EXAMPLES: usize = 10;
+
+// Lines compared when scoring delimiter candidates
+const DELIMITER_SAMPLE_LINES: usize = 50;
+
+// Share of non-empty sampled values that must parse for a type to be inferred
+const TYPE_CONFORMANCE: f64 = 0.9;
+
+// Parsed rows echoed back so callers can eyeball the detected dialect
+const PREVIEW_ROWS: usize = 5;
+
+const INFER_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y", "%m/%d/%Y", "%d-%m-%Y", "%m-%d-%Y", "%d.%m.%Y", "%d %b %Y", "%b %d, %Y"];
+
+const INFER_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.fZ", "%Y-%m-%dT%H:%M:%S%.f%:z", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M",
+    "%d/%m/%Y %H:%M:%S", "%m/%d/%Y %H:%M:%S", "%d/%m/%Y %H:%M", "%m/%d/%Y %H:%M",
+];
+
+/// How much of the file the probe looks at
+pub struct ProbeOptions {
+    pub sample_rows: usize,
+    pub sample_bytes: usize,
+    /// Read the whole file and sample every row (authoritative, but O(file size))
+    pub full_scan: bool,
+    /// Stream the whole file once to count data rows exactly
+    pub count_rows: bool,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self { sample_rows: 100, sample_bytes: PROBE_SIZE, full_scan: false, count_rows: false }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProbeResult {
+    encoding: &'static str,
+    delimiter: char,
+    delimiter_confidence: f64,
+    line_terminator: &'static str,
+    unterminated_last_line: bool,
+    quotechar: char,
+    escape: &'static str,
+    confidence: f64,
+    encodings: Vec<EncodingScore>,
+    file_size: u64,
+    estimated_rows: u64,
+    exact_rows: Option<u64>,
+    has_headers: bool,
+    headers: Vec<String>,
+    header_issues: Vec<HeaderIssue>,
+    suggested_headers: Vec<String>,
+    column_types: Vec<ColumnType>,
+    ragged_rows: RaggedReport,
+    date_formats: Vec<DateFormats>,
+    sample: Vec<serde_json::Map<String, Value>>,
+}
+
+#[derive(Serialize)]
+struct EncodingScore {
+    encoding: &'static str,
+    confidence: f64,
+}
+
+#[derive(Serialize)]
+struct HeaderIssue {
+    index: usize,
+    name: String,
+    issue: &'static str,
+    suggested: String,
+}
+
+#[derive(Serialize)]
+struct ColumnType {
+    name: String,
+    #[serde(rename = "type")]
+    dtype: &'static str,
+    confidence: f64,
+}
+
+#[derive(Serialize)]
+struct RaggedReport {
+    checked: usize,
+    longer: usize,
+    shorter: usize,
+    examples: Vec<RaggedRow>,
+}
+
+#[derive(Serialize)]
+struct RaggedRow {
+    line: u64,
+    fields: usize,
+}
+
+#[derive(Serialize)]
+struct DateFormats {
+    name: String,
+    formats: Vec<FormatShare>,
+    ambiguous: bool,
+    warning: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FormatShare {
+    format: &'static str,
+    share: f64,
+}
+
+pub fn detect_file_metadata(path: &str, options: &ProbeOptions) -> Result<ProbeResult> {
     let file = File::open(path)?;
+    let file_size = file.metadata()?.len();
     let mut reader = BufReader::new(file);
-    let mut buffer = vec![0u8; PROBE_SIZE];
+    let mut buffer = Vec::new();
     
-    let bytes_read = reader.read(&mut buffer)?;
-    buffer.truncate(bytes_read);
+    let (truncated, sample_rows) = if options.full_scan {
+        reader.read_to_end(&mut buffer)?;
+        (false, usize::MAX)
+    } else {
+        (&mut reader).take(options.sample_bytes as u64).read_to_end(&mut buffer)?;
+        let mut next = [0u8; 1];
+        (reader.read(&mut next)? > 0, options.sample_rows)
+    };
     
     // Detect encoding
-    let (encoding, confidence) = detect_encoding(&buffer);
+    let candidates = rank_encodings(&buffer);
+    let (encoding, confidence) = candidates.first()
+        .map(|c| (c.encoding, c.confidence))
+        .unwrap_or((encoding_rs::UTF_8, 0.5));
     
     // Decode to UTF-8
-    let (decoded, _) = encoding.decode_without_bom_handling(&buffer);
+    let (decoded, _) = encoding.decode_with_bom_removal(&buffer);
     
     // Detect delimiter
-    let delimiter = detect_delimiter(&decoded);
+    let (delimiter, delimiter_confidence) = detect_delimiter(&decoded);
+    
+    // Detect line endings
+    let line_terminator = detect_line_terminator(&buffer);
+    let unterminated = last_line_unterminated(path)?;
+    
+    // Detect quoting character and escape style
+    let (quote, escape) = detect_quoting(&decoded, delimiter);
     
     // Extract headers
-    let headers = extract_headers(&decoded, &delimiter);
+    let raw = raw_headers(&decoded, delimiter, quote, escape);
+    let headers: Vec<String> = raw.iter().map(|h| h.trim().to_string()).collect();
+    let (header_issues, suggested_headers) = check_headers(&raw);
     
-    let mut result = HashMap::new();
-    result.insert("encoding".to_string(), encoding.name().to_string());
-    result.insert("delimiter".to_string(), delimiter);
-    result.insert("confidence".to_string(), format!("{:.2}", confidence));
-    result.insert("headers".to_string(), headers.join(","));
+    // Estimate the row count from the average sampled line length
+    let estimated_rows = estimate_rows(&buffer, line_terminator, file_size, truncated);
+    let exact_rows = if options.count_rows {
+        Some(count_records(path, encoding, delimiter, quote, escape)?)
+    } else {
+        None
+    };
     
-    Ok(result)
+    // Parse sample rows and infer column types
+    let records = sample_records(&decoded, delimiter, quote, escape, sample_rows, truncated);
+    let samples = column_samples(&headers, &records);
+    let column_types = infer_column_types(&headers, &samples);
+    let date_formats = infer_date_formats(&headers, &samples, &column_types);
+    let has_headers = detect_has_headers(&headers, &records, &column_types);
+    let ragged_rows = check_ragged(headers.len(), &records);
+    let sample = records.iter().take(PREVIEW_ROWS).map(|record| {
+        headers.iter().enumerate()
+            .map(|(i, h)| (h.clone(), json!(record.get(i).unwrap_or(""))))
+            .collect()
+    }).collect();
+
+    debug!(path, encoding = encoding.name(), delimiter = %delimiter, columns = headers.len(), sampled = records.len(), "Probed file");
+
+    Ok(ProbeResult {
+        encoding: encoding.name(),
+        delimiter,
+        delimiter_confidence,
+        line_terminator,
+        unterminated_last_line: unterminated,
+        quotechar: quote,
+        escape,
+        confidence,
+        encodings: candidates.iter()
+            .map(|c| EncodingScore { encoding: c.label, confidence: c.confidence })
+            .collect(),
+        file_size,
+        estimated_rows,
+        exact_rows,
+        has_headers,
+        headers,
+        header_issues,
+        suggested_headers,
+        column_types,
+        ragged_rows,
+        date_formats,
+        sample,
+    })
+}
+
+fn conforms(dtype: &str, val: &str) -> bool {
+    match dtype {
+        "int" => cast::to_int(val, "", false, None).is_ok(),
+        "float" => cast::to_float_text(val, "", false, None).is_ok(),
+        "bool" => cast::to_bool(val, "", &cast::BoolValues::default()).is_ok(),
+        "date" => INFER_DATE_FORMATS.iter().any(|f| chrono::NaiveDate::parse_from_str(val.trim(), f).is_ok()),
+        "datetime" => cast::to_datetime(val, "", &[]).is_ok()
+            || INFER_DATETIME_FORMATS.iter().any(|f| chrono::NaiveDateTime::parse_from_str(val.trim(), f).is_ok()),
+        _ => true,
+    }
+}
+
+fn dialect_reader<'a>(text: &'a str, delimiter: char, quote: char, escape: &str, has_headers: bool) -> csv::Reader<&'a [u8]> {
+    ReaderBuilder::new()
+        .has_headers(has_headers)
+        .flexible(true)
+        .delimiter(delimiter as u8)
+        .quote(quote as u8)
+        .double_quote(escape == "double")
+        .escape(if escape == "backslash" { Some(b'\\') } else { None })
+        .from_reader(text.as_bytes())
+}
+
+/// Data rows ≈ (file size − header line) ÷ average sampled line length.
+/// Exact when the whole file fit in the buffer and no field spans lines.
+fn estimate_rows(buffer: &[u8], line_terminator: &str, file_size: u64, truncated: bool) -> u64 {
+    let newline = if line_terminator == "\r" { b'\r' } else { b'\n' };
+    let breaks: Vec<usize> = buffer.iter().enumerate().filter(|&(_, &b)| b == newline).map(|(i, _)| i).collect();
+
+    let header_end = match breaks.first() {
+        Some(&pos) => pos + 1,
+        None => return 0,
+    };
+
+    if !truncated {
+        // Whole file sampled: count lines, including an unterminated last one
+        let trailing = buffer[breaks[breaks.len() - 1] + 1..].iter().any(|&b| b != 0);
+        return (breaks.len() - 1 + trailing as usize) as u64;
+    }
+
+    let sampled_lines = breaks.len() - 1;
+    if sampled_lines == 0 {
+        return 0;
+    }
+    let avg = (breaks[breaks.len() - 1] + 1 - header_end) as f64 / sampled_lines as f64;
+    ((file_size - header_end as u64) as f64 / avg).round() as u64
+}
+
+/// Stream the whole file and count data rows (malformed rows included)
+fn count_records(path: &str, encoding: &'static Encoding, delimiter: char, quote: char, escape: &str) -> Result<u64> {
+    let file = File::open(path)?;
+    let decoder = encoding_rs_io::DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .build(file);
+
+    let mut csv_reader = ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .delimiter(delimiter as u8)
+        .quote(quote as u8)
+        .double_quote(escape == "double")
+        .escape(if escape == "backslash" { Some(b'\\') } else { None })
+        .from_reader(BufReader::new(decoder));
+
+    let mut record = csv::ByteRecord::new();
+    let mut count = 0u64;
+    loop {
+        match csv_reader.read_byte_record(&mut record) {
+            Ok(true) => count += 1,
+            Ok(false) => break,
+            Err(_) => count += 1,
+        }
+    }
+    Ok(count)
+}
+
+/// Header cells exactly as the engine will see them (quotes resolved, nothing trimmed)
+fn raw_headers(text: &str, delimiter: char, quote: char, escape: &str) -> Vec<String> {
+    dialect_reader(text, delimiter, quote, escape, false)
+        .records()
+        .next()
+        .and_then(|r| r.ok())
+        .map(|r| r.iter().map(|h| h.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Flag headers that break column lookup by name: duplicates, blanks and
+/// whitespace padding. Also returns a cleaned, unique header list.
+fn check_headers(raw: &[String]) -> (Vec<HeaderIssue>, Vec<String>) {
+    let mut issues = Vec::new();
+    let mut suggested: Vec<String> = Vec::with_capacity(raw.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for name in raw {
+        *seen.entry(name.trim().to_string()).or_insert(0) += 1;
+    }
+    let mut used: HashMap<String, usize> = HashMap::new();
+
+    for (i, name) in raw.iter().enumerate() {
+        let trimmed = name.trim();
+        let mut problems = Vec::new();
+
+        let mut clean = if trimmed.is_empty() {
+            problems.push("empty");
+            format!("column_{}", i + 1)
+        } else {
+            if trimmed.len() != name.len() {
+                problems.push("whitespace");
+            }
+            trimmed.to_string()
+        };
+
+        if !trimmed.is_empty() && seen.get(trimmed).copied().unwrap_or(0) > 1 {
+            problems.push("duplicate");
+        }
+
+        // Suffix repeats (id, id_2, id_3) until the name is unique
+        let count = used.entry(clean.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            let mut n = *count;
+            while seen.contains_key(&format!("{}_{}", clean, n)) || used.contains_key(&format!("{}_{}", clean, n)) {
+                n += 1;
+            }
+            clean = format!("{}_{}", clean, n);
+            used.insert(clean.clone(), 1);
+        }
+
+        for issue in problems {
+            issues.push(HeaderIssue { index: i, name: name.clone(), issue, suggested: clean.clone() });
+        }
+        suggested.push(clean);
+    }
+
+    (issues, suggested)
+}
+
+/// Parse up to `sample_rows` data rows from the probe buffer
+fn sample_records(text: &str, delimiter: char, quote: char, escape: &str, sample_rows: usize, truncated: bool) -> Vec<StringRecord> {
+    // The probe buffer may end mid-row; drop the partial last line
+    let body = match (truncated, text.rfind(['\r', '\n'])) {
+        (true, Some(pos)) => &text[..pos],
+        _ => text,
+    };
+
+    dialect_reader(body, delimiter, quote, escape, true).records().take(sample_rows).flatten().collect()
+}
+
+/// Count sampled rows whose field count differs from the header's
+fn check_ragged(expected: usize, records: &[StringRecord]) -> RaggedReport {
+    let mut report = RaggedReport { checked: records.len(), longer: 0, shorter: 0, examples: Vec::new() };
+
+    for record in records {
+        if record.len() == expected {
+            continue;
+        }
+        if record.len() > expected { report.longer += 1; } else { report.shorter += 1; }
+
+        if report.examples.len() < RAGGED_EXAMPLES {
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            report.examples.push(RaggedRow { line, fields: record.len() });
+        }
+    }
+
+    report
+}
+
+/// Non-empty sampled values, one list per header
+fn column_samples(headers: &[String], records: &[StringRecord]) -> Vec<Vec<String>> {
+    let mut samples: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
+    for record in records {
+        for (i, field) in record.iter().enumerate().take(headers.len()) {
+            if !field.trim().is_empty() {
+                samples[i].push(field.to_string());
+            }
+        }
+    }
+    samples
+}
+
+/// Pick the most specific type most sampled values conform to.
+/// Returns one entry per header.
+fn infer_column_types(headers: &[String], samples: &[Vec<String>]) -> Vec<ColumnType> {
+    headers.iter().zip(samples.iter()).map(|(name, values)| {
+        if values.is_empty() {
+            return ColumnType { name: name.clone(), dtype: "string", confidence: 0.0 };
+        }
+
+        let mut inferred = ("string", 1.0);
+        for dtype in ["int", "float", "bool", "date", "datetime"] {
+            let ok = values.iter().filter(|v| conforms(dtype, v)).count();
+            let ratio = ok as f64 / values.len() as f64;
+            if ratio >= TYPE_CONFORMANCE {
+                inferred = (dtype, ratio);
+                break;
+            }
+        }
+
+        ColumnType { name: name.clone(), dtype: inferred.0, confidence: (inferred.1 * 100.0).round() / 100.0 }
+    }).collect()
+}
+
+/// For date/datetime columns, rank the strptime patterns that fit the sampled values.
+/// Patterns tied for first place (e.g. `%d/%m/%Y` vs `%m/%d/%Y` when no day exceeds 12)
+/// are flagged as ambiguous.
+fn infer_date_formats(headers: &[String], samples: &[Vec<String>], column_types: &[ColumnType]) -> Vec<DateFormats> {
+    let mut report = Vec::new();
+
+    for ((name, values), info) in headers.iter().zip(samples.iter()).zip(column_types.iter()) {
+        let (candidates, datetime) = match info.dtype {
+            "date" => (INFER_DATE_FORMATS, false),
+            "datetime" => (INFER_DATETIME_FORMATS, true),
+            _ => continue,
+        };
+
+        let mut scored: Vec<(&'static str, usize)> = candidates.iter().map(|&f| {
+            let ok = values.iter().filter(|v| {
+                if datetime {
+                    chrono::NaiveDateTime::parse_from_str(v.trim(), f).is_ok()
+                } else {
+                    chrono::NaiveDate::parse_from_str(v.trim(), f).is_ok()
+                }
+            }).count();
+            (f, ok)
+        }).filter(|&(_, ok)| ok > 0).collect();
+        scored.sort_by_key(|s| std::cmp::Reverse(s.1));
+
+        let best = scored.first().map(|s| s.1).unwrap_or(0);
+        let tied: Vec<&str> = scored.iter().filter(|s| s.1 == best).map(|s| s.0).collect();
+        let warning = (tied.len() > 1).then(|| {
+            format!("Sampled values fit {} equally; day/month order cannot be told apart", tied.join(" and "))
+        });
+
+        report.push(DateFormats {
+            name: name.clone(),
+            formats: scored.iter().map(|&(format, ok)| FormatShare {
+                format,
+                share: ((ok as f64 / values.len().max(1) as f64) * 100.0).round() / 100.0,
+            }).collect(),
+            ambiguous: tied.len() > 1,
+            warning,
+        });
+    }
+
+    report
+}
+
+/// Decide whether the first row is a header by comparing it against the
+/// type profile of the rows below it. Each column votes:
+/// - typed column (int/date/...): the first cell not conforming votes header
+/// - string column with fixed-length values: a first cell of another length votes header
+fn detect_has_headers(first_row: &[String], records: &[StringRecord], column_types: &[ColumnType]) -> bool {
+    if records.is_empty() {
+        return true;
+    }
+
+    let mut votes: i32 = 0;
+    for (i, (cell, info)) in first_row.iter().zip(column_types.iter()).enumerate() {
+        let dtype = info.dtype;
+        if dtype != "string" {
+            votes += if conforms(dtype, cell) { -1 } else { 1 };
+            continue;
+        }
+
+        let mut lengths = records.iter().filter_map(|r| r.get(i)).map(|v| v.chars().count());
+        if let Some(first_len) = lengths.next() {
+            if lengths.all(|l| l == first_len) {
+                votes += if cell.chars().count() == first_len { -1 } else { 1 };
+            }
+        }
+    }
+
+    // No evidence either way: assume the conventional header row
+    votes >= 0
 }
 
-fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, f32) {
+/// First probe buffer of a file
+fn read_probe_buffer(path: &str) -> Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+    let mut buffer = vec![0u8; PROBE_SIZE];
+    let bytes_read = reader.read(&mut buffer)?;
+    buffer.truncate(bytes_read);
+    Ok(buffer)
+}
+
+/// Detect only the delimiter of a file, from the first probe buffer
+pub fn sniff_delimiter(path: &str) -> Result<u8> {
+    let buffer = read_probe_buffer(path)?;
+    let (encoding, _) = detect_encoding(&buffer);
+    let (decoded, _) = encoding.decode_with_bom_removal(&buffer);
+    Ok(detect_delimiter(&decoded).0 as u8)
+}
+
+/// Detect only the encoding of a file, from the first probe buffer
+pub fn sniff_encoding(path: &str) -> Result<&'static Encoding> {
+    Ok(detect_encoding(&read_probe_buffer(path)?).0)
+}
+
+struct EncodingCandidate {
+    label: &'static str,
+    encoding: &'static Encoding,
+    confidence: f64,
+}
+
+/// Best encoding guess for the buffer
+fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, f64) {
+    rank_encodings(bytes)
+        .first()
+        .map(|c| (c.encoding, c.confidence))
+        .unwrap_or((encoding_rs::UTF_8, 0.5))
+}
+
+/// Score every supported encoding against the buffer, best first.
+/// Candidates that cannot decode the buffer are left out.
+fn rank_encodings(bytes: &[u8]) -> Vec<EncodingCandidate> {
     // BOM detection (highest priority)
-    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
-        return (encoding_rs::UTF_8, 1.0);
+    let bom = [
+        (&[0xEF, 0xBB, 0xBF][..], "UTF-8", encoding_rs::UTF_8),
+        (&[0xFF, 0xFE][..], "UTF-16LE", encoding_rs::UTF_16LE),
+        (&[0xFE, 0xFF][..], "UTF-16BE", encoding_rs::UTF_16BE),
+    ];
+    for (mark, label, encoding) in bom {
+        if bytes.starts_with(mark) {
+            return vec![EncodingCandidate { label, encoding, confidence: 1.0 }];
+        }
     }
-    
-    if bytes.starts_with(&[0xFF, 0xFE]) {
-        return (encoding_rs::UTF_16LE, 1.0);
+
+    let mut candidates = Vec::new();
+    let mut push = |label, encoding, confidence: f64| {
+        if confidence > 0.0 {
+            candidates.push(EncodingCandidate { label, encoding, confidence: (confidence * 100.0).round() / 100.0 });
+        }
+    };
+
+    // UTF-16 without BOM: ASCII text leaves every other byte zero
+    let (even_zero, odd_zero) = bytes.chunks_exact(2).fold((0usize, 0usize), |(e, o), pair| {
+        (e + (pair[0] == 0) as usize, o + (pair[1] == 0) as usize)
+    });
+    let pairs = (bytes.len() / 2).max(1) as f64;
+    push("UTF-16LE", encoding_rs::UTF_16LE, if odd_zero as f64 / pairs > 0.3 && even_zero * 10 < odd_zero { 0.95 * odd_zero as f64 / pairs } else { 0.0 });
+    push("UTF-16BE", encoding_rs::UTF_16BE, if even_zero as f64 / pairs > 0.3 && odd_zero * 10 < even_zero { 0.95 * even_zero as f64 / pairs } else { 0.0 });
+    if even_zero + odd_zero > 0 {
+        // NUL bytes rule out the ASCII-compatible encodings below
+        candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+        return candidates;
     }
-    
-    // Statistical detection
-    let mut utf8_score = 0.0;
-    let mut latin1_score = 0.0;
-    let mut windows1252_score = 0.0;
-    
-    // Count valid UTF-8 sequences
-    if std::str::from_utf8(bytes).is_ok() {
-        utf8_score = 0.9;
+
+    let high = bytes.iter().filter(|&&b| b > 127).count();
+    if high == 0 {
+        // Pure ASCII decodes identically everywhere; UTF-8 is the natural pick
+        push("UTF-8", encoding_rs::UTF_8, 0.9);
+        push("windows-1252", encoding_rs::WINDOWS_1252, 0.5);
+        push("ISO-8859-1", encoding_rs::WINDOWS_1252, 0.5);
+        push("Shift_JIS", encoding_rs::SHIFT_JIS, 0.4);
+        push("GBK", encoding_rs::GBK, 0.4);
+        return candidates;
     }
-    
-    // Check for Windows-1252 specific bytes (0x80-0x9F range)
-    let special_chars = bytes.iter()
-        .filter(|&&b| (0x80..=0x9F).contains(&b))
+
+    // Valid UTF-8 with multi-byte sequences is rarely a coincidence.
+    // A sequence cut off by the end of the buffer still counts as valid.
+    let utf8_ok = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    push("UTF-8", encoding_rs::UTF_8, if utf8_ok { 0.99 } else { 0.0 });
+
+    // Share of high bytes sitting next to another high byte: double-byte
+    // CJK encodings come in dense runs, Latin accents are isolated
+    let paired = bytes.iter().enumerate()
+        .filter(|&(i, &b)| b > 127 && (bytes.get(i + 1).is_some_and(|&n| n > 127) || (i > 0 && bytes[i - 1] > 127)))
         .count();
-    
-    if special_chars > 0 {
-        windows1252_score = 0.7;
-    } else if bytes.iter().any(|&b| b > 127) {
-        latin1_score = 0.6;
+    let pair_ratio = paired as f64 / high as f64;
+
+    // Latin: decoded non-ASCII characters should be letters or typographic punctuation
+    let (latin, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+    let non_ascii: Vec<char> = latin.chars().filter(|c| !c.is_ascii()).collect();
+    let plausible = non_ascii.iter().filter(|&&c| c.is_alphabetic() || "‘’“”–—…€£°±·×÷".contains(c)).count();
+    let latin_score = 0.8 * plausible as f64 / non_ascii.len().max(1) as f64 * (1.0 - 0.5 * pair_ratio);
+    let c1_bytes = bytes.iter().any(|&b| (0x80..=0x9F).contains(&b));
+    push("windows-1252", encoding_rs::WINDOWS_1252, latin_score);
+    // Latin-1 reads 0x80-0x9F as control characters, so those bytes point to windows-1252
+    push("ISO-8859-1", encoding_rs::WINDOWS_1252, if c1_bytes { latin_score * 0.3 } else { latin_score * 0.95 });
+
+    // CJK: must decode cleanly and yield mostly ideographs/kana rather than half-width or symbol noise
+    let cjk_score = |encoding: &'static Encoding, japanese: bool| -> f64 {
+        let (text, _, had_errors) = encoding.decode(bytes);
+        if had_errors {
+            return 0.0;
+        }
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_ascii()).collect();
+        let good = chars.iter().filter(|&&c| {
+            let cp = c as u32;
+            let kana = (0x3040..=0x30FF).contains(&cp);
+            (0x4E00..=0x9FFF).contains(&cp) || (0x3000..=0x303F).contains(&cp) || (0xFF01..=0xFF5E).contains(&cp) || (kana && japanese)
+        }).count();
+        let good_ratio = good as f64 / chars.len().max(1) as f64;
+
+        if japanese {
+            // Shift_JIS trail bytes may be ASCII, so runs are looser; Japanese text almost always contains kana
+            let has_kana = chars.iter().any(|&c| (0x3040..=0x30FF).contains(&(c as u32)));
+            0.9 * good_ratio * (0.5 + 0.5 * pair_ratio) * if has_kana { 1.0 } else { 0.6 }
+        } else {
+            // Common Chinese text stays inside the GB2312 block (both bytes 0xA1-0xFE)
+            let (mut total, mut gb2312, mut i) = (0usize, 0usize, 0usize);
+            while i < bytes.len() {
+                if bytes[i] > 127 {
+                    total += 1;
+                    if (0xA1..=0xFE).contains(&bytes[i]) && bytes.get(i + 1).is_some_and(|n| (0xA1..=0xFE).contains(n)) {
+                        gb2312 += 1;
+                    }
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            0.9 * good_ratio * pair_ratio * (0.5 + 0.5 * gb2312 as f64 / total.max(1) as f64)
+        }
+    };
+    push("Shift_JIS", encoding_rs::SHIFT_JIS, cjk_score(encoding_rs::SHIFT_JIS, true));
+    push("GBK", encoding_rs::GBK, cjk_score(encoding_rs::GBK, false));
+
+    if utf8_ok {
+        // Valid multi-byte UTF-8 by coincidence is rare; demote the alternatives
+        for c in candidates.iter_mut().skip(1) {
+            c.confidence = (c.confidence * 50.0).round() / 100.0;
+        }
     }
-    
-    // Return highest score
-    let scores = [
-        (encoding_rs::UTF_8, utf8_score),
-        (encoding_rs::WINDOWS_1252, windows1252_score),
-        (encoding_rs::WINDOWS_1252, latin1_score),
-    ];
-    
-    scores.iter()
-        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-        .map(|(enc, score)| (*enc, *score))
-        .unwrap_or((encoding_rs::UTF_8, 0.5))
+
+    candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+    candidates
 }
 
-fn detect_delimiter(text: &str) -> String {
-    let first_line = text.lines().next().unwrap_or("");
-    
+/// Pick the delimiter whose field count is most consistent across the sampled
+/// lines (quote-aware). Returns the delimiter and the share of lines agreeing
+/// with its most common field count.
+fn detect_delimiter(text: &str) -> (char, f64) {
     let candidates = [',', ';', '\t', '|', ':'];
-    let mut scores: HashMap<char, usize> = HashMap::new();
-    
-    for &delimiter in &candidates {
-        scores.insert(delimiter, first_line.matches(delimiter).count());
+
+    // A trailing line without a break may be cut off by the probe buffer
+    let complete = text.ends_with(['\r', '\n']);
+
+    let mut best = (',', 0.0, 0usize);
+    for &delim in &candidates {
+        let mut counts: Vec<usize> = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delim as u8)
+            .from_reader(text.as_bytes())
+            .records()
+            .take(DELIMITER_SAMPLE_LINES)
+            .flatten()
+            .map(|r| r.len())
+            .collect();
+        if !complete && counts.len() > 2 {
+            counts.pop();
+        }
+        if counts.is_empty() {
+            continue;
+        }
+
+        // Most common field count and how many lines share it
+        let mut freq: HashMap<usize, usize> = HashMap::new();
+        for &c in &counts {
+            *freq.entry(c).or_insert(0) += 1;
+        }
+        let (fields, agree) = freq.into_iter()
+            .max_by_key(|&(fields, n)| (n, fields))
+            .unwrap_or((1, 0));
+        if fields < 2 {
+            continue;
+        }
+
+        let consistency = agree as f64 / counts.len() as f64;
+        // Consistency first; more fields breaks ties; earlier candidates win exact ties
+        if consistency > best.1 || (consistency == best.1 && fields > best.2) {
+            best = (delim, consistency, fields);
+        }
     }
-    
-    scores.iter()
-        .max_by_key(|(_, &count)| count)
-        .map(|(&delim, _)| delim.to_string())
-        .unwrap_or_else(|| ",".to_string())
+
+    (best.0, (best.1 * 100.0).round() / 100.0)
 }
 
-fn extract_headers(text: &str, delimiter: &str) -> Vec<String> {
-    text.lines()
-        .next()
-        .unwrap_or("")
-        .split(delimiter)
-        .map(|s| s.trim().to_string())
-        .collect()
-}
\ No newline at end of file
+/// Majority line ending in the buffer: "\r\n", bare "\r" or "\n"
+fn detect_line_terminator(bytes: &[u8]) -> &'static str {
+    let (mut crlf, mut cr, mut lf) = (0usize, 0usize, 0usize);
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => { crlf += 1; i += 1; }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if crlf >= lf && crlf >= cr && crlf > 0 {
+        "\r\n"
+    } else if cr > lf {
+        "\r"
+    } else {
+        "\n"
+    }
+}
+
+/// Whether the file ends without a trailing line break (checked at the real end of file)
+fn last_line_unterminated(path: &str) -> Result<bool> {
+    let mut file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n' && last[0] != b'\r')
+}
+
+/// Detect the quote character ('"' or '\'') from how often it wraps a field,
+/// and whether embedded quotes are escaped by doubling or by backslash
+fn detect_quoting(text: &str, delim: char) -> (char, &'static str) {
+    let wrapped = |q: char| -> usize {
+        text.split(['\r', '\n']).map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            let opens = chars.iter().enumerate()
+                .filter(|&(i, &c)| c == q && (i == 0 || chars[i - 1] == delim))
+                .count();
+            let closes = chars.iter().enumerate()
+                .filter(|&(i, &c)| c == q && (i + 1 == chars.len() || chars[i + 1] == delim))
+                .count();
+            opens.min(closes)
+        }).sum()
+    };
+
+    let double_count = wrapped('"');
+    let single_count = wrapped('\'');
+    let quote = if single_count > double_count { '\'' } else { '"' };
+
+    let backslashed = text.matches(&format!("\\{}", quote)).count();
+    let doubled = text.matches(&format!("{}{}", quote, quote)).count();
+    let escape = if backslashed > doubled { "backslash" } else { "double" };
+
+    (quote, escape)
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::BufReader;
+use csv::{ReaderBuilder, StringRecord};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use crate::error::{PhaetonError, Result};
+use crate::processors::{filter, probe};
+use tracing::debug;
+
+const HLL_PRECISION: u32 = 14;
+const HLL_REGISTERS: usize = 1 << HLL_PRECISION;
+const PROFILE_BATCH: usize = 10_000;
+
+/// HyperLogLog distinct-count estimator (~0.8% standard error at p=14)
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self { registers: vec![0; HLL_REGISTERS] }
+    }
+
+    fn insert(&mut self, val: &str) {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let idx = (hash >> (64 - HLL_PRECISION)) as usize;
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[idx] {
+            self.registers[idx] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        // Small-range correction (linear counting)
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+/// Space-Saving heavy hitters: exact for columns with few distinct values.
+/// Each entry tracks (count, error) so the reported count is a guaranteed lower bound.
+struct TopK {
+    capacity: usize,
+    counts: HashMap<String, (u64, u64)>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self { capacity: (k * 10).max(100), counts: HashMap::new() }
+    }
+
+    fn insert(&mut self, val: &str) {
+        if let Some((c, _)) = self.counts.get_mut(val) {
+            *c += 1;
+            return;
+        }
+        if self.counts.len() < self.capacity {
+            self.counts.insert(val.to_string(), (1, 0));
+            return;
+        }
+        // Evict the current minimum, inheriting its count as error
+        let (min_key, min_count) = self.counts.iter()
+            .min_by_key(|(_, &(c, _))| c)
+            .map(|(k, &(c, _))| (k.clone(), c))
+            .unwrap();
+        self.counts.remove(&min_key);
+        self.counts.insert(val.to_string(), (min_count + 1, min_count));
+    }
+
+    fn top(&self, k: usize) -> Vec<(String, u64)> {
+        let mut items: Vec<(String, u64)> = self.counts.iter().map(|(v, &(c, err))| (v.clone(), c - err)).collect();
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        items.truncate(k);
+        items
+    }
+}
+
+struct ColumnProfile {
+    name: String,
+    nulls: u64,
+    hll: HyperLogLog,
+    top: TopK,
+    min_str: Option<String>,
+    max_str: Option<String>,
+    // Welford running statistics over values that parse as numbers
+    num_count: u64,
+    num_mean: f64,
+    num_m2: f64,
+    num_min: f64,
+    num_max: f64,
+    num_integral: bool,
+}
+
+impl ColumnProfile {
+    fn new(name: &str, k: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            nulls: 0,
+            hll: HyperLogLog::new(),
+            top: TopK::new(k),
+            min_str: None,
+            max_str: None,
+            num_count: 0,
+            num_mean: 0.0,
+            num_m2: 0.0,
+            num_min: f64::INFINITY,
+            num_max: f64::NEG_INFINITY,
+            num_integral: true,
+        }
+    }
+
+    fn observe(&mut self, raw: Option<&str>) {
+        let val = match raw {
+            Some(v) if !filter::is_empty(v) => v.trim(),
+            _ => {
+                self.nulls += 1;
+                return;
+            }
+        };
+
+        self.hll.insert(val);
+        self.top.insert(val);
+
+        if self.min_str.as_deref().is_none_or(|m| val < m) { self.min_str = Some(val.to_string()); }
+        if self.max_str.as_deref().is_none_or(|m| val > m) { self.max_str = Some(val.to_string()); }
+
+        if let Ok(x) = val.parse::<f64>() {
+            if x.is_finite() {
+                self.num_count += 1;
+                let delta = x - self.num_mean;
+                self.num_mean += delta / self.num_count as f64;
+                self.num_m2 += delta * (x - self.num_mean);
+                self.num_min = self.num_min.min(x);
+                self.num_max = self.num_max.max(x);
+                self.num_integral &= val.parse::<i64>().is_ok();
+            }
+        }
+    }
+
+    fn report(&self, rows: u64, k: usize) -> Value {
+        let non_null = rows - self.nulls;
+        // Numeric stats only when (nearly) every value is numeric
+        let numeric = if self.num_count > 0 && self.num_count as f64 >= non_null as f64 * 0.95 {
+            let variance = if self.num_count > 1 { self.num_m2 / (self.num_count - 1) as f64 } else { 0.0 };
+            json!({
+                "count": self.num_count,
+                "min": self.num_min,
+                "max": self.num_max,
+                "mean": self.num_mean,
+                "stddev": variance.sqrt(),
+                "integral": self.num_integral,
+            })
+        } else {
+            Value::Null
+        };
+
+        json!({
+            "name": self.name,
+            "count": non_null,
+            "nulls": self.nulls,
+            "null_ratio": if rows == 0 { 0.0 } else { self.nulls as f64 / rows as f64 },
+            "distinct_estimate": self.hll.estimate().min(non_null),
+            "min": self.min_str,
+            "max": self.max_str,
+            "numeric": numeric,
+            "top_values": self.top.top(k).into_iter().map(|(v, c)| json!([v, c])).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Stream the whole file and build per-column statistics
+pub fn profile_file(path: &str, delimiter: Option<u8>, top_k: usize) -> Result<HashMap<String, Value>> {
+    let delimiter = match delimiter {
+        Some(d) => d,
+        None => probe::sniff_delimiter(path)?,
+    };
+
+    let encoding = probe::sniff_encoding(path)?;
+
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    let decoder = encoding_rs_io::DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .build(file);
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(BufReader::new(decoder));
+
+    let headers = reader.headers()?.clone();
+    let mut profiles: Vec<ColumnProfile> = headers.iter().map(|h| ColumnProfile::new(h, top_k)).collect();
+
+    let mut rows: u64 = 0;
+    let mut malformed: u64 = 0;
+    let mut batch: Vec<StringRecord> = Vec::with_capacity(PROFILE_BATCH);
+    let mut iter = reader.into_records();
+
+    let mut exhausted = false;
+    while !exhausted {
+        batch.clear();
+        for _ in 0..PROFILE_BATCH {
+            match iter.next() {
+                Some(Ok(record)) => batch.push(record),
+                Some(Err(_)) => malformed += 1,
+                None => { exhausted = true; break; },
+            }
+        }
+        rows += batch.len() as u64;
+
+        // Columns are independent, so profile them in parallel
+        profiles.par_iter_mut().enumerate().for_each(|(i, profile)| {
+            for record in &batch {
+                profile.observe(record.get(i));
+            }
+        });
+    }
+
+    debug!(path, rows, malformed, encoding = encoding.name(), columns = profiles.len(), "Profiled file");
+
+    // Column statistics cover the rows that parsed; `rows` counts every record
+    let mut result = HashMap::new();
+    result.insert("rows".to_string(), json!(rows + malformed));
+    result.insert("malformed_rows".to_string(), json!(malformed));
+    result.insert("columns".to_string(), Value::Array(profiles.iter().map(|p| p.report(rows, top_k)).collect()));
+    Ok(result)
+}
+
+// Columns at least this empty are reported instead of cleaned
+const MOSTLY_EMPTY_RATIO: f64 = 0.95;
+
+// String columns with at most this many distinct values count as categorical
+const CATEGORICAL_MAX_DISTINCT: u64 = 20;
+
+// A top value covering this share of non-null rows is treated as canonical
+const CANONICAL_SHARE: f64 = 0.02;
+
+/// Derive a starter step list from a `profile_file` result:
+/// trim string columns, cast numeric-looking ones, align low-cardinality
+/// categoricals against their dominant values. Returns `{steps, notes}`,
+/// where notes cover advice that has no matching step (e.g. mostly-empty columns).
+pub fn suggest_pipeline(profile: &HashMap<String, Value>) -> HashMap<String, Value> {
+    let columns = profile.get("columns").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+
+    let mut trims = Vec::new();
+    let mut casts = Vec::new();
+    let mut aligns = Vec::new();
+    let mut notes = Vec::new();
+
+    for column in &columns {
+        let name = match column["name"].as_str() {
+            Some(n) => n,
+            None => continue,
+        };
+        let count = column["count"].as_u64().unwrap_or(0);
+        let null_ratio = column["null_ratio"].as_f64().unwrap_or(0.0);
+
+        if count == 0 || null_ratio >= MOSTLY_EMPTY_RATIO {
+            notes.push(json!(format!("Column '{}' is {:.0}% empty; consider dropping it", name, null_ratio * 100.0)));
+            continue;
+        }
+
+        let numeric = &column["numeric"];
+        if numeric.is_object() {
+            let dtype = if numeric["integral"].as_bool().unwrap_or(false) { "int" } else { "float" };
+            // A few non-numeric stragglers suggest noise worth stripping
+            let mut cast = json!({"action": "cast", "col": name, "type": dtype});
+            if numeric["count"].as_u64().unwrap_or(0) < count {
+                cast["clean"] = Value::Bool(true);
+            }
+            casts.push(cast);
+            continue;
+        }
+
+        trims.push(json!({"action": "scrub", "col": name, "mode": "trim"}));
+
+        let distinct = column["distinct_estimate"].as_u64().unwrap_or(0);
+        if distinct > 1 && distinct <= CATEGORICAL_MAX_DISTINCT {
+            let min_count = ((count as f64 * CANONICAL_SHARE).ceil() as u64).max(2);
+            let canonical: Vec<&str> = column["top_values"].as_array().into_iter().flatten()
+                .filter(|pair| pair[1].as_u64().unwrap_or(0) >= min_count)
+                .filter_map(|pair| pair[0].as_str())
+                .collect();
+
+            // Only worth aligning when rare variants exist besides the canonical values
+            if !canonical.is_empty() && (canonical.len() as u64) < distinct {
+                aligns.push(json!({"action": "align", "col": name, "ref": canonical}));
+            }
+        }
+    }
+
+    let steps: Vec<Value> = trims.into_iter().chain(casts).chain(aligns).collect();
+
+    let mut result = HashMap::new();
+    result.insert("steps".to_string(), Value::Array(steps));
+    result.insert("notes".to_string(), Value::Array(notes));
+    result
+}
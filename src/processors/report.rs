@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fs;
+use chrono::Local;
+use serde_json::{json, Value};
+use crate::error::{PhaetonError, Result};
+
+/// Wrap a `profile_file` result with provenance so the file stands alone
+fn build_document(source: &str, profile: &HashMap<String, Value>) -> Value {
+    let file_size = fs::metadata(source).map(|m| m.len()).ok();
+    json!({
+        "source": source,
+        "generated_at": Local::now().to_rfc3339(),
+        "generator": format!("phaeton {}", env!("CARGO_PKG_VERSION")),
+        "file_size": file_size,
+        "rows": profile.get("rows").cloned().unwrap_or(Value::Null),
+        "malformed_rows": profile.get("malformed_rows").cloned().unwrap_or(Value::Null),
+        "columns": profile.get("columns").cloned().unwrap_or(Value::Array(Vec::new())),
+    })
+}
+
+fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render a JSON scalar as table text (numbers trimmed to 4 decimals)
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "&ndash;".to_string(),
+        Value::String(s) => escape_html(s),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() => format!("{:.4}", f).trim_end_matches('0').trim_end_matches('.').to_string(),
+            _ => n.to_string(),
+        },
+        other => escape_html(&other.to_string()),
+    }
+}
+
+const REPORT_CSS: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2rem;color:#222}\
+h1{font-size:1.4rem}table{border-collapse:collapse;margin:1rem 0;width:100%}\
+th,td{border:1px solid #ddd;padding:.35rem .6rem;text-align:left;font-size:.9rem;vertical-align:top}\
+th{background:#f4f4f4}.meta td:first-child{font-weight:600;width:12rem}\
+.warn{color:#b35900}ol{margin:0;padding-left:1.2rem}";
+
+fn render_html(doc: &Value) -> String {
+    let mut html = String::new();
+    let source = doc["source"].as_str().unwrap_or("");
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>Phaeton profile: {}</title>\n", escape_html(source)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", REPORT_CSS));
+    html.push_str(&format!("<h1>Data profile: {}</h1>\n", escape_html(source)));
+
+    html.push_str("<table class=\"meta\">\n");
+    for (label, key) in [("Generated", "generated_at"), ("Generator", "generator"), ("File size (bytes)", "file_size"), ("Rows", "rows"), ("Malformed rows", "malformed_rows")] {
+        html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", label, cell(&doc[key])));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<table>\n<tr><th>Column</th><th>Non-null</th><th>Nulls</th><th>Null %</th><th>Distinct (est.)</th>\
+<th>Min</th><th>Max</th><th>Mean</th><th>Std dev</th><th>Top values</th></tr>\n");
+    for col in doc["columns"].as_array().into_iter().flatten() {
+        let null_ratio = col["null_ratio"].as_f64().unwrap_or(0.0);
+        let null_class = if null_ratio >= 0.5 { " class=\"warn\"" } else { "" };
+        let numeric = &col["numeric"];
+        // Numeric columns compare by value, not lexically
+        let (min, max) = if numeric.is_object() { (&numeric["min"], &numeric["max"]) } else { (&col["min"], &col["max"]) };
+
+        let top: String = col["top_values"].as_array().into_iter().flatten()
+            .map(|pair| format!("<li>{} <small>({})</small></li>", cell(&pair[0]), cell(&pair[1])))
+            .collect();
+
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td{}>{:.1}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><ol>{}</ol></td></tr>\n",
+            cell(&col["name"]), cell(&col["count"]), cell(&col["nulls"]), null_class, null_ratio * 100.0,
+            cell(&col["distinct_estimate"]), cell(min), cell(max),
+            cell(&numeric["mean"]), cell(&numeric["stddev"]), top,
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Write a profiling report for `source` to `output` as "json" or "html"
+pub fn write_report(source: &str, profile: &HashMap<String, Value>, output: &str, format: &str) -> Result<()> {
+    let doc = build_document(source, profile);
+    let content = match format {
+        "json" => serde_json::to_string_pretty(&doc)?,
+        "html" => render_html(&doc),
+        other => return Err(PhaetonError::InvalidStep(format!("Unknown report format '{}'", other))),
+    };
+    fs::write(output, content)?;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use csv::ReaderBuilder;
+use crate::error::{PhaetonError, Result};
+use tracing::debug;
+
+/// String similarity metrics, all normalized to 0.0 (different) - 1.0 (identical)
+#[derive(Clone, Copy, Debug)]
+pub enum Metric {
+    JaroWinkler,
+    Jaro,
+    Levenshtein,
+    TokenSort,
+    Ngram,
+}
+
+impl Metric {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "jaro_winkler" => Ok(Metric::JaroWinkler),
+            "jaro" => Ok(Metric::Jaro),
+            "levenshtein" => Ok(Metric::Levenshtein),
+            "token_sort" => Ok(Metric::TokenSort),
+            "ngram" => Ok(Metric::Ngram),
+            unknown => Err(PhaetonError::InvalidStep(format!("Unknown similarity metric: {}", unknown))),
+        }
+    }
+}
+
+pub fn score(metric: Metric, a: &str, b: &str) -> f64 {
+    match metric {
+        Metric::JaroWinkler => strsim::jaro_winkler(a, b),
+        Metric::Jaro => strsim::jaro(a, b),
+        Metric::Levenshtein => strsim::normalized_levenshtein(a, b),
+        Metric::TokenSort => strsim::normalized_levenshtein(&sort_tokens(a), &sort_tokens(b)),
+        // Sørensen-Dice over character bigrams
+        Metric::Ngram => strsim::sorensen_dice(a, b),
+    }
+}
+
+/// Lowercase, split on anything non-alphanumeric, sort: "Smith, John" -> "john smith"
+fn sort_tokens(input: &str) -> String {
+    let lowered = input.to_lowercase();
+    let mut tokens: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tokens.sort_unstable();
+    tokens.join(" ")
+}
+
+/// Load a reference list from a newline-delimited file, or from one column of a CSV (header required)
+pub fn load_reference(path: &str, column: Option<&str>) -> Result<Vec<String>> {
+    let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    let reader = BufReader::new(file);
+
+    let mut values = Vec::new();
+    match column {
+        Some(col) => {
+            let mut csv_reader = ReaderBuilder::new().has_headers(true).flexible(true).from_reader(reader);
+            let idx = csv_reader.headers()?.iter().position(|h| h == col)
+                .ok_or_else(|| PhaetonError::ColumnNotFound(format!("{} (in {})", col, path)))?;
+            for result in csv_reader.records() {
+                let record = result?;
+                if let Some(val) = record.get(idx).map(|v| v.trim()).filter(|v| !v.is_empty()) {
+                    values.push(val.to_string());
+                }
+            }
+        },
+        None => {
+            for line in reader.lines() {
+                let line = line?;
+                let val = line.trim();
+                if !val.is_empty() { values.push(val.to_string()); }
+            }
+        }
+    }
+    debug!(path, column, values = values.len(), "Loaded reference list");
+    Ok(values)
+}
@@ -0,0 +1,186 @@
+use crate::error::{PhaetonError, Result};
+
+/// `[[fill]align][0][width][.precision][type]`, the subset of Python's format
+/// spec that makes sense for text cells. Types: `s` text, `d` integer, `f` fixed point.
+#[derive(Clone, Debug, Default)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Option<Self> {
+        let chars: Vec<char> = spec.chars().collect();
+        let is_align = |c: &char| matches!(c, '<' | '>' | '^' | '=');
+        let mut parsed = Spec::default();
+        let mut i = 0;
+        if chars.get(1).is_some_and(is_align) {
+            parsed.fill = Some(chars[0]);
+            parsed.align = Some(chars[1]);
+            i = 2;
+        } else if chars.first().is_some_and(is_align) {
+            parsed.align = Some(chars[0]);
+            i = 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            parsed.zero = true;
+            i += 1;
+        }
+        let digits = |i: &mut usize| -> Option<usize> {
+            let start = *i;
+            while chars.get(*i).is_some_and(char::is_ascii_digit) { *i += 1; }
+            if *i == start { None } else { chars[start..*i].iter().collect::<String>().parse().ok() }
+        };
+        parsed.width = digits(&mut i).unwrap_or(0);
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            parsed.precision = Some(digits(&mut i)?);
+        }
+        if let Some(&kind @ ('s' | 'd' | 'f')) = chars.get(i) {
+            parsed.kind = Some(kind);
+            i += 1;
+        }
+        (i == chars.len()).then_some(parsed)
+    }
+
+    /// Empty cells stay empty (but padded); `d` and `f` fail on anything that is not a number
+    fn apply(&self, value: &str) -> std::result::Result<String, String> {
+        let number = value.trim().parse::<f64>().ok().filter(|n| n.is_finite());
+        // Plain integers keep their digits; only forms like `1e3` or `12.0` go through f64
+        let integer = (self.kind == Some('d')).then(|| integer_text(value.trim())).flatten();
+        let (body, numeric) = match (self.kind, number) {
+            _ if value.trim().is_empty() => (String::new(), false),
+            (Some('d'), _) if integer.is_some() => (integer.unwrap_or_default(), true),
+            (Some('d'), Some(n)) if n.fract() == 0.0 => (format!("{:.0}", n), true),
+            (Some('d'), _) => return Err(format!("'{}' is not an integer", value)),
+            (Some('f'), Some(n)) => (format!("{:.*}", self.precision.unwrap_or(6), n), true),
+            (Some('f'), _) => return Err(format!("'{}' is not a number", value)),
+            (None, Some(n)) => (self.precision.map_or_else(|| value.trim().to_string(), |p| format!("{:.*}", p, n)), true),
+            _ => (self.precision.map_or_else(|| value.to_string(), |p| value.chars().take(p).collect()), false),
+        };
+
+        let len = body.chars().count();
+        if len >= self.width {
+            return Ok(body);
+        }
+        let pad = self.width - len;
+        let (fill, align) = match (self.fill, self.align, self.zero) {
+            (Some(fill), Some(align), _) => (fill, align),
+            (None, Some(align), zero) => (if zero { '0' } else { ' ' }, align),
+            (_, None, true) => ('0', if numeric { '=' } else { '>' }),
+            (_, None, false) => (' ', if numeric { '>' } else { '<' }),
+        };
+        let padding = |n: usize| std::iter::repeat_n(fill, n).collect::<String>();
+        Ok(match align {
+            '<' => body + &padding(pad),
+            '^' => padding(pad / 2) + &body + &padding(pad - pad / 2),
+            '=' if numeric && (body.starts_with('-') || body.starts_with('+')) => {
+                let (sign, digits) = body.split_at(1);
+                format!("{}{}{}", sign, padding(pad), digits)
+            },
+            _ => padding(pad) + &body,
+        })
+    }
+}
+
+/// `text` as canonical integer digits (sign kept, leading zeros and `+` dropped), without
+/// going through f64, so IDs longer than 2^53 keep every digit
+fn integer_text(text: &str) -> Option<String> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(match digits.trim_start_matches('0') {
+        "" => "0".to_string(),
+        digits => format!("{}{}", sign, digits),
+    })
+}
+
+#[derive(Clone, Debug)]
+enum Part {
+    Literal(String),
+    Field { idx: usize, spec: Spec },
+}
+
+/// A compiled `{column}` / `{column:spec}` template; `{{` and `}}` are literal braces
+#[derive(Clone, Debug)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Compile `template`, resolving each referenced column name with `resolve`
+    pub fn parse(template: &str, resolve: impl Fn(&str) -> Result<usize>) -> Result<Self> {
+        let invalid = |reason: &str| PhaetonError::InvalidStep(format!("Invalid format template {:?}: {}", template, reason));
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => { chars.next(); literal.push('{'); },
+                '}' if chars.peek() == Some(&'}') => { chars.next(); literal.push('}'); },
+                '}' => return Err(invalid("unmatched '}'")),
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed '{'")),
+                            Some(c) => field.push(c),
+                        }
+                    }
+                    let (name, spec) = field.split_once(':').unwrap_or((&field, ""));
+                    if name.is_empty() {
+                        return Err(invalid("empty field name"));
+                    }
+                    let spec = Spec::parse(spec).ok_or_else(|| invalid(&format!("bad format spec '{}'", spec)))?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field { idx: resolve(name)?, spec });
+                },
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { source: template.to_string(), parts })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Columns the template reads, in order of first use
+    pub fn columns(&self) -> Vec<usize> {
+        let mut idxs = Vec::new();
+        for part in &self.parts {
+            if let Part::Field { idx, .. } = part {
+                if !idxs.contains(idx) { idxs.push(*idx); }
+            }
+        }
+        idxs
+    }
+
+    /// Fill the template; `value` gives a column's text (missing cells are empty)
+    pub fn render<'a>(&self, value: impl Fn(usize) -> Option<&'a str>) -> std::result::Result<String, String> {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Field { idx, spec } => out.push_str(&spec.apply(value(*idx).unwrap_or_default())?),
+            }
+        }
+        Ok(out)
+    }
+}
@@ -1,5 +1,9 @@
 use std::borrow::Cow;
 
+use sha2::{Digest, Sha256};
+
+use crate::error::{PhaetonError, Result};
+
 pub fn mask_email(input: &str) -> Cow<'_, str> {
     let parts: Vec<&str> = input.split('@').collect();
     if parts.len() != 2 {
@@ -19,8 +23,207 @@ pub fn mask_email(input: &str) -> Cow<'_, str> {
     Cow::Owned(format!("{}****{}@{}", first_char, last_char, domain))
 }
 
+/// Keyed pseudo-random bytes for masking one value
+struct MaskStream {
+    seed: Sha256,
+    block: [u8; 32],
+    counter: u32,
+    pos: usize,
+}
+
+impl MaskStream {
+    fn new(input: &str, salt: &str) -> Self {
+        let mut seed = Sha256::new();
+        seed.update(salt.as_bytes());
+        seed.update([0]);
+        seed.update(input.as_bytes());
+        Self { seed, block: [0; 32], counter: 0, pos: 32 }
+    }
+
+    fn next(&mut self, n: u8) -> u8 {
+        if self.pos == self.block.len() {
+            let mut hasher = self.seed.clone();
+            hasher.update(self.counter.to_le_bytes());
+            self.block = hasher.finalize().into();
+            self.counter += 1;
+            self.pos = 0;
+        }
+        self.pos += 1;
+        ((u16::from(self.block[self.pos - 1]) * u16::from(n)) >> 8) as u8
+    }
+}
+
+/// Luhn weight of `digit` at `pos` (0 = rightmost)
+fn luhn_weight(digit: u32, pos: usize) -> u32 {
+    if pos % 2 == 1 { (digit * 2) / 10 + (digit * 2) % 10 } else { digit }
+}
+
+/// Masks a value but keeps its format: digits become digits, letters become letters
+/// of the same case, and everything else (dashes, spaces, dots) stays where it is.
+/// The same value and salt always give the same mask. The first `keep_first` and
+/// last `keep_last` letters and digits are kept. With `luhn`, the last masked digit
+/// is chosen so the digits still pass a Luhn check, like card and account numbers.
+pub fn mask_format(input: &str, salt: &str, keep_first: usize, keep_last: usize, luhn: bool) -> String {
+    let maskable = |c: char| c.is_ascii_digit() || c.is_alphabetic();
+    let total = input.chars().filter(|&c| maskable(c)).count();
+    let mut stream = MaskStream::new(input, salt);
+
+    let mut seen = 0;
+    // (character, whether it was masked)
+    let mut out: Vec<(char, bool)> = input.chars().map(|c| {
+        if !maskable(c) {
+            return (c, false);
+        }
+        seen += 1;
+        if seen <= keep_first || seen > total.saturating_sub(keep_last) {
+            (c, false)
+        } else if c.is_ascii_digit() {
+            ((b'0' + stream.next(10)) as char, true)
+        } else if c.is_uppercase() {
+            ((b'A' + stream.next(26)) as char, true)
+        } else {
+            ((b'a' + stream.next(26)) as char, true)
+        }
+    }).collect();
+
+    if luhn {
+        // Digit positions, rightmost first
+        let digits: Vec<usize> = (0..out.len()).rev().filter(|&i| out[i].0.is_ascii_digit()).collect();
+        if let Some(fix) = digits.iter().position(|&i| out[i].1) {
+            let rest: u32 = digits.iter().enumerate()
+                .filter(|&(pos, _)| pos != fix)
+                .map(|(pos, &i)| luhn_weight(out[i].0.to_digit(10).unwrap_or(0), pos))
+                .sum();
+            if let Some(d) = (0..10).find(|&d| (rest + luhn_weight(d, fix)) % 10 == 0) {
+                out[digits[fix]].0 = char::from_digit(d, 10).unwrap_or('0');
+            }
+        }
+    }
+    out.into_iter().map(|(c, _)| c).collect()
+}
+
+/// Exponents written out in full; past this, scientific notation is left as it is
+const MAX_EXPANDED_EXPONENT: i32 = 64;
+
+/// `1.2E+05`-style notation: a decimal mantissa, `e` or `E`, and a signed exponent
+pub fn is_scientific(input: &str) -> bool {
+    let Some((mantissa, exponent)) = input.split_once(['e', 'E']) else { return false };
+    let mantissa = mantissa.strip_prefix(['+', '-']).unwrap_or(mantissa);
+    let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    !(int_part.is_empty() && frac_part.is_empty())
+        && int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit())
+        && !exponent.is_empty()
+        && exponent.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Move the decimal point of a plain number (`-12.5`) by `shift` places, exactly;
+/// leading and trailing zeros are dropped. None when `input` is not a plain number.
+pub fn shift_decimal(input: &str, shift: i32) -> Option<String> {
+    let (negative, unsigned) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Every digit, and where the point goes among them
+    let digits: String = [int_part, frac_part].concat();
+    let point = int_part.len() as i64 + shift as i64;
+    let (whole, fraction) = if point <= 0 {
+        (String::new(), "0".repeat(point.unsigned_abs() as usize) + &digits)
+    } else if point as usize >= digits.len() {
+        (digits.clone() + &"0".repeat(point as usize - digits.len()), String::new())
+    } else {
+        (digits[..point as usize].to_string(), digits[point as usize..].to_string())
+    };
+    let whole = whole.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+
+    let mut out = String::with_capacity(whole.len() + fraction.len() + 3);
+    if negative && !(whole.is_empty() && fraction.is_empty()) { out.push('-'); }
+    out.push_str(if whole.is_empty() { "0" } else { whole });
+    if !fraction.is_empty() {
+        out.push('.');
+        out.push_str(fraction);
+    }
+    Some(out)
+}
+
+/// Scientific notation written as a plain number (`1.2E+05` -> `120000`), exactly.
+/// None when `input` is not scientific or its exponent is beyond 64.
+pub fn expand_scientific(input: &str) -> Option<String> {
+    if !is_scientific(input) {
+        return None;
+    }
+    let (mantissa, exponent) = input.split_once(['e', 'E'])?;
+    let exponent: i32 = exponent.parse().ok().filter(|e: &i32| e.abs() <= MAX_EXPANDED_EXPONENT)?;
+    shift_decimal(mantissa, exponent)
+}
+
+/// `12.5%` -> `0.125`; values without a trailing `%` are left alone
+pub fn scrub_percent(input: &str, decimal: Option<DecimalMark>) -> Cow<'_, str> {
+    let Some(number) = input.trim().strip_suffix('%') else { return Cow::Borrowed(input) };
+    let number = number.trim_end();
+    let number = scrub_currency_as(number, decimal);
+    match shift_decimal(&number, -2) {
+        Some(fraction) => Cow::Owned(fraction),
+        None => Cow::Borrowed(input),
+    }
+}
+
+/// Decimal separator of numbers, when the format guess must not decide it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecimalMark {
+    Dot,
+    Comma,
+}
+
+impl DecimalMark {
+    pub fn parse(mark: &str) -> Result<Self> {
+        match mark {
+            "." => Ok(DecimalMark::Dot),
+            "," => Ok(DecimalMark::Comma),
+            other => Err(PhaetonError::InvalidStep(format!("Unknown decimal separator '{}': expected '.' or ','", other))),
+        }
+    }
+
+    pub fn as_char(self) -> char {
+        match self {
+            DecimalMark::Dot => '.',
+            DecimalMark::Comma => ',',
+        }
+    }
+}
+
+/// The separator `scrub_currency` reads as a thousands separator on a guess: the
+/// only one in the value, followed by exactly three digits (`1,234`, `1.500`)
+pub fn ambiguous_separator(input: &str) -> Option<char> {
+    let skeleton: Vec<char> = input.chars().filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',').collect();
+    let mut separators = skeleton.iter().enumerate().filter(|(_, c)| !c.is_ascii_digit());
+    let (idx, &sep) = separators.next()?;
+    (separators.next().is_none() && idx > 0 && skeleton.len() - idx - 1 == 3).then_some(sep)
+}
+
 pub fn scrub_currency(input: &str) -> Cow<'_, str> {
+    scrub_currency_as(input, None)
+}
+
+/// `scrub_currency` with the decimal separator given; the other one is dropped as a
+/// thousands separator
+pub fn scrub_currency_as(input: &str, decimal: Option<DecimalMark>) -> Cow<'_, str> {
+    // An exponent is not a thousands separator
+    let trimmed = input.trim();
+    if is_scientific(trimmed) {
+        return expand_scientific(trimmed).map_or(Cow::Borrowed(input), Cow::Owned);
+    }
+
+    // The minus sign and en dash of typeset reports count as a hyphen-minus
     let skeleton: String = input.chars()
+        .map(|c| if matches!(c, '\u{2212}' | '\u{2013}') { '-' } else { c })
         .filter(|c| c.is_ascii_digit() || ".,-()".contains(*c))
         .collect();
 
@@ -28,8 +231,9 @@ pub fn scrub_currency(input: &str) -> Cow<'_, str> {
         return Cow::Owned("0".to_string());
     }
 
+    // Accounting negatives: `(1,234.50)` and a trailing minus, `1234.50-`
     let is_negative_paren = skeleton.starts_with('(') && skeleton.ends_with(')');
-    let is_negative_sign = skeleton.starts_with('-');
+    let is_negative_sign = skeleton.starts_with('-') || skeleton.ends_with('-');
 
     let mut cleaned: String = skeleton.chars()
         .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
@@ -39,6 +243,17 @@ pub fn scrub_currency(input: &str) -> Cow<'_, str> {
         return Cow::Owned("0".to_string());
     }
 
+    if let Some(mark) = decimal {
+        cleaned = match mark {
+            DecimalMark::Dot => cleaned.replace(',', ""),
+            DecimalMark::Comma => cleaned.replace('.', "").replace(',', "."),
+        };
+        if is_negative_paren || is_negative_sign {
+            cleaned.insert(0, '-');
+        }
+        return Cow::Owned(cleaned);
+    }
+
     let last_comma = cleaned.rfind(',');
     let last_dot = cleaned.rfind('.');
     let dot_count = cleaned.chars().filter(|c| *c == '.').count();
@@ -88,26 +303,158 @@ pub fn scrub_numeric_only(input: &str) -> Cow<'_, str> {
 }
 
 pub fn trim_whitespace(input: &str) -> Cow<'_, str> {
-    Cow::Borrowed(input.trim())
+    let trimmed = input.trim();
+    // Owned signals a change, so the caller writes the trimmed value back
+    if trimmed.len() == input.len() {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(trimmed.to_string())
+    }
+}
+
+/// Strip whitespace and any of `chars` from both ends, in any mix: `  '"ACME Corp"';`
+/// with `chars` `'";` gives `ACME Corp`
+pub fn trim_chars<'a>(input: &'a str, chars: &[char]) -> Cow<'a, str> {
+    let trimmed = input.trim_matches(|c: char| c.is_whitespace() || chars.contains(&c));
+    if trimmed.len() == input.len() {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(trimmed.to_string())
+    }
+}
+
+/// Strip tags, then decode entities, so `&lt;b&gt;` stays text. With `collapse`,
+/// runs of whitespace (including decoded `&nbsp;`) become one space and the ends are trimmed.
+pub fn remove_html_tags(input: &str, collapse: bool) -> Cow<'_, str> {
+    let stripped = if input.contains('<') {
+        let mut result = String::with_capacity(input.len());
+        let mut inside_tag = false;
+
+        for ch in input.chars() {
+            match ch {
+                '<' => inside_tag = true,
+                '>' => inside_tag = false,
+                _ if !inside_tag => result.push(ch),
+                _ => {}
+            }
+        }
+        Cow::Owned(result)
+    } else {
+        Cow::Borrowed(input)
+    };
+
+    let decoded = match decode_entities(&stripped) {
+        Cow::Owned(decoded) => Cow::Owned(decoded),
+        Cow::Borrowed(_) => stripped,
+    };
+    if !collapse {
+        return decoded;
+    }
+    match collapse_whitespace(&decoded) {
+        Cow::Owned(collapsed) => Cow::Owned(collapsed),
+        Cow::Borrowed(_) => decoded,
+    }
 }
 
-pub fn remove_html_tags(input: &str) -> Cow<'_, str> {
-    if !input.contains('<') {
+/// Character of a named entity; the HTML 4 set, which covers what scraped text carries
+fn named_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" | "AMP" => '&', "lt" | "LT" => '<', "gt" | "GT" => '>', "quot" | "QUOT" => '"', "apos" => '\'',
+        "nbsp" => '\u{A0}', "iexcl" => '¡', "cent" => '¢', "pound" => '£', "curren" => '¤', "yen" => '¥',
+        "brvbar" => '¦', "sect" => '§', "uml" => '¨', "copy" | "COPY" => '©', "ordf" => 'ª', "laquo" => '«',
+        "not" => '¬', "shy" => '\u{AD}', "reg" | "REG" => '®', "macr" => '¯', "deg" => '°', "plusmn" => '±',
+        "sup2" => '²', "sup3" => '³', "acute" => '´', "micro" => 'µ', "para" => '¶', "middot" => '·',
+        "cedil" => '¸', "sup1" => '¹', "ordm" => 'º', "raquo" => '»', "frac14" => '¼', "frac12" => '½',
+        "frac34" => '¾', "iquest" => '¿', "times" => '×', "divide" => '÷',
+        "Agrave" => 'À', "Aacute" => 'Á', "Acirc" => 'Â', "Atilde" => 'Ã', "Auml" => 'Ä', "Aring" => 'Å',
+        "AElig" => 'Æ', "Ccedil" => 'Ç', "Egrave" => 'È', "Eacute" => 'É', "Ecirc" => 'Ê', "Euml" => 'Ë',
+        "Igrave" => 'Ì', "Iacute" => 'Í', "Icirc" => 'Î', "Iuml" => 'Ï', "ETH" => 'Ð', "Ntilde" => 'Ñ',
+        "Ograve" => 'Ò', "Oacute" => 'Ó', "Ocirc" => 'Ô', "Otilde" => 'Õ', "Ouml" => 'Ö', "Oslash" => 'Ø',
+        "Ugrave" => 'Ù', "Uacute" => 'Ú', "Ucirc" => 'Û', "Uuml" => 'Ü', "Yacute" => 'Ý', "THORN" => 'Þ',
+        "szlig" => 'ß', "agrave" => 'à', "aacute" => 'á', "acirc" => 'â', "atilde" => 'ã', "auml" => 'ä',
+        "aring" => 'å', "aelig" => 'æ', "ccedil" => 'ç', "egrave" => 'è', "eacute" => 'é', "ecirc" => 'ê',
+        "euml" => 'ë', "igrave" => 'ì', "iacute" => 'í', "icirc" => 'î', "iuml" => 'ï', "eth" => 'ð',
+        "ntilde" => 'ñ', "ograve" => 'ò', "oacute" => 'ó', "ocirc" => 'ô', "otilde" => 'õ', "ouml" => 'ö',
+        "oslash" => 'ø', "ugrave" => 'ù', "uacute" => 'ú', "ucirc" => 'û', "uuml" => 'ü', "yacute" => 'ý',
+        "thorn" => 'þ', "yuml" => 'ÿ', "OElig" => 'Œ', "oelig" => 'œ', "Scaron" => 'Š', "scaron" => 'š',
+        "Yuml" => 'Ÿ', "fnof" => 'ƒ', "circ" => 'ˆ', "tilde" => '˜',
+        "ensp" => '\u{2002}', "emsp" => '\u{2003}', "thinsp" => '\u{2009}', "zwnj" => '\u{200C}', "zwj" => '\u{200D}',
+        "lrm" => '\u{200E}', "rlm" => '\u{200F}', "ndash" => '–', "mdash" => '—', "lsquo" => '‘', "rsquo" => '’',
+        "sbquo" => '‚', "ldquo" => '“', "rdquo" => '”', "bdquo" => '„', "dagger" => '†', "Dagger" => '‡',
+        "bull" => '•', "hellip" => '…', "permil" => '‰', "prime" => '′', "Prime" => '″', "lsaquo" => '‹',
+        "rsaquo" => '›', "oline" => '‾', "frasl" => '⁄', "euro" => '€', "trade" => '™', "larr" => '←',
+        "uarr" => '↑', "rarr" => '→', "darr" => '↓', "harr" => '↔', "minus" => '−', "le" => '≤', "ge" => '≥',
+        "ne" => '≠', "asymp" => '≈', "infin" => '∞', "check" => '✓',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Character of a numeric reference; 0x80-0x9F are read as windows-1252, like browsers do
+fn numeric_entity(digits: &str) -> Option<char> {
+    let code = match digits.strip_prefix(['x', 'X']) {
+        Some(hex) if !hex.is_empty() && hex.len() <= 6 => u32::from_str_radix(hex, 16).ok()?,
+        None if !digits.is_empty() && digits.len() <= 7 => digits.parse().ok()?,
+        _ => return None,
+    };
+    if (0x80..=0x9F).contains(&code) {
+        return encoding_rs::WINDOWS_1252.decode(&[code as u8]).0.chars().next();
+    }
+    Some(char::from_u32(code).filter(|&c| c != '\0').unwrap_or('\u{FFFD}'))
+}
+
+/// Decode `&name;`, `&#39;` and `&#x27;` references; unknown or unterminated ones stay as they are
+pub fn decode_entities(input: &str) -> Cow<'_, str> {
+    if !input.contains('&') {
         return Cow::Borrowed(input);
     }
-    
+
     let mut result = String::with_capacity(input.len());
-    let mut inside_tag = false;
-    
-    for ch in input.chars() {
-        match ch {
-            '<' => inside_tag = true,
-            '>' => inside_tag = false,
-            _ if !inside_tag => result.push(ch),
-            _ => {}
+    let mut rest = input;
+    let mut changed = false;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // Longest names are a few characters; a far-away ';' belongs to something else
+        let decoded = rest[1..].find(';')
+            .filter(|&end| end > 0 && end <= 10)
+            .and_then(|end| {
+                let body = &rest[1..end + 1];
+                let c = match body.strip_prefix('#') {
+                    Some(digits) => numeric_entity(digits),
+                    None => named_entity(body),
+                };
+                c.map(|c| (c, end + 2))
+            });
+        match decoded {
+            Some((c, len)) => {
+                result.push(c);
+                rest = &rest[len..];
+                changed = true;
+            },
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            },
         }
     }
-    Cow::Owned(result)
+    result.push_str(rest);
+
+    if changed { Cow::Owned(result) } else { Cow::Borrowed(input) }
+}
+
+/// Runs of whitespace (including no-break spaces) become one space; the ends are trimmed
+pub fn collapse_whitespace(input: &str) -> Cow<'_, str> {
+    let mut previous_space = true;
+    let untouched = input.chars().all(|c| {
+        let ok = if c.is_whitespace() { c == ' ' && !previous_space } else { true };
+        previous_space = c.is_whitespace();
+        ok
+    }) && !input.ends_with(char::is_whitespace);
+    if untouched {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(input.split_whitespace().collect::<Vec<_>>().join(" "))
 }
 
 pub fn to_lowercase(input: &str) -> Cow<'_, str> {
@@ -124,4 +471,130 @@ pub fn to_uppercase(input: &str) -> Cow<'_, str> {
     } else {
         Cow::Owned(input.to_uppercase())
     }
-}
\ No newline at end of file
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(c as u32,
+        0x200B..=0x200F     // Zero-width space/joiners, directional marks
+        | 0x202A..=0x202E   // Directional embedding/override
+        | 0x2060..=0x2064   // Word joiner, invisible operators
+        | 0xFEFF            // BOM / zero-width no-break space
+        | 0xFE00..=0xFE0F   // Variation selectors
+        | 0x00AD            // Soft hyphen
+        | 0xE0000..=0xE007F // Tag characters
+    ) || (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // Emoticons, pictographs, transport, flags, skin tones
+        | 0x2600..=0x27BF   // Misc symbols & dingbats
+        | 0x2B00..=0x2BFF   // Arrows & stars used as emoji
+        | 0x20E3            // Combining enclosing keycap
+    )
+}
+
+/// Strip emoji, zero-width characters, BOM remnants and control characters.
+/// Tabs and line breaks are replaced with a single space so adjacent words don't merge.
+pub fn remove_invisible(input: &str) -> Cow<'_, str> {
+    if !input.chars().any(|c| is_invisible(c) || is_emoji(c) || matches!(c, '\t' | '\n' | '\r')) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut result = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '\t' | '\n' | '\r' => {
+                if !result.ends_with(' ') { result.push(' '); }
+            },
+            c if is_invisible(c) || is_emoji(c) => {},
+            c => result.push(c),
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// ASCII stand-in of typographic punctuation and space characters
+fn plain_punctuation_of(c: char) -> Option<&'static str> {
+    let plain = match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' | '\u{2039}' | '\u{203A}' => "'",
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}' | '\u{00BB}' => "\"",
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => "-",
+        '\u{2026}' => "...",
+        '\u{00A0}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => " ",
+        _ => return None,
+    };
+    Some(plain)
+}
+
+/// Curly quotes, dashes, the ellipsis and no-break or fixed-width spaces become
+/// their ASCII equivalents (every dash a plain hyphen), so pasted text matches typed text
+pub fn plain_punctuation(input: &str) -> Cow<'_, str> {
+    if input.is_ascii() || !input.chars().any(|c| plain_punctuation_of(c).is_some()) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut result = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match plain_punctuation_of(ch) {
+            Some(plain) => result.push_str(plain),
+            None => result.push(ch),
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// First code point of each run of ten Unicode decimal digits (0-9) outside ASCII
+const DIGIT_ZEROS: &[u32] = &[
+    0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6, 0x0D66,
+    0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6,
+];
+
+/// ASCII form of a Unicode digit, or of the full-width and Arabic signs and separators around one
+fn ascii_digit_of(c: char) -> Option<char> {
+    let code = c as u32;
+    if let Some(zero) = DIGIT_ZEROS.iter().find(|&&zero| (zero..zero + 10).contains(&code)) {
+        return char::from_digit(code - zero, 10);
+    }
+    match c {
+        '\u{FF0B}' => Some('+'),
+        '\u{FF0C}' | '\u{066C}' => Some(','),
+        '\u{FF0D}' => Some('-'),
+        '\u{FF0E}' | '\u{066B}' => Some('.'),
+        _ => None,
+    }
+}
+
+/// Digits of other scripts (full-width `１２３`, Arabic-Indic `١٢٣`, Devanagari, Thai, ...)
+/// become ASCII digits, so the values cast as numbers; so do the full-width `+ - . ,`
+/// and the Arabic decimal and thousands separators
+pub fn to_ascii_digits(input: &str) -> Cow<'_, str> {
+    if input.is_ascii() || !input.chars().any(|c| ascii_digit_of(c).is_some()) {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(input.chars().map(|c| ascii_digit_of(c).unwrap_or(c)).collect())
+}
+
+/// Best-effort ASCII spelling of any text: accents are dropped, Cyrillic and Greek
+/// are romanized letter by letter and CJK becomes syllables (`北京` -> `Bei Jing`).
+/// Characters the table has no spelling for are removed.
+pub fn transliterate(input: &str) -> Cow<'_, str> {
+    if input.is_ascii() {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(deunicode::deunicode_with_tofu(input, ""))
+}
+
+/// Keep only printable ASCII (0x20-0x7E).
+/// With `transliterate`, the text is spelled in ASCII by [`transliterate`] first instead
+/// of losing its other characters (`José` -> `Jose`, `Москва` -> `Moskva`).
+pub fn to_ascii_only(input: &str, transliterate: bool) -> Cow<'_, str> {
+    if input.bytes().all(|b| (0x20..=0x7E).contains(&b)) {
+        return Cow::Borrowed(input);
+    }
+
+    let spelled = if transliterate { self::transliterate(input) } else { Cow::Borrowed(input) };
+    Cow::Owned(spelled.chars().filter(|c| (' '..='~').contains(c)).collect())
+}
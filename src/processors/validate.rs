@@ -0,0 +1,262 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// One parameter of a step action. `default` is the JSON text of the value the
+/// engine uses when the parameter is left out.
+pub struct Param {
+    pub name: &'static str,
+    pub kind: &'static str,
+    pub required: bool,
+    pub default: Option<&'static str>,
+}
+
+const fn req(name: &'static str, kind: &'static str) -> Param {
+    Param { name, kind, required: true, default: None }
+}
+
+const fn opt(name: &'static str, kind: &'static str, default: Option<&'static str>) -> Param {
+    Param { name, kind, required: false, default }
+}
+
+const COLUMN: &str = "column";
+const COLUMNS: &str = "column | list[column]";
+const PATTERN: &str = "string | list[string]";
+
+/// Parameters accepted by each step action
+const STEP_KEYS: &[(&str, &[Param])] = &[
+    ("keep", &[req("col", COLUMN), req("match", PATTERN), opt("mode", "string", Some("\"exact\"")), opt("threshold", "number", Some("0.85")), opt("metric", "string", Some("\"jaro_winkler\""))]),
+    ("discard", &[req("col", COLUMN), req("match", PATTERN), opt("mode", "string", Some("\"exact\"")), opt("threshold", "number", Some("0.85")), opt("metric", "string", Some("\"jaro_winkler\""))]),
+    ("prune", &[opt("col", COLUMNS, Some("\"*\""))]),
+    ("scrub", &[req("col", COLUMN), opt("mode", "string", Some("\"trim\"")), opt("transliterate", "boolean", Some("false")), opt("collapse_whitespace", "boolean", Some("false")), opt("decimal", "string", None), opt("chars", "string", None)]),
+    ("cast", &[
+        req("col", COLUMN), opt("type", "string", Some("\"str\"")), opt("clean", "boolean", Some("false")),
+        opt("on_error", "string", Some("\"quarantine\"")), opt("format", PATTERN, None), opt("precision", "integer", Some("18")),
+        opt("scale", "integer", Some("2")), opt("validate_only", "boolean", Some("false")),
+        opt("true_values", "list[string]", None), opt("false_values", "list[string]", None), opt("percent", "string", None),
+    ]),
+    ("fill", &[req("col", COLUMN), opt("value", "any", None), opt("method", "string", Some("\"fixed\""))]),
+    ("dedupe", &[opt("col", COLUMNS, None), opt("store", "string", None)]),
+    ("align", &[
+        req("col", COLUMN), req("ref", PATTERN), opt("ref_col", "string", None), opt("threshold", "number", Some("0.85")),
+        opt("metric", "string", Some("\"jaro_winkler\"")), opt("audit", "boolean", Some("false")), opt("phonetic", "string", None),
+    ]),
+    ("map", &[req("col", COLUMN), req("mapping", "object"), opt("default", "string", None)]),
+    ("hash", &[req("col", COLUMNS), opt("salt", "string", Some("\"phaeton_salt\""))]),
+    ("mask", &[
+        req("col", COLUMNS), opt("salt", "string", Some("\"phaeton_salt\"")), opt("keep_first", "integer", Some("0")),
+        opt("keep_last", "integer", Some("0")), opt("luhn", "boolean", Some("false")),
+    ]),
+    ("tokenize", &[req("col", COLUMNS), req("vault", "string"), opt("key_env", "string", Some("\"PHAETON_VAULT_KEY\""))]),
+    ("detokenize", &[req("col", COLUMNS), req("vault", "string"), opt("key_env", "string", Some("\"PHAETON_VAULT_KEY\""))]),
+    ("add_column", &[req("name", "string"), opt("value", "any", None), opt("kind", "string", Some("\"constant\""))]),
+    ("convert_currency", &[
+        req("col", COLUMN), req("currency_col", COLUMN), req("to", "string"), req("rates", "object | string"),
+        opt("decimals", "integer", Some("2")), opt("clean", "boolean", Some("false")),
+    ]),
+    ("json_extract", &[req("col", COLUMN), opt("path", "string", Some("\"$\"")), req("into", "string")]),
+    ("format", &[req("template", "string"), opt("col", COLUMN, None), opt("into", "string", None)]),
+    ("country", &[req("col", COLUMN), opt("format", "string", Some("\"alpha2\"")), opt("threshold", "number", Some("0.9"))]),
+    ("fingerprint", &[opt("col", COLUMNS, Some("\"*\"")), opt("into", "string", Some("\"_fingerprint\""))]),
+    ("expect", &[
+        req("rule", "string"), opt("col", COLUMN, None), opt("mostly", "number", Some("1.0")), opt("values", "list[string]", None),
+        opt("pattern", "string", None), opt("min", "integer", None), opt("max", "integer", None), opt("severity", "string", Some("\"warn\"")),
+    ]),
+    ("rename", &[req("mapping", "object")]),
+    ("headers", &[opt("style", "string", Some("\"snake\""))]),
+    ("dump", &[req("path", "string"), opt("format", "string", Some("\"csv\""))]),
+];
+
+/// Actions the Python builder records but the engine does not execute yet
+const UNSUPPORTED_ACTIONS: &[&str] = &["reformat", "decode", "split", "combine"];
+
+/// Accepted values of enumerated parameters, as (action, key, values)
+const STEP_CHOICES: &[(&str, &str, &[&str])] = &[
+    ("keep", "mode", &["exact", "contains", "startswith", "endswith", "regex", "in", "fuzzy"]),
+    ("discard", "mode", &["exact", "contains", "startswith", "endswith", "regex", "in", "fuzzy"]),
+    ("scrub", "mode", &["email", "html", "trim", "lower", "upper", "currency", "numeric_only", "clean_invisible", "ascii_only", "plain_punctuation", "percent", "ascii_digits", "transliterate"]),
+    ("cast", "type", &["int", "float", "str", "bool", "date", "datetime", "decimal"]),
+    ("fill", "method", &["fixed", "ffill"]),
+    ("headers", "style", &["snake", "camel", "pascal", "kebab", "constant"]),
+    ("keep", "metric", SIMILARITY_METRICS),
+    ("discard", "metric", SIMILARITY_METRICS),
+    ("cast", "on_error", &["quarantine", "null", "ignore"]),
+    ("cast", "percent", &["fraction", "number"]),
+    ("scrub", "decimal", &[".", ","]),
+    ("align", "metric", SIMILARITY_METRICS),
+    ("align", "phonetic", &["soundex", "metaphone"]),
+    ("add_column", "kind", &["constant", "filename", "row_number", "timestamp"]),
+    ("country", "format", &["alpha2", "alpha3"]),
+    ("dump", "format", &["csv", "parquet", "arrow"]),
+    ("expect", "rule", &["not_null", "unique", "in_set", "match", "row_count"]),
+    ("expect", "severity", &["warn", "error"]),
+];
+
+const SIMILARITY_METRICS: &[&str] = &["jaro_winkler", "jaro", "levenshtein", "token_sort", "ngram"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// One finding of a dry-run validation. `step` is the index in the step list,
+/// or None for problems with the source itself.
+#[derive(Serialize, Debug)]
+pub struct Diagnostic {
+    pub step: Option<usize>,
+    pub action: String,
+    pub level: Level,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(step: Option<usize>, action: &str, message: String) -> Self {
+        Self { step, action: action.to_string(), level: Level::Error, message }
+    }
+
+    pub fn warning(step: Option<usize>, action: &str, message: String) -> Self {
+        Self { step, action: action.to_string(), level: Level::Warning, message }
+    }
+}
+
+/// Static checks of a single step: known action, known and required keys,
+/// enumerated values and numeric ranges. Column resolution is left to the step compiler.
+/// With `strict`, unknown parameters are errors instead of warnings.
+pub fn check_step(index: usize, step: &HashMap<String, Value>, strict: bool) -> Vec<Diagnostic> {
+    let mut found = Vec::new();
+    let action = step.get("action").and_then(|v| v.as_str()).unwrap_or("");
+
+    if action.is_empty() {
+        found.push(Diagnostic::error(Some(index), action, "Step has no 'action'".to_string()));
+        return found;
+    }
+    if UNSUPPORTED_ACTIONS.contains(&action) {
+        found.push(Diagnostic::warning(Some(index), action, format!("Action '{}' is not supported by the engine yet and will be ignored", action)));
+        return found;
+    }
+    let Some((_, params)) = STEP_KEYS.iter().find(|(name, _)| *name == action) else {
+        let actions: Vec<&str> = STEP_KEYS.iter().map(|(name, _)| *name).collect();
+        let hint = closest_key(action, &actions).map(|a| format!(" (did you mean '{}'?)", a)).unwrap_or_default();
+        found.push(Diagnostic::error(Some(index), action, format!("Unknown action '{}'{}", action, hint)));
+        return found;
+    };
+
+    let keys: Vec<&str> = params.iter().map(|p| p.name).collect();
+    for param in params.iter().filter(|p| p.required) {
+        if step.get(param.name).is_none_or(Value::is_null) {
+            found.push(Diagnostic::error(Some(index), action, format!("Missing required parameter '{}'", param.name)));
+        }
+    }
+
+    let mut unknown: Vec<&String> = step.keys()
+        .filter(|k| k.as_str() != "action" && !keys.contains(&k.as_str()))
+        .collect();
+    unknown.sort();
+    for key in unknown {
+        let hint = closest_key(key, &keys).map(|k| format!(" (did you mean '{}'?)", k)).unwrap_or_default();
+        if strict {
+            found.push(Diagnostic::error(Some(index), action, format!("Unknown parameter '{}'{}", key, hint)));
+        } else {
+            found.push(Diagnostic::warning(Some(index), action, format!("Unknown parameter '{}' is ignored{}", key, hint)));
+        }
+    }
+
+    for (_, key, choices) in STEP_CHOICES.iter().filter(|(name, _, _)| *name == action) {
+        if let Some(value) = step.get(*key).and_then(|v| v.as_str()) {
+            if !choices.contains(&value) {
+                found.push(Diagnostic::error(Some(index), action, format!("Invalid {} '{}'. Expected one of: {}", key, value, choices.join(", "))));
+            }
+        }
+    }
+
+    if let Some(threshold) = step.get("threshold").filter(|v| !v.is_null()) {
+        match threshold.as_f64() {
+            Some(t) if (0.0..=1.0).contains(&t) => {},
+            _ => found.push(Diagnostic::error(Some(index), action, format!("Threshold must be a number between 0.0 and 1.0, got {}", threshold))),
+        }
+    }
+
+    let empty_ref = |r: &Value| r.as_array().is_some_and(|a| a.is_empty()) || r.as_str().is_some_and(|p| p.trim().is_empty());
+    if action == "align" && step.get("ref").filter(|r| !r.is_null()).is_some_and(empty_ref) {
+        found.push(Diagnostic::error(Some(index), action, "Align step needs a non-empty 'ref' list or file path".to_string()));
+    }
+
+    if action == "expect" {
+        found.extend(check_expectation(index, step));
+    }
+
+    found
+}
+
+/// Parameters each expectation rule needs on top of `rule`
+fn check_expectation(index: usize, step: &HashMap<String, Value>) -> Vec<Diagnostic> {
+    let mut found = Vec::new();
+    let has = |key: &str| step.get(key).is_some_and(|v| !v.is_null());
+    let mut missing = |message: &str| found.push(Diagnostic::error(Some(index), "expect", message.to_string()));
+
+    match step.get("rule").and_then(|v| v.as_str()) {
+        Some("row_count") if !has("min") && !has("max") => missing("Expect row_count needs 'min', 'max' or both"),
+        Some("row_count") => {},
+        Some(rule) => {
+            if !has("col") { missing(&format!("Expect {} needs a 'col'", rule)); }
+            if rule == "in_set" && step.get("values").and_then(Value::as_array).is_none_or(Vec::is_empty) {
+                missing("Expect in_set needs a non-empty 'values' list");
+            }
+            if rule == "match" && !has("pattern") { missing("Expect match needs a 'pattern'"); }
+        },
+        None => {},
+    }
+    if let Some(mostly) = step.get("mostly").filter(|v| !v.is_null()) {
+        if !mostly.as_f64().is_some_and(|m| (0.0..=1.0).contains(&m)) {
+            found.push(Diagnostic::error(Some(index), "expect", format!("'mostly' must be a number between 0.0 and 1.0, got {}", mostly)));
+        }
+    }
+    found
+}
+
+/// Closest accepted name to a misspelled action or parameter
+fn closest_key<'a>(name: &str, accepted: &[&'a str]) -> Option<&'a str> {
+    accepted.iter()
+        .map(|k| (*k, strsim::jaro_winkler(k, name)))
+        .filter(|(_, score)| *score >= 0.8)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(k, _)| k)
+}
+
+/// Machine-readable description of one step action, for UIs and external validators
+#[derive(Serialize, Debug)]
+pub struct ActionSchema {
+    pub action: &'static str,
+    pub params: Vec<ParamSchema>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParamSchema {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub required: bool,
+    pub default: Value,
+    pub choices: Option<&'static [&'static str]>,
+}
+
+/// Every action the engine executes, with its parameters, built from the same
+/// tables `check_step` validates against
+pub fn describe_steps() -> Vec<ActionSchema> {
+    STEP_KEYS.iter()
+        .map(|(action, params)| ActionSchema {
+            action,
+            params: params.iter().map(|p| ParamSchema {
+                name: p.name,
+                kind: p.kind,
+                required: p.required,
+                default: p.default.and_then(|d| serde_json::from_str(d).ok()).unwrap_or(Value::Null),
+                choices: STEP_CHOICES.iter()
+                    .find(|(a, key, _)| a == action && *key == p.name)
+                    .map(|(_, _, values)| *values),
+            }).collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+// TOKENIZATION VAULT
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::{PhaetonError, Result};
+
+/// Environment variable holding the vault passphrase unless a step names another
+pub const DEFAULT_KEY_ENV: &str = "PHAETON_VAULT_KEY";
+pub const TOKEN_PREFIX: &str = "tok_";
+
+/// File layout: magic, KDF salt, nonce, then the encrypted JSON map token -> value
+const MAGIC: &[u8; 8] = b"PHVAULT1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+/// PBKDF2-HMAC-SHA256 rounds deriving the keys from the passphrase
+const KDF_ROUNDS: u32 = 600_000;
+const PENDING_SHARDS: usize = 64;
+
+type Salt = [u8; SALT_LEN];
+/// Vault entries, token -> original value
+type Entries = HashMap<String, String>;
+
+/// Serializes the creation and merge-and-replace of vault files within this process
+static VAULT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Derived keys by (salt, SHA-256 of the passphrase), so the slow derivation runs
+/// once per vault and process rather than once per run
+type KeyCache = HashMap<(Salt, [u8; 32]), Arc<Keys>>;
+static DERIVED: LazyLock<Mutex<KeyCache>> = LazyLock::new(Default::default);
+
+struct Keys {
+    /// Encrypts the vault file
+    cipher: [u8; 32],
+    /// Keys the HMAC that turns values into tokens
+    token: [u8; 32],
+}
+
+fn derive(passphrase: &str, salt: &Salt) -> Arc<Keys> {
+    let id = (*salt, Sha256::digest(passphrase.as_bytes()).into());
+    let mut derived = DERIVED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    derived.entry(id).or_insert_with(|| {
+        let mut okm = [0u8; 64];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KDF_ROUNDS, &mut okm);
+        let mut keys = Keys { cipher: [0; 32], token: [0; 32] };
+        keys.cipher.copy_from_slice(&okm[..32]);
+        keys.token.copy_from_slice(&okm[32..]);
+        Arc::new(keys)
+    }).clone()
+}
+
+/// The passphrase stored in the environment variable `key_env`
+pub fn passphrase(key_env: &str) -> Result<String> {
+    std::env::var(key_env).ok().filter(|k| !k.is_empty()).ok_or_else(|| PhaetonError::InvalidStep(format!(
+        "The vault key is not set: put the passphrase in the {} environment variable", key_env
+    )))
+}
+
+fn invalid_vault(path: &str, reason: &str) -> PhaetonError {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Vault '{}' {}", path, reason)).into()
+}
+
+/// Salt of a vault file, checking its header
+fn salt_of(path: &str, bytes: &[u8]) -> Result<Salt> {
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(invalid_vault(path, "is not a phaeton vault"));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&bytes[MAGIC.len()..MAGIC.len() + SALT_LEN]);
+    Ok(salt)
+}
+
+/// Entries of a vault file whose header `salt_of` accepted
+fn decrypt(path: &str, bytes: &[u8], keys: &Keys) -> Result<Entries> {
+    let nonce = XNonce::from_slice(&bytes[MAGIC.len() + SALT_LEN..HEADER_LEN]);
+    let plain = XChaCha20Poly1305::new(&keys.cipher.into())
+        .decrypt(nonce, &bytes[HEADER_LEN..])
+        .map_err(|_| invalid_vault(path, "could not be decrypted: wrong key or damaged file"))?;
+    serde_json::from_slice(&plain).map_err(|_| invalid_vault(path, "holds damaged entries"))
+}
+
+/// Salt, keys and entries of the vault at `path`
+fn read(path: &str, passphrase: &str) -> Result<(Salt, Arc<Keys>, Entries)> {
+    let bytes = std::fs::read(path)?;
+    let salt = salt_of(path, &bytes)?;
+    let keys = derive(passphrase, &salt);
+    let entries = decrypt(path, &bytes, &keys)?;
+    Ok((salt, keys, entries))
+}
+
+/// Encrypt `entries` under a fresh nonce and replace the file in one step
+fn write(path: &str, salt: &Salt, keys: &Keys, entries: &Entries) -> Result<()> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = XChaCha20Poly1305::new(&keys.cipher.into())
+        .encrypt(&nonce, serde_json::to_vec(entries)?.as_slice())
+        .map_err(|_| invalid_vault(path, "could not be encrypted"))?;
+
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = format!("{}.tmp", path);
+    let mut file = File::create(&temp)?;
+    file.write_all(MAGIC)?;
+    file.write_all(salt)?;
+    file.write_all(&nonce)?;
+    file.write_all(&sealed)?;
+    file.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Entries of an existing vault, for re-identification
+pub fn open_entries(path: &str, passphrase: &str) -> Result<Entries> {
+    if !Path::new(path).exists() {
+        return Err(PhaetonError::FileNotFound(path.to_string()));
+    }
+    read(path, passphrase).map(|(_, _, entries)| entries)
+}
+
+/// Hands out tokens for one run and remembers the values behind them until `save`
+#[derive(Clone)]
+pub struct Vault {
+    pub path: String,
+    salt: Salt,
+    keys: Arc<Keys>,
+    /// Tokens handed out by this run, sharded by token
+    pending: Arc<Vec<Mutex<Entries>>>,
+}
+
+impl Vault {
+    /// Open the vault at `path`, creating it when it does not exist. The key is
+    /// checked by decrypting the file.
+    pub fn open(path: &str, passphrase: &str) -> Result<Self> {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (salt, keys) = if Path::new(path).exists() {
+            let (salt, keys, _) = read(path, passphrase)?;
+            (salt, keys)
+        } else {
+            // Created right away, so runs sharing the vault agree on its salt and tokens
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let keys = derive(passphrase, &salt);
+            write(path, &salt, &keys, &HashMap::new())?;
+            (salt, keys)
+        };
+        Ok(Self { path: path.to_string(), salt, keys, pending: Arc::new(Vec::new()) }.fresh())
+    }
+
+    /// Copy for a new run, with no tokens pending
+    pub fn fresh(&self) -> Self {
+        let pending = (0..PENDING_SHARDS).map(|_| Mutex::new(HashMap::new())).collect();
+        Self { pending: Arc::new(pending), ..self.clone() }
+    }
+
+    /// Stable token of `value`: the same value always gets the same token in this vault
+    pub fn tokenize(&self, value: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.keys.token).expect("HMAC takes keys of any length");
+        mac.update(value.as_bytes());
+        let token = format!("{}{}", TOKEN_PREFIX, hex::encode(&mac.finalize().into_bytes()[..16]));
+
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        let mut shard = self.pending[(hasher.finish() as usize) % PENDING_SHARDS].lock().unwrap();
+        if !shard.contains_key(&token) {
+            shard.insert(token.clone(), value.to_string());
+        }
+        token
+    }
+
+    /// Add the tokens handed out by this run to the vault file
+    pub fn save(&self) -> Result<()> {
+        let mut pending: Entries = HashMap::new();
+        for shard in self.pending.iter() {
+            pending.extend(shard.lock().unwrap().drain());
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+        // Re-read under the lock, so runs sharing the vault keep each other's tokens
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bytes = std::fs::read(&self.path)?;
+        if salt_of(&self.path, &bytes)? != self.salt {
+            return Err(invalid_vault(&self.path, "was replaced during the run"));
+        }
+        let mut entries = decrypt(&self.path, &bytes, &self.keys)?;
+        entries.extend(pending);
+        write(&self.path, &self.salt, &self.keys, &entries)
+    }
+}
@@ -0,0 +1,372 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::engine::Engine;
+use crate::notify::{Notifier, Target};
+use crate::error::{PhaetonError, Result};
+use crate::processors::validate::{self, Level};
+use crate::streaming::{ErrorStrategy, HeaderMatch, RaggedPolicy, ReadMode, Route};
+
+const SPEC_VERSION: u64 = 1;
+
+/// A pipeline spec file: engine options plus the pipelines to run.
+///
+/// ```yaml
+/// version: 1
+/// config: { workers: 4, batch_size: 50000 }
+/// pipelines:
+///   - source: ${DATA_DIR}/orders.csv
+///     steps:
+///       - { action: prune, col: id }
+///     output: { path: clean/orders.csv, delimiter: ";" }
+///     quarantine: clean/orders_rejected.csv
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+    #[serde(default)]
+    pub version: Option<u64>,
+    #[serde(default)]
+    pub config: SpecConfig,
+    pub pipelines: Vec<PipelineSpec>,
+}
+
+/// Engine-wide defaults; every pipeline can override all but `workers`
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SpecConfig {
+    pub workers: Option<u64>,
+    pub batch_size: Option<BatchSize>,
+    pub batch_memory: Option<u64>,
+    pub on_error: Option<String>,
+    pub delimiter: Option<String>,
+    pub output_delimiter: Option<String>,
+    pub preserve_order: Option<bool>,
+    pub read_mode: Option<String>,
+    pub strict_steps: Option<bool>,
+    /// Write a lineage sidecar next to every output
+    pub lineage: Option<bool>,
+    /// Write a checksum manifest next to every output, verifying its row counts
+    pub manifest: Option<bool>,
+    /// State file of incremental runs, shared by the pipelines
+    pub state: Option<String>,
+    /// Webhook URL or command told when each pipeline finishes
+    pub notify: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineSpec {
+    /// Label for error messages; defaults to the position in the list
+    pub name: Option<String>,
+    pub source: String,
+    #[serde(default)]
+    pub steps: Vec<HashMap<String, Value>>,
+    pub output: Option<OutputSpec>,
+    pub quarantine: Option<String>,
+    pub on_error: Option<String>,
+    pub delimiter: Option<String>,
+    pub batch_size: Option<BatchSize>,
+    pub batch_memory: Option<u64>,
+    pub preserve_order: Option<bool>,
+    pub read_mode: Option<String>,
+    pub ragged: Option<String>,
+    pub header_match: Option<String>,
+    pub rename_duplicates: Option<bool>,
+    pub lineage: Option<Sidecar>,
+    pub manifest: Option<Sidecar>,
+    pub state: Option<String>,
+    /// Baseline the output statistics are compared with after the run
+    pub drift: Option<String>,
+    /// Named outputs taking the kept rows that match their condition
+    pub routes: Option<Vec<Route>>,
+    /// Webhook URLs or commands told when the run finishes, as one or a list
+    pub notify: Option<Value>,
+}
+
+/// `batch_size: 50000` or `batch_size: auto`
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum BatchSize {
+    Rows(u64),
+    Named(String),
+}
+
+impl BatchSize {
+    pub fn rows(&self) -> Option<u64> {
+        match self {
+            BatchSize::Rows(n) => Some(*n),
+            BatchSize::Named(_) => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            BatchSize::Rows(n) => Value::from(*n),
+            BatchSize::Named(name) => Value::from(name.clone()),
+        }
+    }
+
+    fn check(&self) -> std::result::Result<(), String> {
+        match self {
+            BatchSize::Rows(0) => Err("batch_size must be greater than 0".to_string()),
+            BatchSize::Named(name) if name != "auto" => Err(format!("batch_size must be a number or 'auto', got '{}'", name)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `lineage: true` for a sidecar next to the output, or `lineage: audit/orders.json`;
+/// `manifest` takes the same forms
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Sidecar {
+    Enabled(bool),
+    Path(String),
+}
+
+impl Sidecar {
+    fn to_value(&self) -> Value {
+        match self {
+            Sidecar::Enabled(on) => Value::from(*on),
+            Sidecar::Path(path) => Value::from(path.clone()),
+        }
+    }
+}
+
+/// `output: path.csv` or `output: { path, format, delimiter }`; `format` can only be `csv` for now
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum OutputSpec {
+    Path(String),
+    Detailed {
+        path: String,
+        #[serde(default)]
+        format: Option<String>,
+        #[serde(default)]
+        delimiter: Option<String>,
+    },
+}
+
+/// Read, interpolate and validate a spec file. `.json` files are parsed as JSON,
+/// anything else as YAML.
+pub fn load(path: &str) -> Result<Spec> {
+    let text = std::fs::read_to_string(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+    let is_json = Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    parse(&text, is_json, path)
+}
+
+/// Interpolate and validate the spec in `text`; `origin` names it in errors
+pub fn parse(text: &str, is_json: bool, origin: &str) -> Result<Spec> {
+    parse_document(text, is_json, origin, true)
+}
+
+/// Validate the spec in `text` without `${VAR}` interpolation, for specs from
+/// untrusted clients that must not read the host's environment
+pub fn parse_verbatim(text: &str, is_json: bool, origin: &str) -> Result<Spec> {
+    parse_document(text, is_json, origin, false)
+}
+
+fn parse_document(text: &str, is_json: bool, origin: &str, interpolate: bool) -> Result<Spec> {
+    let mut raw: Value = if is_json {
+        serde_json::from_str(text).map_err(|e| PhaetonError::SpecError(format!("{}: {}", origin, e)))?
+    } else {
+        serde_yaml::from_str(text).map_err(|e| PhaetonError::SpecError(format!("{}: {}", origin, e)))?
+    };
+    if interpolate {
+        interpolate_value(&mut raw)?;
+    }
+
+    let spec: Spec = serde_json::from_value(raw).map_err(|e| PhaetonError::SpecError(format!("{}: {}", origin, e)))?;
+    spec.check().map_err(|problems| PhaetonError::SpecError(format!("{}: {}", origin, problems.join("; "))))?;
+    Ok(spec)
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` in every string of the document; `$$` is a literal `$`.
+/// Interpolating parsed strings (not raw text) keeps values from breaking the YAML syntax.
+fn interpolate_value(value: &mut Value) -> Result<()> {
+    match value {
+        Value::String(s) => *s = interpolate(s)?,
+        Value::Array(items) => items.iter_mut().try_for_each(interpolate_value)?,
+        Value::Object(map) => map.values_mut().try_for_each(interpolate_value)?,
+        _ => {},
+    }
+    Ok(())
+}
+
+fn interpolate(text: &str) -> Result<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            out.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('{') {
+            let end = after.find('}')
+                .ok_or_else(|| PhaetonError::SpecError(format!("Unclosed '${{' in '{}'", text)))?;
+            let (name, default) = match after[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&after[..end], None),
+            };
+            match (std::env::var(name), default) {
+                (Ok(value), _) => out.push_str(&value),
+                (Err(_), Some(default)) => out.push_str(default),
+                (Err(_), None) => return Err(PhaetonError::SpecError(format!("Environment variable '{}' is not set", name))),
+            }
+            rest = &after[end + 1..];
+        } else {
+            out.push('$');
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+impl Spec {
+    /// Problems the type layout cannot express: versions, choices, outputs and step errors
+    fn check(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        if let Some(version) = self.version.filter(|&v| v != SPEC_VERSION) {
+            problems.push(format!("Unsupported spec version {} (expected {})", version, SPEC_VERSION));
+        }
+        if self.pipelines.is_empty() {
+            problems.push("'pipelines' is empty".to_string());
+        }
+        if let Some(strategy) = &self.config.on_error {
+            if let Err(e) = ErrorStrategy::parse(strategy) { problems.push(format!("config: {}", e)); }
+        }
+        if let Some(Err(e)) = self.config.batch_size.as_ref().map(BatchSize::check) {
+            problems.push(format!("config: {}", e));
+        }
+        if let Some(mode) = &self.config.read_mode {
+            if let Err(e) = ReadMode::parse(mode) { problems.push(format!("config: {}", e)); }
+        }
+        if let Some(Err(e)) = self.config.notify.as_ref().map(Notifier::from_setting) {
+            problems.push(format!("config: {}", e));
+        }
+
+        let strict = self.config.strict_steps.unwrap_or(true);
+        for (i, pipeline) in self.pipelines.iter().enumerate() {
+            let label = pipeline.name.clone().unwrap_or_else(|| format!("pipelines[{}]", i));
+            match &pipeline.output {
+                None => problems.push(format!("{}: needs an 'output' path", label)),
+                Some(OutputSpec::Detailed { format: Some(format), .. }) if !format.eq_ignore_ascii_case("csv") => {
+                    problems.push(format!("{}: output format '{}' is not supported; outputs are written as CSV", label, format));
+                },
+                _ => {},
+            }
+            if let Some(strategy) = &pipeline.on_error {
+                if let Err(e) = ErrorStrategy::parse(strategy) { problems.push(format!("{}: {}", label, e)); }
+            }
+            if let Some(Err(e)) = pipeline.batch_size.as_ref().map(BatchSize::check) {
+                problems.push(format!("{}: {}", label, e));
+            }
+            if let Some(mode) = &pipeline.read_mode {
+                if let Err(e) = ReadMode::parse(mode) { problems.push(format!("{}: {}", label, e)); }
+            }
+            if let Some(policy) = &pipeline.ragged {
+                if let Err(e) = RaggedPolicy::parse(policy) { problems.push(format!("{}: {}", label, e)); }
+            }
+            if let Some(mode) = &pipeline.header_match {
+                if let Err(e) = HeaderMatch::parse(mode) { problems.push(format!("{}: {}", label, e)); }
+            }
+            if let Some(Err(e)) = pipeline.notify.as_ref().map(Notifier::from_setting) {
+                problems.push(format!("{}: {}", label, e));
+            }
+            for diagnostic in pipeline.steps.iter().enumerate().flat_map(|(i, step)| validate::check_step(i, step, strict)) {
+                if diagnostic.level == Level::Error {
+                    problems.push(format!("{}: step {} ({}): {}", label, diagnostic.step.unwrap_or(0), diagnostic.action, diagnostic.message));
+                }
+            }
+        }
+
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
+    /// Shell commands the `notify` settings of the spec would run
+    pub fn notify_commands(&self) -> Vec<String> {
+        std::iter::once(&self.config.notify)
+            .chain(self.pipelines.iter().map(|p| &p.notify))
+            .flatten()
+            .filter_map(|setting| Notifier::from_setting(setting).ok())
+            .flatten()
+            .filter_map(|notifier| match notifier.target {
+                Target::Command(command) => Some(command),
+                Target::Url(_) => None,
+            })
+            .collect()
+    }
+
+    /// Engine sized by the spec's `workers` / `batch_size`, with its config as defaults
+    pub fn engine(&self) -> Engine {
+        let workers = self.config.workers.unwrap_or(0) as usize;
+        let batch_size = self.config.batch_size.as_ref().and_then(BatchSize::rows).unwrap_or(10_000) as usize;
+        Engine::new(workers, batch_size).with_defaults(self.config())
+    }
+
+    /// Engine defaults in the shape `Engine::with_defaults` expects
+    pub fn config(&self) -> HashMap<String, Value> {
+        let c = &self.config;
+        [
+            ("workers", c.workers.map(Value::from)),
+            ("batch_size", c.batch_size.as_ref().map(BatchSize::to_value)),
+            ("batch_memory", c.batch_memory.map(Value::from)),
+            ("on_error", c.on_error.clone().map(Value::from)),
+            ("delimiter", c.delimiter.clone().map(Value::from)),
+            ("output_delimiter", c.output_delimiter.clone().map(Value::from)),
+            ("preserve_order", c.preserve_order.map(Value::from)),
+            ("read_mode", c.read_mode.clone().map(Value::from)),
+            ("strict_steps", c.strict_steps.map(Value::from)),
+            ("lineage", c.lineage.map(Value::from)),
+            ("manifest", c.manifest.map(Value::from)),
+            ("state", c.state.clone().map(Value::from)),
+            ("notify", c.notify.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|v| (key.to_string(), v)))
+        .collect()
+    }
+
+    /// One engine payload per pipeline, in spec order
+    pub fn payloads(&self) -> Vec<HashMap<String, Value>> {
+        self.pipelines.iter().map(|p| {
+            let (output, output_delimiter) = match &p.output {
+                Some(OutputSpec::Path(path)) => (Some(path.clone()), None),
+                Some(OutputSpec::Detailed { path, delimiter, .. }) => (Some(path.clone()), delimiter.clone()),
+                None => (None, None),
+            };
+            [
+                ("source", Some(Value::from(p.source.clone()))),
+                ("steps", serde_json::to_value(&p.steps).ok()),
+                ("output", output.map(Value::from)),
+                ("output_delimiter", output_delimiter.map(Value::from)),
+                ("quarantine", p.quarantine.clone().map(Value::from)),
+                ("on_error", p.on_error.clone().map(Value::from)),
+                ("delimiter", p.delimiter.clone().map(Value::from)),
+                ("batch_size", p.batch_size.as_ref().map(BatchSize::to_value)),
+                ("batch_memory", p.batch_memory.map(Value::from)),
+                ("preserve_order", p.preserve_order.map(Value::from)),
+                ("read_mode", p.read_mode.clone().map(Value::from)),
+                ("ragged", p.ragged.clone().map(Value::from)),
+                ("header_match", p.header_match.clone().map(Value::from)),
+                ("rename_duplicates", p.rename_duplicates.map(Value::from)),
+                ("lineage", p.lineage.as_ref().map(Sidecar::to_value)),
+                ("manifest", p.manifest.as_ref().map(Sidecar::to_value)),
+                ("state", p.state.clone().map(Value::from)),
+                ("drift", p.drift.clone().map(Value::from)),
+                ("routes", p.routes.as_ref().and_then(|r| serde_json::to_value(r).ok())),
+                ("notify", p.notify.clone()),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| (key.to_string(), v)))
+            .collect()
+        }).collect()
+    }
+}
@@ -0,0 +1,130 @@
+// INCREMENTAL RUN STATE
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{PhaetonError, Result};
+use crate::streaming::SourcePosition;
+
+const STATE_VERSION: u64 = 1;
+
+/// Serializes the read-modify-write of state files between the runs of this process
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// What an incremental run remembers about the sources it read
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    version: u64,
+    /// Canonical source path -> progress
+    files: BTreeMap<String, FileState>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileState {
+    /// Where the next run starts reading
+    position: SourcePosition,
+    /// SHA-256 of the bytes before `position.byte`, to notice rewritten files
+    sha256: String,
+    updated_at: String,
+}
+
+/// How a source compares to what the state file remembers of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Increment {
+    /// Not in the state yet: read in full
+    New,
+    /// Grew since the last run: read from the position reached then
+    Appended(SourcePosition),
+    /// Nothing was added: the run is skipped
+    Unchanged,
+    /// The bytes read before were rewritten: read in full again
+    Changed,
+}
+
+impl Increment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Increment::New => "new",
+            Increment::Appended(_) => "appended",
+            Increment::Unchanged => "unchanged",
+            Increment::Changed => "changed",
+        }
+    }
+}
+
+fn invalid_data(message: String) -> PhaetonError {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+fn key(source: &str) -> String {
+    std::fs::canonicalize(source).map_or_else(|_| source.to_string(), |p| p.to_string_lossy().into_owned())
+}
+
+fn load(path: &str) -> Result<StateFile> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(StateFile { version: STATE_VERSION, files: BTreeMap::new() });
+        },
+        Err(e) => return Err(e.into()),
+    };
+    let state: StateFile = serde_json::from_str(&text)
+        .map_err(|e| invalid_data(format!("Unreadable state file '{}': {}", path, e)))?;
+    if state.version != STATE_VERSION {
+        return Err(invalid_data(format!("State file '{}' has version {} (expected {})", path, state.version, STATE_VERSION)));
+    }
+    Ok(state)
+}
+
+/// Replace the state file in one step, so a crash never leaves half of it behind
+fn save(path: &str, state: &StateFile) -> Result<()> {
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = format!("{}.tmp", path);
+    let mut writer = BufWriter::new(File::create(&temp)?);
+    serde_json::to_writer_pretty(&mut writer, state)?;
+    writer.write_all(b"\n")?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// SHA-256 of the first `len` bytes of `source`, or None when it has fewer
+fn prefix_digest(source: &str, len: u64) -> Result<Option<String>> {
+    let file = File::open(source).map_err(|_| PhaetonError::FileNotFound(source.to_string()))?;
+    let mut hasher = Sha256::new();
+    let copied = io::copy(&mut file.take(len), &mut hasher)?;
+    Ok((copied == len).then(|| hex::encode(hasher.finalize())))
+}
+
+/// Compare `source` with its entry in the state file at `path`
+pub fn check(path: &str, source: &str) -> Result<Increment> {
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let state = load(path)?;
+    let Some(entry) = state.files.get(&key(source)) else { return Ok(Increment::New) };
+
+    let size = std::fs::metadata(source).map_err(|_| PhaetonError::FileNotFound(source.to_string()))?.len();
+    if prefix_digest(source, entry.position.byte)?.as_deref() != Some(entry.sha256.as_str()) {
+        return Ok(Increment::Changed);
+    }
+    Ok(if size == entry.position.byte { Increment::Unchanged } else { Increment::Appended(entry.position) })
+}
+
+/// Remember that `source` was read up to `end`
+pub fn record(path: &str, source: &str, end: SourcePosition) -> Result<()> {
+    let sha256 = prefix_digest(source, end.byte)?
+        .ok_or_else(|| invalid_data(format!("'{}' shrank while it was read", source)))?;
+
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut state = load(path)?;
+    state.files.insert(key(source), FileState { position: end, sha256, updated_at: Local::now().to_rfc3339() });
+    save(path, &state)
+}
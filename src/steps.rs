@@ -0,0 +1,386 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::HashMap;
+use pythonize::{depythonize, pythonize};
+use serde_json::Value;
+
+use crate::processors::validate::{self, Level};
+
+/// One checked step definition, built by the typed constructors (`Scrub`, `Cast`,
+/// `Align`, ...). Parameters are validated when the step is built, so a step that
+/// exists is one the engine accepts.
+#[pyclass]
+#[derive(Clone)]
+pub struct Step {
+    pub step: HashMap<String, Value>,
+}
+
+pub fn value_error(message: impl Into<String>) -> PyErr {
+    PyErr::new::<PyValueError, _>(message.into())
+}
+
+/// A column reference: a name, a 0-based position, or a list of either
+fn column(obj: &PyAny) -> PyResult<Value> {
+    let value: Value = depythonize(obj)
+        .map_err(|e| value_error(format!("Invalid column: {}", e)))?;
+    let single = |v: &Value| v.is_string() || v.is_u64();
+    match &value {
+        v if single(v) => Ok(value),
+        Value::Array(items) if !items.is_empty() && items.iter().all(single) => Ok(value),
+        _ => Err(value_error(format!("Invalid column {}: expected a name, a non-negative position or a list of them", value))),
+    }
+}
+
+/// Match patterns are compared as text; a scalar becomes a one-item list for 'in'
+fn pattern(obj: &PyAny, mode: &str) -> PyResult<Value> {
+    let text = |v: Value| match v {
+        Value::String(s) => Ok(Value::String(s)),
+        Value::Number(n) => Ok(Value::String(n.to_string())),
+        Value::Bool(b) => Ok(Value::String(b.to_string())),
+        other => Err(value_error(format!("Invalid match pattern {}", other))),
+    };
+    match depythonize::<Value>(obj).map_err(|e| value_error(format!("Invalid match pattern: {}", e)))? {
+        Value::Array(items) => items.into_iter().map(text).collect::<PyResult<Vec<_>>>().map(Value::Array),
+        scalar if mode == "in" => Ok(Value::Array(vec![text(scalar)?])),
+        scalar => text(scalar),
+    }
+}
+
+fn any_value(name: &str, obj: Option<&PyAny>) -> PyResult<Option<Value>> {
+    obj.map(depythonize::<Value>).transpose()
+        .map_err(|e| value_error(format!("Invalid {}: {}", name, e)))
+}
+
+fn words(name: &str, list: Option<Vec<String>>) -> PyResult<Option<Value>> {
+    match list {
+        Some(list) if list.is_empty() => Err(value_error(format!("Parameter '{}' must be a non-empty list of strings", name))),
+        list => Ok(list.map(Value::from)),
+    }
+}
+
+fn string_map(mapping: HashMap<String, String>) -> Value {
+    Value::Object(mapping.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+}
+
+impl Step {
+    /// Check and build a step; optional parameters left as None are not recorded
+    fn new(action: &str, params: Vec<(&str, Option<Value>)>) -> PyResult<Self> {
+        let mut step: HashMap<String, Value> = params.into_iter()
+            .filter_map(|(key, value)| value.map(|v| (key.to_string(), v)))
+            .collect();
+        step.insert("action".to_string(), Value::from(action));
+
+        match validate::check_step(0, &step, true).into_iter().find(|d| d.level == Level::Error) {
+            Some(d) => Err(value_error(format!("Invalid {} step: {}", action, d.message))),
+            None => Ok(Self { step }),
+        }
+    }
+
+    pub fn action(&self) -> &str {
+        self.step.get("action").and_then(|v| v.as_str()).unwrap_or("")
+    }
+}
+
+#[pymethods]
+impl Step {
+    #[getter(action)]
+    fn py_action(&self) -> String {
+        self.action().to_string()
+    }
+
+    /// The step in the dict format `execute_pipeline` and `Pipeline.extend()` accept
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        pythonize(py, &self.step).map_err(|e| value_error(e.to_string()))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.step == other.step
+    }
+
+    fn __repr__(&self) -> String {
+        let mut params: Vec<String> = self.step.iter()
+            .filter(|(k, _)| k.as_str() != "action")
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        params.sort();
+        format!("<Step {} {}>", self.action(), params.join(" "))
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "Keep", signature = (col, r#match, mode="exact", threshold=None, metric=None))]
+pub fn keep_step(col: &PyAny, r#match: &PyAny, mode: &str, threshold: Option<f64>, metric: Option<String>) -> PyResult<Step> {
+    Step::new("keep", vec![
+        ("col", Some(column(col)?)), ("match", Some(pattern(r#match, mode)?)), ("mode", Some(Value::from(mode))),
+        ("threshold", threshold.map(Value::from)), ("metric", metric.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Discard", signature = (col, r#match, mode="exact", threshold=None, metric=None))]
+pub fn discard_step(col: &PyAny, r#match: &PyAny, mode: &str, threshold: Option<f64>, metric: Option<String>) -> PyResult<Step> {
+    Step::new("discard", vec![
+        ("col", Some(column(col)?)), ("match", Some(pattern(r#match, mode)?)), ("mode", Some(Value::from(mode))),
+        ("threshold", threshold.map(Value::from)), ("metric", metric.map(Value::from)),
+    ])
+}
+
+/// Without columns, a row is dropped when any of its cells is empty
+#[pyfunction]
+#[pyo3(name = "Prune", signature = (col=None))]
+pub fn prune_step(col: Option<&PyAny>) -> PyResult<Step> {
+    let col = col.map(column).transpose()?.unwrap_or_else(|| Value::from("*"));
+    Step::new("prune", vec![("col", Some(col))])
+}
+
+#[pyfunction]
+#[pyo3(name = "Scrub", signature = (col, mode, transliterate=false, collapse_whitespace=false, decimal=None, chars=None))]
+pub fn scrub_step(col: &PyAny, mode: &str, transliterate: bool, collapse_whitespace: bool, decimal: Option<&str>, chars: Option<&str>) -> PyResult<Step> {
+    if decimal.is_some() && !matches!(mode, "currency" | "percent") {
+        return Err(value_error("Parameter 'decimal' is only valid for the 'currency' and 'percent' modes"));
+    }
+    if chars.is_some() && mode != "trim" {
+        return Err(value_error("Parameter 'chars' is only valid for the 'trim' mode"));
+    }
+    if chars == Some("") {
+        return Err(value_error("Parameter 'chars' must be a non-empty string"));
+    }
+    Step::new("scrub", vec![
+        ("col", Some(column(col)?)), ("mode", Some(Value::from(mode))), ("transliterate", Some(Value::from(transliterate))),
+        ("collapse_whitespace", Some(Value::from(collapse_whitespace))), ("decimal", decimal.map(Value::from)),
+        ("chars", chars.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Cast", signature = (col, dtype, clean=false, on_error="quarantine", format=None, precision=None, scale=None, validate_only=false, true_values=None, false_values=None, percent=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn cast_step(
+    col: &PyAny,
+    dtype: &str,
+    clean: bool,
+    on_error: &str,
+    format: Option<&PyAny>,
+    precision: Option<u32>,
+    scale: Option<u32>,
+    validate_only: bool,
+    true_values: Option<Vec<String>>,
+    false_values: Option<Vec<String>>,
+    percent: Option<&str>
+) -> PyResult<Step> {
+    if format.is_some() && !matches!(dtype, "date" | "datetime") {
+        return Err(value_error("Parameter 'format' is only valid for 'date' and 'datetime' casts"));
+    }
+    if (precision.is_some() || scale.is_some()) && dtype != "decimal" {
+        return Err(value_error("Parameters 'precision' and 'scale' are only valid for 'decimal' casts"));
+    }
+    if (true_values.is_some() || false_values.is_some()) && dtype != "bool" {
+        return Err(value_error("Parameters 'true_values' and 'false_values' are only valid for 'bool' casts"));
+    }
+    if percent.is_some() && !matches!(dtype, "int" | "float" | "decimal") {
+        return Err(value_error("Parameter 'percent' is only valid for 'int', 'float' and 'decimal' casts"));
+    }
+    Step::new("cast", vec![
+        ("col", Some(column(col)?)), ("type", Some(Value::from(dtype))), ("clean", Some(Value::from(clean))),
+        ("on_error", Some(Value::from(on_error))), ("validate_only", Some(Value::from(validate_only))),
+        ("format", any_value("format", format)?), ("precision", precision.map(Value::from)), ("scale", scale.map(Value::from)),
+        ("true_values", words("true_values", true_values)?), ("false_values", words("false_values", false_values)?),
+        ("percent", percent.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Fill", signature = (col, value=None, method="fixed"))]
+pub fn fill_step(col: &PyAny, value: Option<&PyAny>, method: &str) -> PyResult<Step> {
+    Step::new("fill", vec![
+        ("col", Some(column(col)?)), ("value", Some(any_value("fill value", value)?.unwrap_or(Value::Null))),
+        ("method", Some(Value::from(method))),
+    ])
+}
+
+/// Without columns, whole rows are compared. A `store` file keeps the keys, so
+/// rows seen by earlier runs count as duplicates too.
+#[pyfunction]
+#[pyo3(name = "Dedupe", signature = (col=None, store=None))]
+pub fn dedupe_step(col: Option<&PyAny>, store: Option<String>) -> PyResult<Step> {
+    let col = col.map(column).transpose()?;
+    Step::new("dedupe", vec![("col", Some(col.unwrap_or(Value::Null))), ("store", store.map(Value::from))])
+}
+
+#[pyfunction]
+#[pyo3(name = "Align", signature = (col, r#ref, threshold=0.85, metric="jaro_winkler", audit=false, ref_col=None, phonetic=None))]
+pub fn align_step(
+    col: &PyAny,
+    r#ref: &PyAny,
+    threshold: f64,
+    metric: &str,
+    audit: bool,
+    ref_col: Option<String>,
+    phonetic: Option<String>
+) -> PyResult<Step> {
+    let reference = any_value("reference", Some(r#ref))?.unwrap_or(Value::Null);
+    if ref_col.is_some() && !reference.is_string() {
+        return Err(value_error("Parameter 'ref_col' requires 'ref' to be a file path"));
+    }
+    Step::new("align", vec![
+        ("col", Some(column(col)?)), ("ref", Some(reference)), ("threshold", Some(Value::from(threshold))),
+        ("metric", Some(Value::from(metric))), ("audit", Some(Value::from(audit))),
+        ("ref_col", ref_col.map(Value::from)), ("phonetic", phonetic.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Map", signature = (col, mapping, default=None))]
+pub fn map_step(col: &PyAny, mapping: HashMap<String, String>, default: Option<String>) -> PyResult<Step> {
+    Step::new("map", vec![
+        ("col", Some(column(col)?)), ("mapping", Some(string_map(mapping))), ("default", default.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Hash", signature = (col, salt=None))]
+pub fn hash_step(col: &PyAny, salt: Option<String>) -> PyResult<Step> {
+    Step::new("hash", vec![("col", Some(column(col)?)), ("salt", salt.map(Value::from))])
+}
+
+#[pyfunction]
+#[pyo3(name = "Mask", signature = (col, salt=None, keep_first=0, keep_last=0, luhn=false))]
+pub fn mask_step(col: &PyAny, salt: Option<String>, keep_first: usize, keep_last: usize, luhn: bool) -> PyResult<Step> {
+    Step::new("mask", vec![
+        ("col", Some(column(col)?)), ("salt", salt.map(Value::from)), ("keep_first", Some(Value::from(keep_first))),
+        ("keep_last", Some(Value::from(keep_last))), ("luhn", Some(Value::from(luhn))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Tokenize", signature = (col, vault, key_env="PHAETON_VAULT_KEY"))]
+pub fn tokenize_step(col: &PyAny, vault: String, key_env: &str) -> PyResult<Step> {
+    Step::new("tokenize", vec![
+        ("col", Some(column(col)?)), ("vault", Some(Value::from(vault))), ("key_env", Some(Value::from(key_env))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Detokenize", signature = (col, vault, key_env="PHAETON_VAULT_KEY"))]
+pub fn detokenize_step(col: &PyAny, vault: String, key_env: &str) -> PyResult<Step> {
+    Step::new("detokenize", vec![
+        ("col", Some(column(col)?)), ("vault", Some(Value::from(vault))), ("key_env", Some(Value::from(key_env))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "AddColumn", signature = (name, value=None, kind="constant"))]
+pub fn add_column_step(name: String, value: Option<&PyAny>, kind: &str) -> PyResult<Step> {
+    Step::new("add_column", vec![
+        ("name", Some(Value::from(name))), ("value", Some(any_value("column value", value)?.unwrap_or(Value::Null))),
+        ("kind", Some(Value::from(kind))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "ConvertCurrency", signature = (col, currency_col, to, rates, decimals=2, clean=false))]
+pub fn convert_currency_step(col: &PyAny, currency_col: &PyAny, to: String, rates: &PyAny, decimals: u32, clean: bool) -> PyResult<Step> {
+    Step::new("convert_currency", vec![
+        ("col", Some(column(col)?)), ("currency_col", Some(column(currency_col)?)), ("to", Some(Value::from(to))),
+        ("rates", any_value("rates", Some(rates))?), ("decimals", Some(Value::from(decimals))), ("clean", Some(Value::from(clean))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "JsonExtract")]
+pub fn json_extract_step(col: &PyAny, path: String, into: String) -> PyResult<Step> {
+    Step::new("json_extract", vec![
+        ("col", Some(column(col)?)), ("path", Some(Value::from(path))), ("into", Some(Value::from(into))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Format", signature = (template, col=None, into=None))]
+pub fn format_step(template: String, col: Option<&PyAny>, into: Option<String>) -> PyResult<Step> {
+    if col.is_some() == into.is_some() {
+        return Err(value_error("Format needs exactly one of 'col' and 'into'"));
+    }
+    Step::new("format", vec![
+        ("template", Some(Value::from(template))), ("col", col.map(column).transpose()?), ("into", into.map(Value::from)),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Country", signature = (col, format="alpha2", threshold=0.9))]
+pub fn country_step(col: &PyAny, format: &str, threshold: f64) -> PyResult<Step> {
+    Step::new("country", vec![
+        ("col", Some(column(col)?)), ("format", Some(Value::from(format))), ("threshold", Some(Value::from(threshold))),
+    ])
+}
+
+/// Without columns, every column present at this point is fingerprinted
+#[pyfunction]
+#[pyo3(name = "Fingerprint", signature = (col=None, into="_fingerprint"))]
+pub fn fingerprint_step(col: Option<&PyAny>, into: &str) -> PyResult<Step> {
+    let col = col.map(column).transpose()?.unwrap_or_else(|| Value::from("*"));
+    Step::new("fingerprint", vec![("col", Some(col)), ("into", Some(Value::from(into)))])
+}
+
+/// Data-quality assertion over the rows reaching the step; rows are not changed.
+/// `rule` is one of not_null, unique, in_set (`values`), match (`pattern`) or
+/// row_count (`min` / `max`, without a column).
+#[pyfunction]
+#[pyo3(name = "Expect", signature = (rule, col=None, mostly=1.0, values=None, pattern=None, min=None, max=None, severity="warn"))]
+#[allow(clippy::too_many_arguments)]
+pub fn expect_step(
+    rule: &str,
+    col: Option<&PyAny>,
+    mostly: f64,
+    values: Option<&PyAny>,
+    pattern: Option<String>,
+    min: Option<u64>,
+    max: Option<u64>,
+    severity: &str
+) -> PyResult<Step> {
+    let values = values.map(|v| self::pattern(v, "in")).transpose()?;
+    Step::new("expect", vec![
+        ("rule", Some(Value::from(rule))), ("col", col.map(column).transpose()?),
+        ("mostly", (rule != "row_count").then(|| Value::from(mostly))), ("values", values),
+        ("pattern", pattern.map(Value::from)), ("min", min.map(Value::from)), ("max", max.map(Value::from)),
+        ("severity", Some(Value::from(severity))),
+    ])
+}
+
+#[pyfunction]
+#[pyo3(name = "Rename")]
+pub fn rename_step(mapping: HashMap<String, String>) -> PyResult<Step> {
+    Step::new("rename", vec![("mapping", Some(string_map(mapping)))])
+}
+
+#[pyfunction]
+#[pyo3(name = "Headers", signature = (style="snake"))]
+pub fn headers_step(style: &str) -> PyResult<Step> {
+    Step::new("headers", vec![("style", Some(Value::from(style)))])
+}
+
+pub fn register(m: &PyModule) -> PyResult<()> {
+    m.add_class::<Step>()?;
+    m.add_function(wrap_pyfunction!(keep_step, m)?)?;
+    m.add_function(wrap_pyfunction!(discard_step, m)?)?;
+    m.add_function(wrap_pyfunction!(prune_step, m)?)?;
+    m.add_function(wrap_pyfunction!(scrub_step, m)?)?;
+    m.add_function(wrap_pyfunction!(cast_step, m)?)?;
+    m.add_function(wrap_pyfunction!(fill_step, m)?)?;
+    m.add_function(wrap_pyfunction!(dedupe_step, m)?)?;
+    m.add_function(wrap_pyfunction!(align_step, m)?)?;
+    m.add_function(wrap_pyfunction!(map_step, m)?)?;
+    m.add_function(wrap_pyfunction!(hash_step, m)?)?;
+    m.add_function(wrap_pyfunction!(mask_step, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize_step, m)?)?;
+    m.add_function(wrap_pyfunction!(detokenize_step, m)?)?;
+    m.add_function(wrap_pyfunction!(add_column_step, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_currency_step, m)?)?;
+    m.add_function(wrap_pyfunction!(json_extract_step, m)?)?;
+    m.add_function(wrap_pyfunction!(format_step, m)?)?;
+    m.add_function(wrap_pyfunction!(country_step, m)?)?;
+    m.add_function(wrap_pyfunction!(fingerprint_step, m)?)?;
+    m.add_function(wrap_pyfunction!(expect_step, m)?)?;
+    m.add_function(wrap_pyfunction!(rename_step, m)?)?;
+    m.add_function(wrap_pyfunction!(headers_step, m)?)?;
+    Ok(())
+}
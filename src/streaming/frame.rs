@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringBuilder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use csv::{ByteRecord, StringRecord};
+
+use super::RowSink;
+use crate::error::Result;
+
+/// Rows per record batch of the in-memory table
+const FRAME_BATCH_ROWS: usize = 65_536;
+
+/// Collects the kept rows as Arrow record batches instead of writing CSV, for
+/// handing the cleaned table to pandas/pyarrow without an intermediate file.
+/// Every column is Utf8; empty cells become nulls, as `read_csv` would read them.
+#[derive(Default)]
+pub struct ArrowSink {
+    schema: Option<SchemaRef>,
+    builders: Vec<StringBuilder>,
+    rows: usize,
+    batches: Vec<RecordBatch>,
+}
+
+impl ArrowSink {
+    fn finish_batch(&mut self) -> Result<()> {
+        let Some(schema) = &self.schema else { return Ok(()) };
+        if self.rows == 0 && !self.batches.is_empty() { return Ok(()); }
+        let columns: Vec<ArrayRef> = self.builders.iter_mut()
+            .map(|b| Arc::new(b.finish()) as ArrayRef)
+            .collect();
+        self.batches.push(RecordBatch::try_new(schema.clone(), columns)?);
+        self.rows = 0;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<ArrowTable> {
+        self.finish_batch()?;
+        let schema = self.schema.clone().unwrap_or_else(|| Arc::new(Schema::empty()));
+        Ok(ArrowTable { schema, batches: self.batches })
+    }
+}
+
+/// The kept rows of an in-memory run
+pub struct ArrowTable {
+    pub schema: SchemaRef,
+    pub batches: Vec<RecordBatch>,
+}
+
+impl ArrowTable {
+    pub fn num_rows(&self) -> usize {
+        self.batches.iter().map(RecordBatch::num_rows).sum()
+    }
+
+    /// The table as an Arrow IPC stream, readable with `pyarrow.ipc.open_stream`
+    pub fn to_ipc(&self) -> Result<Vec<u8>> {
+        let mut writer = StreamWriter::try_new(Vec::new(), &self.schema)?;
+        for batch in &self.batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+        Ok(writer.into_inner()?)
+    }
+}
+
+/// Column names of an Arrow input, in the header form the steps resolve against
+pub fn schema_headers(schema: &Schema) -> StringRecord {
+    schema.fields().iter().map(|f| f.name().as_str()).collect()
+}
+
+/// Append the rows of an Arrow batch as byte records. Values of any type are cast
+/// to their text form (as a CSV export would write them); nulls become empty fields.
+pub fn append_records(batch: &RecordBatch, records: &mut Vec<ByteRecord>) -> Result<()> {
+    let columns = batch.columns().iter()
+        .map(|c| cast(c, &DataType::Utf8))
+        .collect::<std::result::Result<Vec<ArrayRef>, _>>()?;
+    let columns: Vec<_> = columns.iter().map(|c| c.as_string::<i32>()).collect();
+
+    for row in 0..batch.num_rows() {
+        let mut record = ByteRecord::with_capacity(0, columns.len());
+        for column in &columns {
+            record.push_field(if column.is_null(row) { b"" } else { column.value(row).as_bytes() });
+        }
+        records.push(record);
+    }
+    Ok(())
+}
+
+impl RowSink for ArrowSink {
+    fn write_header(&mut self, headers: &StringRecord) -> Result<()> {
+        let fields: Vec<Field> = headers.iter()
+            .map(|name| Field::new(name, DataType::Utf8, true))
+            .collect();
+        self.builders = fields.iter().map(|_| StringBuilder::new()).collect();
+        self.schema = Some(Arc::new(Schema::new(fields)));
+        Ok(())
+    }
+
+    fn write_row(&mut self, record: &ByteRecord) -> Result<()> {
+        for (i, builder) in self.builders.iter_mut().enumerate() {
+            match record.get(i) {
+                Some(field) if !field.is_empty() => builder.append_value(String::from_utf8_lossy(field)),
+                _ => builder.append_null(),
+            }
+        }
+        self.rows += 1;
+        if self.rows >= FRAME_BATCH_ROWS {
+            self.finish_batch()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
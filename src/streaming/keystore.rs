@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{PhaetonError, Result};
+
+/// File header: format name and version
+const MAGIC: &[u8; 8] = b"PHDKEYS1";
+
+/// Serializes the merge-and-replace of key stores between the runs of this process
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Row key hash that stays the same across builds and platforms, unlike
+/// `DefaultHasher`, so keys saved by one run match the next
+#[derive(Default)]
+pub struct StableHasher(Sha256);
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().expect("SHA-256 digests are 32 bytes"))
+    }
+}
+
+/// Keys saved at `path`; a store that does not exist yet is empty
+pub fn load(path: &str) -> Result<HashSet<u64>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0u8; 8];
+    if reader.read_exact(&mut magic).is_err() || &magic != MAGIC || (len - 8) % 8 != 0 {
+        return Err(PhaetonError::InvalidStep(format!("'{}' is not a dedupe key store", path)));
+    }
+    let mut keys = HashSet::with_capacity(((len - 8) / 8) as usize);
+    let mut key = [0u8; 8];
+    while reader.read_exact(&mut key).is_ok() {
+        keys.insert(u64::from_le_bytes(key));
+    }
+    Ok(keys)
+}
+
+/// Add `keys` to the store at `path`. The store is re-read first, so runs that
+/// share it do not drop each other's keys, and replaced in one step.
+pub fn save(path: &str, keys: impl IntoIterator<Item = u64>) -> Result<()> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut merged = load(path)?;
+    merged.extend(keys);
+
+    if let Some(dir) = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = format!("{}.tmp", path);
+    let mut writer = BufWriter::new(File::create(&temp)?);
+    writer.write_all(MAGIC)?;
+    for key in merged {
+        writer.write_all(&key.to_le_bytes())?;
+    }
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
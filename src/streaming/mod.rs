@@ -1,83 +1,777 @@
 use std::borrow::Cow;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom};
 use std::collections::HashMap;
-use csv::{ReaderBuilder, WriterBuilder, StringRecord};
+use csv::{ReaderBuilder, WriterBuilder, ByteRecord, StringRecord};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 use sha2::{Sha256, Digest};
 use heck::*;
+use chrono::Local;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
+use arrow::ffi_stream::ArrowArrayStreamReader;
+use arrow::record_batch::RecordBatchReader;
 use crate::error::{PhaetonError, Result};
-use crate::processors::{text, cast, filter}; 
+use crate::metrics::{self, StepClock, StepTimer};
+use tracing::{debug, info, warn};
+use crate::processors::{text, cast, filter, currency, json, similarity, country, phonetic, template};
+use crate::processors::expect::{self, Expectation, Outcome};
+use crate::processors::vault::{self, Vault};
+use crate::processors::validate::{self, Diagnostic, Level}; 
 
+mod frame;
+mod keystore;
+mod route;
+use frame::ArrowSink;
+use keystore::StableHasher;
+use route::RouteSink;
+pub use route::{Route, RouteStats};
+pub use frame::ArrowTable;
 
+
+/// Rejected rows carry the position of the prepared step that rejected them
 enum RowResult {
-    Keep(StringRecord),
-    Discarded(StringRecord, String),
+    Keep(ByteRecord),
+    Discarded(usize, ByteRecord, String),
+    /// A step could not process the row (bad cast, invalid JSON, ...), handled per `ErrorStrategy`
+    Failed(usize, ByteRecord, String),
 }
 
 // save pre-compiled steps
+#[derive(Clone)]
 enum PreparedStep {
     Prune { col_idx: Option<usize> },
     PruneSelected { col_idxs: Vec<usize> },
     
     KeepRegex { col_idx: usize, re: Regex }, 
+    KeepRegexSet { col_idx: usize, set: RegexSet },
     KeepString { col_idx: usize, pattern: String, mode: String },
-    KeepMultiString { col_idx: usize, patterns: Vec<String>, mode: String },
+    KeepMultiString { col_idx: usize, patterns: filter::PatternSet },
+    KeepSet { col_idx: usize, values: HashSet<String> },
+    KeepFuzzy { col_idx: usize, patterns: Vec<String>, threshold: f64, metric: similarity::Metric },
     
     DiscardRegex { col_idx: usize, re: Regex },
+    DiscardRegexSet { col_idx: usize, set: RegexSet },
     DiscardString { col_idx: usize, pattern: String, mode: String },
-    DiscardMultiString { col_idx: usize, patterns: Vec<String>, mode: String },
+    DiscardMultiString { col_idx: usize, patterns: filter::PatternSet },
+    DiscardSet { col_idx: usize, values: HashSet<String> },
+    DiscardFuzzy { col_idx: usize, patterns: Vec<String>, threshold: f64, metric: similarity::Metric },
 
-    Scrub { col_idx: usize, mode: String },
+    Scrub { col_idx: usize, mode: String, transliterate: bool, collapse_whitespace: bool, decimal: Option<text::DecimalMark>, chars: Vec<char> },
 
-    Cast { col_idx: usize, dtype: String, clean: bool },
+    Cast { col_idx: usize, col_name: String, dtype: String, clean: bool, formats: Vec<String>, precision: u32, scale: u32, percent: Option<cast::Percent>, bools: cast::BoolValues, validate_only: bool },
 
     FillFixed { col_idx: usize, value: String },
     FillForward { col_idx: usize, last_valid: Arc<Mutex<String>> },
 
     Dedupe { col_idxs: Option<Vec<usize>>, state: Arc<DedupeState> },
 
-    Align { col_idx: usize, ref_list: Vec<String>, threshold: f64 },
+    Align { col_idx: usize, ref_list: Vec<String>, threshold: f64, metric: similarity::Metric, phonetic: Option<phonetic::PhoneticIndex>, audit: bool, cache: Arc<AlignCache> },
 
     Map { col_idx: usize, mapping: HashMap<String, String>, default: Option<String> },
 
     Hash { col_idxs: Vec<usize>, salt: String },
+    Mask { col_idxs: Vec<usize>, salt: String, keep_first: usize, keep_last: usize, luhn: bool },
+    Tokenize { col_idxs: Vec<usize>, vault: Vault },
+    /// Vault entries (token -> value) of the vault at `path`
+    Detokenize { col_idxs: Vec<usize>, path: String, entries: Arc<HashMap<String, String>> },
+
+    AddColumnFixed { value: String },
+    AddColumnRowNumber,
+
+    ConvertCurrency { col_idx: usize, currency_idx: usize, target: String, rates: HashMap<String, f64>, decimals: usize, clean: bool },
+
+    JsonExtract { col_idx: usize, path: Vec<json::PathSegment> },
+
+    /// Rewrites `col_idx`, or appends a column when it is `None`
+    Format { col_idx: Option<usize>, template: template::Template },
+
+    Country { col_idx: usize, alpha3: bool, threshold: f64, resolver: country::CountryResolver },
+
+    Fingerprint { col_idxs: Option<Vec<usize>> },
+
+    Expect { col_idx: Option<usize>, check: Expectation },
+}
+
+/// Reference lists above this size make align searches noticeably slow
+const ALIGN_LARGE_REF: usize = 50_000;
+
+/// What a prepared step does to the stream, for `StreamProcessor::explain`
+struct StepSummary {
+    effect: &'static str,
+    col_idxs: Vec<usize>,
+    detail: String,
+    quarantines: bool,
+}
+
+/// Short rendering of a pattern list for plan output
+fn summarize_list<'a>(items: impl ExactSizeIterator<Item = &'a String>) -> String {
+    const SHOWN: usize = 5;
+    let total = items.len();
+    let mut shown: Vec<String> = items.take(SHOWN).map(|s| format!("{:?}", s)).collect();
+    if total > SHOWN { shown.push(format!("... ({} total)", total)); }
+    format!("[{}]", shown.join(", "))
+}
+
+impl PreparedStep {
+    /// Copy for a new run: compiled data is kept, per-run state (dedupe keys,
+    /// forward-fill value, align memo) starts empty
+    fn fresh(&self) -> Result<Self> {
+        let mut step = self.clone();
+        match &mut step {
+            PreparedStep::FillForward { last_valid, .. } => *last_valid = Arc::new(Mutex::new(String::new())),
+            PreparedStep::Dedupe { state, .. } => *state = Arc::new(DedupeState::new(state.store.clone())?),
+            PreparedStep::Align { cache, .. } => *cache = Arc::new(AlignCache::new()),
+            PreparedStep::Expect { check, .. } => *check = check.fresh(),
+            PreparedStep::Tokenize { vault, .. } => *vault = vault.fresh(),
+            _ => {},
+        }
+        Ok(step)
+    }
+
+    /// Columns the step reads as text; `None` means every field of the row.
+    /// Fingerprint hashes raw bytes and appenders read nothing.
+    fn decoded_columns(&self) -> Option<Vec<usize>> {
+        match self {
+            PreparedStep::Prune { col_idx } => col_idx.map(|i| vec![i]),
+            PreparedStep::Dedupe { col_idxs, .. } => col_idxs.clone(),
+            PreparedStep::PruneSelected { col_idxs } | PreparedStep::Hash { col_idxs, .. }
+            | PreparedStep::Tokenize { col_idxs, .. } | PreparedStep::Detokenize { col_idxs, .. }
+            | PreparedStep::Mask { col_idxs, .. } => Some(col_idxs.clone()),
+            PreparedStep::ConvertCurrency { col_idx, currency_idx, .. } => Some(vec![*col_idx, *currency_idx]),
+            PreparedStep::AddColumnFixed { .. } | PreparedStep::AddColumnRowNumber | PreparedStep::Fingerprint { .. } => Some(Vec::new()),
+            PreparedStep::Expect { col_idx, .. } => Some(col_idx.iter().copied().collect()),
+            PreparedStep::Format { template, .. } => Some(template.columns()),
+
+            PreparedStep::KeepRegex { col_idx, .. } | PreparedStep::KeepRegexSet { col_idx, .. }
+            | PreparedStep::DiscardRegexSet { col_idx, .. } | PreparedStep::KeepString { col_idx, .. }
+            | PreparedStep::KeepMultiString { col_idx, .. } | PreparedStep::KeepSet { col_idx, .. }
+            | PreparedStep::KeepFuzzy { col_idx, .. } | PreparedStep::DiscardRegex { col_idx, .. }
+            | PreparedStep::DiscardString { col_idx, .. } | PreparedStep::DiscardMultiString { col_idx, .. }
+            | PreparedStep::DiscardSet { col_idx, .. } | PreparedStep::DiscardFuzzy { col_idx, .. }
+            | PreparedStep::Scrub { col_idx, .. } | PreparedStep::Cast { col_idx, .. }
+            | PreparedStep::FillFixed { col_idx, .. } | PreparedStep::FillForward { col_idx, .. }
+            | PreparedStep::Align { col_idx, .. } | PreparedStep::Map { col_idx, .. }
+            | PreparedStep::JsonExtract { col_idx, .. } | PreparedStep::Country { col_idx, .. } => Some(vec![*col_idx]),
+        }
+    }
+
+    /// Non-fatal problem with the compiled step, reported with the run's warnings
+    fn warning(&self) -> Option<String> {
+        match self {
+            PreparedStep::Align { ref_list, phonetic: None, .. } if ref_list.len() > ALIGN_LARGE_REF => Some(format!(
+                "Reference list has {} values; every new value is compared against all of them. Consider phonetic bucketing",
+                ref_list.len()
+            )),
+            _ => None,
+        }
+    }
+
+    fn summary(&self) -> StepSummary {
+        let filter = |col_idxs: Vec<usize>, detail: String| StepSummary { effect: "filter", col_idxs, detail, quarantines: true };
+        let transform = |col_idxs: Vec<usize>, detail: String| StepSummary { effect: "transform", col_idxs, detail, quarantines: false };
+        let append = |col_idxs: Vec<usize>, detail: String| StepSummary { effect: "append", col_idxs, detail, quarantines: false };
+
+        match self {
+            PreparedStep::Prune { col_idx: None } => filter(Vec::new(), "Drop rows with any empty field".into()),
+            PreparedStep::Prune { col_idx: Some(i) } => filter(vec![*i], "Drop rows where the column is empty".into()),
+            PreparedStep::PruneSelected { col_idxs } => filter(col_idxs.clone(), "Drop rows where any selected column is empty".into()),
+
+            PreparedStep::KeepRegex { col_idx, re } => filter(vec![*col_idx], format!("Keep rows matching regex /{}/", re.as_str())),
+            PreparedStep::KeepRegexSet { col_idx, set } => filter(vec![*col_idx], format!("Keep rows matching any of {} regexes {}", set.len(), summarize_list(set.patterns().iter()))),
+            PreparedStep::KeepString { col_idx, pattern, mode } => filter(vec![*col_idx], format!("Keep rows where value {} {:?}", mode, pattern)),
+            PreparedStep::KeepMultiString { col_idx, patterns } => filter(vec![*col_idx], format!("Keep rows where value {} any of {}", patterns.mode(), summarize_list(patterns.patterns().iter()))),
+            PreparedStep::KeepSet { col_idx, values } => filter(vec![*col_idx], format!("Keep rows whose value is in a set of {} values", values.len())),
+            PreparedStep::KeepFuzzy { col_idx, patterns, threshold, metric } => filter(vec![*col_idx], format!("Keep rows similar to {} ({:?} >= {})", summarize_list(patterns.iter()), metric, threshold)),
+
+            PreparedStep::DiscardRegex { col_idx, re } => filter(vec![*col_idx], format!("Discard rows matching regex /{}/", re.as_str())),
+            PreparedStep::DiscardRegexSet { col_idx, set } => filter(vec![*col_idx], format!("Discard rows matching any of {} regexes {}", set.len(), summarize_list(set.patterns().iter()))),
+            PreparedStep::DiscardString { col_idx, pattern, mode } => filter(vec![*col_idx], format!("Discard rows where value {} {:?}", mode, pattern)),
+            PreparedStep::DiscardMultiString { col_idx, patterns } => filter(vec![*col_idx], format!("Discard rows where value {} any of {}", patterns.mode(), summarize_list(patterns.patterns().iter()))),
+            PreparedStep::DiscardSet { col_idx, values } => filter(vec![*col_idx], format!("Discard rows whose value is in a set of {} values", values.len())),
+            PreparedStep::DiscardFuzzy { col_idx, patterns, threshold, metric } => filter(vec![*col_idx], format!("Discard rows similar to {} ({:?} >= {})", summarize_list(patterns.iter()), metric, threshold)),
+
+            PreparedStep::Scrub { col_idx, mode, transliterate, collapse_whitespace, decimal, chars } => {
+                let mut extra = if chars.is_empty() { String::new() } else { format!(" of whitespace and {:?}", chars.iter().collect::<String>()) };
+                let options: Vec<String> = [
+                    transliterate.then(|| "transliteration".to_string()),
+                    collapse_whitespace.then(|| "whitespace collapsed".to_string()),
+                    decimal.map(|mark| format!("'{}' as decimal separator", mark.as_char())),
+                ].into_iter().flatten().collect();
+                if !options.is_empty() {
+                    extra.push_str(&format!(" with {}", options.join(", ")));
+                }
+                transform(vec![*col_idx], format!("Scrub values ({}{})", mode, extra))
+            },
+            PreparedStep::Cast { col_idx, dtype, clean, formats, precision, scale, percent, bools, validate_only, .. } => {
+                let mut detail = if dtype == "decimal" { format!("Cast to decimal({}, {})", precision, scale) } else { format!("Cast to {}", dtype) };
+                if *clean { detail.push_str(", cleaning symbols first"); }
+                if let Some(percent) = percent { detail.push_str(&format!(", percentages as {}", percent.as_str())); }
+                if dtype == "bool" && *bools != cast::BoolValues::default() {
+                    detail.push_str(&format!(", true {} / false {}", summarize_list(bools.truthy().iter()), summarize_list(bools.falsy().iter())));
+                }
+                if !formats.is_empty() { detail.push_str(&format!(", formats {}", summarize_list(formats.iter()))); }
+                detail.push_str(if *validate_only { "; validate only" } else { "; writes normalized value" });
+                StepSummary { effect: if *validate_only { "filter" } else { "transform" }, col_idxs: vec![*col_idx], detail, quarantines: true }
+            },
+            PreparedStep::FillFixed { col_idx, value } => transform(vec![*col_idx], format!("Fill empty values with {:?}", value)),
+            PreparedStep::FillForward { col_idx, .. } => transform(vec![*col_idx], "Forward-fill empty values from the previous row".into()),
+            PreparedStep::Dedupe { col_idxs, state } => {
+                let mut detail = match col_idxs {
+                    Some(_) => "Drop rows with a key already seen".to_string(),
+                    None => "Drop rows identical to one already seen".to_string(),
+                };
+                if let Some(store) = &state.store { detail.push_str(&format!(", in this or an earlier run ({})", store)); }
+                filter(col_idxs.clone().unwrap_or_default(), detail)
+            },
+            PreparedStep::Align { col_idx, ref_list, threshold, metric, phonetic, audit, .. } => {
+                let mut detail = format!("Align to {} reference values ({:?} >= {})", ref_list.len(), metric, threshold);
+                if let Some(index) = phonetic { detail.push_str(&format!(", {:?} phonetic buckets searched first", index.algorithm)); }
+                StepSummary { effect: if *audit { "append" } else { "transform" }, col_idxs: vec![*col_idx], detail, quarantines: false }
+            },
+            PreparedStep::Map { col_idx, mapping, default } => {
+                let fallback = match default { Some(d) => format!("default {:?}", d), None => "others unchanged".to_string() };
+                transform(vec![*col_idx], format!("Map {} values, {}", mapping.len(), fallback))
+            },
+            PreparedStep::Hash { col_idxs, .. } => transform(col_idxs.clone(), "Replace values with salted SHA-256".into()),
+            PreparedStep::Mask { col_idxs, keep_first, keep_last, luhn, .. } => {
+                let mut note = "Mask values, keeping their format".to_string();
+                if *keep_first > 0 || *keep_last > 0 {
+                    note.push_str(&format!(" (first {} / last {} kept)", keep_first, keep_last));
+                }
+                if *luhn {
+                    note.push_str(", Luhn-valid");
+                }
+                transform(col_idxs.clone(), note)
+            },
+            PreparedStep::Tokenize { col_idxs, vault } => transform(col_idxs.clone(), format!("Replace values with tokens kept in vault {}", vault.path)),
+            PreparedStep::Detokenize { col_idxs, path, entries } => transform(col_idxs.clone(), format!("Restore tokens from vault {} ({} entries)", path, entries.len())),
+            PreparedStep::AddColumnFixed { value } => append(Vec::new(), format!("Append constant {:?}", value)),
+            PreparedStep::AddColumnRowNumber => append(Vec::new(), "Append source row number".into()),
+            PreparedStep::ConvertCurrency { col_idx, currency_idx, target, rates, decimals, .. } => StepSummary {
+                effect: "transform",
+                col_idxs: vec![*col_idx, *currency_idx],
+                detail: format!("Convert amounts to {} using {} rates, {} decimals", target, rates.len(), decimals),
+                quarantines: true,
+            },
+            PreparedStep::JsonExtract { col_idx, .. } => StepSummary { effect: "append", col_idxs: vec![*col_idx], detail: "Append value extracted from JSON".into(), quarantines: true },
+            PreparedStep::Format { col_idx, template } => StepSummary {
+                effect: if col_idx.is_some() { "transform" } else { "append" },
+                col_idxs: col_idx.iter().copied().chain(template.columns().into_iter().filter(|i| Some(*i) != *col_idx)).collect(),
+                detail: format!("{} from template {:?}", if col_idx.is_some() { "Rewrite values" } else { "Append value" }, template.source()),
+                quarantines: true,
+            },
+            PreparedStep::Country { col_idx, alpha3, threshold, .. } => StepSummary {
+                effect: "transform",
+                col_idxs: vec![*col_idx],
+                detail: format!("Normalize to ISO 3166 {} codes (fuzzy >= {})", if *alpha3 { "alpha-3" } else { "alpha-2" }, threshold),
+                quarantines: true,
+            },
+            PreparedStep::Fingerprint { col_idxs } => append(col_idxs.clone().unwrap_or_default(), match col_idxs {
+                Some(_) => "Append SHA-256 of the selected columns".into(),
+                None => "Append SHA-256 of the whole row".into(),
+            }),
+            PreparedStep::Expect { col_idx, check } => StepSummary {
+                effect: "check",
+                col_idxs: col_idx.iter().copied().collect(),
+                detail: format!("Expect {} ({} severity); rows are not changed", check.describe(), check.severity.as_str()),
+                quarantines: false,
+            },
+        }
+    }
+}
+
+/// A column referenced by a plan step, resolved to its position
+#[derive(Serialize)]
+pub struct PlanColumn {
+    pub index: usize,
+    pub name: String,
+}
+
+/// One entry of `StreamProcessor::explain`
+#[derive(Serialize)]
+pub struct PlanStep {
+    pub step: usize,
+    pub action: String,
+    /// filter, transform, append, check, header, output or ignored
+    pub effect: &'static str,
+    pub columns: Vec<PlanColumn>,
+    pub adds_columns: Vec<String>,
+    pub quarantines: bool,
+    pub detail: String,
+}
+
+/// Result of `StreamProcessor::peek`
+#[derive(Serialize)]
+pub struct Preview {
+    pub headers: Vec<String>,
+    pub rows: Vec<PreviewRow>,
+}
+
+/// One previewed source row and its fate
+#[derive(Serialize)]
+pub struct PreviewRow {
+    /// 1-based source row number
+    pub row: u64,
+    /// kept, quarantined, skipped or failed
+    pub status: &'static str,
+    /// Step-list position of the step that rejected the row
+    pub step: Option<usize>,
+    pub reason: Option<String>,
+    /// Values after the steps that ran, in the requested column order
+    pub values: Vec<String>,
+    /// Guesses the steps made about the row, e.g. which separator of `1,234` is decimal
+    pub notes: Vec<String>,
+}
+
+/// Compiled steps and their step-list positions
+type CompiledSteps = (Vec<PreparedStep>, Vec<usize>);
+
+/// Steps compiled once per source header layout and reused by later runs, so
+/// regexes, reference files and phonetic indexes are only built once.
+/// Reference files are therefore read on the first run of each layout only.
+#[derive(Default)]
+pub struct StepCache {
+    compiled: Mutex<HashMap<Vec<String>, Arc<CompiledSteps>>>,
+}
+
+impl StepCache {
+    /// Number of header layouts compiled so far
+    pub fn layouts(&self) -> usize {
+        self.compiled.lock().map(|c| c.len()).unwrap_or(0)
+    }
 }
 
 pub struct StreamProcessor {
     source: String,
     steps: Vec<HashMap<String, Value>>,
-    limit: Option<usize>,
     batch_size: usize,
+    progress: Option<ProgressCallback>,
+    cancel: Option<CancelFlag>,
+    on_error: ErrorStrategy,
+    delimiter: u8,
+    /// Falls back to the input delimiter
+    output_delimiter: Option<u8>,
+    step_cache: Option<Arc<StepCache>>,
+    /// Write rows in input order (default) or as soon as they are processed
+    preserve_order: bool,
+    read_mode: ReadMode,
+    /// Per-batch memory budget in bytes; when set, `batch_size` is only the first guess
+    batch_memory: Option<usize>,
+    /// Add a `_phaeton_raw` quarantine column holding the source text of malformed records
+    quarantine_raw: bool,
+    ragged: RaggedPolicy,
+    header_match: HeaderMatch,
+    /// Reject unknown actions and parameters instead of ignoring them
+    strict: bool,
+    /// Make repeated header names unique (`amount`, `amount_2`) before steps are resolved
+    rename_duplicates: bool,
+    /// Rows come from this Arrow stream instead of the source file; taken by the run
+    arrow_input: Mutex<Option<ArrowArrayStreamReader>>,
+    on_quarantine: Option<QuarantineHook>,
+    /// Continue a source read by an earlier run from here instead of its first row
+    resume: Option<SourcePosition>,
+    /// Leave an unterminated last line (a writer caught mid-append) for a later run
+    whole_lines: bool,
+    /// Add rows to existing output and quarantine files instead of replacing them
+    append: bool,
+    /// Named outputs that take the kept rows matching their condition
+    routes: Vec<Route>,
+}
+
+/// A point in a CSV source between two records: its byte offset, the line the
+/// parser is on, and the data rows before it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SourcePosition {
+    pub byte: u64,
+    pub line: u64,
+    pub rows: u64,
+}
+
+/// Snapshot handed to the progress callback after every batch
+pub struct Progress<'a> {
+    pub source: &'a str,
+    pub processed: u64,
+    pub saved: u64,
+    pub quarantined: u64,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    pub elapsed_ms: u64,
+    pub eta_ms: Option<u64>,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// A rejected row as handed to the quarantine callback
+pub struct QuarantinedRow {
+    /// 1-based source row; None for records the CSV parser rejected
+    pub row: Option<u64>,
+    /// Index of the rejecting step; None for parse and ragged-row failures
+    pub step: Option<usize>,
+    pub reason: String,
+    /// Output columns of the row, as far as the steps got
+    pub values: Vec<String>,
+}
+
+/// Rejected rows of a run, in the order they were written
+pub struct QuarantineBatch<'a> {
+    pub source: &'a str,
+    pub headers: &'a StringRecord,
+    pub rows: Vec<QuarantinedRow>,
+}
+
+pub type QuarantineCallback = Arc<dyn Fn(QuarantineBatch) + Send + Sync>;
+
+/// Rows routed to quarantine are also handed to `callback`, in lists of up to `batch`
+/// rows (and at every batch boundary), so consumers see them while the run goes on
+#[derive(Clone)]
+pub struct QuarantineHook {
+    pub callback: QuarantineCallback,
+    pub batch: usize,
+}
+
+/// Set to true to stop a run at the next batch boundary
+pub type CancelFlag = Arc<AtomicBool>;
+
+/// What happens to rows that fail to parse or that a step cannot process
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ErrorStrategy {
+    /// Route the row to quarantine with the reason (default)
+    #[default]
+    Quarantine,
+    /// Drop the row silently, only counting it
+    Skip,
+    /// Abort the run on the first failure
+    Fail,
+}
+
+impl ErrorStrategy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "quarantine" => Ok(Self::Quarantine),
+            "skip" => Ok(Self::Skip),
+            "fail" => Ok(Self::Fail),
+            other => Err(PhaetonError::InvalidStep(format!(
+                "Unknown on_error strategy '{}'. Expected 'quarantine', 'skip' or 'fail'", other
+            ))),
+        }
+    }
+}
+
+/// What happens to records with more or fewer fields than the header
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RaggedPolicy {
+    /// Pad short records with empty fields; long records are quarantined
+    Pad,
+    /// Fit every record to the header: pad short ones, drop the extra fields of long ones
+    Truncate,
+    /// Handle the record like a malformed one, per `ErrorStrategy` (default)
+    #[default]
+    Quarantine,
+    /// Abort the run
+    Error,
+}
+
+impl RaggedPolicy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "pad" => Ok(Self::Pad),
+            "truncate" => Ok(Self::Truncate),
+            "quarantine" => Ok(Self::Quarantine),
+            "error" => Ok(Self::Error),
+            other => Err(PhaetonError::InvalidStep(format!(
+                "Unknown ragged policy '{}'. Expected 'pad', 'truncate', 'quarantine' or 'error'", other
+            ))),
+        }
+    }
+
+    /// Fit `record` to `width` fields where the policy allows it; otherwise the reason it was rejected
+    fn fit(self, record: &mut ByteRecord, width: usize) -> Option<String> {
+        let found = record.len();
+        if found == width { return None; }
+        match self {
+            RaggedPolicy::Pad | RaggedPolicy::Truncate if found < width => {
+                while record.len() < width { record.push_field(b""); }
+                None
+            },
+            RaggedPolicy::Truncate => {
+                record.truncate(width);
+                None
+            },
+            _ => Some(format!("Ragged: expected {} fields, found {}", width, found)),
+        }
+    }
+}
+
+/// How `execute` reads the source file
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReadMode {
+    /// Memory-map regular files, buffered reads for everything else (default)
+    #[default]
+    Auto,
+    /// Always memory-map; fails for sources that cannot be mapped
+    Mmap,
+    /// Always use buffered `read` calls
+    Buffered,
+}
+
+impl ReadMode {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "auto" => Ok(Self::Auto),
+            "mmap" => Ok(Self::Mmap),
+            "buffered" => Ok(Self::Buffered),
+            other => Err(PhaetonError::InvalidStep(format!(
+                "Unknown read_mode '{}'. Expected 'auto', 'mmap' or 'buffered'", other
+            ))),
+        }
+    }
+}
+
+/// How step column names are matched against the header row
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeaderMatch {
+    /// Byte-exact names (default)
+    #[default]
+    Exact,
+    /// Ignore surrounding whitespace: `email` finds `" email "`
+    Trim,
+    /// Ignore surrounding whitespace and case: `email` finds `" Email "`
+    IgnoreCase,
+}
+
+impl HeaderMatch {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "exact" => Ok(Self::Exact),
+            "trim" => Ok(Self::Trim),
+            "ignore_case" => Ok(Self::IgnoreCase),
+            other => Err(PhaetonError::InvalidStep(format!(
+                "Unknown header_match '{}'. Expected 'exact', 'trim' or 'ignore_case'", other
+            ))),
+        }
+    }
+
+    fn matches(self, header: &str, name: &str) -> bool {
+        match self {
+            HeaderMatch::Exact => header == name,
+            HeaderMatch::Trim => header.trim() == name.trim(),
+            HeaderMatch::IgnoreCase => header.trim().to_lowercase() == name.trim().to_lowercase(),
+        }
+    }
+
+    /// Index of `name` in `columns`. An exact match always wins; a tolerant match
+    /// must be unique. `name[k]` picks the k-th (0-based) of several columns sharing
+    /// a name. Unknown names are reported with the closest header, if any.
+    fn resolve(self, columns: &[String], name: &str) -> Result<usize> {
+        let exact: Vec<usize> = columns.iter().enumerate().filter(|(_, h)| *h == name).map(|(i, _)| i).collect();
+        match exact.as_slice() {
+            [idx] => return Ok(*idx),
+            [] => {},
+            _ => return Err(PhaetonError::InvalidStep(format!(
+                "Column '{}' appears {} times in the header; use '{}[0]' to '{}[{}]' or rename_duplicates",
+                name, exact.len(), name, name, exact.len() - 1
+            ))),
+        }
+
+        if let Some((base, nth)) = split_occurrence(name) {
+            let hits: Vec<usize> = columns.iter().enumerate().filter(|(_, h)| self.matches(h, base)).map(|(i, _)| i).collect();
+            if !hits.is_empty() {
+                return hits.get(nth).copied().ok_or_else(|| PhaetonError::ColumnNotFound(format!(
+                    "{} (the header has {} column(s) named '{}')", name, hits.len(), base
+                )));
+            }
+        }
+
+        let found: Vec<usize> = columns.iter().enumerate()
+            .filter(|(_, h)| self != HeaderMatch::Exact && self.matches(h, name))
+            .map(|(i, _)| i)
+            .collect();
+        match found.as_slice() {
+            [idx] => Ok(*idx),
+            [] => Err(PhaetonError::ColumnNotFound(match suggest_column(columns, name) {
+                Some(close) => format!("{} (did you mean '{}'?)", name, close),
+                None => name.to_string(),
+            })),
+            _ => Err(PhaetonError::InvalidStep(format!(
+                "Column '{}' is ambiguous: matches {}", name,
+                found.iter().map(|&i| format!("'{}'", columns[i])).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+}
+
+/// `amount[1]` -> ("amount", 1)
+fn split_occurrence(name: &str) -> Option<(&str, usize)> {
+    let (base, rest) = name.strip_suffix(']')?.rsplit_once('[')?;
+    Some((base, rest.parse().ok()?))
+}
+
+/// Header with repeated names made unique: `amount, amount` -> `amount, amount_2`,
+/// skipping suffixes already taken by another column
+fn unique_headers(headers: &StringRecord) -> StringRecord {
+    let original: HashSet<&str> = headers.iter().collect();
+    let mut used: HashSet<String> = HashSet::new();
+    let renamed: Vec<String> = headers.iter().map(|h| {
+        if used.insert(h.to_string()) { return h.to_string(); }
+        let name = (2..).map(|n| format!("{}_{}", h, n))
+            .find(|c| !original.contains(c.as_str()) && !used.contains(c))
+            .unwrap_or_default();
+        used.insert(name.clone());
+        name
+    }).collect();
+    StringRecord::from(renamed)
+}
+
+/// Closest header to a name that did not resolve, compared trimmed and lowercased
+fn suggest_column<'a>(columns: &'a [String], name: &str) -> Option<&'a str> {
+    const MIN_SIMILARITY: f64 = 0.8;
+    let wanted = name.trim().to_lowercase();
+    columns.iter()
+        .map(|h| (h, strsim::jaro_winkler(&h.trim().to_lowercase(), &wanted)))
+        .filter(|(_, score)| *score >= MIN_SIMILARITY)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(h, _)| h.as_str())
+}
+
+/// Offset just past the last line terminator in the first `len` bytes of `path`
+/// (0 when there is none)
+fn complete_lines_end(path: &str, len: u64) -> Result<u64> {
+    const CHUNK: u64 = 64 * 1024;
+    let mut file = File::open(path)?;
+    let mut end = len;
+    let mut buffer = vec![0u8; CHUNK as usize];
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK);
+        let chunk = &mut buffer[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        if let Some(i) = chunk.iter().rposition(|&b| b == b'\n' || b == b'\r') {
+            return Ok(start + i as u64 + 1);
+        }
+        end = start;
+    }
+    Ok(0)
 }
 
+/// The source of a run: a memory map of the whole file, or a buffered file handle
+/// for pipes, FIFOs and other sources that cannot be mapped
+enum SourceReader {
+    Mapped(Cursor<memmap2::Mmap>),
+    Buffered(BufReader<File>),
+}
+
+impl SourceReader {
+    fn open(path: &str, mode: ReadMode) -> Result<(Self, u64)> {
+        let file = File::open(path).map_err(|_| PhaetonError::FileNotFound(path.to_string()))?;
+        let meta = file.metadata()?;
+        let total_bytes = meta.len();
+
+        // Empty files cannot be mapped on every platform, and there is nothing to gain
+        let mappable = meta.is_file() && total_bytes > 0;
+        if mode == ReadMode::Buffered || (mode == ReadMode::Auto && !mappable) {
+            return Ok((SourceReader::Buffered(BufReader::new(file)), total_bytes));
+        }
+
+        // SAFETY: the map is read-only and lives as long as the run. A file truncated by
+        // another process while mapped is the caller's problem, as with any mmap reader.
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(map) => Ok((SourceReader::Mapped(Cursor::new(map)), total_bytes)),
+            Err(e) if mode == ReadMode::Auto => {
+                debug!(source = %path, error = %e, "Memory map failed, using buffered reads");
+                Ok((SourceReader::Buffered(BufReader::new(file)), total_bytes))
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Seek for SourceReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            SourceReader::Mapped(map) => map.seek(pos),
+            SourceReader::Buffered(file) => file.seek(pos),
+        }
+    }
+}
+
+impl Read for SourceReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            SourceReader::Mapped(map) => map.read(buf),
+            SourceReader::Buffered(file) => file.read(buf),
+        }
+    }
+}
+
+/// Rows rejected by one step of the pipeline
+pub struct StepStats {
+    /// Position of the step in the submitted step list
+    pub step: usize,
+    pub action: String,
+    pub rejected: u64,
+}
+
+#[derive(Default)]
 pub struct ExecutionStats {
+    pub steps: Vec<StepStats>,
     pub processed: u64,
     pub saved: u64,
     pub quarantined: u64,
-    pub duration_ms: u64,
+    pub skipped: u64,
+    /// Records the CSV parser rejected; also counted in `quarantined` or `skipped`
+    pub malformed: u64,
+    pub bytes_read: u64,
+    pub duration: Duration,
+    pub rows_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub cancelled: bool,
+    /// Non-fatal problems found while compiling the steps
+    pub warnings: Vec<String>,
+    /// Outcome of every `expect` step, in step order
+    pub expectations: Vec<Outcome>,
+    /// Where reading stopped; a later run over the grown source can resume here
+    pub end: SourcePosition,
+    /// Rows each route took, in route order
+    pub routes: Vec<RouteStats>,
 }
 
 const NUM_SHARDS: usize = 256;
 
 struct DedupeState {
     shards: Vec<Mutex<HashSet<u64>>>,
+    /// Key store the keys of earlier runs come from and this run's go back to
+    store: Option<String>,
 }
 
 impl DedupeState {
-    fn new() -> Self {
-        let mut shards = Vec::with_capacity(NUM_SHARDS);
-        for _ in 0..NUM_SHARDS {
-            shards.push(Mutex::new(HashSet::new()));
+    fn new(store: Option<String>) -> Result<Self> {
+        let mut shards: Vec<HashSet<u64>> = (0..NUM_SHARDS).map(|_| HashSet::new()).collect();
+        if let Some(path) = &store {
+            for key in keystore::load(path)? {
+                shards[(key as usize) % NUM_SHARDS].insert(key);
+            }
         }
-        Self { shards }
+        Ok(Self { shards: shards.into_iter().map(Mutex::new).collect(), store })
+    }
+
+    /// Write the keys seen so far back to the key store, if there is one
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.store else { return Ok(()) };
+        let keys: Vec<u64> = self.shards.iter()
+            .flat_map(|shard| shard.lock().unwrap().iter().copied().collect::<Vec<_>>())
+            .collect();
+        keystore::save(path, keys)
     }
 
     fn check_and_insert(&self, hash: u64) -> bool {
@@ -92,18 +786,209 @@ impl DedupeState {
     }
 }
 
+// Per-shard cap so high-cardinality columns can't grow the cache without bound
+const ALIGN_CACHE_SHARD_LIMIT: usize = 4096;
+
+#[derive(Clone, Copy)]
+struct AlignMatch {
+    idx: usize,
+    score: f64,
+}
+
+type AlignShard = Mutex<HashMap<String, Option<AlignMatch>>>;
+
+/// Memoizes fuzzy lookups per execution: value -> best match in ref_list
+struct AlignCache {
+    shards: Vec<AlignShard>,
+}
+
+impl AlignCache {
+    fn new() -> Self {
+        let mut shards = Vec::with_capacity(NUM_SHARDS);
+        for _ in 0..NUM_SHARDS {
+            shards.push(Mutex::new(HashMap::new()));
+        }
+        Self { shards }
+    }
+
+    fn shard_for(&self, val: &str) -> &AlignShard {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        &self.shards[(hasher.finish() as usize) % NUM_SHARDS]
+    }
+
+    fn get_or_compute(&self, val: &str, compute: impl FnOnce() -> Option<AlignMatch>) -> Option<AlignMatch> {
+        let shard = self.shard_for(val);
+        if let Some(hit) = shard.lock().unwrap().get(val) {
+            return *hit;
+        }
+
+        // Compute outside the lock so other workers aren't blocked on the fuzzy search
+        let result = compute();
+        let mut guard = shard.lock().unwrap();
+        if guard.len() < ALIGN_CACHE_SHARD_LIMIT {
+            guard.insert(val.to_string(), result);
+        }
+        result
+    }
+}
+
 impl StreamProcessor {
-    pub fn new(source: String, steps: Vec<HashMap<String, Value>>, limit: usize, batch_size: usize) -> Self {
+    pub fn new(source: String, steps: Vec<HashMap<String, Value>>, batch_size: usize) -> Self {
         let effective_batch = if batch_size == 0 { 10_000 } else { batch_size };
         
         Self { 
             source, 
             steps, 
-            limit: if limit == 0 { None } else { Some(limit) },
-            batch_size: effective_batch 
+            batch_size: effective_batch,
+            progress: None,
+            cancel: None,
+            on_error: ErrorStrategy::default(),
+            delimiter: b',',
+            output_delimiter: None,
+            step_cache: None,
+            preserve_order: true,
+            read_mode: ReadMode::default(),
+            batch_memory: None,
+            quarantine_raw: false,
+            ragged: RaggedPolicy::default(),
+            header_match: HeaderMatch::default(),
+            strict: true,
+            rename_duplicates: false,
+            arrow_input: Mutex::new(None),
+            on_quarantine: None,
+            resume: None,
+            whole_lines: false,
+            append: false,
+            routes: Vec::new(),
         }
     }
 
+    pub fn with_progress(mut self, progress: Option<ProgressCallback>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: Option<CancelFlag>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_on_quarantine(mut self, hook: Option<QuarantineHook>) -> Self {
+        self.on_quarantine = hook;
+        self
+    }
+
+    pub fn with_on_error(mut self, on_error: ErrorStrategy) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_output_delimiter(mut self, delimiter: Option<u8>) -> Self {
+        self.output_delimiter = delimiter;
+        self
+    }
+
+    /// With `false`, rows of a batch are written in completion order for throughput.
+    /// The default keeps output rows in input order, across batches and within them.
+    pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
+        self.preserve_order = preserve_order;
+        self
+    }
+
+    pub fn with_read_mode(mut self, read_mode: ReadMode) -> Self {
+        self.read_mode = read_mode;
+        self
+    }
+
+    /// Size batches from the measured row width so each stays within `budget` bytes
+    pub fn with_batch_memory(mut self, budget: Option<usize>) -> Self {
+        self.batch_memory = budget;
+        self
+    }
+
+    pub fn with_quarantine_raw(mut self, quarantine_raw: bool) -> Self {
+        self.quarantine_raw = quarantine_raw;
+        self
+    }
+
+    pub fn with_ragged(mut self, ragged: RaggedPolicy) -> Self {
+        self.ragged = ragged;
+        self
+    }
+
+    pub fn with_header_match(mut self, header_match: HeaderMatch) -> Self {
+        self.header_match = header_match;
+        self
+    }
+
+    /// With `false`, unknown actions are skipped and unknown parameters ignored
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn with_rename_duplicates(mut self, rename_duplicates: bool) -> Self {
+        self.rename_duplicates = rename_duplicates;
+        self
+    }
+
+    /// Read the rows from an Arrow stream (e.g. a polars DataFrame) instead of the
+    /// source file, which then only names the run
+    pub fn with_arrow_input(mut self, input: Option<ArrowArrayStreamReader>) -> Self {
+        self.arrow_input = Mutex::new(input);
+        self
+    }
+
+    /// Skip the records an earlier run read (up to `position`); the header row is
+    /// still read from the start of the file
+    pub fn with_resume(mut self, position: Option<SourcePosition>) -> Self {
+        self.resume = position;
+        self
+    }
+
+    /// Only read records whose line terminator is already written; the run ends
+    /// before a half-written last line instead of processing it
+    pub fn with_whole_lines(mut self, whole_lines: bool) -> Self {
+        self.whole_lines = whole_lines;
+        self
+    }
+
+    /// Append to the output and quarantine files; a header row is only written to
+    /// files that are empty or new
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Send kept rows matching a route's condition to that route instead of the output
+    pub fn with_routes(mut self, routes: Vec<Route>) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// The header row as steps see it
+    fn read_headers<R: Read>(&self, reader: &mut csv::Reader<R>) -> Result<StringRecord> {
+        let headers = reader.headers()?;
+        Ok(if self.rename_duplicates { unique_headers(headers) } else { headers.clone() })
+    }
+
+    fn arrow_headers(&self, stream: &ArrowArrayStreamReader) -> StringRecord {
+        let headers = frame::schema_headers(&stream.schema());
+        if self.rename_duplicates { unique_headers(&headers) } else { headers }
+    }
+
+    /// Reuse steps compiled by earlier runs with the same header layout
+    pub fn with_step_cache(mut self, cache: Option<Arc<StepCache>>) -> Self {
+        self.step_cache = cache;
+        self
+    }
+
     fn transform_headers(&self, original_headers: &StringRecord) -> StringRecord {
         let mut new_headers = original_headers.clone();
         
@@ -115,7 +1000,10 @@ impl StreamProcessor {
                     if let Some(mapping) = step.get("mapping").and_then(|v| v.as_object()) {
                         let mut temp_headers = StringRecord::new();
                         for h in new_headers.iter() {
-                            if let Some(new_name) = mapping.get(h).and_then(|v| v.as_str()) {
+                            let new_name = mapping.get(h)
+                                .or_else(|| mapping.iter().find(|(old, _)| self.header_match.matches(h, old)).map(|(_, v)| v))
+                                .and_then(|v| v.as_str());
+                            if let Some(new_name) = new_name {
                                 temp_headers.push_field(new_name);
                             } else {
                                 temp_headers.push_field(h);
@@ -141,394 +1029,1567 @@ impl StreamProcessor {
                     }
                     new_headers = temp_headers;
                 },
+                "add_column" => {
+                    let name = step.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                    new_headers.push_field(name);
+                },
+                "json_extract" => {
+                    let into = step.get("into").and_then(|v| v.as_str()).unwrap_or("");
+                    new_headers.push_field(into);
+                },
+                "fingerprint" => {
+                    let into = step.get("into").and_then(|v| v.as_str()).unwrap_or("_fingerprint");
+                    new_headers.push_field(into);
+                },
+                "format" => {
+                    if let Some(into) = step.get("into").and_then(|v| v.as_str()) {
+                        new_headers.push_field(into);
+                    }
+                },
+                "align" if step.get("audit").and_then(|v| v.as_bool()).unwrap_or(false) => {
+                    let col = match step.get("col") {
+                        Some(Value::Number(n)) => n.as_u64().and_then(|i| new_headers.get(i as usize)).unwrap_or("").to_string(),
+                        other => other.and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    };
+                    new_headers.push_field(&format!("{}_original", col));
+                    new_headers.push_field(&format!("{}_score", col));
+                },
                 _ => {}
             }
         }
         new_headers
     }
 
-    fn compile_steps(&self, headers: &StringRecord) -> Result<Vec<PreparedStep>> {
-        let mut prepared_steps = Vec::new();
-        
-        let get_idx = |col_name: &str| -> Result<usize> {
-            headers.iter().position(|h| h == col_name)
-                .ok_or_else(|| PhaetonError::ColumnNotFound(col_name.to_string()))
-        };
+    /// Compile the row steps against `headers`, with the step-list position of each prepared step
+    fn compile_steps_indexed(&self, headers: &StringRecord) -> Result<(Vec<PreparedStep>, Vec<usize>)> {
+        let mut prepared_steps = Vec::new();
+        let mut origins = Vec::new();
+
+        // Columns appended by earlier steps can be referenced by later ones
+        let mut columns: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if self.strict {
+                if let Some(d) = validate::check_step(i, step, true).into_iter().find(|d| d.level == Level::Error) {
+                    return Err(PhaetonError::InvalidStep(format!("Step {} ({}): {}", i, d.action, d.message)));
+                }
+            }
+            if let Some(p_step) = self.compile_step(step, &mut columns)? {
+                prepared_steps.push(p_step);
+                origins.push(i);
+            }
+        }
+        Ok((prepared_steps, origins))
+    }
+
+    /// `compile_steps_indexed` through the step cache, if one is set
+    fn prepare_steps(&self, headers: &StringRecord) -> Result<CompiledSteps> {
+        let Some(cache) = &self.step_cache else { return self.compile_steps_indexed(headers) };
+
+        let layout: Vec<String> = headers.iter().map(str::to_string).collect();
+        let cached = cache.compiled.lock().ok().and_then(|c| c.get(&layout).cloned());
+        let compiled = match cached {
+            Some(compiled) => compiled,
+            None => {
+                let compiled = Arc::new(self.compile_steps_indexed(headers)?);
+                if let Ok(mut c) = cache.compiled.lock() { c.insert(layout, compiled.clone()); }
+                compiled
+            },
+        };
+        let (steps, origins) = &*compiled;
+        Ok((steps.iter().map(PreparedStep::fresh).collect::<Result<_>>()?, origins.clone()))
+    }
+
+    /// Compile one step against the columns available at that point of the pipeline.
+    /// Returns None for steps that only affect headers or are not row operations.
+    fn compile_step(&self, step: &HashMap<String, Value>, columns: &mut Vec<String>) -> Result<Option<PreparedStep>> {
+        let action = step.get("action").and_then(|v| v.as_str()).unwrap_or("");
+
+        let get_idx = |col_name: &str| -> Result<usize> { self.header_match.resolve(columns, col_name) };
+
+        // A column given by header name, or by 0-based position
+        let value_idx = |col: &Value| -> Result<usize> {
+            let Value::Number(n) = col else { return get_idx(col.as_str().unwrap_or("")) };
+            let idx = n.as_u64().ok_or_else(|| PhaetonError::InvalidStep(format!("Column index must be a non-negative integer, got {}", n)))? as usize;
+            if idx >= columns.len() {
+                return Err(PhaetonError::InvalidStep(format!("Column index {} is out of range: the row has {} columns", idx, columns.len())));
+            }
+            Ok(idx)
+        };
+        let col_idx = |key: &str| -> Result<usize> { value_idx(step.get(key).unwrap_or(&Value::Null)) };
+        // One column or a list of them
+        let col_list = |key: &str| -> Result<Vec<usize>> {
+            match step.get(key) {
+                Some(Value::Array(arr)) => arr.iter().map(&value_idx).collect(),
+                Some(col @ (Value::String(_) | Value::Number(_))) => Ok(vec![value_idx(col)?]),
+                _ => Err(PhaetonError::InvalidStep(format!("{} step missing '{}' parameter", action, key))),
+            }
+        };
+        let vault_key = || vault::passphrase(step.get("key_env").and_then(|v| v.as_str()).unwrap_or(vault::DEFAULT_KEY_ENV));
+        
+        let extract_match_val = |key: &str| -> String {
+            step.get(key).map(|v| match v {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => String::new(),
+            }).unwrap_or_default()
+        };
+
+        // Set membership ("in" mode): a list or a single scalar, trimmed like the row values
+        let extract_match_set = || -> HashSet<String> {
+            match step.get("match") {
+                Some(Value::Array(arr)) => arr.iter().map(|v| match v {
+                    Value::String(s) => s.trim().to_string(),
+                    other => other.to_string(),
+                }).collect(),
+                _ => std::iter::once(extract_match_val("match").trim().to_string()).collect(),
+            }
+        };
+
+        // Regex mode with a list: all patterns in one RegexSet
+        let extract_regex_set = |arr: &Vec<Value>| -> Result<RegexSet> {
+            let patterns: Vec<&str> = arr.iter().map(|v| v.as_str().unwrap_or("")).collect();
+            RegexSet::new(&patterns).map_err(|e| PhaetonError::InvalidStep(format!("Invalid Regex set: {}", e)))
+        };
+
+        // Fuzzy mode: (patterns, threshold, metric)
+        let extract_fuzzy = || -> Result<(Vec<String>, f64, similarity::Metric)> {
+            let patterns = match step.get("match") {
+                Some(Value::Array(arr)) => arr.iter().map(|v| v.as_str().unwrap_or("").trim().to_string()).collect(),
+                _ => vec![extract_match_val("match").trim().to_string()],
+            };
+            let threshold = step.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.85);
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(PhaetonError::InvalidStep(format!("Fuzzy threshold must be between 0.0 and 1.0, got {}", threshold)));
+            }
+            let metric = similarity::Metric::parse(step.get("metric").and_then(|v| v.as_str()).unwrap_or("jaro_winkler"))?;
+            Ok((patterns, threshold, metric))
+        };
+
+        let p_step = match action {
+            "keep" => {
+                let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("exact");
+                let idx = col_idx("col")?;
+                
+                if let (true, Some(Value::Array(arr))) = (mode == "regex", step.get("match")) {
+                    PreparedStep::KeepRegexSet { col_idx: idx, set: extract_regex_set(arr)? }
+                } else if mode == "regex" {
+                    let match_val = extract_match_val("match");
+                    let re = Regex::new(&match_val).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", match_val)))?;
+                    PreparedStep::KeepRegex { col_idx: idx, re }
+                } else if mode == "in" {
+                    PreparedStep::KeepSet { col_idx: idx, values: extract_match_set() }
+                } else if mode == "fuzzy" {
+                    let (patterns, threshold, metric) = extract_fuzzy()?;
+                    PreparedStep::KeepFuzzy { col_idx: idx, patterns, threshold, metric }
+                } else if let Some(Value::Array(arr)) = step.get("match") {
+                    let patterns: Vec<String> = arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect();
+                    PreparedStep::KeepMultiString { col_idx: idx, patterns: filter::PatternSet::new(patterns, mode)? }
+                } else {
+                    let match_val = extract_match_val("match");
+                    PreparedStep::KeepString { col_idx: idx, pattern: match_val, mode: mode.to_string() }
+                }
+            },
+            "discard" => {
+                let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("exact");
+                let idx = col_idx("col")?;
+
+                if let (true, Some(Value::Array(arr))) = (mode == "regex", step.get("match")) {
+                    PreparedStep::DiscardRegexSet { col_idx: idx, set: extract_regex_set(arr)? }
+                } else if mode == "regex" {
+                    let match_val = extract_match_val("match");
+                    let re = Regex::new(&match_val).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", match_val)))?;
+                    PreparedStep::DiscardRegex { col_idx: idx, re }
+                } else if mode == "in" {
+                    PreparedStep::DiscardSet { col_idx: idx, values: extract_match_set() }
+                } else if mode == "fuzzy" {
+                    let (patterns, threshold, metric) = extract_fuzzy()?;
+                    PreparedStep::DiscardFuzzy { col_idx: idx, patterns, threshold, metric }
+                } else if let Some(Value::Array(arr)) = step.get("match") {
+                    let patterns: Vec<String> = arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect();
+                    PreparedStep::DiscardMultiString { col_idx: idx, patterns: filter::PatternSet::new(patterns, mode)? }
+                } else {
+                    let match_val = extract_match_val("match");
+                    PreparedStep::DiscardString { col_idx: idx, pattern: match_val, mode: mode.to_string() }
+                }
+            },
+            "prune" => {
+                let col_val = step.get("col").unwrap_or(&Value::Null);
+                match col_val {
+                    Value::Array(arr) => {
+                        let mut indices = Vec::new();
+                        for v in arr {
+                            indices.push(value_idx(v)?);
+                        }
+                        PreparedStep::PruneSelected { col_idxs: indices }
+                    },
+                    Value::String(s) => {
+                        if s == "*" { PreparedStep::Prune { col_idx: None } } 
+                        else { PreparedStep::Prune { col_idx: Some(get_idx(s)?) } }
+                    },
+                    Value::Number(_) => PreparedStep::Prune { col_idx: Some(value_idx(col_val)?) },
+                    _ => PreparedStep::Prune { col_idx: None }
+                }
+            },
+            "scrub" => {
+                let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("trim").to_string();
+                let transliterate = step.get("transliterate").and_then(|v| v.as_bool()).unwrap_or(false);
+                let collapse_whitespace = step.get("collapse_whitespace").and_then(|v| v.as_bool()).unwrap_or(false);
+                let decimal = step.get("decimal").and_then(|v| v.as_str()).map(text::DecimalMark::parse).transpose()?;
+                let chars: Vec<char> = match step.get("chars") {
+                    None | Some(Value::Null) => Vec::new(),
+                    Some(_) if mode != "trim" => return Err(PhaetonError::InvalidStep("Parameter 'chars' is only valid for the 'trim' mode".to_string())),
+                    Some(Value::String(s)) if !s.is_empty() => s.chars().collect(),
+                    Some(_) => return Err(PhaetonError::InvalidStep("Parameter 'chars' must be a non-empty string".to_string())),
+                };
+                PreparedStep::Scrub { col_idx: col_idx("col")?, mode, transliterate, collapse_whitespace, decimal, chars }
+            },
+            "cast" => {
+                let dtype = step.get("type").and_then(|v| v.as_str()).unwrap_or("str").to_string();
+                let clean = step.get("clean").and_then(|v| v.as_bool()).unwrap_or(false);
+                let formats: Vec<String> = match step.get("format") {
+                    Some(Value::String(f)) => vec![f.clone()],
+                    Some(Value::Array(arr)) => arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect(),
+                    _ => Vec::new(),
+                };
+                let precision = step.get("precision").and_then(|v| v.as_u64()).unwrap_or(18) as u32;
+                let scale = step.get("scale").and_then(|v| v.as_u64()).unwrap_or(2) as u32;
+                if dtype == "decimal" && (precision == 0 || scale > precision) {
+                    return Err(PhaetonError::InvalidStep(format!("Invalid decimal({}, {}): scale must be between 0 and precision", precision, scale)));
+                }
+                let word_list = |key: &str| -> Option<Vec<String>> {
+                    step.get(key).and_then(|v| v.as_array())
+                        .map(|arr| arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+                };
+                let bools = cast::BoolValues::new(word_list("true_values"), word_list("false_values"))?;
+                let percent = step.get("percent").and_then(|v| v.as_str()).map(cast::Percent::parse).transpose()?;
+                let validate_only = step.get("validate_only").and_then(|v| v.as_bool()).unwrap_or(false);
+                let idx = col_idx("col")?;
+                PreparedStep::Cast { col_idx: idx, col_name: columns[idx].clone(), dtype, clean, formats, precision, scale, percent, bools, validate_only }
+            },
+            "fill" => {
+                let idx = col_idx("col")?;
+                
+                let method = step.get("method").and_then(|v| v.as_str()).unwrap_or("fixed");
+                
+                if method == "ffill" {
+                    let state = Arc::new(Mutex::new(String::new()));
+                    PreparedStep::FillForward { col_idx: idx, last_valid: state }
+                } else {
+                    let fill_val = extract_match_val("value"); 
+                    PreparedStep::FillFixed { col_idx: idx, value: fill_val }
+                }
+            },
+            "dedupe" => {
+                let col_val = step.get("col").unwrap_or(&Value::Null);
+                
+                let col_idxs = match col_val {
+                    Value::Array(arr) => {
+                        let mut indices = Vec::new();
+                        for v in arr {
+                            indices.push(value_idx(v)?);
+                        }
+                        Some(indices)
+                    },
+                    
+                    Value::String(s) => {
+                        if s == "*" {
+                            None
+                        } else {
+                            Some(vec![get_idx(s)?])
+                        }
+                    },
+
+                    Value::Number(_) => Some(vec![value_idx(col_val)?]),
+                    
+                    _ => None,
+                };
+                
+                let store = step.get("store").and_then(|v| v.as_str()).map(str::to_string);
+                let state = Arc::new(DedupeState::new(store)?);
+                
+                PreparedStep::Dedupe { col_idxs, state }
+            },
+            "align" => {
+                let threshold = step.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.85);
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(PhaetonError::InvalidStep(format!("Align threshold must be between 0.0 and 1.0, got {}", threshold)));
+                }
+                let ref_list: Vec<String> = match step.get("ref") {
+                    Some(Value::Array(arr)) => arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect(),
+                    Some(Value::String(path)) => {
+                        let ref_col = step.get("ref_col").and_then(|v| v.as_str());
+                        similarity::load_reference(path, ref_col)?
+                    },
+                    _ => Vec::new(),
+                };
+                // An empty list would compile to a step that never changes a value
+                if ref_list.is_empty() {
+                    let origin = step.get("ref").and_then(|v| v.as_str()).map(|path| format!(" ({} has no values)", path)).unwrap_or_default();
+                    return Err(PhaetonError::InvalidStep(format!("Align reference list is empty{}", origin)));
+                }
+                let metric = similarity::Metric::parse(step.get("metric").and_then(|v| v.as_str()).unwrap_or("jaro_winkler"))?;
+                let audit = step.get("audit").and_then(|v| v.as_bool()).unwrap_or(false);
+                let phonetic = match step.get("phonetic").and_then(|v| v.as_str()) {
+                    Some(name) => Some(phonetic::PhoneticIndex::build(phonetic::Algorithm::parse(name)?, &ref_list)),
+                    None => None,
+                };
+                let idx = col_idx("col")?;
+                let p = PreparedStep::Align { col_idx: idx, ref_list, threshold, metric, phonetic, audit, cache: Arc::new(AlignCache::new()) };
+                if audit {
+                    let col = step.get("col").and_then(|v| v.as_str()).map_or_else(|| columns[idx].clone(), str::to_string);
+                    columns.push(format!("{}_original", col));
+                    columns.push(format!("{}_score", col));
+                }
+                p
+            },
+            "map" => {
+                let default_val = step.get("default").and_then(|v| v.as_str()).map(|s| s.to_string());
+                
+                let mut map_lookup = HashMap::new();
+                if let Some(mapping) = step.get("mapping").and_then(|v| v.as_object()) {
+                    for (k, v) in mapping {
+                        if let Some(val_str) = v.as_str() {
+                            map_lookup.insert(k.clone(), val_str.to_string());
+                        }
+                    }
+                }
+                
+                PreparedStep::Map { 
+                    col_idx: col_idx("col")?, 
+                    mapping: map_lookup, 
+                    default: default_val 
+                }
+            },
+            "hash" => {
+                let col_val = step.get("col").unwrap_or(&Value::Null);
+                let salt = step.get("salt").and_then(|v| v.as_str()).unwrap_or("phaeton_salt").to_string();
+                
+                let col_idxs = match col_val {
+                    Value::Array(arr) => {
+                        let mut indices = Vec::new();
+                        for v in arr {
+                            indices.push(value_idx(v)?);
+                        }
+                        indices
+                    },
+                    Value::String(s) => vec![get_idx(s)?],
+                    Value::Number(_) => vec![value_idx(col_val)?],
+                    
+                    _ => return Err(PhaetonError::InvalidStep("Hash step missing 'col' parameter".to_string())),
+                };
+                
+                PreparedStep::Hash { col_idxs, salt }
+            },
+            "mask" => {
+                let salt = step.get("salt").and_then(|v| v.as_str()).unwrap_or("phaeton_salt").to_string();
+                let count = |key: &str| step.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let luhn = step.get("luhn").and_then(|v| v.as_bool()).unwrap_or(false);
+                PreparedStep::Mask { col_idxs: col_list("col")?, salt, keep_first: count("keep_first"), keep_last: count("keep_last"), luhn }
+            },
+            "tokenize" => {
+                let path = step.get("vault").and_then(|v| v.as_str())
+                    .ok_or_else(|| PhaetonError::InvalidStep("Tokenize step missing 'vault' parameter".to_string()))?;
+                PreparedStep::Tokenize { col_idxs: col_list("col")?, vault: Vault::open(path, &vault_key()?)? }
+            },
+            "detokenize" => {
+                let path = step.get("vault").and_then(|v| v.as_str())
+                    .ok_or_else(|| PhaetonError::InvalidStep("Detokenize step missing 'vault' parameter".to_string()))?;
+                let entries = Arc::new(vault::open_entries(path, &vault_key()?)?);
+                PreparedStep::Detokenize { col_idxs: col_list("col")?, path: path.to_string(), entries }
+            },
+            "add_column" => {
+                let name = step.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                if name.is_empty() {
+                    return Err(PhaetonError::InvalidStep("Add column step missing 'name' parameter".to_string()));
+                }
+                let kind = step.get("kind").and_then(|v| v.as_str()).unwrap_or("constant");
+
+                let p = match kind {
+                    "constant" => PreparedStep::AddColumnFixed { value: extract_match_val("value") },
+                    "filename" => {
+                        let filename = Path::new(&self.source).file_name()
+                            .map(|f| f.to_string_lossy().to_string())
+                            .unwrap_or_else(|| self.source.clone());
+                        PreparedStep::AddColumnFixed { value: filename }
+                    },
+                    // Resolved once so every row of a run carries the same timestamp
+                    "timestamp" => PreparedStep::AddColumnFixed { value: Local::now().to_rfc3339() },
+                    "row_number" => PreparedStep::AddColumnRowNumber,
+                    unknown => return Err(PhaetonError::InvalidStep(format!("Unknown add_column kind: {}", unknown))),
+                };
+                columns.push(name.to_string());
+                p
+            },
+            "convert_currency" => {
+                let target = step.get("to").and_then(|v| v.as_str()).unwrap_or("").trim().to_uppercase();
+                let decimals = step.get("decimals").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+                let clean = step.get("clean").and_then(|v| v.as_bool()).unwrap_or(false);
+
+                let rates = match step.get("rates") {
+                    Some(Value::Object(obj)) => {
+                        let mut table = HashMap::new();
+                        for (code, rate) in obj {
+                            let rate = rate.as_f64().ok_or_else(|| PhaetonError::InvalidStep(format!("Rate for '{}' is not a number", code)))?;
+                            let rate = currency::checked_rate(code, rate).map_err(PhaetonError::InvalidStep)?;
+                            table.insert(code.trim().to_uppercase(), rate);
+                        }
+                        table
+                    },
+                    Some(Value::String(path)) => currency::load_rates(path)?,
+                    _ => return Err(PhaetonError::InvalidStep("Convert currency step missing 'rates' parameter".to_string())),
+                };
+
+                if !rates.contains_key(&target) {
+                    return Err(PhaetonError::InvalidStep(format!("No rate defined for target currency '{}'", target)));
+                }
+
+                PreparedStep::ConvertCurrency {
+                    col_idx: col_idx("col")?,
+                    currency_idx: col_idx("currency_col")?,
+                    target,
+                    rates,
+                    decimals,
+                    clean,
+                }
+            },
+            "json_extract" => {
+                let into = step.get("into").and_then(|v| v.as_str()).unwrap_or("");
+                if into.is_empty() {
+                    return Err(PhaetonError::InvalidStep("JSON extract step missing 'into' parameter".to_string()));
+                }
+                let path = step.get("path").and_then(|v| v.as_str()).unwrap_or("$");
+                let p = PreparedStep::JsonExtract { col_idx: col_idx("col")?, path: json::parse_path(path)? };
+                columns.push(into.to_string());
+                p
+            },
+            "format" => {
+                let source = step.get("template").and_then(|v| v.as_str())
+                    .ok_or_else(|| PhaetonError::InvalidStep("Format step missing 'template' parameter".to_string()))?;
+                let template = template::Template::parse(source, get_idx)?;
+                match (step.get("col").filter(|v| !v.is_null()), step.get("into").and_then(|v| v.as_str())) {
+                    (Some(col), None) => PreparedStep::Format { col_idx: Some(value_idx(col)?), template },
+                    (None, Some(into)) if !into.is_empty() => {
+                        columns.push(into.to_string());
+                        PreparedStep::Format { col_idx: None, template }
+                    },
+                    _ => return Err(PhaetonError::InvalidStep("Format step needs exactly one of 'col' and 'into'".to_string())),
+                }
+            },
+            "country" => {
+                let alpha3 = match step.get("format").and_then(|v| v.as_str()).unwrap_or("alpha2") {
+                    "alpha2" => false,
+                    "alpha3" => true,
+                    unknown => return Err(PhaetonError::InvalidStep(format!("Unknown country code format: {}", unknown))),
+                };
+                let threshold = step.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.9);
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(PhaetonError::InvalidStep(format!("Country threshold must be between 0.0 and 1.0, got {}", threshold)));
+                }
+                PreparedStep::Country { col_idx: col_idx("col")?, alpha3, threshold, resolver: country::CountryResolver::new() }
+            },
+            "fingerprint" => {
+                let col_val = step.get("col").unwrap_or(&Value::Null);
+                let into = step.get("into").and_then(|v| v.as_str()).unwrap_or("_fingerprint");
+
+                let col_idxs = match col_val {
+                    Value::Array(arr) => {
+                        let mut indices = Vec::new();
+                        for v in arr {
+                            indices.push(value_idx(v)?);
+                        }
+                        Some(indices)
+                    },
+                    Value::String(s) if s != "*" => Some(vec![get_idx(s)?]),
+                    Value::Number(_) => Some(vec![value_idx(col_val)?]),
+                    _ => None,
+                };
+
+                let p = PreparedStep::Fingerprint { col_idxs };
+                columns.push(into.to_string());
+                p
+            },
+            "expect" => {
+                let rule = match step.get("rule").and_then(|v| v.as_str()).unwrap_or("") {
+                    "not_null" => expect::Rule::NotNull,
+                    "unique" => expect::Rule::Unique,
+                    "in_set" => {
+                        let values = step.get("values").and_then(|v| v.as_array())
+                            .ok_or_else(|| PhaetonError::InvalidStep("Expect in_set needs a 'values' list".to_string()))?;
+                        expect::Rule::InSet(values.iter().map(|v| match v {
+                            Value::String(s) => s.trim().to_string(),
+                            other => other.to_string(),
+                        }).collect())
+                    },
+                    "match" => {
+                        let pattern = step.get("pattern").and_then(|v| v.as_str()).unwrap_or("");
+                        let re = Regex::new(pattern).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", pattern)))?;
+                        expect::Rule::Matches(re)
+                    },
+                    "row_count" => expect::Rule::RowCount {
+                        min: step.get("min").and_then(|v| v.as_u64()),
+                        max: step.get("max").and_then(|v| v.as_u64()),
+                    },
+                    unknown => return Err(PhaetonError::InvalidStep(format!("Unknown expectation rule: {}", unknown))),
+                };
+                let col_idx = match (&rule, step.get("col").filter(|v| !v.is_null())) {
+                    (expect::Rule::RowCount { .. }, _) => None,
+                    (_, Some(col)) => Some(value_idx(col)?),
+                    (_, None) => return Err(PhaetonError::InvalidStep(format!("Expect {} needs a 'col'", rule.name()))),
+                };
+                let mostly = step.get("mostly").and_then(|v| v.as_f64()).unwrap_or(1.0);
+                let severity = expect::Severity::parse(step.get("severity").and_then(|v| v.as_str()).unwrap_or("warn"))?;
+                let column = col_idx.map(|i| columns[i].clone());
+                PreparedStep::Expect { col_idx, check: Expectation::new(rule, column, mostly, severity)? }
+            },
+            "rename" | "headers" => return Ok(None),
+            _ => return Ok(None), 
+        };
+        Ok(Some(p_step))
+    }
+
+    /// Dry-run check of the whole pipeline: reads only the header row, then
+    /// resolves columns and compiles every step, collecting all problems
+    /// instead of stopping at the first one.
+    pub fn validate(&self) -> Result<Vec<Diagnostic>> {
+        let file = File::open(&self.source).map_err(|_| PhaetonError::FileNotFound(self.source.clone()))?;
+        let mut reader = ReaderBuilder::new().has_headers(true).delimiter(self.delimiter).from_reader(BufReader::new(file));
+        let headers = self.read_headers(&mut reader)?;
+
+        let mut found = Vec::new();
+        let mut columns: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let action = step.get("action").and_then(|v| v.as_str()).unwrap_or("");
+            found.extend(validate::check_step(i, step, self.strict));
+
+            if action == "rename" {
+                if let Some(mapping) = step.get("mapping").and_then(|v| v.as_object()) {
+                    for old in mapping.keys().filter(|old| !columns.iter().any(|h| self.header_match.matches(h, old))) {
+                        found.push(Diagnostic::warning(Some(i), action, format!("Rename source column '{}' not found", old)));
+                    }
+                }
+            }
+
+            match self.compile_step(step, &mut columns) {
+                Ok(Some(prepared)) => {
+                    if let Some(w) = prepared.warning() { found.push(Diagnostic::warning(Some(i), action, w)); }
+                },
+                Ok(None) => {},
+                Err(e) => found.push(Diagnostic::error(Some(i), action, e.to_string())),
+            }
+        }
+        Ok(found)
+    }
+
+    /// Describe what each step will do, in execution order, with columns
+    /// resolved against the source headers. Nothing beyond the header row is read.
+    pub fn explain(&self) -> Result<Vec<PlanStep>> {
+        let file = File::open(&self.source).map_err(|_| PhaetonError::FileNotFound(self.source.clone()))?;
+        let mut reader = ReaderBuilder::new().has_headers(true).delimiter(self.delimiter).from_reader(BufReader::new(file));
+        let headers = self.read_headers(&mut reader)?;
+
+        let mut plan = Vec::new();
+        let mut columns: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let action = step.get("action").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            let before = columns.len();
+
+            let summary = match self.compile_step(step, &mut columns)? {
+                Some(prepared) => prepared.summary(),
+                None => match action.as_str() {
+                    "rename" => {
+                        let pairs: Vec<String> = step.get("mapping").and_then(|v| v.as_object())
+                            .map(|m| m.iter().map(|(old, new)| format!("{} -> {}", old, new.as_str().unwrap_or(""))).collect())
+                            .unwrap_or_default();
+                        StepSummary { effect: "header", col_idxs: Vec::new(), detail: format!("Rename output headers: {}", pairs.join(", ")), quarantines: false }
+                    },
+                    "headers" => {
+                        let style = step.get("style").and_then(|v| v.as_str()).unwrap_or("");
+                        StepSummary { effect: "header", col_idxs: Vec::new(), detail: format!("Convert output headers to {} case", style), quarantines: false }
+                    },
+                    "dump" => {
+                        let path = step.get("path").and_then(|v| v.as_str()).unwrap_or("");
+                        StepSummary { effect: "output", col_idxs: Vec::new(), detail: format!("Write kept rows to {}", path), quarantines: false }
+                    },
+                    _ => StepSummary { effect: "ignored", col_idxs: Vec::new(), detail: "Not executed by the engine".into(), quarantines: false },
+                },
+            };
+
+            plan.push(PlanStep {
+                step: i,
+                action,
+                effect: summary.effect,
+                columns: summary.col_idxs.iter().map(|&index| PlanColumn { index, name: columns[index].clone() }).collect(),
+                adds_columns: columns[before..].to_vec(),
+                quarantines: summary.quarantines,
+                detail: summary.detail,
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Run the steps over the first `n` source records and report what would happen
+    /// to each one: kept, quarantined, skipped or failed, with the reason.
+    /// Nothing is written; a row that would abort the run ends the preview.
+    pub fn peek(&self, n: usize, target_columns: Option<Vec<String>>) -> Result<Preview> {
+        let file = File::open(&self.source).map_err(|_| PhaetonError::FileNotFound(self.source.clone()))?;
+        let reader = BufReader::new(file);
+        let mut csv_reader = ReaderBuilder::new().has_headers(true).delimiter(self.delimiter).flexible(true).from_reader(reader);
+        
+        let file_headers = self.read_headers(&mut csv_reader)?;
+
+        let display_headers = self.transform_headers(&file_headers);
+
+        let (prepared_steps, origins) = self.compile_steps_indexed(&file_headers)?;
+
+        let (indices_to_show, final_output_headers): (Vec<usize>, Vec<String>) = match target_columns {
+            Some(cols) => {
+                let mut idxs = Vec::new();
+                let mut headers_vec = Vec::new();
+                
+                for requested_col in cols {
+                    let found = file_headers.iter().position(|h| h == requested_col)
+                        .or_else(|| display_headers.iter().position(|h| h == requested_col))
+                        .or_else(|| file_headers.iter().position(|h| self.header_match.matches(h, &requested_col)));
+                    if let Some(idx) = found {
+                        idxs.push(idx);
+                        headers_vec.push(display_headers.get(idx).unwrap_or("").to_string());
+                    }
+                }
+                (idxs, headers_vec)
+            },
+            None => {
+                let len = display_headers.len();
+                (
+                    (0..len).collect(), 
+                    display_headers.iter().map(|h| h.to_string()).collect()
+                )
+            }
+        };
+
+        let mut preview = Preview { headers: final_output_headers, rows: Vec::new() };
+        if indices_to_show.is_empty() && !preview.headers.is_empty() {
+            return Ok(preview);
+        }
+
+        // Rejected rows go where `execute` would send them
+        let rejected = match self.on_error {
+            ErrorStrategy::Quarantine => "quarantined",
+            ErrorStrategy::Skip => "skipped",
+            ErrorStrategy::Fail => "failed",
+        };
+        let values = |record: &ByteRecord| -> Vec<String> {
+            indices_to_show.iter().map(|&i| String::from_utf8_lossy(record.get(i).unwrap_or_default()).into_owned()).collect()
+        };
+
+        // Number scrubs left to guess the decimal separator, as (step position, column)
+        let guessing: Vec<(usize, usize)> = prepared_steps.iter().zip(&origins)
+            .filter_map(|(step, &origin)| match step {
+                PreparedStep::Scrub { col_idx, mode, decimal: None, .. } if mode == "currency" || mode == "percent" => Some((origin, *col_idx)),
+                _ => None,
+            })
+            .collect();
+        let notes = |record: &ByteRecord| -> Vec<String> {
+            guessing.iter().filter_map(|&(origin, idx)| {
+                let value = String::from_utf8_lossy(record.get(idx)?);
+                let sep = text::ambiguous_separator(&value)?;
+                Some(format!(
+                    "Step {} read '{}' in '{}' with '{}' as a thousands separator; set decimal='{}' if it is the decimal mark",
+                    origin, value.trim(), display_headers.get(idx).unwrap_or(""), sep, sep
+                ))
+            }).collect()
+        };
+
+        let readers = first_readers(&prepared_steps, file_headers.len());
+        for (row_idx, result) in csv_reader.byte_records().take(n).enumerate() {
+            let row = row_idx as u64 + 1;
+            let entry = match result {
+                Err(e) => PreviewRow { row, status: rejected, step: None, reason: Some(format!("Parse: {}", e)), values: Vec::new(), notes: Vec::new() },
+                Ok(mut record) => match self.ragged.fit(&mut record, file_headers.len()) {
+                    Some(reason) => {
+                        let status = if self.ragged == RaggedPolicy::Error { "failed" } else { rejected };
+                        PreviewRow { row, status, step: None, reason: Some(reason), values: values(&record), notes: Vec::new() }
+                    },
+                    None => {
+                        let notes = notes(&record);
+                        match apply_pipeline(&record, &prepared_steps, &readers, row, None) {
+                            RowResult::Keep(rec) => PreviewRow { row, status: "kept", step: None, reason: None, values: values(&rec), notes },
+                            RowResult::Discarded(step_idx, rec, reason) => {
+                                PreviewRow { row, status: "quarantined", step: Some(origins[step_idx]), reason: Some(reason), values: values(&rec), notes }
+                            },
+                            RowResult::Failed(step_idx, rec, reason) => {
+                                PreviewRow { row, status: rejected, step: Some(origins[step_idx]), reason: Some(reason), values: values(&rec), notes }
+                            },
+                        }
+                    },
+                },
+            };
+            let stop = entry.status == "failed";
+            preview.rows.push(entry);
+            if stop { break; }
+        }
+
+        Ok(preview)
+    }
+
+    pub fn execute(&self, output_path: &str, quarantine_path: Option<&str>) -> Result<ExecutionStats> {
+        let output_delimiter = self.output_delimiter.unwrap_or(self.delimiter);
+        let open_clean = || -> Result<_> {
+            let (out_file, new) = open_output(output_path, self.append)?;
+            Ok((WriterBuilder::new().delimiter(output_delimiter).from_writer(BufWriter::new(out_file)), new))
+        };
+        self.run_into(open_clean, Some(output_path), quarantine_path).map(|(stats, _)| stats)
+    }
+
+    /// Run the pipeline keeping the clean rows in memory as an Arrow IPC stream.
+    /// Rejected rows still go to the quarantine file.
+    pub fn execute_to_arrow(&self, quarantine_path: Option<&str>) -> Result<(ExecutionStats, ArrowTable)> {
+        let (stats, sink) = self.run_into(|| Ok((ArrowSink::default(), true)), None, quarantine_path)?;
+        Ok((stats, sink.finish()?))
+    }
+
+    /// Run the pipeline, writing kept rows to the sink `open_clean` creates once the
+    /// source headers are read and the steps compiled (so a missing source or an
+    /// invalid step leaves no empty output behind),
+    /// along with whether it still needs a header row; returns the stats and the sink
+    fn run_into<S: RowSink + Send>(
+        &self,
+        open_clean: impl FnOnce() -> Result<(S, bool)>,
+        clean_path: Option<&str>,
+        quarantine_path: Option<&str>,
+    ) -> Result<(ExecutionStats, S)> {
+        let start = Instant::now();
+        let arrow_input = self.arrow_input.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+        let (input, original_headers, total_bytes) = match arrow_input {
+            Some(stream) => {
+                let headers = self.arrow_headers(&stream);
+                (Input::Arrow(stream), headers, 0)
+            },
+            None => {
+                let (source, total_bytes) = SourceReader::open(&self.source, self.read_mode)?;
+                // Field counts are checked against the header by the ragged policy, not by the parser
+                let mut reader = ReaderBuilder::new().has_headers(true).delimiter(self.delimiter).flexible(true).from_reader(source);
+                let headers = self.read_headers(&mut reader)?;
+                if let Some(resume) = self.resume.filter(|p| p.byte > 0) {
+                    let mut position = csv::Position::new();
+                    position.set_byte(resume.byte).set_line(resume.line).set_record(resume.rows + 1);
+                    reader.seek(position)?;
+                }
+                let complete_until = self.whole_lines.then(|| complete_lines_end(&self.source, total_bytes)).transpose()?;
+                // Progress covers the bytes this run reads
+                (Input::Csv(reader, complete_until), headers, total_bytes.saturating_sub(self.resume.map_or(0, |p| p.byte)))
+            },
+        };
+        let final_headers = self.transform_headers(&original_headers);
+
+        // Steps and routes are compiled before any output is opened, so an invalid
+        // one leaves existing files untouched
+        let (prepared_steps, origins) = self.prepare_steps(&original_headers)?;
+        debug!(source = %self.source, columns = original_headers.len(), steps = self.steps.len(), prepared = prepared_steps.len(), "Compiled pipeline");
+
+        let output_delimiter = self.output_delimiter.unwrap_or(self.delimiter);
+        let final_columns: Vec<String> = final_headers.iter().map(str::to_string).collect();
+        let reserved: Vec<&str> = clean_path.into_iter().chain(quarantine_path).collect();
+        let mut routes = route::compile_routes(
+            &self.routes, &|name| self.header_match.resolve(&final_columns, name), output_delimiter, &reserved,
+        )?;
+
+        let (mut clean, new) = open_clean()?;
+        if new { clean.write_header(&final_headers)?; }
+
+        let quarantine_writer = if let Some(path) = quarantine_path {
+            let (q_file, new) = open_output(path, self.append)?;
+            let mut w = WriterBuilder::new().delimiter(output_delimiter).from_writer(BufWriter::new(q_file));
+            if new {
+                let mut q_headers = final_headers.clone();
+                q_headers.push_field("_phaeton_reason");
+                if self.quarantine_raw { q_headers.push_field("_phaeton_raw"); }
+                w.write_record(&q_headers)?;
+            }
+            Some(w)
+        } else { None };
+
+        route::open_routes(&mut routes, &final_headers, self.append)?;
+
+        let clocks: Option<Vec<StepClock>> = metrics::step_timings_enabled()
+            .then(|| prepared_steps.iter().map(|_| StepClock::default()).collect());
+        let readers = first_readers(&prepared_steps, original_headers.len());
+
+        // Reading, processing and writing run as three stages joined by bounded
+        // channels, so disk I/O overlaps the CPU-bound steps
+        let (batch_tx, batch_rx) = mpsc::sync_channel::<ReadBatch>(PIPELINE_DEPTH);
+        let (write_tx, write_rx) = mpsc::sync_channel::<WriteMsg>(PIPELINE_DEPTH);
+        // Emptied batch buffers go back to the reader instead of being reallocated
+        let (spare_tx, spare_rx) = mpsc::channel::<Vec<ByteRecord>>();
+
+        let (read_result, write_result) = std::thread::scope(|scope| {
+            let width = original_headers.len();
+            let read_stage = scope.spawn(move || match input {
+                Input::Csv(reader, complete_until) => self.read_stage(reader, width, complete_until, batch_tx, spare_rx),
+                Input::Arrow(stream) => self.read_arrow_stage(stream, batch_tx, spare_rx),
+            });
+            let write_stage = scope.spawn(|| -> Result<(Totals, S)> {
+                let mut sink = BatchSink {
+                    processor: self,
+                    clean_writer: clean,
+                    quarantine_writer,
+                    width: final_headers.len(),
+                    totals: Totals { step_rejected: vec![0; prepared_steps.len()], ..Totals::default() },
+                    headers: &final_headers,
+                    origins: &origins,
+                    rejected: Vec::new(),
+                    routes,
+                };
+                sink.drain(write_rx, start, total_bytes)?;
+                Ok((sink.totals, sink.clean_writer))
+            });
+
+            self.process_stage(batch_rx, write_tx, spare_tx, &prepared_steps, &readers, clocks.as_deref());
+            (
+                read_stage.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+                write_stage.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            )
+        });
+        // A failed write stops the other stages, so it is the root cause when both fail
+        let (totals, clean) = write_result?;
+        let ReadSummary { end, cancelled } = read_result?;
+        let bytes_read = end.byte.saturating_sub(self.resume.map_or(0, |p| p.byte));
+
+        let elapsed = start.elapsed();
+        // Sub-millisecond runs would otherwise divide by zero
+        let secs = elapsed.as_secs_f64().max(1e-3);
+
+        if let Some(clocks) = &clocks {
+            for (&origin, clock) in origins.iter().zip(clocks) {
+                metrics::add_step_time(self.steps[origin].get("action").and_then(|v| v.as_str()).unwrap_or(""), clock);
+            }
+        }
+
+        let mut warnings: Vec<String> = prepared_steps.iter().zip(&origins)
+            .filter_map(|(step, &origin)| step.warning().map(|w| {
+                format!("Step {} ({}): {}", origin, self.steps[origin].get("action").and_then(|v| v.as_str()).unwrap_or(""), w)
+            }))
+            .collect();
+
+        // Keys seen by this run, for the next runs sharing the key store
+        // and the vault entries of the tokens handed out
+        for (step, &origin) in prepared_steps.iter().zip(&origins) {
+            let (action, saved) = match step {
+                PreparedStep::Dedupe { state, .. } => ("dedupe", state.save()),
+                PreparedStep::Tokenize { vault, .. } => ("tokenize", vault.save()),
+                _ => continue,
+            };
+            if let Err(e) = saved {
+                warn!(source = %self.source, error = %e, "Step state not saved");
+                warnings.push(format!("Step {} ({}): state was not saved: {}", origin, action, e));
+            }
+        }
+
+        let expectations = prepared_steps.iter().zip(&origins)
+            .filter_map(|(step, &origin)| match step {
+                PreparedStep::Expect { check, .. } => Some(check.outcome(origin)),
+                _ => None,
+            })
+            .collect();
+
+        let steps = origins.into_iter().zip(totals.step_rejected)
+            .map(|(step, rejected)| StepStats {
+                step,
+                action: self.steps[step].get("action").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                rejected,
+            })
+            .collect();
+
+        let stats = ExecutionStats {
+            steps,
+            processed: totals.processed,
+            saved: totals.saved,
+            quarantined: totals.quarantined,
+            skipped: totals.skipped,
+            malformed: totals.malformed,
+            bytes_read,
+            duration: elapsed,
+            rows_per_sec: totals.processed as f64 / secs,
+            bytes_per_sec: bytes_read as f64 / secs,
+            cancelled,
+            warnings,
+            expectations,
+            end,
+            routes: totals.routes,
+        };
+        Ok((stats, clean))
+    }
+
+    /// Stage 1: parse the source into batches. Stops at EOF, on cancellation, on a
+    /// parse error under `ErrorStrategy::Fail`, or when the later stages are gone.
+    fn read_stage(
+        &self,
+        reader: csv::Reader<SourceReader>,
+        width: usize,
+        complete_until: Option<u64>,
+        batches: SyncSender<ReadBatch>,
+        spare: Receiver<Vec<ByteRecord>>,
+    ) -> Result<ReadSummary> {
+        let mut iter = reader.into_byte_records();
+        let resumed_at = self.resume.unwrap_or_default();
+        let mut next_row = resumed_at.rows + 1;
+        // Where the records stop, once the reader has moved past them
+        let position = |reader: &csv::Reader<SourceReader>, next_row: u64| SourcePosition {
+            byte: reader.position().byte(),
+            line: reader.position().line(),
+            rows: next_row - 1,
+        };
+        // Start of a record that runs past the last line terminator
+        let mut partial: Option<csv::Position> = None;
+
+        // Batches in flight take at most a quarter of the memory that was free at the start
+        let budget = self.batch_memory.map(|budget| match available_memory() {
+            Some(free) => budget.min(free as usize / (BATCHES_IN_FLIGHT * 4)),
+            None => budget,
+        });
+        let mut batch_rows = match budget {
+            Some(_) => self.batch_size.min(ADAPTIVE_FIRST_BATCH),
+            None => self.batch_size,
+        };
+
+        loop {
+            let bytes_read = iter.reader().position().byte();
+            // Checked between batches so outputs only ever hold whole batches
+            if self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+                info!(source = %self.source, processed = next_row - 1 - resumed_at.rows, "Run cancelled");
+                return Ok(ReadSummary { end: position(iter.reader(), next_row), cancelled: true });
+            }
+
+            let started = Instant::now();
+            let mut records = spare.try_recv().unwrap_or_else(|_| Vec::with_capacity(batch_rows));
+            let mut malformed = Vec::new();
+            let mut eof = false;
+
+            while records.len() < batch_rows {
+                let before = complete_until.map(|_| iter.reader().position().clone());
+                let item = iter.next();
+                if let (Some(limit), Some(before), Some(_)) = (complete_until, before, &item) {
+                    if iter.reader().position().byte() > limit {
+                        debug!(source = %self.source, byte = before.byte(), "Unterminated last line left for the next run");
+                        partial = Some(before);
+                        eof = true;
+                        break;
+                    }
+                }
+                match item {
+                    Some(Ok(mut record)) => {
+                        let Some(reason) = self.ragged.fit(&mut record, width) else {
+                            records.push(record);
+                            continue;
+                        };
+                        let row = next_row + (records.len() + malformed.len()) as u64;
+                        if self.ragged == RaggedPolicy::Error || self.on_error == ErrorStrategy::Fail {
+                            return Err(PhaetonError::RowFailed { row, reason });
+                        }
+                        let raw = match (self.quarantine_raw, record.position()) {
+                            (true, Some(pos)) => self.raw_range(pos.byte(), iter.reader().position().byte()),
+                            _ => None,
+                        };
+                        malformed.push(MalformedRecord { row, reason, raw, fields: Some(record) });
+                    },
+                    Some(Err(e)) => {
+                        warn!(source = %self.source, error = %e, "Malformed record");
+                        if self.on_error == ErrorStrategy::Fail { return Err(e.into()); }
+                        // The parser does not hand back the offending record; its source
+                        // text is read back from the file when asked for
+                        let raw = match (self.quarantine_raw, e.position()) {
+                            (true, Some(pos)) => self.raw_range(pos.byte(), iter.reader().position().byte()),
+                            _ => None,
+                        };
+                        let row = next_row + (records.len() + malformed.len()) as u64;
+                        malformed.push(MalformedRecord { row, reason: format!("Parse: {}", e), raw, fields: None });
+                    },
+                    None => { eof = true; break; },
+                }
+            }
+
+            let batch_end = iter.reader().position().byte();
+            if let (Some(budget), Some(first)) = (budget, records.first()) {
+                let rows = rows_for_budget(budget, batch_end - bytes_read, records.len(), first.len());
+                // Drifts under 10% are ignored so the size settles instead of jittering
+                if rows.abs_diff(batch_rows) * 10 > batch_rows {
+                    debug!(source = %self.source, rows, budget, "Batch size adjusted");
+                    batch_rows = rows;
+                }
+            }
+            if !records.is_empty() || !malformed.is_empty() {
+                let batch = ReadBatch { first_row: next_row, bytes_read: batch_end - resumed_at.byte, bytes: batch_end - bytes_read, started, records, malformed };
+                next_row += (batch.records.len() + batch.malformed.len()) as u64;
+                if batches.send(batch).is_err() { eof = true; }
+            }
+            if eof {
+                let end = match &partial {
+                    Some(start) => SourcePosition { byte: start.byte(), line: start.line(), rows: next_row - 1 },
+                    None => position(iter.reader(), next_row),
+                };
+                return Ok(ReadSummary { end, cancelled: false });
+            }
+        }
+    }
+
+    /// Stage 1 for Arrow input: each record batch, split to `batch_size` rows, becomes
+    /// a batch of byte records. Progress counts the in-memory size of the arrays read.
+    fn read_arrow_stage(
+        &self,
+        stream: ArrowArrayStreamReader,
+        batches: SyncSender<ReadBatch>,
+        spare: Receiver<Vec<ByteRecord>>,
+    ) -> Result<ReadSummary> {
+        let mut next_row = 1;
+        let mut bytes_read = 0;
+
+        for batch in stream {
+            let batch = batch?;
+            // Slices share the batch buffers, so progress gets each chunk's share of them
+            let row_bytes = batch.get_array_memory_size() as u64 / batch.num_rows().max(1) as u64;
+            let mut offset = 0;
+            while offset < batch.num_rows() {
+                if self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed)) {
+                    info!(source = %self.source, processed = next_row - 1, "Run cancelled");
+                    return Ok(ReadSummary { end: SourcePosition { byte: bytes_read, line: 0, rows: next_row - 1 }, cancelled: true });
+                }
+
+                let started = Instant::now();
+                let chunk = batch.slice(offset, self.batch_size.min(batch.num_rows() - offset));
+                offset += chunk.num_rows();
+                let mut records = spare.try_recv().unwrap_or_else(|_| Vec::with_capacity(chunk.num_rows()));
+                frame::append_records(&chunk, &mut records)?;
+
+                let bytes = row_bytes * chunk.num_rows() as u64;
+                bytes_read += bytes;
+                let batch = ReadBatch { first_row: next_row, bytes_read, bytes, started, records, malformed: Vec::new() };
+                next_row += batch.records.len() as u64;
+                if batches.send(batch).is_err() {
+                    return Ok(ReadSummary { end: SourcePosition { byte: bytes_read, line: 0, rows: next_row - 1 }, cancelled: false });
+                }
+            }
+        }
+        Ok(ReadSummary { end: SourcePosition { byte: bytes_read, line: 0, rows: next_row - 1 }, cancelled: false })
+    }
+
+    /// Source bytes in `start..end`, without the line terminator. None for sources that
+    /// cannot be re-read (pipes) and for spans over `MAX_RAW_BYTES`.
+    fn raw_range(&self, start: u64, end: u64) -> Option<Vec<u8>> {
+        let len = end.checked_sub(start).filter(|&len| len <= MAX_RAW_BYTES)?;
+        let mut file = File::open(&self.source).ok()?;
+        if !file.metadata().ok()?.is_file() { return None; }
+        file.seek(SeekFrom::Start(start)).ok()?;
+        let mut raw = vec![0; len as usize];
+        file.read_exact(&mut raw).ok()?;
+        while raw.last().is_some_and(|b| *b == b'\n' || *b == b'\r') { raw.pop(); }
+        Some(raw)
+    }
+
+    /// Stage 2: run the steps over each batch on the rayon pool. In order-preserving
+    /// mode a batch is handed over whole; otherwise every chunk goes to the writer
+    /// as soon as a worker finishes it.
+    fn process_stage(
+        &self,
+        batches: Receiver<ReadBatch>,
+        writes: SyncSender<WriteMsg>,
+        spare: Sender<Vec<ByteRecord>>,
+        steps: &[PreparedStep],
+        readers: &[Option<usize>],
+        clocks: Option<&[StepClock]>,
+    ) {
+        for batch in batches {
+            let ReadBatch { mut records, malformed, first_row, bytes_read, bytes, started } = batch;
+            let malformed_rows = malformed.len() as u64;
+            // Rejected records hold row numbers too: `gaps[k]` is the index of the record
+            // the k-th of them came before
+            let gaps: Vec<usize> = malformed.iter().enumerate().map(|(k, m)| (m.row - first_row) as usize - k).collect();
+            let row_of = |i: usize| first_row + (i + gaps.partition_point(|&at| at <= i)) as u64;
+            if !malformed.is_empty() && writes.send(WriteMsg::Malformed(malformed)).is_err() { return; }
+
+            if self.preserve_order {
+                let results: Vec<(u64, RowResult)> = records.par_iter()
+                    .enumerate()
+                    .map(|(i, record)| (row_of(i), apply_pipeline(record, steps, readers, row_of(i), clocks)))
+                    .collect();
+                if writes.send(WriteMsg::Rows(results)).is_err() { return; }
+            } else {
+                let chunk_len = (records.len() / (rayon::current_num_threads() * 4)).clamp(64, 4096);
+                records.par_chunks(chunk_len).enumerate().for_each_with(writes.clone(), |writes, (c, chunk)| {
+                    let results = chunk.iter().enumerate()
+                        .map(|(i, record)| {
+                            let row = row_of(c * chunk_len + i);
+                            (row, apply_pipeline(record, steps, readers, row, clocks))
+                        })
+                        .collect();
+                    // A closed channel means the writer failed; the loop below notices
+                    let _ = writes.send(WriteMsg::Rows(results));
+                });
+            }
+
+            let end = WriteMsg::BatchEnd { rows: records.len() as u64 + malformed_rows, bytes_read, bytes, started };
+            if writes.send(end).is_err() { return; }
+            records.clear();
+            let _ = spare.send(records);
+        }
+    }
+}
+
+/// Batches in flight between two stages: enough to keep every stage busy while
+/// bounding memory to a few batches
+const PIPELINE_DEPTH: usize = 2;
+
+/// Batches alive at once: read-ahead and write queues plus the one being processed
+const BATCHES_IN_FLIGHT: usize = 2 * PIPELINE_DEPTH + 1;
+
+/// Adaptive sizing starts with a small batch to measure the rows
+const ADAPTIVE_FIRST_BATCH: usize = 1_000;
+const ADAPTIVE_MIN_ROWS: usize = 256;
+const ADAPTIVE_MAX_ROWS: usize = 1_000_000;
+
+/// Default per-batch budget when `batch_size="auto"` is used without `batch_memory`
+pub const DEFAULT_BATCH_MEMORY: usize = 16 * 1024 * 1024;
+
+/// Rows that fit in `budget` bytes, from a batch of `rows` rows spanning `bytes` of input
+fn rows_for_budget(budget: usize, bytes: u64, rows: usize, fields: usize) -> usize {
+    // Each row is held as the parsed record and the record written out: field bytes and
+    // one bound per field for both, plus the fixed cost of the record and its result
+    let avg_bytes = bytes as usize / rows.max(1);
+    let per_row = 2 * (avg_bytes + fields * std::mem::size_of::<usize>()) + 128;
+    (budget / per_row).clamp(ADAPTIVE_MIN_ROWS, ADAPTIVE_MAX_ROWS)
+}
+
+/// `MemAvailable` from /proc/meminfo; None where it cannot be read
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Longest malformed record whose source text is copied to quarantine
+const MAX_RAW_BYTES: u64 = 64 * 1024;
+
+/// A record the CSV parser rejected
+struct MalformedRecord {
+    /// Source row number; rejected records are counted like any other
+    row: u64,
+    reason: String,
+    /// Source text, when `quarantine_raw` is on and the source can be re-read
+    raw: Option<Vec<u8>>,
+    /// Parsed fields of ragged records; parse errors have none
+    fields: Option<ByteRecord>,
+}
+
+/// A batch as parsed by the read stage
+struct ReadBatch {
+    records: Vec<ByteRecord>,
+    /// Reasons of the records that failed to parse, for the Skip/Quarantine strategies
+    malformed: Vec<MalformedRecord>,
+    /// Source row number of the first record, kept or rejected (1-based)
+    first_row: u64,
+    /// Reader position after the batch
+    bytes_read: u64,
+    bytes: u64,
+    started: Instant,
+}
+
+/// Work for the write stage, in the order it has to be written
+enum WriteMsg {
+    Malformed(Vec<MalformedRecord>),
+    /// Results of rows in source order, with their row numbers
+    Rows(Vec<(u64, RowResult)>),
+    /// Every row of the batch has been sent
+    BatchEnd { rows: u64, bytes_read: u64, bytes: u64, started: Instant },
+}
+
+struct ReadSummary {
+    /// Position after the last record read; for Arrow input `byte` is the bytes read
+    end: SourcePosition,
+    cancelled: bool,
+}
+
+#[derive(Default)]
+struct Totals {
+    processed: u64,
+    saved: u64,
+    quarantined: u64,
+    skipped: u64,
+    malformed: u64,
+    step_rejected: Vec<u64>,
+    routes: Vec<RouteStats>,
+}
+
+/// Where the rows of a run come from
+enum Input {
+    /// With the byte offset where complete lines end, when only those are read
+    Csv(csv::Reader<SourceReader>, Option<u64>),
+    Arrow(ArrowArrayStreamReader),
+}
+
+/// Destination of the kept rows: the CSV output file, or an in-memory table
+pub trait RowSink {
+    fn write_header(&mut self, headers: &StringRecord) -> Result<()>;
+    fn write_row(&mut self, record: &ByteRecord) -> Result<()>;
+    fn flush(&mut self) -> Result<()>;
+}
+
+impl<W: std::io::Write> RowSink for csv::Writer<W> {
+    fn write_header(&mut self, headers: &StringRecord) -> Result<()> {
+        Ok(self.write_record(headers)?)
+    }
+
+    fn write_row(&mut self, record: &ByteRecord) -> Result<()> {
+        Ok(self.write_byte_record(record)?)
+    }
 
-        for step in &self.steps {
-            let action = step.get("action").and_then(|v| v.as_str()).unwrap_or("");
-            
-            let extract_match_val = |key: &str| -> String {
-                step.get(key).map(|v| match v {
-                    Value::String(s) => s.clone(),
-                    Value::Number(n) => n.to_string(),
-                    Value::Bool(b) => b.to_string(),
-                    _ => String::new(),
-                }).unwrap_or_default()
-            };
+    fn flush(&mut self) -> Result<()> {
+        Ok(csv::Writer::flush(self)?)
+    }
+}
 
-            let p_step = match action {
-                "keep" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("exact");
-                    let idx = get_idx(col)?;
-                    
-                    if mode == "regex" {
-                        let match_val = extract_match_val("match");
-                        let re = Regex::new(&match_val).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", match_val)))?;
-                        PreparedStep::KeepRegex { col_idx: idx, re }
-                    } else if let Some(Value::Array(arr)) = step.get("match") {
-                        let patterns: Vec<String> = arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect();
-                        PreparedStep::KeepMultiString { col_idx: idx, patterns, mode: mode.to_string() }
-                    } else {
-                        let match_val = extract_match_val("match");
-                        PreparedStep::KeepString { col_idx: idx, pattern: match_val, mode: mode.to_string() }
-                    }
-                },
-                "discard" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("exact");
-                    let idx = get_idx(col)?;
-
-                    if mode == "regex" {
-                        let match_val = extract_match_val("match");
-                        let re = Regex::new(&match_val).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", match_val)))?;
-                        PreparedStep::DiscardRegex { col_idx: idx, re }
-                    } else if let Some(Value::Array(arr)) = step.get("match") {
-                        let patterns: Vec<String> = arr.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect();
-                        PreparedStep::DiscardMultiString { col_idx: idx, patterns, mode: mode.to_string() }
-                    } else {
-                        let match_val = extract_match_val("match");
-                        PreparedStep::DiscardString { col_idx: idx, pattern: match_val, mode: mode.to_string() }
-                    }
-                },
-                "prune" => {
-                    let col_val = step.get("col").unwrap_or(&Value::Null);
-                    match col_val {
-                        Value::Array(arr) => {
-                            let mut indices = Vec::new();
-                            for v in arr {
-                                if let Some(col_name) = v.as_str() { indices.push(get_idx(col_name)?); }
-                            }
-                            PreparedStep::PruneSelected { col_idxs: indices }
-                        },
-                        Value::String(s) => {
-                            if s == "*" { PreparedStep::Prune { col_idx: None } } 
-                            else { PreparedStep::Prune { col_idx: Some(get_idx(s)?) } }
-                        },
-                        _ => PreparedStep::Prune { col_idx: None }
+/// Open an output file, replacing it unless `append` is set. Returns the file and
+/// whether it needs a header row (it is new or empty).
+fn open_output(path: &str, append: bool) -> Result<(File, bool)> {
+    if !append {
+        return Ok((File::create(path)?, true));
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let new = file.metadata()?.len() == 0;
+    Ok((file, new))
+}
+
+/// Stage 3: the output files and the counters, owned by the writer thread
+struct BatchSink<'a, S: RowSink, W: std::io::Write> {
+    processor: &'a StreamProcessor,
+    clean_writer: S,
+    quarantine_writer: Option<csv::Writer<W>>,
+    /// Number of output columns, which quarantined rows are padded to
+    width: usize,
+    totals: Totals,
+    headers: &'a StringRecord,
+    /// Original step index of each prepared step
+    origins: &'a [usize],
+    /// Quarantined rows not yet handed to the `on_quarantine` callback
+    rejected: Vec<QuarantinedRow>,
+    /// Kept rows matching one of these go to it instead of `clean_writer`
+    routes: Vec<RouteSink>,
+}
+
+impl<S: RowSink, W: std::io::Write> BatchSink<'_, S, W> {
+    fn drain(&mut self, messages: Receiver<WriteMsg>, run_started: Instant, total_bytes: u64) -> Result<()> {
+        let mut before = (0, 0, 0);
+
+        for message in messages {
+            match message {
+                WriteMsg::Malformed(records) => {
+                    for record in records {
+                        self.totals.malformed += 1;
+                        if self.processor.on_error == ErrorStrategy::Skip {
+                            self.totals.skipped += 1;
+                            continue;
+                        }
+                        self.hand_over(Some(record.row), None, record.fields.as_ref(), &record.reason);
+                        if let Some(q_writer) = &mut self.quarantine_writer {
+                            let mut q_rec = record.fields.unwrap_or_default();
+                            // Fitted to the output columns so the reason stays in its column
+                            q_rec.truncate(self.width);
+                            while q_rec.len() < self.width { q_rec.push_field(b""); }
+                            q_rec.push_field(record.reason.as_bytes());
+                            if self.processor.quarantine_raw { q_rec.push_field(record.raw.as_deref().unwrap_or_default()); }
+                            q_writer.write_byte_record(&q_rec)?;
+                        }
+                        self.totals.quarantined += 1;
                     }
                 },
-                "scrub" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let mode = step.get("mode").and_then(|v| v.as_str()).unwrap_or("trim").to_string();
-                    PreparedStep::Scrub { col_idx: get_idx(col)?, mode }
-                },
-                "cast" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let dtype = step.get("type").and_then(|v| v.as_str()).unwrap_or("str").to_string();
-                    let clean = step.get("clean").and_then(|v| v.as_bool()).unwrap_or(false);
-                    PreparedStep::Cast { col_idx: get_idx(col)?, dtype, clean }
-                },
-                "fill" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let idx = get_idx(col)?;
-                    
-                    let method = step.get("method").and_then(|v| v.as_str()).unwrap_or("fixed");
-                    
-                    if method == "ffill" {
-                        let state = Arc::new(Mutex::new(String::new()));
-                        PreparedStep::FillForward { col_idx: idx, last_valid: state }
-                    } else {
-                        let fill_val = extract_match_val("value"); 
-                        PreparedStep::FillFixed { col_idx: idx, value: fill_val }
+                WriteMsg::Rows(results) => {
+                    for (row, res) in results {
+                        self.write(row, res)?;
                     }
                 },
-                "dedupe" => {
-                    let col_val = step.get("col").unwrap_or(&Value::Null);
-                    
-                    let col_idxs = match col_val {
-                        Value::Array(arr) => {
-                            let mut indices = Vec::new();
-                            for v in arr {
-                                if let Some(col_name) = v.as_str() {
-                                    indices.push(get_idx(col_name)?);
-                                }
-                            }
-                            Some(indices)
-                        },
-                        
-                        Value::String(s) => {
-                            if s == "*" {
-                                None
-                            } else {
-                                Some(vec![get_idx(s)?])
-                            }
-                        },
-                        
-                        _ => None,
-                    };
-                    
-                    let state = Arc::new(DedupeState::new());
-                    
-                    PreparedStep::Dedupe { col_idxs, state }
+                WriteMsg::BatchEnd { rows, bytes_read, bytes, started } => {
+                    let t = &mut self.totals;
+                    t.processed += rows;
+                    metrics::batch_done(rows, t.saved - before.0, t.quarantined - before.1, t.skipped - before.2, bytes, started.elapsed());
+                    before = (t.saved, t.quarantined, t.skipped);
+                    debug!(source = %self.processor.source, rows, processed = t.processed, saved = t.saved, quarantined = t.quarantined, "Batch done");
+                    self.report_progress(bytes_read, run_started, total_bytes);
+                    self.flush_rejected();
                 },
-                "align" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let threshold = step.get("threshold").and_then(|v| v.as_f64()).unwrap_or(0.85);
-                    let ref_list: Vec<String> = step.get("ref").and_then(|v| v.as_array())
-                        .map(|arr| arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
-                        .unwrap_or_default();
-                    PreparedStep::Align { col_idx: get_idx(col)?, ref_list, threshold }
-                },
-                "map" => {
-                    let col = step.get("col").and_then(|v| v.as_str()).unwrap_or("");
-                    let default_val = step.get("default").and_then(|v| v.as_str()).map(|s| s.to_string());
-                    
-                    let mut map_lookup = HashMap::new();
-                    if let Some(mapping) = step.get("mapping").and_then(|v| v.as_object()) {
-                        for (k, v) in mapping {
-                            if let Some(val_str) = v.as_str() {
-                                map_lookup.insert(k.clone(), val_str.to_string());
-                            }
-                        }
-                    }
-                    
-                    PreparedStep::Map { 
-                        col_idx: get_idx(col)?, 
-                        mapping: map_lookup, 
-                        default: default_val 
-                    }
+            }
+        }
+
+        self.flush_rejected();
+        self.clean_writer.flush()?;
+        if let Some(q_writer) = &mut self.quarantine_writer { q_writer.flush()?; }
+        for route in &mut self.routes { route.flush()?; }
+        self.totals.routes = self.routes.iter().map(RouteSink::stats).collect();
+        Ok(())
+    }
+
+    /// Write one row result; `row` is the 1-based source row number
+    fn write(&mut self, row: u64, res: RowResult) -> Result<()> {
+        let t = &mut self.totals;
+        let (step_idx, rec, reason) = match res {
+            RowResult::Keep(rec) => {
+                if route::dispatch(&mut self.routes, &rec)? { return Ok(()); }
+                self.clean_writer.write_row(&rec)?;
+                t.saved += 1;
+                return Ok(());
+            },
+            RowResult::Discarded(step_idx, rec, reason) => {
+                t.step_rejected[step_idx] += 1;
+                (step_idx, rec, reason)
+            },
+            RowResult::Failed(step_idx, rec, reason) => match self.processor.on_error {
+                ErrorStrategy::Quarantine => {
+                    t.step_rejected[step_idx] += 1;
+                    (step_idx, rec, reason)
                 },
-                "hash" => {
-                    let col_val = step.get("col").unwrap_or(&Value::Null);
-                    let salt = step.get("salt").and_then(|v| v.as_str()).unwrap_or("phaeton_salt").to_string();
-                    
-                    let col_idxs = match col_val {
-                        Value::Array(arr) => {
-                            let mut indices = Vec::new();
-                            for v in arr {
-                                if let Some(name) = v.as_str() { indices.push(get_idx(name)?); }
-                            }
-                            indices
-                        },
-                        Value::String(s) => vec![get_idx(s)?],
-                        
-                        _ => return Err(PhaetonError::InvalidStep("Hash step missing 'col' parameter".to_string())),
-                    };
-                    
-                    PreparedStep::Hash { col_idxs, salt }
+                ErrorStrategy::Skip => {
+                    t.step_rejected[step_idx] += 1;
+                    t.skipped += 1;
+                    return Ok(());
                 },
-                "rename" | "headers" => continue,
-                _ => continue, 
-            };
-            prepared_steps.push(p_step);
+                ErrorStrategy::Fail => return Err(row_failure(row, reason)),
+            },
+        };
+
+        self.hand_over(Some(row), Some(self.origins[step_idx]), Some(&rec), &reason);
+        if let Some(q_writer) = &mut self.quarantine_writer {
+            let mut q_rec = rec;
+            // Rows rejected before an add_column step are short of the appended columns
+            while q_rec.len() < self.width { q_rec.push_field(b""); }
+            q_rec.push_field(reason.as_bytes());
+            if self.processor.quarantine_raw { q_rec.push_field(b""); }
+            q_writer.write_byte_record(&q_rec)?;
         }
-        Ok(prepared_steps)
+        self.totals.quarantined += 1;
+        Ok(())
     }
 
-    pub fn peek(&self, n: usize, target_columns: Option<Vec<String>>) -> Result<(Vec<String>, Vec<Vec<String>>)> {
-        let file = File::open(&self.source).map_err(|_| PhaetonError::FileNotFound(self.source.clone()))?;
-        let reader = BufReader::new(file);
-        let mut csv_reader = ReaderBuilder::new().has_headers(true).flexible(true).from_reader(reader);
-        
-        let file_headers = csv_reader.headers()?.clone();
+    /// Queue a quarantined row for the `on_quarantine` callback, if there is one
+    fn hand_over(&mut self, row: Option<u64>, step: Option<usize>, record: Option<&ByteRecord>, reason: &str) {
+        let Some(hook) = &self.processor.on_quarantine else { return };
+        let mut values: Vec<String> = record.map_or_else(Vec::new, |r| {
+            r.iter().take(self.width).map(|f| String::from_utf8_lossy(f).into_owned()).collect()
+        });
+        values.resize(self.width, String::new());
+        self.rejected.push(QuarantinedRow { row, step, reason: reason.to_string(), values });
+        if self.rejected.len() >= hook.batch {
+            self.flush_rejected();
+        }
+    }
 
-        let display_headers = self.transform_headers(&file_headers);
+    fn flush_rejected(&mut self) {
+        let Some(hook) = &self.processor.on_quarantine else { return };
+        if self.rejected.is_empty() { return; }
+        (hook.callback)(QuarantineBatch {
+            source: &self.processor.source,
+            headers: self.headers,
+            rows: std::mem::take(&mut self.rejected),
+        });
+    }
 
-        let prepared_steps = self.compile_steps(&file_headers)?;
+    fn report_progress(&self, bytes_read: u64, run_started: Instant, total_bytes: u64) {
+        let Some(progress) = &self.processor.progress else { return };
+        let elapsed_ms = run_started.elapsed().as_millis() as u64;
+        // Linear ETA from the share of bytes consumed so far
+        let eta_ms = (bytes_read > 0 && total_bytes >= bytes_read)
+            .then(|| (elapsed_ms as f64 * (total_bytes - bytes_read) as f64 / bytes_read as f64) as u64);
 
-        let (indices_to_show, final_output_headers): (Vec<usize>, Vec<String>) = match target_columns {
-            Some(cols) => {
-                let mut idxs = Vec::new();
-                let mut headers_vec = Vec::new();
-                
-                for requested_col in cols {
-                    if let Some(idx) = file_headers.iter().position(|h| h == requested_col) {
-                        idxs.push(idx);
-                        headers_vec.push(display_headers.get(idx).unwrap_or("").to_string());
-                    }
+        progress(&Progress {
+            source: &self.processor.source,
+            processed: self.totals.processed,
+            saved: self.totals.saved,
+            quarantined: self.totals.quarantined,
+            bytes_read,
+            total_bytes,
+            elapsed_ms,
+            eta_ms,
+        });
+    }
+}
+
+/// Hash of the trimmed key columns of a row (every field without columns)
+fn dedupe_key(row: &Row, col_idxs: Option<&[usize]>, mut hasher: impl Hasher) -> u64 {
+    match col_idxs {
+        Some(indices) => {
+            for idx in indices {
+                if let Some(val) = row.text(*idx) {
+                    val.trim().hash(&mut hasher);
                 }
-                (idxs, headers_vec)
-            },
-            None => {
-                let len = file_headers.len();
-                (
-                    (0..len).collect(), 
-                    display_headers.iter().map(|h| h.to_string()).collect()
-                )
+                hasher.write_u8(0xFF); 
             }
-        };
-
-        if indices_to_show.is_empty() && !final_output_headers.is_empty() {
-             return Ok((vec![], vec![]));
-        }
+        },
         
-        let mut rows = Vec::new();
-        let mut collected_count = 0;
-
-        for result in csv_reader.records() {
-            let record = result?;
-            
-            let processed = apply_pipeline(&record, &prepared_steps);
-            
-            if let RowResult::Keep(final_rec) = processed {
-                let mut row_values = Vec::new();
-                for &i in &indices_to_show {
-                    let val = final_rec.get(i).unwrap_or("").to_string();
-                    row_values.push(val);
-                }
-                rows.push(row_values);
-                collected_count += 1;
-                
-                if collected_count >= n {
-                    break;
-                }
+        None => {
+            for field in row.iter().map(text_of) {
+                field.trim().hash(&mut hasher);
+                hasher.write_u8(0xFF); 
             }
         }
+    };
+    hasher.finish()
+}
+
+// --- CORE LOGIC ---
+/// Text of a raw field. Fields that steps read are UTF-8 checked before the steps
+/// run (see `first_readers`), so invalid bytes can only be in pass-through fields.
+fn text_of(field: &[u8]) -> &str {
+    std::str::from_utf8(field).unwrap_or_default()
+}
+
+/// A row on its way through the steps. Fields are read from the parsed record until
+/// a step edits the row; from then on unchanged fields stay borrowed, so replacing
+/// one cell never copies the other columns. It is turned back into a record once,
+/// when it leaves the pipeline.
+struct Row<'a> {
+    record: &'a ByteRecord,
+    fields: Option<Vec<Cow<'a, [u8]>>>,
+}
 
-        Ok((final_output_headers, rows))
+impl<'a> Row<'a> {
+    fn new(record: &'a ByteRecord) -> Self {
+        Row { record, fields: None }
     }
 
-    pub fn execute(&self, output_path: &str, quarantine_path: Option<&str>) -> Result<ExecutionStats> {
-        let file = File::open(&self.source).map_err(|_| PhaetonError::FileNotFound(self.source.clone()))?;
-        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(BufReader::new(file));
-        let original_headers = reader.headers()?.clone();
-        let final_headers = self.transform_headers(&original_headers);
+    fn get(&self, idx: usize) -> Option<&[u8]> {
+        match &self.fields {
+            Some(fields) => fields.get(idx).map(|f| f.as_ref()),
+            None => self.record.get(idx),
+        }
+    }
 
-        let out_file = File::create(output_path)?;
-        let mut clean_writer = WriterBuilder::new().from_writer(BufWriter::new(out_file));
-        clean_writer.write_record(&final_headers)?;
+    fn text(&self, idx: usize) -> Option<&str> {
+        self.get(idx).map(text_of)
+    }
 
-        let mut quarantine_writer = if let Some(path) = quarantine_path {
-            let q_file = File::create(path)?;
-            let mut w = WriterBuilder::new().from_writer(BufWriter::new(q_file));
-            let mut q_headers = final_headers.clone();
-            q_headers.push_field("_phaeton_reason");
-            w.write_record(&q_headers)?;
-            Some(w)
-        } else { None };
+    fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.len()).filter_map(|i| self.get(i))
+    }
 
-        let prepared_steps = self.compile_steps(&original_headers)?;
+    fn len(&self) -> usize {
+        self.fields.as_ref().map_or(self.record.len(), Vec::len)
+    }
 
-        let mut total_processed = 0;
-        let mut total_saved = 0;
-        let mut total_quarantined = 0;
-        let mut batch = Vec::with_capacity(self.batch_size);
-        let mut iter = reader.into_records();
+    fn fields_mut(&mut self) -> &mut Vec<Cow<'a, [u8]>> {
+        let record = self.record;
+        self.fields.get_or_insert_with(|| record.iter().map(Cow::Borrowed).collect())
+    }
 
-        loop {
-            batch.clear();
-            for _ in 0..self.batch_size {
-                match iter.next() {
-                    Some(Ok(record)) => batch.push(record),
-                    Some(Err(e)) => eprintln!("CSV Parse Error: {:?}", e),
-                    None => break,
-                }
-            }
+    fn set(&mut self, idx: usize, value: impl Into<Vec<u8>>) {
+        if let Some(field) = self.fields_mut().get_mut(idx) {
+            *field = Cow::Owned(value.into());
+        }
+    }
 
-            if batch.is_empty() { break; }
-            total_processed += batch.len() as u64;
+    fn push(&mut self, value: impl Into<Vec<u8>>) {
+        self.fields_mut().push(Cow::Owned(value.into()));
+    }
 
-            let results: Vec<RowResult> = batch.par_iter()
-                .map(|record| apply_pipeline(record, &prepared_steps))
-                .collect();
+    fn into_record(self) -> ByteRecord {
+        let Some(fields) = self.fields else { return self.record.clone() };
+        let mut rec = ByteRecord::with_capacity(fields.iter().map(|f| f.len()).sum(), fields.len());
+        for field in &fields { rec.push_field(field); }
+        rec
+    }
+}
 
-            for res in results {
-                match res {
-                    RowResult::Keep(rec) => {
-                        clean_writer.write_record(&rec)?;
-                        total_saved += 1;
-                    },
-                    RowResult::Discarded(rec, reason) => {
-                        if let Some(ref mut q_writer) = quarantine_writer {
-                            let mut q_rec = rec.clone();
-                            q_rec.push_field(&reason);
-                            q_writer.write_record(&q_rec)?;
-                        }
-                        total_quarantined += 1;
-                    }
-                }
-            }
+/// For each source column, the first step that decodes it as text, if any.
+/// Only these fields are UTF-8 validated; every other field is copied as raw bytes.
+fn first_readers(steps: &[PreparedStep], width: usize) -> Vec<Option<usize>> {
+    let mut readers = vec![None; width];
+    for (step_idx, step) in steps.iter().enumerate() {
+        match step.decoded_columns() {
+            Some(cols) => for col in cols {
+                if let Some(slot) = readers.get_mut(col) { slot.get_or_insert(step_idx); }
+            },
+            None => for slot in readers.iter_mut() { slot.get_or_insert(step_idx); },
         }
-        
-        clean_writer.flush()?;
-        if let Some(mut qw) = quarantine_writer { qw.flush()?; }
-
-        Ok(ExecutionStats {
-            processed: total_processed,
-            saved: total_saved,
-            quarantined: total_quarantined,
-            duration_ms: 0,
-        })
     }
+    readers
 }
 
-// --- CORE LOGIC ---
-fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
-    let mut owned_rec: Option<StringRecord> = None;
+const ENCODING_REASON: &str = "Encoding: ";
+
+/// The error a failed row raises under on_error='fail'; undecodable fields are an EncodingError
+fn row_failure(row: u64, reason: String) -> PhaetonError {
+    match reason.strip_prefix(ENCODING_REASON) {
+        Some(detail) => PhaetonError::EncodingError(format!("Row {}: {}", row, detail)),
+        None => PhaetonError::RowFailed { row, reason },
+    }
+}
 
-    let get_val = |idx: usize, owned: &Option<StringRecord>, original: &StringRecord| -> Option<String> {
-        match owned {
-            Some(r) => r.get(idx).map(|s| s.to_string()),
-            None => original.get(idx).map(|s| s.to_string()),
+fn apply_pipeline(record: &ByteRecord, steps: &[PreparedStep], readers: &[Option<usize>], row_num: u64, clocks: Option<&[StepClock]>) -> RowResult {
+    for (col, step_idx) in readers.iter().enumerate() {
+        let Some(step_idx) = *step_idx else { continue };
+        if record.get(col).is_some_and(|field| std::str::from_utf8(field).is_err()) {
+            return RowResult::Failed(step_idx, record.clone(), format!("{}Invalid UTF-8 in field {}", ENCODING_REASON, col + 1));
         }
-    };
+    }
 
-    let finalize_rec = |owned: Option<StringRecord>, original: &StringRecord| -> StringRecord {
-        owned.unwrap_or_else(|| original.clone())
-    };
+    let mut row = Row::new(record);
 
-    for step in steps {
+    for (step_idx, step) in steps.iter().enumerate() {
+        let _timer = clocks.map(|c| StepTimer::start(&c[step_idx]));
         match step {
             // Prune
             PreparedStep::Prune { col_idx } => {
-                let current = owned_rec.as_ref().unwrap_or(record);
                 let should_prune = match col_idx {
-                    Some(idx) => current.get(*idx)
-                        .map(|val| filter::is_empty(val))
+                    Some(idx) => row.text(*idx)
+                        .map(filter::is_empty)
                         .unwrap_or(true),
                     
-                    None => current.iter().any(|field| filter::is_empty(field))
+                    None => row.iter().map(text_of).any(filter::is_empty)
                 };
                 
                 if should_prune { 
-                    return RowResult::Discarded(finalize_rec(owned_rec, record), "Prune: Empty value".into()); 
+                    return RowResult::Discarded(step_idx, row.into_record(), "Prune: Empty value".into()); 
                 }
             },
 
             PreparedStep::PruneSelected { col_idxs } => {
-                let current = owned_rec.as_ref().unwrap_or(record);
                 
                 let should_prune = col_idxs.iter().any(|&idx| {
-                    current.get(idx)
-                        .map(|val| filter::is_empty(val))
+                    row.text(idx)
+                        .map(filter::is_empty)
                         .unwrap_or(true)
                 });
 
                 if should_prune {
-                    return RowResult::Discarded(finalize_rec(owned_rec, record), "Prune: Selected column empty".into());
+                    return RowResult::Discarded(step_idx, row.into_record(), "Prune: Selected column empty".into());
                 }
             },
             
             // Keep Regex
             PreparedStep::KeepRegex { col_idx, re } => {
-                let val_opt = get_val(*col_idx, &owned_rec, record);
+                let val_opt = row.text(*col_idx);
                 let violation = if let Some(raw_val) = val_opt {
                     if raw_val.trim().is_empty() {
                         Some("Keep: Column missing/null".to_string())
-                    } else if !re.is_match(&raw_val) { 
-                        Some(format!("Keep: Regex mismatch")) 
+                    } else if !re.is_match(raw_val) { 
+                        Some("Keep: Regex mismatch".to_string()) 
                     } else { None }
                 } else {
                     Some("Keep: Column missing/null".to_string()) 
                 };
-                if let Some(reason) = violation { return RowResult::Discarded(finalize_rec(owned_rec, record), reason); }
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
+            PreparedStep::KeepRegexSet { col_idx, set } => {
+                let violation = match row.text(*col_idx) {
+                    Some(val) if !val.trim().is_empty() => (!set.is_match(val)).then(|| "Keep: Regex mismatch".to_string()),
+                    _ => Some("Keep: Column missing/null".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
             },
 
             // Keep Single String
             PreparedStep::KeepString { col_idx, pattern, mode } => {
-                let val_opt = get_val(*col_idx, &owned_rec, record);
+                let val_opt = row.text(*col_idx);
                 let violation = if let Some(raw_val) = val_opt {
                     let val = raw_val.trim();
                     let pat = pattern.trim();
@@ -537,47 +2598,61 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
                         "contains" => filter::contains_pattern(val, pat),
                         "startswith" => filter::starts_with_pattern(val, pat),
                         "endswith" => filter::ends_with_pattern(val, pat),
-                        unknown => return RowResult::Discarded(finalize_rec(owned_rec, record), format!("Error: Unknown match mode '{}'", unknown)),
+                        unknown => return RowResult::Failed(step_idx, row.into_record(), format!("Error: Unknown match mode '{}'", unknown)),
                     };
                     if !matches { Some(format!("Keep: Mismatch '{}'", pattern)) } else { None }
                 } else {
                     Some("Keep: Column missing/null".to_string())
                 };
-                if let Some(reason) = violation { return RowResult::Discarded(finalize_rec(owned_rec, record), reason); }
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
             },
 
             // Keep Multi String
-            PreparedStep::KeepMultiString { col_idx, patterns, mode } => {
-                let violation = if let Some(raw_val) = get_val(*col_idx, &owned_rec, record) {
-                    let val = raw_val.trim();
-                    let matched = patterns.iter().any(|pat| {
-                        match mode.as_str() {
-                            "exact" => val == pat,
-                            "contains" => val.contains(pat),
-                            "startswith" => val.starts_with(pat),
-                            "endswith" => val.ends_with(pat),
-                            _ => false
-                        }
-                    });
+            PreparedStep::KeepMultiString { col_idx, patterns } => {
+                let violation = if let Some(raw_val) = row.text(*col_idx) {
+                    let matched = patterns.find(raw_val.trim()).is_some();
                     if !matched { Some("Keep: No match found".to_string()) } else { None }
                 } else {
                     Some("Keep: Column missing".to_string())
                 };
 
                 if let Some(reason) = violation { 
-                    return RowResult::Discarded(finalize_rec(owned_rec, record), reason); 
+                    return RowResult::Discarded(step_idx, row.into_record(), reason); 
                 }
             },
             
+            // Keep Set
+            PreparedStep::KeepSet { col_idx, values } => {
+                let violation = match row.text(*col_idx) {
+                    Some(raw_val) if !values.contains(raw_val.trim()) => Some("Keep: Value not in allowed set".to_string()),
+                    Some(_) => None,
+                    None => Some("Keep: Column missing".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
+            // Keep Fuzzy
+            PreparedStep::KeepFuzzy { col_idx, patterns, threshold, metric } => {
+                let violation = match row.text(*col_idx) {
+                    Some(raw_val) => {
+                        let val = raw_val.trim();
+                        let best = patterns.iter().map(|p| similarity::score(*metric, val, p)).fold(0.0, f64::max);
+                        if best < *threshold { Some(format!("Keep: Similarity {:.2} below threshold", best)) } else { None }
+                    },
+                    None => Some("Keep: Column missing".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
             // Discard Regex
             PreparedStep::DiscardRegex { col_idx, re } => {
-                let val_opt = get_val(*col_idx, &owned_rec, record);
+                let val_opt = row.text(*col_idx);
                 let violation = if let Some(raw_val) = val_opt {
                     if raw_val.trim().is_empty() {
                          Some("Discard: Column missing/null".to_string())
                     
-                    } else if re.is_match(&raw_val) { 
-                        Some(format!("Discard: Regex matched pattern")) 
+                    } else if re.is_match(raw_val) { 
+                        Some("Discard: Regex matched pattern".to_string()) 
                     
                     } else { 
                         None
@@ -585,12 +2660,22 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
                 } else {
                     Some("Discard: Column missing/null".to_string())
                 };
-                if let Some(reason) = violation { return RowResult::Discarded(finalize_rec(owned_rec, record), reason); }
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
+            PreparedStep::DiscardRegexSet { col_idx, set } => {
+                let violation = match row.text(*col_idx) {
+                    // One pass over the cell; the lowest matching pattern index is reported
+                    Some(val) if !val.trim().is_empty() => set.matches(val).iter().next()
+                        .map(|i| format!("Discard: Regex {} matched pattern /{}/", i, set.patterns()[i])),
+                    _ => Some("Discard: Column missing/null".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
             },
 
             // Discard String
             PreparedStep::DiscardString { col_idx, pattern, mode } => {
-                 let val_opt = get_val(*col_idx, &owned_rec, record);
+                 let val_opt = row.text(*col_idx);
                  let violation = if let Some(raw_val) = val_opt {
                     let val = raw_val.trim();
                     let pat = pattern.trim();
@@ -599,93 +2684,96 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
                         "contains" => filter::contains_pattern(val, pat),
                         "startswith" => filter::starts_with_pattern(val, pat),
                         "endswith" => filter::ends_with_pattern(val, pat),
-                         unknown => return RowResult::Discarded(finalize_rec(owned_rec, record), format!("Error: Unknown match mode '{}'", unknown)),
+                         unknown => return RowResult::Failed(step_idx, row.into_record(), format!("Error: Unknown match mode '{}'", unknown)),
                     };
                     if matches { Some(format!("Discard: Matched forbidden '{}'", pattern)) } else { None }
                 } else { 
                     Some("Discard: Column missing/null".to_string())
                 };
-                if let Some(reason) = violation { return RowResult::Discarded(finalize_rec(owned_rec, record), reason); }
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
             },
             
             // Discard Multi String
-            PreparedStep::DiscardMultiString { col_idx, patterns, mode } => {
-                let violation = if let Some(raw_val) = get_val(*col_idx, &owned_rec, record) {
-                    let val = raw_val.trim();
-                    let matched = patterns.iter().any(|pat| {
-                        match mode.as_str() {
-                            "exact" => val == pat,
-                            "contains" => val.contains(pat),
-                            "startswith" => val.starts_with(pat),
-                            "endswith" => val.ends_with(pat),
-                            _ => false
-                        }
-                    });
-                    if matched { Some(format!("Discard: Matched forbidden value")) } else { None }
+            PreparedStep::DiscardMultiString { col_idx, patterns } => {
+                let violation = if let Some(raw_val) = row.text(*col_idx) {
+                    patterns.find(raw_val.trim()).map(|pat| format!("Discard: Matched forbidden '{}'", pat))
                 } else {
                     Some("Discard: Column missing".to_string())
                 };
 
                 if let Some(reason) = violation { 
-                    return RowResult::Discarded(finalize_rec(owned_rec, record), reason); 
+                    return RowResult::Discarded(step_idx, row.into_record(), reason); 
                 }
             },
             
+            // Discard Set
+            PreparedStep::DiscardSet { col_idx, values } => {
+                let violation = match row.text(*col_idx) {
+                    Some(raw_val) if values.contains(raw_val.trim()) => Some(format!("Discard: Matched forbidden value '{}'", raw_val.trim())),
+                    Some(_) => None,
+                    None => Some("Discard: Column missing".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
+            // Discard Fuzzy
+            PreparedStep::DiscardFuzzy { col_idx, patterns, threshold, metric } => {
+                let violation = match row.text(*col_idx) {
+                    Some(raw_val) => {
+                        let val = raw_val.trim();
+                        patterns.iter()
+                            .map(|p| (p, similarity::score(*metric, val, p)))
+                            .find(|(_, score)| *score >= *threshold)
+                            .map(|(p, score)| format!("Discard: Similar ({:.2}) to forbidden '{}'", score, p))
+                    },
+                    None => Some("Discard: Column missing".to_string()),
+                };
+                if let Some(reason) = violation { return RowResult::Discarded(step_idx, row.into_record(), reason); }
+            },
+
             // Scrub
-            PreparedStep::Scrub { col_idx, mode } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                
-                if let Some(val) = current_ref.get(*col_idx) {
+            PreparedStep::Scrub { col_idx, mode, transliterate, collapse_whitespace, decimal, chars } => {
+                if let Some(val) = row.text(*col_idx) {
                     let new_val = match mode.as_str() {
                         "email" => text::mask_email(val),
-                        "currency" => text::scrub_currency(val),
+                        "currency" => text::scrub_currency_as(val, *decimal),
                         "numeric_only" => text::scrub_numeric_only(val),
+                        "trim" if !chars.is_empty() => text::trim_chars(val, chars),
                         "trim" => text::trim_whitespace(val),
-                        "html" => text::remove_html_tags(val),
+                        "html" => text::remove_html_tags(val, *collapse_whitespace),
                         "lower" => text::to_lowercase(val),
                         "upper" => text::to_uppercase(val),
+                        "clean_invisible" => text::remove_invisible(val),
+                        "ascii_only" => text::to_ascii_only(val, *transliterate),
+                        "plain_punctuation" => text::plain_punctuation(val),
+                        "percent" => text::scrub_percent(val, *decimal),
+                        "ascii_digits" => text::to_ascii_digits(val),
+                        "transliterate" => text::transliterate(val),
                         _ => Cow::Borrowed(val) 
                     };
 
                     if let Cow::Owned(v) = new_val {
-                        let mut new_rec = StringRecord::new();
-                        for (i, field) in current_ref.iter().enumerate() {
-                            if i == *col_idx { new_rec.push_field(&v); } else { new_rec.push_field(field); }
-                        }
-                        owned_rec = Some(new_rec);
+                        row.set(*col_idx, v);
                     }
                 }
             },
             
             // Fill
             PreparedStep::FillFixed { col_idx, value } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                if let Some(val) = current_ref.get(*col_idx) {
+                if let Some(val) = row.text(*col_idx) {
                     if filter::is_empty(val) {
-                        let mut new_rec = StringRecord::new();
-                        for (i, field) in current_ref.iter().enumerate() {
-                            if i == *col_idx { new_rec.push_field(value); } 
-                            else { new_rec.push_field(field); }
-                        }
-                        owned_rec = Some(new_rec);
+                        row.set(*col_idx, value.as_bytes());
                     }
                 }
             },
 
             PreparedStep::FillForward { col_idx, last_valid } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                
-                if let Some(val) = current_ref.get(*col_idx) {
+                if let Some(val) = row.text(*col_idx) {
                     let mut last = last_valid.lock().unwrap();
 
                     if filter::is_empty(val) {
                         if !last.is_empty() {
-                            let mut new_rec = StringRecord::new();
-                            for (i, field) in current_ref.iter().enumerate() {
-                                if i == *col_idx { new_rec.push_field(&last); } 
-                                else { new_rec.push_field(field); }
-                            }
-                            owned_rec = Some(new_rec);
+                            row.set(*col_idx, last.as_bytes());
                         }
                     } else {
                         *last = val.to_string();
@@ -695,88 +2783,99 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
             
             // Dedupe
             PreparedStep::Dedupe { col_idxs, state } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                let mut hasher = DefaultHasher::new();
-                
-                match col_idxs {
-                    Some(indices) => {
-                        for idx in indices {
-                            if let Some(val) = current_ref.get(*idx) {
-                                val.trim().hash(&mut hasher);
-                            }
-                            hasher.write_u8(0xFF); 
-                        }
-                    },
-                    
-                    None => {
-                        for field in current_ref.iter() {
-                            field.trim().hash(&mut hasher);
-                            hasher.write_u8(0xFF); 
-                        }
-                    }
+                // Keys kept across runs need a hash that does not change between builds
+                let key = match state.store {
+                    Some(_) => dedupe_key(&row, col_idxs.as_deref(), StableHasher::default()),
+                    None => dedupe_key(&row, col_idxs.as_deref(), DefaultHasher::new()),
                 };
                 
-                if state.check_and_insert(hasher.finish()) {
+                if state.check_and_insert(key) {
                     return RowResult::Discarded(
-                        finalize_rec(owned_rec, record), 
+                        step_idx,
+                        row.into_record(), 
                         "Dedupe: Duplicate found".to_string()
                     );
                 }
             },
 
             // Align / fuzzyalign
-            PreparedStep::Align { col_idx, ref_list, threshold } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                if let Some(val) = current_ref.get(*col_idx) {
-                    if !filter::is_empty(val) {
-                        let mut best_match: Option<&String> = None;
-                        let mut best_score = 0.0;
-                        for target in ref_list {
-                            let score = strsim::jaro_winkler(val, target);
-                            if score > best_score {
-                                best_score = score;
-                                best_match = Some(target);
-                            }
-                        }
-                        if best_score >= *threshold {
-                            if let Some(target) = best_match {
-                                if val != target { 
-                                    let mut new_rec = StringRecord::new();
-                                    for (i, field) in current_ref.iter().enumerate() {
-                                        if i == *col_idx { new_rec.push_field(target); } else { new_rec.push_field(field); }
-                                    }
-                                    owned_rec = Some(new_rec);
+            PreparedStep::Align { col_idx, ref_list, threshold, metric, phonetic, audit, cache } => {
+                let val = row.text(*col_idx).unwrap_or("");
+
+                let best = if filter::is_empty(val) {
+                    None
+                } else {
+                    cache.get_or_compute(val, || {
+                        let best_of = |candidates: &mut dyn Iterator<Item = usize>| {
+                            let mut best: Option<AlignMatch> = None;
+                            for i in candidates {
+                                let score = similarity::score(*metric, val, &ref_list[i]);
+                                if best.is_none_or(|b| score > b.score) {
+                                    best = Some(AlignMatch { idx: i, score });
                                 }
                             }
-                        }
-                    }
+                            best
+                        };
+
+                        // Same-sounding entries win first if they pass the threshold; otherwise scan the whole list
+                        phonetic.as_ref()
+                            .and_then(|index| index.candidates(val))
+                            .and_then(|bucket| best_of(&mut bucket.iter().copied()))
+                            .filter(|m| m.score >= *threshold)
+                            .or_else(|| best_of(&mut (0..ref_list.len())))
+                    })
+                };
+
+                let replacement = best
+                    .filter(|m| m.score >= *threshold)
+                    .map(|m| &ref_list[m.idx])
+                    .filter(|target| val != target.as_str());
+
+                let original = audit.then(|| val.to_string());
+                if let Some(target) = replacement {
+                    row.set(*col_idx, target.as_bytes());
+                }
+                if let Some(original) = original {
+                    // Original value and best candidate score, even when below threshold
+                    row.push(original);
+                    row.push(best.map(|m| format!("{:.4}", m.score)).unwrap_or_default());
                 }
             },
 
             // Cast
-            PreparedStep::Cast { col_idx, dtype, clean } => {
-                 let val_opt = get_val(*col_idx, &owned_rec, record);
-                 let cast_error = if let Some(val) = val_opt {
-                     if filter::is_empty(&val) {
-                         Some("Cannot convert empty string".to_string())
-                     } else {
-                         let result = match dtype.as_str() {
-                             "float" => cast::to_float(&val, "unknown", *clean).map(|_| ()),
-                             "int" => cast::to_int(&val, "unknown", *clean).map(|_| ()),
-                             "bool" => cast::to_bool(&val, "unknown").map(|_| ()),
-                             _ => Ok(())
-                         };
-                         if let Err(e) = result { Some(e.to_string()) } else { None }
-                     }
-                 } else { None };
-
-                 if let Some(reason) = cast_error { 
-                     return RowResult::Discarded(finalize_rec(owned_rec, record), reason); 
-                 }
+            PreparedStep::Cast { col_idx, col_name, dtype, clean, formats, precision, scale, percent, bools, validate_only } => {
+                let val_opt = row.text(*col_idx);
+                let Some(val) = val_opt else { continue };
+
+                if filter::is_empty(val) {
+                    return RowResult::Failed(step_idx, row.into_record(), "Cannot convert empty string".to_string());
+                }
+
+                // Normalized representation of the value, written back unless validate_only
+                let result = match dtype.as_str() {
+                    "float" => cast::to_float_text(val, col_name, *clean, *percent),
+                    "int" => cast::to_int(val, col_name, *clean, *percent).map(|v| v.to_string()),
+                    "bool" => cast::to_bool(val, col_name, bools).map(|v| v.to_string()),
+                    "date" => cast::to_date(val, col_name, formats).map(|d| d.format("%Y-%m-%d").to_string()),
+                    "datetime" => cast::to_datetime(val, col_name, formats).map(|d| d.format("%Y-%m-%dT%H:%M:%S%.f").to_string()),
+                    "decimal" => cast::to_decimal(val, col_name, *clean, *percent, *precision, *scale),
+                    _ => Ok(val.to_string())
+                };
+
+                let normalized = match result {
+                    Ok(v) => v,
+                    Err(e) => {
+                        let line = record.position().map(|p| p.line());
+                        return RowResult::Failed(step_idx, row.into_record(), e.at_row(row_num, line).to_string());
+                    },
+                };
+
+                if !*validate_only && normalized != val {
+                    row.set(*col_idx, normalized);
+                }
             },
             PreparedStep::Map { col_idx, mapping, default } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                if let Some(val) = current_ref.get(*col_idx) {
+                if let Some(val) = row.text(*col_idx) {
                     // Cari di dictionary
                     let new_val_opt = mapping.get(val).cloned();
                     
@@ -788,12 +2887,7 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
 
                     if let Some(res) = replacement {
                         // Kalau ada replacement, update record
-                         let mut new_rec = StringRecord::new();
-                         for (i, field) in current_ref.iter().enumerate() {
-                             if i == *col_idx { new_rec.push_field(&res); } 
-                             else { new_rec.push_field(field); }
-                         }
-                         owned_rec = Some(new_rec);
+                         row.set(*col_idx, res);
                     }
                     // Kalau gak ketemu & gak ada default, biarkan nilai asli
                 }
@@ -801,31 +2895,147 @@ fn apply_pipeline(record: &StringRecord, steps: &[PreparedStep]) -> RowResult {
 
             // IMPLEMENTASI HASH
             PreparedStep::Hash { col_idxs, salt } => {
-                let current_ref = owned_rec.as_ref().unwrap_or(record);
-                
                 let needs_update = col_idxs.iter().any(|idx| {
-                     current_ref.get(*idx).map(|v| !filter::is_empty(v)).unwrap_or(false)
+                     row.text(*idx).map(|v| !filter::is_empty(v)).unwrap_or(false)
                 });
 
                 if needs_update {
-                    let mut new_rec = StringRecord::new();
-
-                    for (i, field) in current_ref.iter().enumerate() {
-                        if col_idxs.contains(&i) {
-                             let mut hasher = Sha256::new();
-                             hasher.update(field.as_bytes());
-                             hasher.update(salt.as_bytes());
-                             let res = hex::encode(hasher.finalize());
-                             new_rec.push_field(&res);
-                        } else {
-                             new_rec.push_field(field);
+                    for &idx in col_idxs {
+                        let Some(field) = row.get(idx) else { continue };
+                        let mut hasher = Sha256::new();
+                        hasher.update(field);
+                        hasher.update(salt.as_bytes());
+                        let res = hex::encode(hasher.finalize());
+                        row.set(idx, res);
+                    }
+                }
+            },
+
+            PreparedStep::Mask { col_idxs, salt, keep_first, keep_last, luhn } => {
+                for &idx in col_idxs {
+                    let Some(val) = row.text(idx).filter(|v| !filter::is_empty(v)) else { continue };
+                    let masked = text::mask_format(val, salt, *keep_first, *keep_last, *luhn);
+                    row.set(idx, masked);
+                }
+            },
+
+            // Empty values stay empty, so tokens never stand for "no value"
+            PreparedStep::Tokenize { col_idxs, vault } => {
+                for &idx in col_idxs {
+                    let Some(val) = row.text(idx).filter(|v| !filter::is_empty(v)) else { continue };
+                    let token = vault.tokenize(val);
+                    row.set(idx, token);
+                }
+            },
+
+            // Values that are not tokens of the vault are left as they are
+            PreparedStep::Detokenize { col_idxs, entries, .. } => {
+                for &idx in col_idxs {
+                    let Some(original) = row.text(idx).and_then(|v| entries.get(v.trim())) else { continue };
+                    let original = original.clone();
+                    row.set(idx, original);
+                }
+            },
+
+            // Add Column
+            PreparedStep::AddColumnFixed { value } => {
+                row.push(value.as_bytes());
+            },
+
+            PreparedStep::AddColumnRowNumber => {
+                row.push(row_num.to_string());
+            },
+
+            // JSON Extract
+            PreparedStep::JsonExtract { col_idx, path } => {
+                let raw = row.text(*col_idx).unwrap_or_default();
+
+                let extracted = if filter::is_empty(raw) {
+                    String::new()
+                } else {
+                    match serde_json::from_str::<Value>(raw) {
+                        Ok(doc) => json::extract(&doc, path).map(json::to_cell).unwrap_or_default(),
+                        Err(e) => return RowResult::Failed(step_idx, row.into_record(), format!("JSON: Invalid document ({})", e)),
+                    }
+                };
+
+                row.push(extracted);
+            },
+
+            // Format
+            PreparedStep::Format { col_idx, template } => {
+                let value = match template.render(|i| row.text(i)) {
+                    Ok(value) => value,
+                    Err(e) => return RowResult::Failed(step_idx, row.into_record(), format!("Format: {}", e)),
+                };
+                match col_idx {
+                    Some(idx) => row.set(*idx, value),
+                    None => row.push(value),
+                }
+            },
+
+            // Country
+            PreparedStep::Country { col_idx, alpha3, threshold, resolver } => {
+                if let Some(val) = row.text(*col_idx) {
+                    if !filter::is_empty(val) {
+                        let code = match resolver.resolve(val, *threshold) {
+                            Some((a2, a3)) => if *alpha3 { a3 } else { a2 },
+                            None => {
+                                let reason = format!("Country: Unrecognized '{}'", val);
+                                return RowResult::Failed(step_idx, row.into_record(), reason);
+                            }
+                        };
+
+                        if val != code {
+                            row.set(*col_idx, code.as_bytes());
+                        }
+                    }
+                }
+            },
+
+            PreparedStep::Expect { col_idx, check } => check.observe(col_idx.and_then(|i| row.text(i))),
+
+            // Fingerprint (stable across runs, unlike the dedupe hasher)
+            PreparedStep::Fingerprint { col_idxs } => {
+                let mut hasher = Sha256::new();
+
+                match col_idxs {
+                    Some(indices) => {
+                        for idx in indices {
+                            hasher.update(row.get(*idx).unwrap_or_default());
+                            hasher.update([0x1F]);
+                        }
+                    },
+                    None => {
+                        for field in row.iter() {
+                            hasher.update(field);
+                            hasher.update([0x1F]);
                         }
                     }
-                    owned_rec = Some(new_rec);
                 }
+
+                row.push(hex::encode(hasher.finalize()));
+            },
+
+            // Currency Conversion
+            PreparedStep::ConvertCurrency { col_idx, currency_idx, target, rates, decimals, clean } => {
+                let amount = row.text(*col_idx).unwrap_or("");
+                let code = row.text(*currency_idx).unwrap_or("").trim().to_uppercase();
+
+                if filter::is_empty(amount) {
+                    continue;
+                }
+
+                let converted = match currency::convert(amount, &code, target, rates, *clean) {
+                    Ok(v) => format!("{:.*}", *decimals, v),
+                    Err(reason) => return RowResult::Failed(step_idx, row.into_record(), reason),
+                };
+
+                row.set(*col_idx, converted);
+                row.set(*currency_idx, target.as_bytes());
             },
         }
     }
 
-    RowResult::Keep(finalize_rec(owned_rec, record))
+    RowResult::Keep(row.into_record())
 }
\ No newline at end of file
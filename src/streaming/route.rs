@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+
+use csv::{ByteRecord, StringRecord, WriterBuilder};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{PhaetonError, Result};
+use crate::processors::filter;
+use super::open_output;
+
+/// A named output taking the kept rows its condition matches; routes are tried
+/// in order and the first match wins
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Route {
+    pub name: String,
+    pub when: Condition,
+    /// CSV file of the route; without one the matching rows are dropped
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Falls back to the pipeline's output delimiter
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// Columns written, in this order; all of them by default
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default = "header_default")]
+    pub header: bool,
+}
+
+fn header_default() -> bool {
+    true
+}
+
+/// Condition tree: `{"all": [...]}`, `{"any": [...]}`, `{"not": {...}}` or a test
+/// `{"col": "email", "op": "empty"}`
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Condition {
+    All { all: Vec<Condition> },
+    Any { any: Vec<Condition> },
+    Not { not: Box<Condition> },
+    Test {
+        col: String,
+        op: String,
+        #[serde(default, skip_serializing_if = "Value::is_null")]
+        value: Value,
+    },
+}
+
+/// Rows written to one route
+#[derive(Serialize, Clone, Debug)]
+pub struct RouteStats {
+    pub name: String,
+    pub output: Option<String>,
+    pub rows: u64,
+    /// Whether the route's file starts with a header row
+    #[serde(skip)]
+    pub header: bool,
+}
+
+enum Test {
+    Empty,
+    NotEmpty,
+    Eq(String),
+    Ne(String),
+    In(HashSet<String>),
+    NotIn(HashSet<String>),
+    Contains(String),
+    StartsWith(String),
+    EndsWith(String),
+    Regex(Regex),
+    /// Numeric comparison; values that are not numbers never match
+    Compare(std::cmp::Ordering, bool, f64),
+}
+
+enum Compiled {
+    All(Vec<Compiled>),
+    Any(Vec<Compiled>),
+    Not(Box<Compiled>),
+    Test { idx: usize, test: Test },
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+impl Compiled {
+    fn new(condition: &Condition, resolve: &dyn Fn(&str) -> Result<usize>) -> Result<Self> {
+        Ok(match condition {
+            Condition::All { all } => Compiled::All(all.iter().map(|c| Compiled::new(c, resolve)).collect::<Result<_>>()?),
+            Condition::Any { any } => Compiled::Any(any.iter().map(|c| Compiled::new(c, resolve)).collect::<Result<_>>()?),
+            Condition::Not { not } => Compiled::Not(Box::new(Compiled::new(not, resolve)?)),
+            Condition::Test { col, op, value } => {
+                let idx = resolve(col)?;
+                let needs = |kind: &str| PhaetonError::InvalidStep(format!("Route condition '{}' on '{}' needs {} 'value'", op, col, kind));
+                let single = || text(value).ok_or_else(|| needs("a single"));
+                let set = || -> Result<HashSet<String>> {
+                    match value {
+                        Value::Array(items) => Ok(items.iter().filter_map(text).collect()),
+                        _ => Err(needs("a list as")),
+                    }
+                };
+                let number = || value.as_f64().or_else(|| value.as_str().and_then(|s| s.trim().parse().ok())).ok_or_else(|| needs("a number as"));
+                use std::cmp::Ordering::{Greater, Less};
+                let test = match op.as_str() {
+                    "empty" => Test::Empty,
+                    "not_empty" => Test::NotEmpty,
+                    "eq" => Test::Eq(single()?),
+                    "ne" => Test::Ne(single()?),
+                    "in" => Test::In(set()?),
+                    "not_in" => Test::NotIn(set()?),
+                    "contains" => Test::Contains(single()?),
+                    "starts_with" => Test::StartsWith(single()?),
+                    "ends_with" => Test::EndsWith(single()?),
+                    "regex" => {
+                        let pattern = single()?;
+                        Test::Regex(Regex::new(&pattern).map_err(|_| PhaetonError::InvalidStep(format!("Invalid Regex: {}", pattern)))?)
+                    },
+                    "gt" => Test::Compare(Greater, false, number()?),
+                    "ge" => Test::Compare(Greater, true, number()?),
+                    "lt" => Test::Compare(Less, false, number()?),
+                    "le" => Test::Compare(Less, true, number()?),
+                    other => return Err(PhaetonError::InvalidStep(format!(
+                        "Unknown route condition '{}'. Expected empty, not_empty, eq, ne, in, not_in, contains, starts_with, ends_with, regex, gt, ge, lt or le", other
+                    ))),
+                };
+                Compiled::Test { idx, test }
+            },
+        })
+    }
+
+    fn matches(&self, row: &ByteRecord) -> bool {
+        match self {
+            Compiled::All(all) => all.iter().all(|c| c.matches(row)),
+            Compiled::Any(any) => any.iter().any(|c| c.matches(row)),
+            Compiled::Not(not) => !not.matches(row),
+            Compiled::Test { idx, test } => {
+                let raw = String::from_utf8_lossy(row.get(*idx).unwrap_or_default());
+                let val = raw.trim();
+                match test {
+                    Test::Empty => filter::is_empty(val),
+                    Test::NotEmpty => !filter::is_empty(val),
+                    Test::Eq(v) => val == v,
+                    Test::Ne(v) => val != v,
+                    Test::In(set) => set.contains(val),
+                    Test::NotIn(set) => !set.contains(val),
+                    Test::Contains(v) => val.contains(v.as_str()),
+                    Test::StartsWith(v) => val.starts_with(v.as_str()),
+                    Test::EndsWith(v) => val.ends_with(v.as_str()),
+                    Test::Regex(re) => re.is_match(val),
+                    Test::Compare(ordering, or_equal, bound) => val.parse::<f64>().ok()
+                        .and_then(|x| x.partial_cmp(bound))
+                        .is_some_and(|o| o == *ordering || (*or_equal && o.is_eq())),
+                }
+            },
+        }
+    }
+}
+
+/// One route's condition and writer, owned by the writer stage
+pub(super) struct RouteSink {
+    name: String,
+    output: Option<String>,
+    header: bool,
+    when: Compiled,
+    delimiter: u8,
+    /// Opened by `open_routes` once everything else compiled
+    writer: Option<csv::Writer<BufWriter<File>>>,
+    /// Output columns by position; None writes the row as it is
+    columns: Option<Vec<usize>>,
+    record: ByteRecord,
+    rows: u64,
+}
+
+impl RouteSink {
+    fn write(&mut self, row: &ByteRecord) -> Result<()> {
+        self.rows += 1;
+        let Some(writer) = &mut self.writer else { return Ok(()) };
+        match &self.columns {
+            Some(columns) => {
+                self.record.clear();
+                for &idx in columns {
+                    self.record.push_field(row.get(idx).unwrap_or_default());
+                }
+                writer.write_byte_record(&self.record)?;
+            },
+            None => writer.write_byte_record(row)?,
+        }
+        Ok(())
+    }
+
+    pub(super) fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = &mut self.writer {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn stats(&self) -> RouteStats {
+        RouteStats { name: self.name.clone(), output: self.output.clone(), rows: self.rows, header: self.header }
+    }
+}
+
+/// Compile the routes against the output header without touching any file.
+/// `reserved` are the pipeline's own output and quarantine paths, which no route may write to.
+pub(super) fn compile_routes(
+    routes: &[Route],
+    resolve: &dyn Fn(&str) -> Result<usize>,
+    delimiter: u8,
+    reserved: &[&str],
+) -> Result<Vec<RouteSink>> {
+    let mut names = HashSet::new();
+    let mut outputs = HashSet::new();
+    routes.iter().map(|route| {
+        if !names.insert(route.name.as_str()) {
+            return Err(PhaetonError::InvalidStep(format!("Route '{}' is defined twice", route.name)));
+        }
+        if let Some(path) = &route.output {
+            if reserved.contains(&path.as_str()) {
+                return Err(PhaetonError::InvalidStep(format!("Route '{}' writes to '{}', which is the pipeline's own output or quarantine file", route.name, path)));
+            }
+            if !outputs.insert(path.as_str()) {
+                return Err(PhaetonError::InvalidStep(format!("Route '{}' writes to '{}', which another route already uses", route.name, path)));
+            }
+        }
+        let when = Compiled::new(&route.when, resolve).map_err(|e| match e {
+            PhaetonError::InvalidStep(reason) => PhaetonError::InvalidStep(format!("Route '{}': {}", route.name, reason)),
+            other => other,
+        })?;
+        let columns = route.columns.as_ref()
+            .map(|cols| cols.iter().map(|c| resolve(c)).collect::<Result<Vec<_>>>())
+            .transpose()?;
+
+        let delimiter = match route.delimiter.as_deref() {
+            Some(d) if d.len() == 1 => d.as_bytes()[0],
+            Some(d) => return Err(PhaetonError::InvalidStep(format!(
+                "Route '{}': invalid delimiter '{}', expected a single ASCII character", route.name, d
+            ))),
+            None => delimiter,
+        };
+        Ok(RouteSink {
+            name: route.name.clone(), output: route.output.clone(), header: route.header, when, delimiter,
+            writer: None, columns, record: ByteRecord::new(), rows: 0,
+        })
+    }).collect()
+}
+
+/// Open the files of compiled routes, writing their header when a file is new
+pub(super) fn open_routes(routes: &mut [RouteSink], headers: &StringRecord, append: bool) -> Result<()> {
+    for route in routes {
+        let Some(path) = &route.output else { continue };
+        let (file, new) = open_output(path, append)?;
+        let mut writer = WriterBuilder::new().delimiter(route.delimiter).from_writer(BufWriter::new(file));
+        if new && route.header {
+            match &route.columns {
+                Some(columns) => writer.write_record(columns.iter().map(|&i| &headers[i]))?,
+                None => writer.write_record(headers)?,
+            }
+        }
+        route.writer = Some(writer);
+    }
+    Ok(())
+}
+
+/// Hand `row` to the first route whose condition it meets; false if none does
+pub(super) fn dispatch(routes: &mut [RouteSink], row: &ByteRecord) -> Result<bool> {
+    match routes.iter_mut().find(|route| route.when.matches(row)) {
+        Some(route) => route.write(row).map(|()| true),
+        None => Ok(false),
+    }
+}